@@ -0,0 +1,279 @@
+use anchor_lang::prelude::*;
+use anchor_lang::system_program;
+use anchor_lang::solana_program::{
+    hash::hash,
+    instruction::{AccountMeta, Instruction},
+    program::invoke,
+};
+use anchor_spl::token::{transfer, Token, TokenAccount, Transfer};
+
+declare_id!("Ezs2JgZv7SM8xJTpxKL3DQ1y1hYM6hKn3wgoSv5XC2tT");
+
+// Test-only stand-ins for the external programs octasol_contract CPIs into.
+// Never deploy this outside of localnet.
+#[program]
+pub mod mock_integrations {
+    use super::*;
+
+    // Mock lending protocol: park the depositor's tokens in its vault.
+    // Extra accounts turn it into a hostile protocol that calls back into the
+    // program in remaining_accounts[0] with `withdraw_from_yield` and the rest.
+    pub fn deposit<'info>(ctx: Context<'_, '_, '_, 'info, YieldDeposit<'info>>, amount: u64) -> Result<()> {
+        let cpi_ctx = CpiContext::new(
+            ctx.accounts.token_program.to_account_info(),
+            Transfer {
+                from: ctx.accounts.source.to_account_info(),
+                to: ctx.accounts.vault.to_account_info(),
+                authority: ctx.accounts.depositor.to_account_info(),
+            },
+        );
+        transfer(cpi_ctx, amount)?;
+
+        if let Some((target, accounts)) = ctx.remaining_accounts.split_first() {
+            let ix = Instruction {
+                program_id: target.key(),
+                accounts: accounts
+                    .iter()
+                    .map(|acc| AccountMeta {
+                        pubkey: acc.key(),
+                        is_signer: acc.is_signer,
+                        is_writable: acc.is_writable,
+                    })
+                    .collect(),
+                data: hash(b"global:withdraw_from_yield").to_bytes()[..8].to_vec(),
+            };
+            invoke(&ix, ctx.remaining_accounts)?;
+        }
+        Ok(())
+    }
+
+    // Mock lending protocol: hand back the whole vault, i.e. principal plus
+    // whatever "interest" the test minted into it
+    pub fn withdraw(ctx: Context<YieldWithdraw>, _amount: u64) -> Result<()> {
+        let depositor = ctx.accounts.depositor.key();
+        let seeds = &[b"vault", depositor.as_ref(), &[ctx.bumps.vault_authority]];
+        let signer = &[&seeds[..]];
+
+        let cpi_ctx = CpiContext::new_with_signer(
+            ctx.accounts.token_program.to_account_info(),
+            Transfer {
+                from: ctx.accounts.vault.to_account_info(),
+                to: ctx.accounts.destination.to_account_info(),
+                authority: ctx.accounts.vault_authority.to_account_info(),
+            },
+            signer,
+        );
+        transfer(cpi_ctx, ctx.accounts.vault.amount)
+    }
+
+    // Simulate a loss in the lending protocol by moving funds out of the vault
+    pub fn simulate_loss(ctx: Context<SimulateLoss>, amount: u64) -> Result<()> {
+        let depositor = ctx.accounts.depositor.key();
+        let seeds = &[b"vault", depositor.as_ref(), &[ctx.bumps.vault_authority]];
+        let signer = &[&seeds[..]];
+
+        let cpi_ctx = CpiContext::new_with_signer(
+            ctx.accounts.token_program.to_account_info(),
+            Transfer {
+                from: ctx.accounts.vault.to_account_info(),
+                to: ctx.accounts.sink.to_account_info(),
+                authority: ctx.accounts.vault_authority.to_account_info(),
+            },
+            signer,
+        );
+        transfer(cpi_ctx, amount)
+    }
+
+    // Mock AMM: take the LP tokens into the pool and pay out the underlying 1:1 from its reserve
+    pub fn unwrap(ctx: Context<Unwrap>, amount: u64) -> Result<()> {
+        let cpi_ctx = CpiContext::new(
+            ctx.accounts.token_program.to_account_info(),
+            Transfer {
+                from: ctx.accounts.source.to_account_info(),
+                to: ctx.accounts.lp_vault.to_account_info(),
+                authority: ctx.accounts.owner.to_account_info(),
+            },
+        );
+        transfer(cpi_ctx, amount)?;
+
+        let seeds = &[b"reserve".as_ref(), &[ctx.bumps.reserve_authority]];
+        let signer = &[&seeds[..]];
+        let cpi_ctx = CpiContext::new_with_signer(
+            ctx.accounts.token_program.to_account_info(),
+            Transfer {
+                from: ctx.accounts.reserve.to_account_info(),
+                to: ctx.accounts.destination.to_account_info(),
+                authority: ctx.accounts.reserve_authority.to_account_info(),
+            },
+            signer,
+        );
+        transfer(cpi_ctx, amount)
+    }
+
+    // Mock AMM: take SOL into the pool and pay the same number of base units from its token reserve
+    pub fn swap_sol(ctx: Context<SwapSol>, lamports: u64, _min_out: u64) -> Result<()> {
+        system_program::transfer(
+            CpiContext::new(
+                ctx.accounts.system_program.to_account_info(),
+                system_program::Transfer {
+                    from: ctx.accounts.payer.to_account_info(),
+                    to: ctx.accounts.reserve_authority.to_account_info(),
+                },
+            ),
+            lamports,
+        )?;
+
+        let seeds = &[b"reserve".as_ref(), &[ctx.bumps.reserve_authority]];
+        let signer = &[&seeds[..]];
+        let cpi_ctx = CpiContext::new_with_signer(
+            ctx.accounts.token_program.to_account_info(),
+            Transfer {
+                from: ctx.accounts.reserve.to_account_info(),
+                to: ctx.accounts.destination.to_account_info(),
+                authority: ctx.accounts.reserve_authority.to_account_info(),
+            },
+            signer,
+        );
+        transfer(cpi_ctx, lamports)
+    }
+
+    // Mock AMM: take the input tokens into the pool and pay the output token 1:1 from its reserve
+    pub fn swap(ctx: Context<Unwrap>, amount_in: u64, _min_out: u64) -> Result<()> {
+        unwrap(ctx, amount_in)
+    }
+
+    // Mock account compression: a "tree" that only tracks a running hash of its leaves
+    pub fn init_tree(ctx: Context<InitTree>) -> Result<()> {
+        let tree = &mut ctx.accounts.merkle_tree;
+        tree.authority = ctx.accounts.authority.key();
+        tree.root = [0u8; 32];
+        tree.leaf_count = 0;
+        Ok(())
+    }
+
+    pub fn append(ctx: Context<Append>, leaf: [u8; 32]) -> Result<()> {
+        let tree = &mut ctx.accounts.merkle_tree;
+        tree.root = anchor_lang::solana_program::hash::hashv(&[&tree.root, &leaf]).to_bytes();
+        tree.leaf_count += 1;
+        Ok(())
+    }
+
+    // Mock notification program: just records what it was told
+    pub fn notify(_ctx: Context<Notify>, kind: u8, bounty_id: u64, contributor: Pubkey) -> Result<()> {
+        emit!(Notified {
+            kind,
+            bounty_id,
+            contributor,
+        });
+        Ok(())
+    }
+}
+
+#[event]
+pub struct Notified {
+    pub kind: u8,
+    pub bounty_id: u64,
+    pub contributor: Pubkey,
+}
+
+#[derive(Accounts)]
+pub struct Notify {}
+
+#[derive(Accounts)]
+pub struct YieldDeposit<'info> {
+    pub depositor: Signer<'info>,
+    #[account(mut)]
+    pub source: Account<'info, TokenAccount>,
+    pub token_program: Program<'info, Token>,
+    #[account(
+        mut,
+        constraint = vault.owner == Pubkey::find_program_address(&[b"vault", depositor.key().as_ref()], &crate::ID).0
+    )]
+    pub vault: Account<'info, TokenAccount>,
+}
+
+#[derive(Accounts)]
+pub struct YieldWithdraw<'info> {
+    pub depositor: Signer<'info>,
+    #[account(mut)]
+    pub destination: Account<'info, TokenAccount>,
+    pub token_program: Program<'info, Token>,
+    /// CHECK: PDA signer for the vault
+    #[account(seeds = [b"vault", depositor.key().as_ref()], bump)]
+    pub vault_authority: UncheckedAccount<'info>,
+    #[account(mut, token::authority = vault_authority)]
+    pub vault: Account<'info, TokenAccount>,
+}
+
+#[derive(Accounts)]
+pub struct SimulateLoss<'info> {
+    /// CHECK: Only used to derive the vault authority
+    pub depositor: UncheckedAccount<'info>,
+    /// CHECK: PDA signer for the vault
+    #[account(seeds = [b"vault", depositor.key().as_ref()], bump)]
+    pub vault_authority: UncheckedAccount<'info>,
+    #[account(mut, token::authority = vault_authority)]
+    pub vault: Account<'info, TokenAccount>,
+    #[account(mut)]
+    pub sink: Account<'info, TokenAccount>,
+    pub token_program: Program<'info, Token>,
+}
+
+#[derive(Accounts)]
+pub struct Unwrap<'info> {
+    pub owner: Signer<'info>,
+    #[account(mut)]
+    pub source: Account<'info, TokenAccount>,
+    #[account(mut)]
+    pub destination: Account<'info, TokenAccount>,
+    pub token_program: Program<'info, Token>,
+    #[account(mut, constraint = lp_vault.mint == source.mint)]
+    pub lp_vault: Account<'info, TokenAccount>,
+    /// CHECK: PDA signer for the reserve
+    #[account(seeds = [b"reserve"], bump)]
+    pub reserve_authority: UncheckedAccount<'info>,
+    #[account(mut, token::authority = reserve_authority, constraint = reserve.mint == destination.mint)]
+    pub reserve: Account<'info, TokenAccount>,
+}
+
+#[derive(Accounts)]
+pub struct SwapSol<'info> {
+    #[account(mut)]
+    pub payer: Signer<'info>,
+    #[account(mut)]
+    pub destination: Account<'info, TokenAccount>,
+    pub token_program: Program<'info, Token>,
+    pub system_program: Program<'info, System>,
+    /// CHECK: PDA signer for the reserve, also holds the pool's SOL
+    #[account(mut, seeds = [b"reserve"], bump)]
+    pub reserve_authority: UncheckedAccount<'info>,
+    #[account(mut, token::authority = reserve_authority, constraint = reserve.mint == destination.mint)]
+    pub reserve: Account<'info, TokenAccount>,
+}
+
+#[account]
+pub struct MockTree {
+    pub authority: Pubkey,
+    pub root: [u8; 32],
+    pub leaf_count: u64,
+}
+
+#[derive(Accounts)]
+pub struct InitTree<'info> {
+    #[account(mut)]
+    pub payer: Signer<'info>,
+    /// CHECK: Only recorded as the tree authority
+    pub authority: UncheckedAccount<'info>,
+    #[account(init, payer = payer, space = 8 + 32 + 32 + 8)]
+    pub merkle_tree: Account<'info, MockTree>,
+    pub system_program: Program<'info, System>,
+}
+
+#[derive(Accounts)]
+pub struct Append<'info> {
+    #[account(mut, has_one = authority)]
+    pub merkle_tree: Account<'info, MockTree>,
+    pub authority: Signer<'info>,
+    /// CHECK: Unused, mirrors the real program's accounts
+    pub noop: UncheckedAccount<'info>,
+}
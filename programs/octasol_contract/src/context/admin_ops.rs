@@ -1,6 +1,6 @@
 use anchor_lang::prelude::*;
-use anchor_spl::{associated_token::AssociatedToken, token::{ Token, TokenAccount}};
-use crate::state::{Bounty, ConfigState};
+use anchor_spl::{associated_token::AssociatedToken, token_interface::{Mint, TokenAccount, TokenInterface}};
+use crate::state::{Bounty, ConfigState, CountByState, FeeEpochStats, GlobalStats};
 
 #[derive(Accounts)]
 pub struct AdminAssignAndRelease<'info> {
@@ -9,17 +9,28 @@ pub struct AdminAssignAndRelease<'info> {
 
     #[account(
         seeds = [b"config"],
-        bump,
+        bump = config.bump,
+        owner = crate::ID,
         constraint = config.admin == admin.key() @ crate::util::errors::ContractError::Unauthorized
     )]
     pub config: Account<'info, ConfigState>,
 
     #[account(
         mut,
-        close = maintainer
+        has_one = mint @ crate::util::errors::ContractError::InvalidMint,
+        has_one = rent_payer @ crate::util::errors::ContractError::RentPayerMismatch,
+        close = rent_payer,
+        constraint = bounty.token_program == token_program.key() @ crate::util::errors::ContractError::TokenProgramMismatch
     )]
     pub bounty: Account<'info, Bounty>,
 
+    #[account(mut, seeds = [b"count_by_state"], bump = count_by_state.bump)]
+    pub count_by_state: Account<'info, CountByState>,
+
+    // Writable so config.burn_bps can be burned from escrow
+    #[account(mut)]
+    pub mint: InterfaceAccount<'info, Mint>,
+
     #[account(
         seeds=[b"escrow_auth",bounty.key().as_ref()],
         bump = bounty.bump
@@ -27,28 +38,101 @@ pub struct AdminAssignAndRelease<'info> {
     /// CHECK:PDA SIGNER
     pub escrow_authority: UncheckedAccount<'info>,
 
-    /// CHECK: Maintainer account for rent collection
-    #[account(mut)]
+    /// CHECK: Maintainer account for escrow rent collection, pinned to the bounty's maintainer
+    #[account(
+        mut,
+        constraint = maintainer.key() == bounty.maintainer @ crate::util::errors::ContractError::MaintainerMismatch
+    )]
     pub maintainer: AccountInfo<'info>,
 
+    /// CHECK: Receives the bounty account's rent, pinned by `has_one` on the bounty
+    #[account(mut)]
+    pub rent_payer: UncheckedAccount<'info>,
+
     /// CHECK: Contributor to be assigned and paid
     #[account(mut)]
     pub contributor: UncheckedAccount<'info>,
 
     #[account(
         mut,
-        constraint = contributor_token_account.mint == bounty.mint @ crate::util::errors::ContractError::InvalidMint,
-        constraint = contributor_token_account.owner == contributor.key() @ crate::util::errors::ContractError::InvalidTokenAccount
+        constraint = crate::util::validate::mint_matches(&contributor_token_account, &bounty.mint) @ crate::util::errors::ContractError::InvalidMint,
+        constraint = crate::util::validate::owner_matches(&contributor_token_account, &contributor.key()) @ crate::util::errors::ContractError::InvalidTokenAccount
+    )]
+    pub contributor_token_account:InterfaceAccount<'info, TokenAccount>,
+
+    #[account(
+        mut,
+        constraint = crate::util::validate::mint_matches(&escrow_token_account, &bounty.mint) @ crate::util::errors::ContractError::InvalidMint
+    )]
+    pub escrow_token_account: InterfaceAccount<'info, TokenAccount>,
+
+    #[account(
+        mut,
+        constraint = crate::util::validate::mint_matches(treasury_token_account, &bounty.mint) @ crate::util::errors::ContractError::InvalidMint,
+        constraint = crate::util::validate::owner_matches(treasury_token_account, &config.treasury) @ crate::util::errors::ContractError::InvalidTokenAccount
     )]
-    pub contributor_token_account:Account<'info,TokenAccount>,
+    pub treasury_token_account: Option<InterfaceAccount<'info, TokenAccount>>,
 
     #[account(
         mut,
-        constraint = escrow_token_account.mint == bounty.mint @ crate::util::errors::ContractError::InvalidMint
+        constraint = crate::util::validate::mint_matches(staker_token_account, &bounty.mint) @ crate::util::errors::ContractError::InvalidMint,
+        constraint = crate::util::validate::owner_matches(staker_token_account, &config.staker_pool) @ crate::util::errors::ContractError::InvalidTokenAccount
+    )]
+    pub staker_token_account: Option<InterfaceAccount<'info, TokenAccount>>,
+
+    // Receives the contributor's pledged donation, required when bounty.donate_bps > 0
+    #[account(
+        mut,
+        constraint = crate::util::validate::mint_matches(charity_token_account, &bounty.mint) @ crate::util::errors::ContractError::InvalidMint,
+        constraint = Some(charity_token_account.owner) == config.charity @ crate::util::errors::ContractError::InvalidTokenAccount
+    )]
+    pub charity_token_account: Option<InterfaceAccount<'info, TokenAccount>>,
+
+    #[account(
+        init_if_needed,
+        payer = admin,
+        space = FeeEpochStats::LEN,
+        seeds = [b"fee_epoch_stats", mint.key().as_ref()],
+        bump
+    )]
+    pub fee_epoch_stats: Account<'info, FeeEpochStats>,
+
+    #[account(
+        init_if_needed,
+        payer = admin,
+        space = GlobalStats::LEN,
+        seeds = [b"global_stats"],
+        bump
     )]
-    pub escrow_token_account: Account<'info, TokenAccount>,
+    pub global_stats: Account<'info, GlobalStats>,
+
+    /// CHECK: [b"mint_fee", mint] PDA, read as a MintFeeConfig only if initialized
+    #[account(seeds = [b"mint_fee", mint.key().as_ref()], bump)]
+    pub mint_fee_config: UncheckedAccount<'info>,
 
-    pub token_program: Program<'info, Token>,
+    pub token_program: Interface<'info, TokenInterface>,
     pub system_program: Program<'info,System>,
     pub associated_token_program: Program<'info,AssociatedToken>
 }
+
+#[derive(Accounts)]
+pub struct AdminUnassign<'info> {
+    pub admin: Signer<'info>,
+
+    #[account(
+        seeds = [b"config"],
+        bump = config.bump,
+        owner = crate::ID,
+        constraint = config.admin == admin.key() @ crate::util::errors::ContractError::Unauthorized
+    )]
+    pub config: Account<'info, ConfigState>,
+
+    #[account(
+        mut,
+        constraint = bounty.state == crate::state::BountyState::InProgress @ crate::util::errors::ContractError::InvalidBountyStateForOperation
+    )]
+    pub bounty: Account<'info, Bounty>,
+
+    #[account(mut, seeds = [b"count_by_state"], bump = count_by_state.bump)]
+    pub count_by_state: Account<'info, CountByState>,
+}
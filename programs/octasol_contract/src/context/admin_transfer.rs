@@ -0,0 +1,32 @@
+use anchor_lang::prelude::*;
+use crate::state::ConfigState;
+
+#[derive(Accounts)]
+pub struct ProposeAdmin<'info> {
+    #[account(mut)]
+    pub admin: Signer<'info>, // The current admin must sign
+
+    #[account(
+        mut,
+        seeds = [b"config"],
+        bump = config.bump,
+        owner = crate::ID,
+        has_one = admin, 
+    )]
+    pub config: Account<'info, ConfigState>,
+}
+
+#[derive(Accounts)]
+pub struct AcceptAdmin<'info> {
+    // The proposed admin must sign, proving the key is one someone actually holds
+    pub new_admin: Signer<'info>,
+
+    #[account(
+        mut,
+        seeds = [b"config"],
+        bump = config.bump,
+        owner = crate::ID,
+        constraint = config.pending_admin == Some(new_admin.key()) @ crate::util::errors::ContractError::NotPendingAdmin
+    )]
+    pub config: Account<'info, ConfigState>,
+}
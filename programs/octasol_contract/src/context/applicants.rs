@@ -0,0 +1,47 @@
+use anchor_lang::prelude::*;
+use crate::state::{ApplicantList, Bounty, CountByState};
+
+#[derive(Accounts)]
+pub struct ApplyToBounty<'info> {
+    #[account(mut)]
+    pub contributor: Signer<'info>,
+
+    #[account(
+        constraint = bounty.contributor.is_none() @ crate::util::errors::ContractError::ContributorAlreadyAssigned,
+        constraint = bounty.state == crate::state::BountyState::Created @ crate::util::errors::ContractError::InvalidBountyStateForOperation
+    )]
+    pub bounty: Account<'info, Bounty>,
+
+    #[account(
+        init_if_needed,
+        payer = contributor,
+        space = ApplicantList::LEN,
+        seeds = [b"applicants", bounty.key().as_ref()],
+        bump
+    )]
+    pub applicant_list: Account<'info, ApplicantList>,
+
+    pub system_program: Program<'info, System>,
+}
+
+#[derive(Accounts)]
+pub struct AssignFromApplicants<'info> {
+    pub maintainer: Signer<'info>,
+
+    #[account(
+        mut,
+        has_one = maintainer,
+        constraint = bounty.contributor.is_none() @ crate::util::errors::ContractError::ContributorAlreadyAssigned,
+        constraint = bounty.state == crate::state::BountyState::Created @ crate::util::errors::ContractError::InvalidBountyStateForOperation
+    )]
+    pub bounty: Account<'info, Bounty>,
+
+    #[account(
+        seeds = [b"applicants", bounty.key().as_ref()],
+        bump = applicant_list.bump
+    )]
+    pub applicant_list: Account<'info, ApplicantList>,
+
+    #[account(mut, seeds = [b"count_by_state"], bump = count_by_state.bump)]
+    pub count_by_state: Account<'info, CountByState>,
+}
@@ -0,0 +1,13 @@
+use anchor_lang::prelude::*;
+use crate::state::{Bounty, BountyState};
+
+#[derive(Accounts)]
+pub struct ApproveWork<'info> {
+    pub reviewer: Signer<'info>,
+
+    #[account(
+        mut,
+        constraint = bounty.state == BountyState::InProgress @ crate::util::errors::ContractError::InvalidBountyStateForOperation
+    )]
+    pub bounty: Account<'info, Bounty>,
+}
@@ -0,0 +1,46 @@
+use anchor_lang::prelude::*;
+use crate::state::{Bounty, ConfigState};
+
+#[derive(Accounts)]
+pub struct ArchiveCompressed<'info> {
+    pub admin: Signer<'info>,
+
+    #[account(
+        seeds = [b"config"],
+        bump = config.bump,
+        owner = crate::ID,
+        has_one = admin @ crate::util::errors::ContractError::Unauthorized
+    )]
+    pub config: Account<'info, ConfigState>,
+
+    #[account(
+        mut,
+        constraint = bounty.state == crate::state::BountyState::Completed @ crate::util::errors::ContractError::InvalidBountyStateForOperation,
+        has_one = rent_payer @ crate::util::errors::ContractError::RentPayerMismatch,
+        close = rent_payer
+    )]
+    pub bounty: Account<'info, Bounty>,
+
+    /// CHECK: Receives the bounty account's rent, pinned by `has_one` on the bounty
+    #[account(mut)]
+    pub rent_payer: UncheckedAccount<'info>,
+
+    /// CHECK: PDA that owns the archive tree and signs appends
+    #[account(seeds = [b"archive_authority"], bump)]
+    pub archive_authority: UncheckedAccount<'info>,
+
+    /// CHECK: Concurrent merkle tree, must be the configured one and owned by the compression program
+    #[account(
+        mut,
+        constraint = Some(merkle_tree.key()) == config.archive_tree @ crate::util::errors::ContractError::InvalidArchiveTree,
+        constraint = merkle_tree.owner == compression_program.key @ crate::util::errors::ContractError::InvalidArchiveTree
+    )]
+    pub merkle_tree: UncheckedAccount<'info>,
+
+    /// CHECK: Program owning the tree, checked against it above
+    #[account(executable)]
+    pub compression_program: UncheckedAccount<'info>,
+
+    /// CHECK: Log wrapper the compression program emits changelogs through
+    pub noop_program: UncheckedAccount<'info>,
+}
@@ -1,5 +1,6 @@
 use anchor_lang::prelude::*;
-use crate::state::Bounty;
+use anchor_spl::token_interface::TokenAccount;
+use crate::state::{Bounty, ConfigState, CountByState};
 
 #[derive(Accounts)]
 pub struct AssignContributor<'info> {
@@ -20,5 +21,18 @@ pub struct AssignContributor<'info> {
     /// CHECK: We are only using this account to get its public key.
     pub contributor: UncheckedAccount<'info>,
 
+    // The contributor's payout account, if known, so a wrong mint is caught now rather than at completion
+    #[account(
+        constraint = crate::util::validate::mint_matches(contributor_token_account, &bounty.mint) @ crate::util::errors::ContractError::InvalidMint,
+        constraint = crate::util::validate::owner_matches(contributor_token_account, &contributor.key()) @ crate::util::errors::ContractError::InvalidTokenAccount
+    )]
+    pub contributor_token_account: Option<InterfaceAccount<'info, TokenAccount>>,
+
+    #[account(seeds = [b"config"], bump = config.bump, owner = crate::ID)]
+    pub config: Account<'info, ConfigState>,
+
+    #[account(mut, seeds = [b"count_by_state"], bump = count_by_state.bump)]
+    pub count_by_state: Account<'info, CountByState>,
+
     pub system_program: Program<'info, System>,
 }
\ No newline at end of file
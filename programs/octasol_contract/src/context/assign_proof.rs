@@ -0,0 +1,30 @@
+use anchor_lang::prelude::*;
+use crate::state::{Bounty, CountByState};
+
+#[derive(Accounts)]
+pub struct SetContributorMerkleRoot<'info> {
+    pub maintainer: Signer<'info>,
+
+    #[account(
+        mut,
+        has_one = maintainer,
+        constraint = bounty.state == crate::state::BountyState::Created @ crate::util::errors::ContractError::InvalidBountyStateForOperation
+    )]
+    pub bounty: Account<'info, Bounty>,
+}
+
+#[derive(Accounts)]
+pub struct AssignWithProof<'info> {
+    // Contributors on the invite list claim the bounty themselves
+    pub contributor: Signer<'info>,
+
+    #[account(
+        mut,
+        constraint = bounty.contributor.is_none() @ crate::util::errors::ContractError::ContributorAlreadyAssigned,
+        constraint = bounty.state == crate::state::BountyState::Created @ crate::util::errors::ContractError::InvalidBountyStateForOperation
+    )]
+    pub bounty: Account<'info, Bounty>,
+
+    #[account(mut, seeds = [b"count_by_state"], bump = count_by_state.bump)]
+    pub count_by_state: Account<'info, CountByState>,
+}
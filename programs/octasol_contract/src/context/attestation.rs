@@ -0,0 +1,119 @@
+use anchor_lang::prelude::*;
+use anchor_lang::solana_program::sysvar::instructions::ID as INSTRUCTIONS_SYSVAR_ID;
+use anchor_spl::token_interface::{Mint, TokenAccount, TokenInterface};
+use crate::state::{Bounty, ConfigState, CountByState, FeeEpochStats, GlobalStats};
+
+#[derive(Accounts)]
+pub struct CompleteViaAttestation<'info> {
+    // Anyone may relay the oracle's attestation; it pays for the fee stats account if needed
+    #[account(mut)]
+    pub payer: Signer<'info>,
+
+    #[account(seeds = [b"config"], bump = config.bump, owner = crate::ID)]
+    pub config: Account<'info, ConfigState>,
+
+    #[account(
+        mut,
+        constraint = bounty.state == crate::state::BountyState::InProgress @ crate::util::errors::ContractError::InvalidBountyStateForOperation,
+        has_one = mint @ crate::util::errors::ContractError::InvalidMint,
+        has_one = rent_payer @ crate::util::errors::ContractError::RentPayerMismatch,
+        close = rent_payer,
+        constraint = bounty.token_program == token_program.key() @ crate::util::errors::ContractError::TokenProgramMismatch
+    )]
+    pub bounty: Account<'info, Bounty>,
+
+    #[account(mut, seeds = [b"count_by_state"], bump = count_by_state.bump)]
+    pub count_by_state: Account<'info, CountByState>,
+
+    // Writable so config.burn_bps can be burned from escrow
+    #[account(mut)]
+    pub mint: InterfaceAccount<'info, Mint>,
+
+    #[account(
+        seeds=[b"escrow_auth",bounty.key().as_ref()],
+        bump = bounty.bump
+    )]
+    /// CHECK:PDA SIGNER
+    pub escrow_authority: UncheckedAccount<'info>,
+
+    /// CHECK: Maintainer account for escrow rent collection
+    #[account(
+        mut,
+        constraint = maintainer.key() == bounty.maintainer @ crate::util::errors::ContractError::MaintainerMismatch
+    )]
+    pub maintainer: AccountInfo<'info>,
+
+    /// CHECK: Receives the bounty account's rent, pinned by `has_one` on the bounty
+    #[account(mut)]
+    pub rent_payer: UncheckedAccount<'info>,
+
+    /// CHECK: Contributor is validated by bounty.contributor field
+    #[account(
+        constraint = Some(contributor.key()) == bounty.contributor @ crate::util::errors::ContractError::InvalidContributor
+    )]
+    pub contributor: UncheckedAccount<'info>,
+
+    #[account(
+        mut,
+        constraint = crate::util::validate::mint_matches(&contributor_token_account, &bounty.mint) @ crate::util::errors::ContractError::InvalidMint,
+        constraint = crate::util::validate::owner_matches(&contributor_token_account, &contributor.key()) @ crate::util::errors::ContractError::InvalidTokenAccount
+    )]
+    pub contributor_token_account: InterfaceAccount<'info, TokenAccount>,
+
+    #[account(
+        mut,
+        constraint = crate::util::validate::mint_matches(&escrow_token_account, &bounty.mint) @ crate::util::errors::ContractError::InvalidMint
+    )]
+    pub escrow_token_account: InterfaceAccount<'info, TokenAccount>,
+
+    #[account(
+        mut,
+        constraint = crate::util::validate::mint_matches(treasury_token_account, &bounty.mint) @ crate::util::errors::ContractError::InvalidMint,
+        constraint = crate::util::validate::owner_matches(treasury_token_account, &config.treasury) @ crate::util::errors::ContractError::InvalidTokenAccount
+    )]
+    pub treasury_token_account: Option<InterfaceAccount<'info, TokenAccount>>,
+
+    #[account(
+        mut,
+        constraint = crate::util::validate::mint_matches(staker_token_account, &bounty.mint) @ crate::util::errors::ContractError::InvalidMint,
+        constraint = crate::util::validate::owner_matches(staker_token_account, &config.staker_pool) @ crate::util::errors::ContractError::InvalidTokenAccount
+    )]
+    pub staker_token_account: Option<InterfaceAccount<'info, TokenAccount>>,
+
+    // Receives the contributor's pledged donation, required when bounty.donate_bps > 0
+    #[account(
+        mut,
+        constraint = crate::util::validate::mint_matches(charity_token_account, &bounty.mint) @ crate::util::errors::ContractError::InvalidMint,
+        constraint = Some(charity_token_account.owner) == config.charity @ crate::util::errors::ContractError::InvalidTokenAccount
+    )]
+    pub charity_token_account: Option<InterfaceAccount<'info, TokenAccount>>,
+
+    #[account(
+        init_if_needed,
+        payer = payer,
+        space = FeeEpochStats::LEN,
+        seeds = [b"fee_epoch_stats", mint.key().as_ref()],
+        bump
+    )]
+    pub fee_epoch_stats: Account<'info, FeeEpochStats>,
+
+    #[account(
+        init_if_needed,
+        payer = payer,
+        space = GlobalStats::LEN,
+        seeds = [b"global_stats"],
+        bump
+    )]
+    pub global_stats: Account<'info, GlobalStats>,
+
+    /// CHECK: [b"mint_fee", mint] PDA, read as a MintFeeConfig only if initialized
+    #[account(seeds = [b"mint_fee", mint.key().as_ref()], bump)]
+    pub mint_fee_config: UncheckedAccount<'info>,
+
+    /// CHECK: Instructions sysvar, read to find the Ed25519 verification
+    #[account(address = INSTRUCTIONS_SYSVAR_ID)]
+    pub instructions: UncheckedAccount<'info>,
+
+    pub token_program: Interface<'info, TokenInterface>,
+    pub system_program: Program<'info, System>,
+}
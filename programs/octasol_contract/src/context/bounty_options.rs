@@ -0,0 +1,112 @@
+use anchor_lang::prelude::*;
+use crate::state::Bounty;
+
+#[derive(Accounts)]
+pub struct SetUnwrapOnComplete<'info> {
+    pub maintainer: Signer<'info>,
+
+    #[account(mut, has_one = maintainer)]
+    pub bounty: Account<'info, Bounty>,
+}
+
+#[derive(Accounts)]
+pub struct SetDonateBps<'info> {
+    pub contributor: Signer<'info>,
+
+    #[account(
+        mut,
+        constraint = bounty.contributor == Some(contributor.key()) @ crate::util::errors::ContractError::InvalidContributor,
+        constraint = bounty.state == crate::state::BountyState::InProgress @ crate::util::errors::ContractError::InvalidBountyStateForOperation
+    )]
+    pub bounty: Account<'info, Bounty>,
+}
+
+#[derive(Accounts)]
+pub struct SetPayoutMint<'info> {
+    pub contributor: Signer<'info>,
+
+    #[account(
+        mut,
+        constraint = bounty.contributor == Some(contributor.key()) @ crate::util::errors::ContractError::InvalidContributor,
+        constraint = bounty.state == crate::state::BountyState::InProgress @ crate::util::errors::ContractError::InvalidBountyStateForOperation
+    )]
+    pub bounty: Account<'info, Bounty>,
+}
+
+// Fixed once a contributor is assigned, so the maintainer can't stall completion by moving the bar
+#[derive(Accounts)]
+pub struct SetRequiredApprovals<'info> {
+    pub maintainer: Signer<'info>,
+
+    #[account(
+        mut,
+        has_one = maintainer,
+        constraint = bounty.state == crate::state::BountyState::Created @ crate::util::errors::ContractError::InvalidBountyStateForOperation
+    )]
+    pub bounty: Account<'info, Bounty>,
+}
+
+// Same window as `SetRequiredApprovals`, the contributor knows who signs off before starting
+#[derive(Accounts)]
+pub struct SetReviewers<'info> {
+    pub maintainer: Signer<'info>,
+
+    #[account(
+        mut,
+        has_one = maintainer,
+        constraint = bounty.state == crate::state::BountyState::Created @ crate::util::errors::ContractError::InvalidBountyStateForOperation
+    )]
+    pub bounty: Account<'info, Bounty>,
+}
+
+#[derive(Accounts)]
+pub struct SetAllowedKeepers<'info> {
+    pub maintainer: Signer<'info>,
+
+    #[account(mut, has_one = maintainer)]
+    pub bounty: Account<'info, Bounty>,
+}
+
+// Fixed once a contributor is assigned, so their delivery window can't be cut short
+#[derive(Accounts)]
+pub struct SetGracePeriod<'info> {
+    pub maintainer: Signer<'info>,
+
+    #[account(
+        mut,
+        has_one = maintainer,
+        constraint = bounty.state == crate::state::BountyState::Created
+            || bounty.state == crate::state::BountyState::Draft @ crate::util::errors::ContractError::InvalidBountyStateForOperation
+    )]
+    pub bounty: Account<'info, Bounty>,
+}
+
+// Settled before assignment, so a contributor knows up front whether admin can step in
+#[derive(Accounts)]
+pub struct SetAdminOverrideAllowed<'info> {
+    pub maintainer: Signer<'info>,
+
+    #[account(
+        mut,
+        has_one = maintainer,
+        constraint = bounty.state == crate::state::BountyState::Created
+            || bounty.state == crate::state::BountyState::Draft @ crate::util::errors::ContractError::InvalidBountyStateForOperation
+    )]
+    pub bounty: Account<'info, Bounty>,
+}
+
+#[derive(Accounts)]
+pub struct SetAllowCustomFeeRecipient<'info> {
+    pub maintainer: Signer<'info>,
+
+    #[account(mut, has_one = maintainer)]
+    pub bounty: Account<'info, Bounty>,
+}
+
+#[derive(Accounts)]
+pub struct SetRequireContributorAck<'info> {
+    pub maintainer: Signer<'info>,
+
+    #[account(mut, has_one = maintainer)]
+    pub bounty: Account<'info, Bounty>,
+}
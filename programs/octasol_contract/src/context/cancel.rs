@@ -1,7 +1,7 @@
 use anchor_lang::prelude::*;
-use anchor_spl::{associated_token::AssociatedToken, token::{Token, TokenAccount}};
+use anchor_spl::{associated_token::AssociatedToken, token_interface::{Mint, TokenAccount, TokenInterface}};
 
-use crate::state::{Bounty, ConfigState};
+use crate::state::{Bounty, ConfigState, CountByState};
 
 #[derive(Accounts)]
 pub struct CancelBounty<'info> {
@@ -10,18 +10,27 @@ pub struct CancelBounty<'info> {
     
     #[account(
         seeds = [b"config"],
-        bump,
+        bump = config.bump,
+        owner = crate::ID,
         constraint = config.admin == admin.key() @ crate::util::errors::ContractError::Unauthorized
     )]
     pub config: Account<'info, ConfigState>,
     
     #[account(
         mut,
-        close = maintainer, 
+        has_one = rent_payer @ crate::util::errors::ContractError::RentPayerMismatch,
+        close = rent_payer, 
+        has_one = mint @ crate::util::errors::ContractError::InvalidMint,
         constraint = bounty.state != crate::state::BountyState::Completed @ crate::util::errors::ContractError::BountyAlreadyCompleted,
-        constraint = bounty.state != crate::state::BountyState::Cancelled @ crate::util::errors::ContractError::BountyAlreadyCancelled
+        constraint = bounty.state != crate::state::BountyState::Cancelled @ crate::util::errors::ContractError::BountyAlreadyCancelled,
+        constraint = bounty.token_program == token_program.key() @ crate::util::errors::ContractError::TokenProgramMismatch
     )]
     pub bounty: Account<'info, Bounty>,
+
+    #[account(mut, seeds = [b"count_by_state"], bump = count_by_state.bump)]
+    pub count_by_state: Account<'info, CountByState>,
+
+    pub mint: InterfaceAccount<'info, Mint>,
     
     #[account(
         mut,
@@ -31,26 +40,93 @@ pub struct CancelBounty<'info> {
     /// CHECK: Account for transferring funds from escrow to maintainer
     pub escrow_authority: UncheckedAccount<'info>,
     
-    #[account(mut)]
-    /// CHECK: The maintainer who will receive tokens and rent (doesn't need to sign)
+    // May be the same key as `admin` when the admin cancels their own bounty; the account is
+    // then passed twice (signer + rent destination) and lamports are only ever credited to it
+    #[account(
+        mut,
+        // Otherwise the admin could redirect the escrow rent to an address of their choosing
+        constraint = maintainer.key() == bounty.maintainer @ crate::util::errors::ContractError::MaintainerMismatch
+    )]
+    /// CHECK: The maintainer who will receive the tokens and escrow rent (doesn't need to sign)
     pub maintainer: UncheckedAccount<'info>,
+
+    /// CHECK: Receives the bounty account's rent, pinned by `has_one` on the bounty
+    #[account(mut)]
+    pub rent_payer: UncheckedAccount<'info>,
     
+    // Recreated at the admin's expense if the maintainer closed it, so a refund can always land
     #[account(
-        mut,
-        constraint = maintainer_token_account.mint == bounty.mint @ crate::util::errors::ContractError::InvalidMint,
-        constraint = maintainer_token_account.owner == maintainer.key() @ crate::util::errors::ContractError::InvalidTokenAccount
+        init_if_needed,
+        payer = admin,
+        associated_token::mint = mint,
+        associated_token::authority = maintainer,
+        associated_token::token_program = token_program,
+        constraint = crate::util::validate::mint_matches(&maintainer_token_account, &bounty.mint) @ crate::util::errors::ContractError::InvalidMint,
+        // Refunds always go to the maintainer recorded on the bounty, never to a caller-chosen account
+        constraint = crate::util::validate::owner_matches(&maintainer_token_account, &bounty.maintainer) @ crate::util::errors::ContractError::InvalidTokenAccount
     )]
-    pub maintainer_token_account: Account<'info, TokenAccount>,
+    pub maintainer_token_account: InterfaceAccount<'info, TokenAccount>,
     
     #[account(
         mut,
-        close = maintainer, // Rent goes to maintainer
-        constraint = escrow_token_account.mint == bounty.mint @ crate::util::errors::ContractError::InvalidMint
+        // Closed through the token program in the handler, rent goes to maintainer
+        constraint = crate::util::validate::mint_matches(&escrow_token_account, &bounty.mint) @ crate::util::errors::ContractError::InvalidMint
     )]
-    pub escrow_token_account: Account<'info, TokenAccount>,
+    pub escrow_token_account: InterfaceAccount<'info, TokenAccount>,
     
     pub system_program: Program<'info, System>,
-    pub token_program: Program<'info, Token>,
+    pub token_program: Interface<'info, TokenInterface>,
     pub associated_token_program: Program<'info, AssociatedToken>,
     pub rent: Sysvar<'info, Rent>
 }
+
+#[derive(Accounts)]
+pub struct MaintainerCancel<'info> {
+    #[account(mut)]
+    pub maintainer: Signer<'info>,
+
+    #[account(
+        mut,
+        has_one = maintainer @ crate::util::errors::ContractError::Unauthorized,
+        has_one = rent_payer @ crate::util::errors::ContractError::RentPayerMismatch,
+        has_one = mint @ crate::util::errors::ContractError::InvalidMint,
+        close = rent_payer,
+        // Once a contributor is on the bounty only an admin can unwind it
+        constraint = bounty.state == crate::state::BountyState::Created @ crate::util::errors::ContractError::InvalidBountyStateForOperation,
+        constraint = bounty.contributor.is_none() @ crate::util::errors::ContractError::ContributorAlreadyAssigned,
+        constraint = bounty.token_program == token_program.key() @ crate::util::errors::ContractError::TokenProgramMismatch
+    )]
+    pub bounty: Account<'info, Bounty>,
+
+    #[account(mut, seeds = [b"count_by_state"], bump = count_by_state.bump)]
+    pub count_by_state: Account<'info, CountByState>,
+
+    pub mint: InterfaceAccount<'info, Mint>,
+
+    #[account(
+        seeds = [b"escrow_auth", bounty.key().as_ref()],
+        bump = bounty.bump
+    )]
+    /// CHECK: PDA SIGNER
+    pub escrow_authority: UncheckedAccount<'info>,
+
+    /// CHECK: Receives the bounty account's rent, pinned by `has_one` on the bounty
+    #[account(mut)]
+    pub rent_payer: UncheckedAccount<'info>,
+
+    #[account(
+        mut,
+        constraint = crate::util::validate::mint_matches(&maintainer_token_account, &mint.key()) @ crate::util::errors::ContractError::InvalidMint,
+        constraint = crate::util::validate::owner_matches(&maintainer_token_account, &maintainer.key()) @ crate::util::errors::ContractError::InvalidTokenAccount
+    )]
+    pub maintainer_token_account: InterfaceAccount<'info, TokenAccount>,
+
+    #[account(
+        mut,
+        constraint = crate::util::validate::mint_matches(&escrow_token_account, &mint.key()) @ crate::util::errors::ContractError::InvalidMint,
+        constraint = crate::util::validate::owner_matches(&escrow_token_account, &escrow_authority.key()) @ crate::util::errors::ContractError::InvalidTokenAccount
+    )]
+    pub escrow_token_account: InterfaceAccount<'info, TokenAccount>,
+
+    pub token_program: Interface<'info, TokenInterface>,
+}
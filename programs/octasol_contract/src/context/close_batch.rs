@@ -0,0 +1,77 @@
+use anchor_lang::prelude::*;
+use anchor_spl::token_interface::{Mint, TokenAccount, TokenInterface};
+use crate::state::{ConfigState, CountByState, FeeEpochStats, GlobalStats};
+
+// Bounties to close are passed as `remaining_accounts` quadruples:
+//   [bounty (mut), escrow authority, escrow token account (mut), bounty rent payer (mut)]
+// The escrow authority has to be in the transaction for it to sign closing an empty escrow.
+#[derive(Accounts)]
+pub struct CloseCompletedBatch<'info> {
+    pub token_program: Interface<'info, TokenInterface>,
+}
+
+// Bounties to settle are passed as `remaining_accounts` quadruples:
+//   [bounty (mut), escrow authority, escrow token account (mut), contributor token account (mut)]
+// The escrow authority has to be in the transaction for it to sign the payout CPI.
+#[derive(Accounts)]
+pub struct CompleteBatch<'info> {
+    #[account(mut)]
+    pub keeper: Signer<'info>,
+
+    #[account(seeds = [b"config"], bump = config.bump, owner = crate::ID)]
+    pub config: Account<'info, ConfigState>,
+
+    #[account(mut, seeds = [b"count_by_state"], bump = count_by_state.bump)]
+    pub count_by_state: Account<'info, CountByState>,
+
+    // Every bounty in the batch must escrow this mint; writable for config.burn_bps
+    #[account(mut)]
+    pub mint: InterfaceAccount<'info, Mint>,
+
+    #[account(
+        mut,
+        constraint = crate::util::validate::mint_matches(treasury_token_account, &mint.key()) @ crate::util::errors::ContractError::InvalidMint,
+        constraint = crate::util::validate::owner_matches(treasury_token_account, &config.treasury) @ crate::util::errors::ContractError::InvalidTokenAccount
+    )]
+    pub treasury_token_account: Option<InterfaceAccount<'info, TokenAccount>>,
+
+    #[account(
+        mut,
+        constraint = crate::util::validate::mint_matches(staker_token_account, &mint.key()) @ crate::util::errors::ContractError::InvalidMint,
+        constraint = crate::util::validate::owner_matches(staker_token_account, &config.staker_pool) @ crate::util::errors::ContractError::InvalidTokenAccount
+    )]
+    pub staker_token_account: Option<InterfaceAccount<'info, TokenAccount>>,
+
+    // Receives the contributor's pledged donation, required when bounty.donate_bps > 0
+    #[account(
+        mut,
+        constraint = crate::util::validate::mint_matches(charity_token_account, &mint.key()) @ crate::util::errors::ContractError::InvalidMint,
+        constraint = Some(charity_token_account.owner) == config.charity @ crate::util::errors::ContractError::InvalidTokenAccount
+    )]
+    pub charity_token_account: Option<InterfaceAccount<'info, TokenAccount>>,
+
+    #[account(
+        init_if_needed,
+        payer = keeper,
+        space = FeeEpochStats::LEN,
+        seeds = [b"fee_epoch_stats", mint.key().as_ref()],
+        bump
+    )]
+    pub fee_epoch_stats: Account<'info, FeeEpochStats>,
+
+    #[account(
+        init_if_needed,
+        payer = keeper,
+        space = GlobalStats::LEN,
+        seeds = [b"global_stats"],
+        bump
+    )]
+    pub global_stats: Account<'info, GlobalStats>,
+
+    /// CHECK: [b"mint_fee", mint] PDA, read as a MintFeeConfig only if initialized
+    #[account(seeds = [b"mint_fee", mint.key().as_ref()], bump)]
+    pub mint_fee_config: UncheckedAccount<'info>,
+
+    pub token_program: Interface<'info, TokenInterface>,
+    pub system_program: Program<'info, System>,
+}
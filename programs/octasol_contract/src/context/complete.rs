@@ -1,6 +1,6 @@
 use anchor_lang::prelude::*;
-use anchor_spl::{associated_token::AssociatedToken, token::{ Token, TokenAccount}};
-use crate::state::{Bounty, ConfigState};
+use anchor_spl::{associated_token::{get_associated_token_address_with_program_id, AssociatedToken}, token_interface::{Mint, TokenAccount, TokenInterface}};
+use crate::state::{Bounty, ConfigState, CountByState, FeeEpochStats, GlobalStats, UserStats};
 
 #[derive(Accounts)]
 pub struct CompleteBounty<'info> {
@@ -8,10 +8,21 @@ pub struct CompleteBounty<'info> {
         mut,
         constraint = bounty.contributor.is_some() @ crate::util::errors::ContractError::InvalidContributor,
         constraint = bounty.state == crate::state::BountyState::InProgress @ crate::util::errors::ContractError::InvalidBountyStateForOperation,
-        close = maintainer
+        has_one = mint @ crate::util::errors::ContractError::InvalidMint,
+        has_one = rent_payer @ crate::util::errors::ContractError::RentPayerMismatch,
+        close = rent_payer,
+        constraint = bounty.token_program == token_program.key() @ crate::util::errors::ContractError::TokenProgramMismatch,
+        constraint = bounty.keeper_allowed(&keeper.as_ref().map_or(admin.key(), |keeper| keeper.key())) @ crate::util::errors::ContractError::KeeperNotAllowed
     )]
     pub bounty: Account<'info, Bounty>,
 
+    #[account(mut, seeds = [b"count_by_state"], bump = count_by_state.bump)]
+    pub count_by_state: Account<'info, CountByState>,
+
+    // Writable so config.burn_bps can be burned from escrow
+    #[account(mut)]
+    pub mint: InterfaceAccount<'info, Mint>,
+
     #[account(
         seeds=[b"escrow_auth",bounty.key().as_ref()],
         bump = bounty.bump
@@ -19,38 +30,138 @@ pub struct CompleteBounty<'info> {
     /// CHECK:PDA SIGNER
     pub escrow_authority: UncheckedAccount<'info>,
 
-    /// CHECK: Maintainer account for rent collection
+    /// CHECK: Maintainer account for escrow rent collection
     #[account(mut)]
     pub maintainer: AccountInfo<'info>,
 
+    /// CHECK: Receives the bounty account's rent, pinned by `has_one` on the bounty
+    #[account(mut)]
+    pub rent_payer: UncheckedAccount<'info>,
+
     /// CHECK: Contributor is validated by bounty.contributor field 
     #[account(
         mut,
-        constraint = contributor.key() == bounty.contributor.unwrap() @ crate::util::errors::ContractError::InvalidContributor
+        constraint = bounty.contributor == Some(contributor.key()) @ crate::util::errors::ContractError::InvalidContributor
     )]
     pub contributor: UncheckedAccount<'info>,
 
     #[account(
         seeds = [b"config"],
-        bump,
+        bump = config.bump,
+        owner = crate::ID,
         constraint = config.admin == admin.key() @ crate::util::errors::ContractError::Unauthorized
     )]
     pub config: Account<'info, ConfigState>,
 
+    #[account(mut)]
     pub admin: Signer<'info>,
 
+    // Settles the bounty, so must be bounty.keeper (or in bounty.allowed_keepers once set).
+    // Left out, the admin settles as the keeper.
+    pub keeper: Option<Signer<'info>>,
+
+    #[account(
+        init_if_needed,
+        payer = admin,
+        space = UserStats::LEN,
+        seeds = [b"user_stats", contributor.key().as_ref()],
+        bump
+    )]
+    pub contributor_stats: Account<'info, UserStats>,
+
+    // SOL reserve for config.first_completion_bonus_lamports, only read on a first completion
+    #[account(mut, seeds = [b"bonus_reserve"], bump)]
+    pub bonus_reserve: Option<SystemAccount<'info>>,
+
+    #[account(
+        init_if_needed,
+        payer = admin,
+        space = FeeEpochStats::LEN,
+        seeds = [b"fee_epoch_stats", mint.key().as_ref()],
+        bump
+    )]
+    pub fee_epoch_stats: Account<'info, FeeEpochStats>,
+
+    #[account(
+        init_if_needed,
+        payer = admin,
+        space = GlobalStats::LEN,
+        seeds = [b"global_stats"],
+        bump
+    )]
+    pub global_stats: Account<'info, GlobalStats>,
+
+    /// CHECK: [b"mint_fee", mint] PDA, read as a MintFeeConfig only if initialized
+    #[account(seeds = [b"mint_fee", mint.key().as_ref()], bump)]
+    pub mint_fee_config: UncheckedAccount<'info>,
+
+    // Must be the contributor's ATA. The handler creates it if they have never held the mint, so
+    // it knows whether this completion paid for it (see config.ata_creation_fee)
+    /// CHECK: Pinned to the contributor's ATA for the bounty mint and token program
+    #[account(
+        mut,
+        address = get_associated_token_address_with_program_id(&contributor.key(), &mint.key(), &token_program.key()) @ crate::util::errors::ContractError::InvalidTokenAccount
+    )]
+    pub contributor_token_account: UncheckedAccount<'info>,
+    #[account(
+        mut,
+        constraint = crate::util::validate::mint_matches(&escrow_token_account, &bounty.mint) @ crate::util::errors::ContractError::InvalidMint
+    )]
+    pub escrow_token_account: InterfaceAccount<'info, TokenAccount>,
+
+    #[account(
+        mut,
+        constraint = crate::util::validate::mint_matches(treasury_token_account, &bounty.mint) @ crate::util::errors::ContractError::InvalidMint,
+        constraint = crate::util::validate::owner_matches(treasury_token_account, &config.treasury) @ crate::util::errors::ContractError::InvalidTokenAccount
+    )]
+    pub treasury_token_account: Option<InterfaceAccount<'info, TokenAccount>>,
+
+    #[account(
+        mut,
+        constraint = crate::util::validate::mint_matches(staker_token_account, &bounty.mint) @ crate::util::errors::ContractError::InvalidMint,
+        constraint = crate::util::validate::owner_matches(staker_token_account, &config.staker_pool) @ crate::util::errors::ContractError::InvalidTokenAccount
+    )]
+    pub staker_token_account: Option<InterfaceAccount<'info, TokenAccount>>,
+
+    // Takes the whole fee in place of the treasury and staker pool, if the bounty allows it and
+    // the account belongs to the recipient the maintainer approved
     #[account(
         mut,
-        constraint = contributor_token_account.mint == bounty.mint @ crate::util::errors::ContractError::InvalidMint,
-        constraint = contributor_token_account.owner == contributor.key() @ crate::util::errors::ContractError::InvalidTokenAccount
+        constraint = bounty.allow_custom_fee_recipient @ crate::util::errors::ContractError::CustomFeeRecipientNotAllowed,
+        constraint = crate::util::validate::mint_matches(fee_recipient, &bounty.mint) @ crate::util::errors::ContractError::InvalidMint,
+        constraint = bounty.custom_fee_recipient.is_some_and(|recipient| crate::util::validate::owner_matches(fee_recipient, &recipient)) @ crate::util::errors::ContractError::InvalidTokenAccount
     )]
-    pub contributor_token_account:Account<'info,TokenAccount>,
+    pub fee_recipient: Option<InterfaceAccount<'info, TokenAccount>>,
+
+    // Receives the contributor's pledged donation, required when bounty.donate_bps > 0
+    #[account(
+        mut,
+        constraint = crate::util::validate::mint_matches(charity_token_account, &bounty.mint) @ crate::util::errors::ContractError::InvalidMint,
+        constraint = Some(charity_token_account.owner) == config.charity @ crate::util::errors::ContractError::InvalidTokenAccount
+    )]
+    pub charity_token_account: Option<InterfaceAccount<'info, TokenAccount>>,
+
+    // Optional third party paying the protocol fee on the contributor's behalf
+    pub sponsor: Option<Signer<'info>>,
+
+    #[account(
+        mut,
+        constraint = crate::util::validate::mint_matches(sponsor_token_account, &bounty.mint) @ crate::util::errors::ContractError::InvalidMint
+    )]
+    pub sponsor_token_account: Option<InterfaceAccount<'info, TokenAccount>>,
+
+    // Both set to redeem an LP-token escrow through the AMM when the bounty asks for it,
+    // otherwise the contributor is paid in LP tokens. Also used to swap into bounty.payout_mint,
+    // where they are required.
+    /// CHECK: Must match config.amm_program, checked in the handler
+    pub amm_program: Option<UncheckedAccount<'info>>,
+
     #[account(
         mut,
-        constraint = escrow_token_account.mint == bounty.mint @ crate::util::errors::ContractError::InvalidMint
+        constraint = crate::util::validate::owner_matches(contributor_underlying_token_account, &contributor.key()) @ crate::util::errors::ContractError::InvalidTokenAccount
     )]
-    pub escrow_token_account: Account<'info, TokenAccount>,
-    pub token_program: Program<'info, Token>,
+    pub contributor_underlying_token_account: Option<InterfaceAccount<'info, TokenAccount>>,
+    pub token_program: Interface<'info, TokenInterface>,
     pub system_program: Program<'info,System>,
     pub associated_token_program: Program<'info,AssociatedToken>
 }
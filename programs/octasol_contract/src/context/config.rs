@@ -1,5 +1,6 @@
 use anchor_lang::prelude::*;
-use crate::state::config::ConfigState;
+use anchor_spl::token_interface::Mint;
+use crate::state::{config::ConfigState, MintFeeConfig, MintWhitelist};
 #[derive(Accounts)]
 pub struct InitializeConfig<'info> {
     #[account(mut)]
@@ -14,4 +15,158 @@ pub struct InitializeConfig<'info> {
     )]
     pub config: Account<'info, ConfigState>,
     pub system_program: Program<'info, System>,
-}
\ No newline at end of file
+}
+
+#[derive(Accounts)]
+pub struct UpdateConfig<'info> {
+    pub admin: Signer<'info>,
+
+    #[account(
+        mut,
+        seeds = [b"config"],
+        bump = config.bump,
+        owner = crate::ID,
+        has_one = admin @ crate::util::errors::ContractError::Unauthorized
+    )]
+    pub config: Account<'info, ConfigState>,
+}
+
+#[derive(Accounts)]
+pub struct MigrateConfig<'info> {
+    #[account(mut)]
+    pub admin: Signer<'info>,
+
+    /// CHECK: an old layout doesn't deserialize as `ConfigState`, so `realloc` can't be a
+    /// constraint here. Only `admin` is read, it has been the first field since the original layout
+    #[account(
+        mut,
+        seeds = [b"config"],
+        bump,
+        owner = crate::ID,
+        constraint = config.data_len() < ConfigState::LEN @ crate::util::errors::ContractError::ConfigAlreadyMigrated,
+        constraint = config.try_borrow_data()?.get(8..40) == Some(admin.key().as_ref()) @ crate::util::errors::ContractError::Unauthorized
+    )]
+    pub config: UncheckedAccount<'info>,
+
+    pub system_program: Program<'info, System>,
+}
+
+#[derive(Accounts)]
+pub struct SetMintFee<'info> {
+    #[account(mut)]
+    pub admin: Signer<'info>,
+
+    #[account(
+        seeds = [b"config"],
+        bump = config.bump,
+        owner = crate::ID,
+        has_one = admin @ crate::util::errors::ContractError::Unauthorized
+    )]
+    pub config: Account<'info, ConfigState>,
+
+    pub mint: InterfaceAccount<'info, Mint>,
+
+    #[account(
+        init_if_needed,
+        payer = admin,
+        space = MintFeeConfig::LEN,
+        seeds = [b"mint_fee", mint.key().as_ref()],
+        bump
+    )]
+    pub mint_fee_config: Account<'info, MintFeeConfig>,
+
+    pub system_program: Program<'info, System>,
+}
+
+#[derive(Accounts)]
+pub struct ClearMintFee<'info> {
+    #[account(mut)]
+    pub admin: Signer<'info>,
+
+    #[account(
+        seeds = [b"config"],
+        bump = config.bump,
+        owner = crate::ID,
+        has_one = admin @ crate::util::errors::ContractError::Unauthorized
+    )]
+    pub config: Account<'info, ConfigState>,
+
+    pub mint: InterfaceAccount<'info, Mint>,
+
+    #[account(
+        mut,
+        seeds = [b"mint_fee", mint.key().as_ref()],
+        bump = mint_fee_config.bump,
+        close = admin
+    )]
+    pub mint_fee_config: Account<'info, MintFeeConfig>,
+}
+
+#[derive(Accounts)]
+pub struct WhitelistMint<'info> {
+    #[account(mut)]
+    pub admin: Signer<'info>,
+
+    #[account(
+        seeds = [b"config"],
+        bump = config.bump,
+        owner = crate::ID,
+        has_one = admin @ crate::util::errors::ContractError::Unauthorized
+    )]
+    pub config: Account<'info, ConfigState>,
+
+    pub mint: InterfaceAccount<'info, Mint>,
+
+    #[account(
+        init_if_needed,
+        payer = admin,
+        space = MintWhitelist::LEN,
+        seeds = [b"mint_whitelist", mint.key().as_ref()],
+        bump
+    )]
+    pub mint_whitelist: Account<'info, MintWhitelist>,
+
+    pub system_program: Program<'info, System>,
+}
+
+#[derive(Accounts)]
+pub struct UnwhitelistMint<'info> {
+    #[account(mut)]
+    pub admin: Signer<'info>,
+
+    #[account(
+        seeds = [b"config"],
+        bump = config.bump,
+        owner = crate::ID,
+        has_one = admin @ crate::util::errors::ContractError::Unauthorized
+    )]
+    pub config: Account<'info, ConfigState>,
+
+    pub mint: InterfaceAccount<'info, Mint>,
+
+    #[account(
+        mut,
+        seeds = [b"mint_whitelist", mint.key().as_ref()],
+        bump = mint_whitelist.bump,
+        close = admin
+    )]
+    pub mint_whitelist: Account<'info, MintWhitelist>,
+}
+
+#[derive(Accounts)]
+pub struct IsMintAllowed<'info> {
+    #[account(seeds = [b"config"], bump = config.bump, owner = crate::ID)]
+    pub config: Account<'info, ConfigState>,
+
+    pub mint: InterfaceAccount<'info, Mint>,
+
+    /// CHECK: [b"mint_whitelist", mint] PDA, only its existence is checked
+    #[account(seeds = [b"mint_whitelist", mint.key().as_ref()], bump)]
+    pub mint_whitelist: UncheckedAccount<'info>,
+}
+
+#[derive(Accounts)]
+pub struct GetProgramInfo<'info> {
+    #[account(seeds = [b"config"], bump = config.bump, owner = crate::ID)]
+    pub config: Account<'info, ConfigState>,
+}
@@ -0,0 +1,8 @@
+use anchor_lang::prelude::*;
+use crate::state::CountByState;
+
+#[derive(Accounts)]
+pub struct GetCountByState<'info> {
+    #[account(seeds = [b"count_by_state"], bump = count_by_state.bump)]
+    pub count_by_state: Account<'info, CountByState>,
+}
@@ -0,0 +1,144 @@
+use anchor_lang::prelude::*;
+use anchor_spl::token_interface::{Mint, TokenAccount, TokenInterface};
+use crate::state::{Bounty, BountyState, ConfigState, CountByState, FeeEpochStats, GlobalStats};
+
+#[derive(Accounts)]
+pub struct SubmitWork<'info> {
+    pub contributor: Signer<'info>,
+
+    #[account(
+        mut,
+        constraint = bounty.state == BountyState::InProgress @ crate::util::errors::ContractError::InvalidBountyStateForOperation,
+        constraint = bounty.contributor == Some(contributor.key()) @ crate::util::errors::ContractError::InvalidContributor
+    )]
+    pub bounty: Account<'info, Bounty>,
+}
+
+#[derive(Accounts)]
+pub struct CommitDeliverable<'info> {
+    pub contributor: Signer<'info>,
+
+    #[account(
+        mut,
+        constraint = bounty.state == BountyState::InProgress @ crate::util::errors::ContractError::InvalidBountyStateForOperation,
+        constraint = bounty.contributor == Some(contributor.key()) @ crate::util::errors::ContractError::InvalidContributor
+    )]
+    pub bounty: Account<'info, Bounty>,
+}
+
+// Permissionless: anyone may settle a bounty whose auto-release window has run out
+#[derive(Accounts)]
+pub struct CrankAutoRelease<'info> {
+    #[account(mut)]
+    pub cranker: Signer<'info>,
+
+    #[account(seeds = [b"config"], bump = config.bump, owner = crate::ID)]
+    pub config: Account<'info, ConfigState>,
+
+    #[account(
+        mut,
+        has_one = mint @ crate::util::errors::ContractError::InvalidMint,
+        has_one = maintainer @ crate::util::errors::ContractError::MaintainerMismatch,
+        has_one = rent_payer @ crate::util::errors::ContractError::RentPayerMismatch,
+        close = rent_payer,
+        constraint = bounty.state == BountyState::InProgress @ crate::util::errors::ContractError::InvalidBountyStateForOperation,
+        constraint = bounty.token_program == token_program.key() @ crate::util::errors::ContractError::TokenProgramMismatch
+    )]
+    pub bounty: Account<'info, Bounty>,
+
+    #[account(mut, seeds = [b"count_by_state"], bump = count_by_state.bump)]
+    pub count_by_state: Account<'info, CountByState>,
+
+    // Writable so config.burn_bps can be burned from escrow
+    #[account(mut)]
+    pub mint: InterfaceAccount<'info, Mint>,
+
+    #[account(
+        seeds = [b"escrow_auth", bounty.key().as_ref()],
+        bump = bounty.bump
+    )]
+    /// CHECK: PDA SIGNER
+    pub escrow_authority: UncheckedAccount<'info>,
+
+    /// CHECK: Receives the escrow rent, pinned by `has_one` on the bounty
+    #[account(mut)]
+    pub maintainer: UncheckedAccount<'info>,
+
+    /// CHECK: Receives the bounty account's rent, pinned by `has_one` on the bounty
+    #[account(mut)]
+    pub rent_payer: UncheckedAccount<'info>,
+
+    /// CHECK: Validated against bounty.contributor
+    #[account(
+        constraint = bounty.contributor == Some(contributor.key()) @ crate::util::errors::ContractError::InvalidContributor
+    )]
+    pub contributor: UncheckedAccount<'info>,
+
+    #[account(
+        mut,
+        constraint = crate::util::validate::mint_matches(&contributor_token_account, &bounty.mint) @ crate::util::errors::ContractError::InvalidMint,
+        constraint = crate::util::validate::owner_matches(&contributor_token_account, &contributor.key()) @ crate::util::errors::ContractError::InvalidTokenAccount
+    )]
+    pub contributor_token_account: InterfaceAccount<'info, TokenAccount>,
+
+    // Receives the crank reward, required when that reward is non-zero
+    #[account(
+        mut,
+        constraint = crate::util::validate::mint_matches(cranker_token_account, &bounty.mint) @ crate::util::errors::ContractError::InvalidMint,
+        constraint = crate::util::validate::owner_matches(cranker_token_account, &cranker.key()) @ crate::util::errors::ContractError::InvalidTokenAccount
+    )]
+    pub cranker_token_account: Option<InterfaceAccount<'info, TokenAccount>>,
+
+    #[account(
+        mut,
+        constraint = crate::util::validate::mint_matches(&escrow_token_account, &bounty.mint) @ crate::util::errors::ContractError::InvalidMint
+    )]
+    pub escrow_token_account: InterfaceAccount<'info, TokenAccount>,
+
+    #[account(
+        mut,
+        constraint = crate::util::validate::mint_matches(treasury_token_account, &bounty.mint) @ crate::util::errors::ContractError::InvalidMint,
+        constraint = crate::util::validate::owner_matches(treasury_token_account, &config.treasury) @ crate::util::errors::ContractError::InvalidTokenAccount
+    )]
+    pub treasury_token_account: Option<InterfaceAccount<'info, TokenAccount>>,
+
+    #[account(
+        mut,
+        constraint = crate::util::validate::mint_matches(staker_token_account, &bounty.mint) @ crate::util::errors::ContractError::InvalidMint,
+        constraint = crate::util::validate::owner_matches(staker_token_account, &config.staker_pool) @ crate::util::errors::ContractError::InvalidTokenAccount
+    )]
+    pub staker_token_account: Option<InterfaceAccount<'info, TokenAccount>>,
+
+    // Receives the contributor's pledged donation, required when bounty.donate_bps > 0
+    #[account(
+        mut,
+        constraint = crate::util::validate::mint_matches(charity_token_account, &bounty.mint) @ crate::util::errors::ContractError::InvalidMint,
+        constraint = Some(charity_token_account.owner) == config.charity @ crate::util::errors::ContractError::InvalidTokenAccount
+    )]
+    pub charity_token_account: Option<InterfaceAccount<'info, TokenAccount>>,
+
+    #[account(
+        init_if_needed,
+        payer = cranker,
+        space = FeeEpochStats::LEN,
+        seeds = [b"fee_epoch_stats", mint.key().as_ref()],
+        bump
+    )]
+    pub fee_epoch_stats: Account<'info, FeeEpochStats>,
+
+    #[account(
+        init_if_needed,
+        payer = cranker,
+        space = GlobalStats::LEN,
+        seeds = [b"global_stats"],
+        bump
+    )]
+    pub global_stats: Account<'info, GlobalStats>,
+
+    /// CHECK: [b"mint_fee", mint] PDA, read as a MintFeeConfig only if initialized
+    #[account(seeds = [b"mint_fee", mint.key().as_ref()], bump)]
+    pub mint_fee_config: UncheckedAccount<'info>,
+
+    pub token_program: Interface<'info, TokenInterface>,
+    pub system_program: Program<'info, System>,
+}
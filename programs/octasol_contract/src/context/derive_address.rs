@@ -0,0 +1,5 @@
+use anchor_lang::prelude::*;
+
+// Pure helper, reads no accounts
+#[derive(Accounts)]
+pub struct DeriveBountyAddress {}
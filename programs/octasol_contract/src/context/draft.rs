@@ -0,0 +1,109 @@
+use anchor_lang::prelude::*;
+use anchor_spl::token_interface::{Mint, TokenAccount, TokenInterface};
+use crate::state::{Bounty, ConfigState, CountByState, VolumeWindow};
+
+#[derive(Accounts)]
+pub struct FundDraft<'info> {
+    #[account(mut)]
+    pub maintainer: Signer<'info>,
+
+    #[account(
+        mut,
+        has_one = maintainer,
+        has_one = mint @ crate::util::errors::ContractError::InvalidMint,
+        constraint = bounty.state == crate::state::BountyState::Draft @ crate::util::errors::ContractError::InvalidBountyStateForOperation,
+        constraint = bounty.token_program == token_program.key() @ crate::util::errors::ContractError::TokenProgramMismatch
+    )]
+    pub bounty: Account<'info, Bounty>,
+
+    #[account(seeds = [b"config"], bump = config.bump, owner = crate::ID)]
+    pub config: Account<'info, ConfigState>,
+
+    #[account(mut, seeds = [b"count_by_state"], bump = count_by_state.bump)]
+    pub count_by_state: Account<'info, CountByState>,
+
+    pub mint: InterfaceAccount<'info, Mint>,
+
+    /// CHECK: [b"mint_whitelist", mint] PDA, only its existence is checked
+    #[account(seeds = [b"mint_whitelist", mint.key().as_ref()], bump)]
+    pub mint_whitelist: UncheckedAccount<'info>,
+
+    #[account(
+        seeds = [b"escrow_auth", bounty.key().as_ref()],
+        bump = bounty.bump
+    )]
+    /// CHECK: PDA SIGNER
+    pub escrow_authority: UncheckedAccount<'info>,
+
+    // Created and checked fresh by `initialize_draft`
+    #[account(
+        mut,
+        token::mint = mint,
+        token::authority = escrow_authority,
+        token::token_program = token_program
+    )]
+    pub escrow_token_account: InterfaceAccount<'info, TokenAccount>,
+
+    #[account(
+        mut,
+        constraint = crate::util::validate::mint_matches(&maintainer_token_account, &mint.key()) @ crate::util::errors::ContractError::InvalidMint,
+        constraint = crate::util::validate::owner_matches(&maintainer_token_account, &maintainer.key()) @ crate::util::errors::ContractError::InvalidTokenAccount
+    )]
+    pub maintainer_token_account: InterfaceAccount<'info, TokenAccount>,
+
+    #[account(
+        init_if_needed,
+        payer = maintainer,
+        space = VolumeWindow::LEN,
+        seeds = [b"volume_window", mint.key().as_ref()],
+        bump
+    )]
+    pub volume_window: Account<'info, VolumeWindow>,
+
+    pub token_program: Interface<'info, TokenInterface>,
+    pub system_program: Program<'info, System>,
+}
+
+#[derive(Accounts)]
+pub struct DiscardDraft<'info> {
+    #[account(mut)]
+    pub maintainer: Signer<'info>,
+
+    #[account(
+        mut,
+        has_one = maintainer,
+        has_one = rent_payer @ crate::util::errors::ContractError::RentPayerMismatch,
+        has_one = mint @ crate::util::errors::ContractError::InvalidMint,
+        close = rent_payer,
+        constraint = bounty.state == crate::state::BountyState::Draft @ crate::util::errors::ContractError::InvalidBountyStateForOperation,
+        constraint = bounty.token_program == token_program.key() @ crate::util::errors::ContractError::TokenProgramMismatch
+    )]
+    pub bounty: Account<'info, Bounty>,
+
+    #[account(mut, seeds = [b"count_by_state"], bump = count_by_state.bump)]
+    pub count_by_state: Account<'info, CountByState>,
+
+    pub mint: InterfaceAccount<'info, Mint>,
+
+    #[account(
+        seeds = [b"escrow_auth", bounty.key().as_ref()],
+        bump = bounty.bump
+    )]
+    /// CHECK: PDA SIGNER
+    pub escrow_authority: UncheckedAccount<'info>,
+
+    /// CHECK: Receives the bounty account's rent, pinned by `has_one` on the bounty
+    #[account(mut)]
+    pub rent_payer: UncheckedAccount<'info>,
+
+    // The empty escrow `initialize_draft` created, closed to the maintainer who paid for it
+    #[account(
+        mut,
+        token::mint = mint,
+        token::authority = escrow_authority,
+        token::token_program = token_program
+    )]
+    pub escrow_token_account: InterfaceAccount<'info, TokenAccount>,
+
+    pub token_program: Interface<'info, TokenInterface>,
+}
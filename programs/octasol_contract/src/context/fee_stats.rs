@@ -0,0 +1,14 @@
+use anchor_lang::prelude::*;
+use anchor_spl::token_interface::Mint;
+use crate::state::FeeEpochStats;
+
+#[derive(Accounts)]
+pub struct GetFeeEpochStats<'info> {
+    pub mint: InterfaceAccount<'info, Mint>,
+
+    #[account(
+        seeds = [b"fee_epoch_stats", mint.key().as_ref()],
+        bump = fee_epoch_stats.bump
+    )]
+    pub fee_epoch_stats: Account<'info, FeeEpochStats>,
+}
@@ -0,0 +1,157 @@
+use anchor_lang::prelude::*;
+use anchor_spl::token_interface::{Mint, TokenAccount, TokenInterface};
+use crate::state::{Bounty, ConfigState, Contribution, CountByState};
+
+#[derive(Accounts)]
+pub struct FundBounty<'info> {
+    #[account(mut)]
+    pub funder: Signer<'info>,
+
+    #[account(
+        mut,
+        has_one = mint @ crate::util::errors::ContractError::InvalidMint,
+        constraint = bounty.state == crate::state::BountyState::Created
+            || bounty.state == crate::state::BountyState::InProgress @ crate::util::errors::ContractError::InvalidBountyStateForOperation,
+        constraint = bounty.token_program == token_program.key() @ crate::util::errors::ContractError::TokenProgramMismatch
+    )]
+    pub bounty: Account<'info, Bounty>,
+
+    #[account(
+        init_if_needed,
+        payer = funder,
+        space = Contribution::LEN,
+        seeds = [Contribution::SEED_PREFIX, bounty.key().as_ref(), funder.key().as_ref()],
+        bump
+    )]
+    pub contribution: Account<'info, Contribution>,
+
+    pub mint: InterfaceAccount<'info, Mint>,
+
+    #[account(
+        seeds = [b"escrow_auth", bounty.key().as_ref()],
+        bump = bounty.bump
+    )]
+    /// CHECK: PDA SIGNER
+    pub escrow_authority: UncheckedAccount<'info>,
+
+    #[account(
+        mut,
+        token::mint = mint,
+        token::authority = escrow_authority,
+        token::token_program = token_program
+    )]
+    pub escrow_token_account: InterfaceAccount<'info, TokenAccount>,
+
+    #[account(
+        mut,
+        constraint = crate::util::validate::mint_matches(&funder_token_account, &mint.key()) @ crate::util::errors::ContractError::InvalidMint,
+        constraint = crate::util::validate::owner_matches(&funder_token_account, &funder.key()) @ crate::util::errors::ContractError::InvalidTokenAccount
+    )]
+    pub funder_token_account: InterfaceAccount<'info, TokenAccount>,
+
+    pub token_program: Interface<'info, TokenInterface>,
+    pub system_program: Program<'info, System>,
+}
+
+#[derive(Accounts)]
+pub struct TipBounty<'info> {
+    pub tipper: Signer<'info>,
+
+    #[account(
+        mut,
+        has_one = mint @ crate::util::errors::ContractError::InvalidMint,
+        constraint = bounty.state == crate::state::BountyState::Created
+            || bounty.state == crate::state::BountyState::InProgress @ crate::util::errors::ContractError::InvalidBountyStateForOperation,
+        constraint = bounty.token_program == token_program.key() @ crate::util::errors::ContractError::TokenProgramMismatch
+    )]
+    pub bounty: Account<'info, Bounty>,
+
+    pub mint: InterfaceAccount<'info, Mint>,
+
+    #[account(
+        seeds = [b"escrow_auth", bounty.key().as_ref()],
+        bump = bounty.bump
+    )]
+    /// CHECK: PDA SIGNER
+    pub escrow_authority: UncheckedAccount<'info>,
+
+    #[account(
+        mut,
+        token::mint = mint,
+        token::authority = escrow_authority,
+        token::token_program = token_program
+    )]
+    pub escrow_token_account: InterfaceAccount<'info, TokenAccount>,
+
+    #[account(
+        mut,
+        constraint = crate::util::validate::mint_matches(&tipper_token_account, &mint.key()) @ crate::util::errors::ContractError::InvalidMint,
+        constraint = crate::util::validate::owner_matches(&tipper_token_account, &tipper.key()) @ crate::util::errors::ContractError::InvalidTokenAccount
+    )]
+    pub tipper_token_account: InterfaceAccount<'info, TokenAccount>,
+
+    pub token_program: Interface<'info, TokenInterface>,
+}
+
+// Funders to refund are passed as `remaining_accounts` triples:
+//   [contribution (mut), funder token account (mut), funder (mut, receives the contribution's rent)]
+#[derive(Accounts)]
+pub struct RefundFundersBatch<'info> {
+    pub admin: Signer<'info>,
+
+    #[account(
+        seeds = [b"config"],
+        bump = config.bump,
+        owner = crate::ID,
+        constraint = config.admin == admin.key() @ crate::util::errors::ContractError::Unauthorized
+    )]
+    pub config: Account<'info, ConfigState>,
+
+    // Closed in the handler by the call that refunds the last funder
+    #[account(
+        mut,
+        has_one = maintainer @ crate::util::errors::ContractError::MaintainerMismatch,
+        has_one = rent_payer @ crate::util::errors::ContractError::RentPayerMismatch,
+        has_one = mint @ crate::util::errors::ContractError::InvalidMint,
+        constraint = bounty.state != crate::state::BountyState::Completed @ crate::util::errors::ContractError::BountyAlreadyCompleted,
+        constraint = bounty.state != crate::state::BountyState::Cancelled @ crate::util::errors::ContractError::BountyAlreadyCancelled,
+        constraint = bounty.token_program == token_program.key() @ crate::util::errors::ContractError::TokenProgramMismatch
+    )]
+    pub bounty: Account<'info, Bounty>,
+
+    #[account(mut, seeds = [b"count_by_state"], bump = count_by_state.bump)]
+    pub count_by_state: Account<'info, CountByState>,
+
+    pub mint: InterfaceAccount<'info, Mint>,
+
+    #[account(
+        seeds = [b"escrow_auth", bounty.key().as_ref()],
+        bump = bounty.bump
+    )]
+    /// CHECK: PDA SIGNER
+    pub escrow_authority: UncheckedAccount<'info>,
+
+    /// CHECK: Receives the maintainer's share and the escrow rent, pinned by `has_one` on the bounty
+    #[account(mut)]
+    pub maintainer: UncheckedAccount<'info>,
+
+    /// CHECK: Receives the bounty account's rent, pinned by `has_one` on the bounty
+    #[account(mut)]
+    pub rent_payer: UncheckedAccount<'info>,
+
+    #[account(
+        mut,
+        constraint = crate::util::validate::mint_matches(&maintainer_token_account, &bounty.mint) @ crate::util::errors::ContractError::InvalidMint,
+        constraint = crate::util::validate::owner_matches(&maintainer_token_account, &bounty.maintainer) @ crate::util::errors::ContractError::InvalidTokenAccount
+    )]
+    pub maintainer_token_account: InterfaceAccount<'info, TokenAccount>,
+
+    #[account(
+        mut,
+        constraint = crate::util::validate::mint_matches(&escrow_token_account, &bounty.mint) @ crate::util::errors::ContractError::InvalidMint,
+        constraint = crate::util::validate::owner_matches(&escrow_token_account, &escrow_authority.key()) @ crate::util::errors::ContractError::InvalidTokenAccount
+    )]
+    pub escrow_token_account: InterfaceAccount<'info, TokenAccount>,
+
+    pub token_program: Interface<'info, TokenInterface>,
+}
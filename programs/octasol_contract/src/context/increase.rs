@@ -0,0 +1,44 @@
+use anchor_lang::prelude::*;
+use anchor_spl::token_interface::{Mint, TokenAccount, TokenInterface};
+use crate::state::Bounty;
+
+#[derive(Accounts)]
+pub struct IncreaseBounty<'info> {
+    pub maintainer: Signer<'info>,
+
+    #[account(
+        mut,
+        has_one = maintainer,
+        has_one = mint @ crate::util::errors::ContractError::InvalidMint,
+        constraint = bounty.state == crate::state::BountyState::Created
+            || bounty.state == crate::state::BountyState::InProgress @ crate::util::errors::ContractError::InvalidBountyStateForOperation,
+        constraint = bounty.token_program == token_program.key() @ crate::util::errors::ContractError::TokenProgramMismatch
+    )]
+    pub bounty: Account<'info, Bounty>,
+
+    pub mint: InterfaceAccount<'info, Mint>,
+
+    #[account(
+        seeds = [b"escrow_auth", bounty.key().as_ref()],
+        bump = bounty.bump
+    )]
+    /// CHECK: PDA SIGNER
+    pub escrow_authority: UncheckedAccount<'info>,
+
+    #[account(
+        mut,
+        token::mint = mint,
+        token::authority = escrow_authority,
+        token::token_program = token_program
+    )]
+    pub escrow_token_account: InterfaceAccount<'info, TokenAccount>,
+
+    #[account(
+        mut,
+        constraint = crate::util::validate::mint_matches(&maintainer_token_account, &mint.key()) @ crate::util::errors::ContractError::InvalidMint,
+        constraint = crate::util::validate::owner_matches(&maintainer_token_account, &maintainer.key()) @ crate::util::errors::ContractError::InvalidTokenAccount
+    )]
+    pub maintainer_token_account: InterfaceAccount<'info, TokenAccount>,
+
+    pub token_program: Interface<'info, TokenInterface>,
+}
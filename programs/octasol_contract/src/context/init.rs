@@ -1,25 +1,47 @@
 use anchor_lang::prelude::*;
-use anchor_spl::token::{Mint, TokenAccount, Token};
+use anchor_spl::token_interface::{Mint, TokenAccount, TokenInterface};
 use anchor_spl::associated_token::AssociatedToken;
-use crate::state::Bounty;
+use crate::state::{Bounty, ConfigState, CountByState, VolumeWindow};
 
 #[derive(Accounts)]
+#[instruction(bounty_id: u64)]
 pub struct InitializeBounty<'info> {
     #[account(mut)]
     pub maintainer: Signer<'info>,
+    // One account per maintainer and bounty id, so a retried create lands on the same address.
+    // `init_if_needed` lets the handler reject that retry with BountyAlreadyExists rather than
+    // the system program's "already in use".
     #[account(
-        init,
+        init_if_needed,
         payer = maintainer,
-        space = Bounty::LEN
+        space = Bounty::LEN,
+        seeds = [Bounty::SEED_PREFIX, maintainer.key().as_ref(), &bounty_id.to_le_bytes()],
+        bump
     )]
     pub bounty: Account<'info, Bounty>,
 
+    // Covers the bounty account's rent instead of the maintainer, and is refunded it on close
+    #[account(mut)]
+    pub rent_payer: Option<Signer<'info>>,
+
+    #[account(seeds = [b"config"], bump = config.bump, owner = crate::ID)]
+    pub config: Account<'info, ConfigState>,
+
+    #[account(
+        init_if_needed,
+        payer = maintainer,
+        space = CountByState::LEN,
+        seeds = [b"count_by_state"],
+        bump
+    )]
+    pub count_by_state: Account<'info, CountByState>,
+
     #[account(
         mut,
-        constraint = maintainer_token_account.owner == maintainer.key() @ crate::util::errors::ContractError::InvalidTokenAccount,
-        constraint = maintainer_token_account.mint == mint.key() @ crate::util::errors::ContractError::InvalidMint
+        constraint = crate::util::validate::owner_matches(&maintainer_token_account, &maintainer.key()) @ crate::util::errors::ContractError::InvalidTokenAccount,
+        constraint = crate::util::validate::mint_matches(&maintainer_token_account, &mint.key()) @ crate::util::errors::ContractError::InvalidMint
     )]
-    pub maintainer_token_account: Account<'info, TokenAccount>,
+    pub maintainer_token_account: InterfaceAccount<'info, TokenAccount>,
 
     #[account(
         seeds = [b"escrow_auth",bounty.key().as_ref()],
@@ -28,17 +50,53 @@ pub struct InitializeBounty<'info> {
     /// CHECK: PDA SIGNER
     pub escrow_authority : UncheckedAccount<'info>,
 
+    // May be left over from an earlier failed attempt, the handler requires it to be empty
     #[account(
-        init,
+        init_if_needed,
         payer = maintainer,
         associated_token::mint = mint,
         associated_token::authority = escrow_authority,
+        associated_token::token_program = token_program,
     )]
-    pub escrow_token_account: Account<'info, TokenAccount>,
+    pub escrow_token_account: InterfaceAccount<'info, TokenAccount>,
+
+    pub mint: InterfaceAccount<'info, Mint>,
+
+    /// CHECK: [b"mint_whitelist", mint] PDA, only its existence is checked
+    #[account(seeds = [b"mint_whitelist", mint.key().as_ref()], bump)]
+    pub mint_whitelist: UncheckedAccount<'info>,
+
+    #[account(
+        init_if_needed,
+        payer = maintainer,
+        space = VolumeWindow::LEN,
+        seeds = [b"volume_window", mint.key().as_ref()],
+        bump
+    )]
+    pub volume_window: Account<'info, VolumeWindow>,
 
-    pub mint: Account<'info, Mint>,
     pub system_program: Program<'info, System>,
-    pub token_program: Program<'info, Token>,
+    pub token_program: Interface<'info, TokenInterface>,
     pub associated_token_program: Program<'info, AssociatedToken>,
     pub rent: Sysvar<'info, Rent>,
 }
+
+#[derive(Accounts)]
+pub struct ValidateCreate<'info> {
+    #[account(seeds = [b"config"], bump = config.bump, owner = crate::ID)]
+    pub config: Account<'info, ConfigState>,
+
+    pub mint: InterfaceAccount<'info, Mint>,
+}
+
+#[derive(Accounts)]
+pub struct GetCreationPolicy<'info> {
+    #[account(seeds = [b"config"], bump = config.bump, owner = crate::ID)]
+    pub config: Account<'info, ConfigState>,
+
+    pub mint: InterfaceAccount<'info, Mint>,
+
+    /// CHECK: [b"mint_whitelist", mint] PDA, only its existence is checked
+    #[account(seeds = [b"mint_whitelist", mint.key().as_ref()], bump)]
+    pub mint_whitelist: UncheckedAccount<'info>,
+}
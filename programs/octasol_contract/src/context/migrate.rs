@@ -0,0 +1,68 @@
+use anchor_lang::prelude::*;
+use anchor_spl::{associated_token::AssociatedToken, token_interface::{Mint, TokenAccount, TokenInterface}};
+use crate::state::Bounty;
+
+#[derive(Accounts)]
+pub struct MigrateBountyMint<'info> {
+    #[account(mut)]
+    pub maintainer: Signer<'info>,
+
+    #[account(
+        mut,
+        has_one = maintainer,
+        has_one = mint @ crate::util::errors::ContractError::InvalidMint,
+        constraint = bounty.state == crate::state::BountyState::Created
+            || bounty.state == crate::state::BountyState::InProgress @ crate::util::errors::ContractError::InvalidBountyStateForOperation,
+        constraint = bounty.token_program == token_program.key() @ crate::util::errors::ContractError::TokenProgramMismatch
+    )]
+    pub bounty: Account<'info, Bounty>,
+
+    #[account(
+        seeds = [b"escrow_auth", bounty.key().as_ref()],
+        bump = bounty.bump
+    )]
+    /// CHECK: PDA SIGNER
+    pub escrow_authority: UncheckedAccount<'info>,
+
+    // Current mint and escrow, refunded and closed
+    pub mint: InterfaceAccount<'info, Mint>,
+
+    #[account(
+        mut,
+        token::mint = mint,
+        token::authority = escrow_authority,
+        token::token_program = token_program
+    )]
+    pub escrow_token_account: InterfaceAccount<'info, TokenAccount>,
+
+    #[account(
+        mut,
+        constraint = crate::util::validate::mint_matches(&maintainer_token_account, &mint.key()) @ crate::util::errors::ContractError::InvalidMint,
+        constraint = crate::util::validate::owner_matches(&maintainer_token_account, &maintainer.key()) @ crate::util::errors::ContractError::InvalidTokenAccount
+    )]
+    pub maintainer_token_account: InterfaceAccount<'info, TokenAccount>,
+
+    // Replacement mint and escrow, funded by the maintainer
+    #[account(constraint = new_mint.key() != mint.key() @ crate::util::errors::ContractError::InvalidMint)]
+    pub new_mint: InterfaceAccount<'info, Mint>,
+
+    #[account(
+        init,
+        payer = maintainer,
+        associated_token::mint = new_mint,
+        associated_token::authority = escrow_authority,
+        associated_token::token_program = token_program,
+    )]
+    pub new_escrow_token_account: InterfaceAccount<'info, TokenAccount>,
+
+    #[account(
+        mut,
+        constraint = crate::util::validate::mint_matches(&new_maintainer_token_account, &new_mint.key()) @ crate::util::errors::ContractError::InvalidMint,
+        constraint = crate::util::validate::owner_matches(&new_maintainer_token_account, &maintainer.key()) @ crate::util::errors::ContractError::InvalidTokenAccount
+    )]
+    pub new_maintainer_token_account: InterfaceAccount<'info, TokenAccount>,
+
+    pub system_program: Program<'info, System>,
+    pub token_program: Interface<'info, TokenInterface>,
+    pub associated_token_program: Program<'info, AssociatedToken>,
+}
@@ -10,5 +10,49 @@ pub mod config;
 pub use config::*;
 pub mod admin_ops;
 pub use admin_ops::*;
-pub mod update_admin;
-pub use update_admin::*;
+pub mod admin_transfer;
+pub use admin_transfer::*;
+pub mod yield_ops;
+pub use yield_ops::*;
+pub mod reclaim_orphan;
+pub use reclaim_orphan::*;
+pub mod assign_proof;
+pub use assign_proof::*;
+pub mod migrate;
+pub use migrate::*;
+pub mod applicants;
+pub use applicants::*;
+pub mod release_partial;
+pub use release_partial::*;
+pub mod derive_address;
+pub use derive_address::*;
+pub mod increase;
+pub use increase::*;
+pub mod fee_stats;
+pub use fee_stats::*;
+pub mod verify_funded;
+pub use verify_funded::*;
+pub mod close_batch;
+pub use close_batch::*;
+pub mod bounty_options;
+pub use bounty_options::*;
+pub mod attestation;
+pub use attestation::*;
+pub mod count_by_state;
+pub use count_by_state::*;
+pub mod archive;
+pub use archive::*;
+pub mod approve;
+pub use approve::*;
+pub mod rent_swap;
+pub use rent_swap::*;
+pub mod fund;
+pub use fund::*;
+pub mod draft;
+pub use draft::*;
+pub mod reclaim_expired;
+pub use reclaim_expired::*;
+pub mod crank;
+pub use crank::*;
+pub mod preview;
+pub use preview::*;
@@ -0,0 +1,32 @@
+use anchor_lang::prelude::*;
+use anchor_spl::token_interface::{Mint, TokenAccount};
+use crate::state::{Bounty, ConfigState};
+
+// Read-only, meant to be simulated
+#[derive(Accounts)]
+pub struct PreviewPayout<'info> {
+    #[account(seeds = [b"config"], bump = config.bump, owner = crate::ID)]
+    pub config: Account<'info, ConfigState>,
+
+    #[account(has_one = mint @ crate::util::errors::ContractError::InvalidMint)]
+    pub bounty: Account<'info, Bounty>,
+
+    pub mint: InterfaceAccount<'info, Mint>,
+
+    #[account(
+        seeds = [b"escrow_auth", bounty.key().as_ref()],
+        bump = bounty.bump
+    )]
+    /// CHECK: PDA the escrow must belong to
+    pub escrow_authority: UncheckedAccount<'info>,
+
+    #[account(
+        constraint = crate::util::validate::mint_matches(&escrow_token_account, &bounty.mint) @ crate::util::errors::ContractError::InvalidMint,
+        constraint = crate::util::validate::owner_matches(&escrow_token_account, &escrow_authority.key()) @ crate::util::errors::ContractError::InvalidTokenAccount
+    )]
+    pub escrow_token_account: InterfaceAccount<'info, TokenAccount>,
+
+    /// CHECK: [b"mint_fee", mint] PDA, read as a MintFeeConfig only if initialized
+    #[account(seeds = [b"mint_fee", mint.key().as_ref()], bump)]
+    pub mint_fee_config: UncheckedAccount<'info>,
+}
@@ -0,0 +1,50 @@
+use anchor_lang::prelude::*;
+use anchor_spl::token_interface::{Mint, TokenAccount, TokenInterface};
+use crate::state::ConfigState;
+
+#[derive(Accounts)]
+pub struct ReclaimOrphanEscrow<'info> {
+    pub admin: Signer<'info>,
+
+    #[account(
+        seeds = [b"config"],
+        bump = config.bump,
+        owner = crate::ID,
+        has_one = admin @ crate::util::errors::ContractError::Unauthorized,
+        has_one = treasury
+    )]
+    pub config: Account<'info, ConfigState>,
+
+    /// CHECK: The (closed) bounty the escrow was created for; verified empty in the handler
+    pub bounty: UncheckedAccount<'info>,
+
+    #[account(
+        seeds = [b"escrow_auth", bounty.key().as_ref()],
+        bump
+    )]
+    /// CHECK: PDA SIGNER
+    pub escrow_authority: UncheckedAccount<'info>,
+
+    #[account(
+        mut,
+        token::mint = mint,
+        token::authority = escrow_authority,
+        token::token_program = token_program
+    )]
+    pub escrow_token_account: InterfaceAccount<'info, TokenAccount>,
+
+    pub mint: InterfaceAccount<'info, Mint>,
+
+    #[account(
+        mut,
+        constraint = crate::util::validate::mint_matches(&treasury_token_account, &mint.key()) @ crate::util::errors::ContractError::InvalidMint,
+        constraint = crate::util::validate::owner_matches(&treasury_token_account, &config.treasury) @ crate::util::errors::ContractError::InvalidTokenAccount
+    )]
+    pub treasury_token_account: InterfaceAccount<'info, TokenAccount>,
+
+    /// CHECK: Receives the escrow rent, pinned to config.treasury
+    #[account(mut)]
+    pub treasury: UncheckedAccount<'info>,
+
+    pub token_program: Interface<'info, TokenInterface>,
+}
@@ -0,0 +1,29 @@
+use anchor_lang::prelude::*;
+use anchor_spl::token_interface::{Mint, TokenAccount, TokenInterface};
+use crate::state::ConfigState;
+
+#[derive(Accounts)]
+pub struct SwapReclaimedRent<'info> {
+    #[account(mut)]
+    pub maintainer: Signer<'info>,
+
+    #[account(seeds = [b"config"], bump = config.bump, owner = crate::ID)]
+    pub config: Account<'info, ConfigState>,
+
+    pub mint: InterfaceAccount<'info, Mint>,
+
+    #[account(
+        mut,
+        token::mint = mint,
+        token::authority = maintainer,
+        token::token_program = token_program
+    )]
+    pub maintainer_token_account: InterfaceAccount<'info, TokenAccount>,
+
+    /// CHECK: Must match config.amm_program, checked in the handler
+    #[account(executable)]
+    pub amm_program: UncheckedAccount<'info>,
+
+    pub token_program: Interface<'info, TokenInterface>,
+    pub system_program: Program<'info, System>,
+}
@@ -0,0 +1,21 @@
+use anchor_lang::prelude::*;
+use anchor_spl::token_interface::TokenAccount;
+use crate::state::Bounty;
+
+#[derive(Accounts)]
+pub struct VerifyFunded<'info> {
+    pub bounty: Account<'info, Bounty>,
+
+    #[account(
+        seeds = [b"escrow_auth", bounty.key().as_ref()],
+        bump = bounty.bump
+    )]
+    /// CHECK: PDA SIGNER
+    pub escrow_authority: UncheckedAccount<'info>,
+
+    #[account(
+        token::mint = bounty.mint,
+        token::authority = escrow_authority
+    )]
+    pub escrow_token_account: InterfaceAccount<'info, TokenAccount>,
+}
@@ -0,0 +1,99 @@
+use anchor_lang::prelude::*;
+use anchor_spl::token_interface::{Mint, TokenAccount, TokenInterface};
+use crate::state::{Bounty, ConfigState};
+
+#[derive(Accounts)]
+pub struct DepositToYield<'info> {
+    pub admin: Signer<'info>,
+
+    #[account(
+        mut,
+        seeds = [b"config"],
+        bump = config.bump,
+        owner = crate::ID,
+        has_one = admin @ crate::util::errors::ContractError::Unauthorized,
+        has_one = yield_program @ crate::util::errors::ContractError::InvalidYieldProgram
+    )]
+    pub config: Account<'info, ConfigState>,
+
+    #[account(
+        mut,
+        has_one = mint @ crate::util::errors::ContractError::InvalidMint,
+        constraint = bounty.token_program == token_program.key() @ crate::util::errors::ContractError::TokenProgramMismatch
+    )]
+    pub bounty: Account<'info, Bounty>,
+
+    pub mint: InterfaceAccount<'info, Mint>,
+
+    #[account(
+        seeds = [b"escrow_auth", bounty.key().as_ref()],
+        bump = bounty.bump
+    )]
+    /// CHECK: PDA SIGNER
+    pub escrow_authority: UncheckedAccount<'info>,
+
+    #[account(
+        mut,
+        constraint = crate::util::validate::mint_matches(&escrow_token_account, &bounty.mint) @ crate::util::errors::ContractError::InvalidMint,
+        constraint = crate::util::validate::owner_matches(&escrow_token_account, &escrow_authority.key()) @ crate::util::errors::ContractError::InvalidTokenAccount
+    )]
+    pub escrow_token_account: InterfaceAccount<'info, TokenAccount>,
+
+    /// CHECK: Pinned to config.yield_program
+    #[account(executable)]
+    pub yield_program: UncheckedAccount<'info>,
+
+    pub token_program: Interface<'info, TokenInterface>,
+}
+
+#[derive(Accounts)]
+pub struct WithdrawFromYield<'info> {
+    pub admin: Signer<'info>,
+
+    #[account(
+        mut,
+        seeds = [b"config"],
+        bump = config.bump,
+        owner = crate::ID,
+        has_one = admin @ crate::util::errors::ContractError::Unauthorized,
+        has_one = yield_program @ crate::util::errors::ContractError::InvalidYieldProgram
+    )]
+    pub config: Account<'info, ConfigState>,
+
+    #[account(
+        mut,
+        has_one = mint @ crate::util::errors::ContractError::InvalidMint,
+        constraint = bounty.token_program == token_program.key() @ crate::util::errors::ContractError::TokenProgramMismatch
+    )]
+    pub bounty: Account<'info, Bounty>,
+
+    pub mint: InterfaceAccount<'info, Mint>,
+
+    #[account(
+        seeds = [b"escrow_auth", bounty.key().as_ref()],
+        bump = bounty.bump
+    )]
+    /// CHECK: PDA SIGNER
+    pub escrow_authority: UncheckedAccount<'info>,
+
+    #[account(
+        mut,
+        constraint = crate::util::validate::mint_matches(&escrow_token_account, &bounty.mint) @ crate::util::errors::ContractError::InvalidMint,
+        constraint = crate::util::validate::owner_matches(&escrow_token_account, &escrow_authority.key()) @ crate::util::errors::ContractError::InvalidTokenAccount
+    )]
+    pub escrow_token_account: InterfaceAccount<'info, TokenAccount>,
+
+    // Receives any yield earned above the principal
+    #[account(
+        mut,
+        constraint = crate::util::validate::mint_matches(&treasury_token_account, &bounty.mint) @ crate::util::errors::ContractError::InvalidMint,
+        constraint = crate::util::validate::owner_matches(&treasury_token_account, &config.treasury) @ crate::util::errors::ContractError::InvalidTokenAccount
+    )]
+    pub treasury_token_account: InterfaceAccount<'info, TokenAccount>,
+
+    /// CHECK: Pinned to config.yield_program
+    #[account(executable)]
+    pub yield_program: UncheckedAccount<'info>,
+
+    pub token_program: Interface<'info, TokenInterface>,
+}
@@ -1,6 +1,8 @@
 use anchor_lang::prelude::*;
-use anchor_spl::token::{transfer,Transfer};
-use anchor_spl::token::{close_account, CloseAccount};
+use anchor_lang::system_program;
+use anchor_spl::token_interface::{transfer_checked, TransferChecked};
+use anchor_spl::token_interface::{close_account, CloseAccount, TokenAccount};
+use anchor_spl::associated_token::{self, get_associated_token_address_with_program_id};
 
 
 pub mod context;
@@ -9,11 +11,15 @@ pub mod util;
 
 use context::*;
 use state::*;
-use util::{errors::ContractError, events::*};
+use util::{errors::ContractError, escrow::{self, transfer_from_escrow}, events::*, payout::EscrowPayout, attestation, compression, fees, merkle, notify, reentrancy, swap, token_ext, unwrap, validate, yield_hook};
 
 
 declare_id!("tMf5EmV2h6sMJ2QMFU6766ACJpf7NTuamPzCudaNFus");
 
+// Bumped on each release, reported by `program_info`
+#[constant]
+pub const PROGRAM_VERSION: u8 = 1;
+
 
 
 
@@ -25,259 +31,2658 @@ pub mod octasol_contract {
 
 
 
-    pub fn initialize_config(ctx: Context<InitializeConfig>) -> Result<()> {
-        let config = &mut ctx.accounts.config;
-        config.admin = ctx.accounts.admin.key(); // Set the initial admin
-        config.bump = ctx.bumps.config;
+    pub fn initialize_config(ctx: Context<InitializeConfig>, fee_bps: u16, treasury: Pubkey) -> Result<()> {
+        let admin = ctx.accounts.admin.key(); // The deployer becomes the initial admin
+        let mut config = ConfigState::new(admin, ctx.bumps.config);
+        config.set_fee_terms(Some(fee_bps), None, Some(treasury), None)?;
+        ctx.accounts.config.set_inner(config);
         Ok(())
     }
 
     pub fn initialize_bounty(
-        ctx: Context<InitializeBounty>,
+        mut ctx: Context<InitializeBounty>,
         bounty_id: u64,
         amount: u64,
+        priority: u8, // 0 (lowest) to 255 (most urgent), the u8 bounds the range
+        deadline: i64,
+        keeper: Option<Pubkey>, // falls back to config.default_keeper
+        milestones: Vec<u64>,
     ) -> Result<()> {
-        require!(amount > 0, ContractError::InvalidAmount);
+        create_bounty(&mut ctx, bounty_id, amount, priority, deadline, keeper, milestones)
+    }
+
+    // Records a bounty for discussion without committing funds; `fund_draft` escrows the amount later
+    pub fn initialize_draft(
+        mut ctx: Context<InitializeBounty>,
+        bounty_id: u64,
+        amount: u64,
+        priority: u8,
+        deadline: i64,
+        keeper: Option<Pubkey>,
+        milestones: Vec<u64>,
+    ) -> Result<()> {
+        write_bounty(&mut ctx, bounty_id, amount, priority, deadline, keeper, milestones)?;
+        ctx.accounts.bounty.state = BountyState::Draft;
+
+        let counts = &mut ctx.accounts.count_by_state;
+        counts.bump = ctx.bumps.count_by_state;
+        counts.record(None, &BountyState::Draft).ok_or(ContractError::ArithmeticOverflow)?;
+
+        emit!(DraftCreated {
+            bounty_id,
+            maintainer: ctx.accounts.maintainer.key(),
+            amount,
+        });
+
+        Ok(())
+    }
 
+    // Escrows a draft's amount, turning it into a regular Created bounty
+    pub fn fund_draft(ctx: Context<FundDraft>, bounty_id: u64) -> Result<BountyStateChange> {
         let bounty = &mut ctx.accounts.bounty;
-        bounty.maintainer = ctx.accounts.maintainer.key();
-        bounty.contributor = None;
-        bounty.mint = ctx.accounts.mint.key();
-        bounty.amount = amount;
-        bounty.bump = ctx.bumps.escrow_authority;
-        bounty.bounty_id = bounty_id;
-        bounty.state = BountyState::Created;
-
-        // Transfer tokens from maintainer to escrow
-        let cpi_accounts = Transfer {
-            from: ctx.accounts.maintainer_token_account.to_account_info(),
-            to: ctx.accounts.escrow_token_account.to_account_info(),
-            authority: ctx.accounts.maintainer.to_account_info(),
-        };
-        let cpi_program = ctx.accounts.token_program.to_account_info();
-        let cpi_ctx = CpiContext::new(cpi_program, cpi_accounts);
-        let _ =transfer(cpi_ctx, amount)?;
+        require!(bounty.bounty_id == bounty_id, ContractError::BountyIdMismatch);
+        // Creation policy may have changed since the draft was written
+        let now = Clock::get()?.unix_timestamp;
+        ctx.accounts.config.check_new_bounty(bounty.amount, ctx.accounts.mint.supply, &ctx.accounts.mint_whitelist, bounty.deadline, now)?;
+        let volume_window = &mut ctx.accounts.volume_window;
+        volume_window.mint = ctx.accounts.mint.key();
+        volume_window.bump = ctx.bumps.volume_window;
+        volume_window.admit(now, bounty.amount, ctx.accounts.config.max_daily_volume)?;
+
+        let cpi_ctx = CpiContext::new(
+            ctx.accounts.token_program.to_account_info(),
+            TransferChecked {
+                from: ctx.accounts.maintainer_token_account.to_account_info(),
+                mint: ctx.accounts.mint.to_account_info(),
+                to: ctx.accounts.escrow_token_account.to_account_info(),
+                authority: ctx.accounts.maintainer.to_account_info(),
+            },
+        );
+        transfer_checked(cpi_ctx, bounty.amount, ctx.accounts.mint.decimals)?;
+
+        // The fee discount window starts when the bounty is funded, not when it was drafted
+        bounty.created_at = now;
+        ctx.accounts.count_by_state
+            .record(Some(&BountyState::Draft), &BountyState::Created)
+            .ok_or(ContractError::ArithmeticOverflow)?;
+        emit!(StateTransition {
+            bounty_id,
+            from_state: BountyState::Draft,
+            to_state: BountyState::Created,
+        });
+        bounty.transition(BountyState::Created)?;
 
         emit!(BountyCreated {
             bounty_id,
+            maintainer: bounty.maintainer,
+            amount: bounty.amount,
+            priority: bounty.priority,
+            keeper: bounty.keeper,
+        });
+
+        Ok(bounty.state_change())
+    }
+
+    // Throws away a draft that was never funded; only account rent is returned, no tokens move
+    pub fn discard_draft(ctx: Context<DiscardDraft>, bounty_id: u64) -> Result<BountyStateChange> {
+        escrow::assert_escrow_authority(&ctx.accounts.escrow_authority, &ctx.accounts.bounty.key(), ctx.accounts.bounty.bump)?;
+        let bounty = &mut ctx.accounts.bounty;
+        require!(bounty.bounty_id == bounty_id, ContractError::BountyIdMismatch);
+        require!(ctx.accounts.escrow_token_account.amount == 0, ContractError::EscrowNotEmpty);
+
+        let bounty_key = bounty.key();
+        let bump = bounty.bump;
+        let seeds = escrow::escrow_signer_seeds(&bounty_key, &bump);
+        close_account(CpiContext::new_with_signer(
+            ctx.accounts.token_program.to_account_info(),
+            CloseAccount {
+                account: ctx.accounts.escrow_token_account.to_account_info(),
+                destination: ctx.accounts.maintainer.to_account_info(),
+                authority: ctx.accounts.escrow_authority.to_account_info(),
+            },
+            &[&seeds[..]],
+        ))?;
+
+        emit!(BountyCancelled {
+            bounty_id,
+            maintainer: ctx.accounts.maintainer.key(),
+            amount: 0,
+            reason: CancelReason::DraftDiscarded as u8,
+            milestones_paid: 0,
+        });
+
+        ctx.accounts.count_by_state
+            .record(Some(&BountyState::Draft), &BountyState::Cancelled)
+            .ok_or(ContractError::ArithmeticOverflow)?;
+        emit!(StateTransition {
+            bounty_id,
+            from_state: BountyState::Draft,
+            to_state: BountyState::Cancelled,
+        });
+        bounty.transition(BountyState::Cancelled)?;
+        emit!(BountyFinalSnapshot::new(bounty_key, bounty));
+
+        Ok(bounty.state_change())
+    }
+
+    // Funds a fresh bounty for the same issue as an earlier one that was closed (e.g. reclaimed after
+    // expiry). The new bounty gets its own id, and so its own PDA, with the old one recorded in
+    // `relaunched_from` for continuity. `new_bounty_id` comes first since the bounty seeds use it.
+    pub fn relaunch_bounty(mut ctx: Context<InitializeBounty>, new_bounty_id: u64, old_bounty_id: u64, new_deadline: i64, amount: u64) -> Result<()> {
+        require!(new_bounty_id != old_bounty_id, ContractError::BountyIdMismatch);
+        create_bounty(&mut ctx, new_bounty_id, amount, 0, new_deadline, None, Vec::new())?;
+        ctx.accounts.bounty.relaunched_from = Some(old_bounty_id);
+
+        emit!(BountyRelaunched {
+            bounty_id: new_bounty_id,
+            old_bounty_id,
+            bounty: ctx.accounts.bounty.key(),
             maintainer: ctx.accounts.maintainer.key(),
             amount,
+            deadline: new_deadline,
         });
 
         Ok(())
     }
 
-pub fn assign_contributor(ctx: Context<AssignContributor>) -> Result<()> {
+    // Dry run of the creation policy checks, no tokens move and no accounts are created
+    pub fn validate_create(ctx: Context<ValidateCreate>, amount: u64) -> Result<CreateReadiness> {
+        Ok(ctx.accounts.config.create_readiness(amount, ctx.accounts.mint.supply))
+    }
+
+    // All config limits on creating a bounty in `mint`, for clients pre-validating a form
+    pub fn creation_policy(ctx: Context<GetCreationPolicy>) -> Result<CreationPolicy> {
+        Ok(ctx.accounts.config.creation_policy(&ctx.accounts.mint_whitelist))
+    }
+
+pub fn assign_contributor<'info>(ctx: Context<'_, '_, 'info, 'info, AssignContributor<'info>>, contributor_is_pda: bool) -> Result<BountyStateChange> {
     let bounty = &mut ctx.accounts.bounty;
 
     // Security checks
     require!(bounty.state == BountyState::Created, ContractError::InvalidBountyStateForOperation);
     require!(bounty.contributor.is_none(), ContractError::ContributorAlreadyAssigned);
     require!(bounty.maintainer == ctx.accounts.maintainer.key(), ContractError::MaintainerMismatch);
+    // Wallets are system owned; PDA contributors are paid by the keeper push and never sign
+    require!(
+        contributor_is_pda || ctx.accounts.contributor.owner == &System::id(),
+        ContractError::ContributorNotWallet
+    );
 
     let contributor_key = ctx.accounts.contributor.key();
+    require!(contributor_key != bounty.maintainer, ContractError::ContributorCannotBeMaintainer);
 
     bounty.contributor = Some(contributor_key);
-    bounty.state = BountyState::InProgress;
+    bounty.contributor_is_pda = contributor_is_pda;
+    bounty.transition(BountyState::InProgress)?;
+    ctx.accounts.count_by_state
+        .record(Some(&BountyState::Created), &BountyState::InProgress)
+        .ok_or(ContractError::ArithmeticOverflow)?;
+    emit!(StateTransition {
+        bounty_id: bounty.bounty_id,
+        from_state: BountyState::Created,
+        to_state: BountyState::InProgress,
+    });
 
     emit!(ContributorAssigned {
         bounty_id: bounty.bounty_id,
         contributor: contributor_key,
+        assigned_by: ctx.accounts.maintainer.key(),
+        via_admin: false,
     });
 
-    Ok(())
+    notify::notify(
+        ctx.accounts.config.notification_program,
+        ctx.remaining_accounts,
+        notify::KIND_ASSIGNED,
+        bounty.bounty_id,
+        contributor_key,
+    )?;
+
+    Ok(bounty.state_change())
 }
 
 
-    // Maintainer completes bounty and pays contributor
-    pub fn complete_bounty(ctx: Context<CompleteBounty>,bounty_id:u64) -> Result<()> {
+    // Redeem the escrowed LP tokens into their underlying when the bounty completes
+    pub fn set_unwrap_on_complete(ctx: Context<SetUnwrapOnComplete>, unwrap_on_complete: bool) -> Result<()> {
+        ctx.accounts.bounty.unwrap_on_complete = unwrap_on_complete;
+        Ok(())
+    }
+
+    // Let completion send the fee to `recipient`'s token account instead of the protocol; None turns it off
+    pub fn set_allow_custom_fee_recipient(ctx: Context<SetAllowCustomFeeRecipient>, recipient: Option<Pubkey>) -> Result<()> {
         let bounty = &mut ctx.accounts.bounty;
-        
-        // Security checks
-        require!(bounty.bounty_id == bounty_id, ContractError::InvalidBountyState);
-        require!(bounty.state == BountyState::InProgress, ContractError::InvalidBountyStateForOperation);
-        require!(bounty.contributor.is_some(), ContractError::InvalidContributor);
-        require!(bounty.contributor.unwrap() == ctx.accounts.contributor.key(), ContractError::InvalidContributor);
-        require!(bounty.mint == ctx.accounts.contributor_token_account.mint, ContractError::InvalidMint);
-        require!(bounty.mint == ctx.accounts.escrow_token_account.mint, ContractError::InvalidMint);
-      
-        let bounty_key = bounty.key();
-        let bump = bounty.bump;
-        let seeds = &[b"escrow_auth",bounty_key.as_ref(),&[bump]];
-        let binding = &[&seeds[..]];
+        bounty.allow_custom_fee_recipient = recipient.is_some();
+        bounty.custom_fee_recipient = recipient;
+        Ok(())
+    }
 
-        let cpi_ctx = CpiContext::new_with_signer(ctx.accounts.token_program.to_account_info(), Transfer{
-            from:ctx.accounts.escrow_token_account.to_account_info(),
-            to:ctx.accounts.contributor_token_account.to_account_info(),
-            authority:ctx.accounts.escrow_authority.to_account_info(),
-        }, binding);
+    pub fn set_require_contributor_ack(ctx: Context<SetRequireContributorAck>, require_ack: bool) -> Result<()> {
+        ctx.accounts.bounty.require_contributor_ack = require_ack;
+        Ok(())
+    }
 
-        let _ = transfer(cpi_ctx, bounty.amount)?;
+    // The assigned contributor pledges part of their payout to the configured charity
+    pub fn set_donate_bps(ctx: Context<SetDonateBps>, donate_bps: u16) -> Result<()> {
+        require!(donate_bps as u64 <= fees::BPS_DENOMINATOR, ContractError::InvalidDonationBps);
+        ctx.accounts.bounty.donate_bps = donate_bps;
+        Ok(())
+    }
 
-        // Now, close the escrow token account using a CPI to the token program
-        // The rent will be sent to the maintainer as specified in the context
-        let cpi_ctx = CpiContext::new_with_signer(
-            ctx.accounts.token_program.to_account_info(),
-            CloseAccount {
-                account: ctx.accounts.escrow_token_account.to_account_info(),
-                destination: ctx.accounts.maintainer.to_account_info(),
-                authority: ctx.accounts.escrow_authority.to_account_info(),
-            },
-            binding
-        );
+    // The assigned contributor picks the token they are paid in, and the least they accept for it
+    pub fn set_payout_mint(ctx: Context<SetPayoutMint>, payout_mint: Option<Pubkey>, min_out: u64) -> Result<()> {
+        let bounty = &mut ctx.accounts.bounty;
+        bounty.payout_mint = payout_mint.filter(|mint| *mint != bounty.mint);
+        bounty.payout_min_out = if bounty.payout_mint.is_some() { min_out } else { 0 };
+        Ok(())
+    }
 
-        close_account(cpi_ctx)?;
+    pub fn set_required_approvals(ctx: Context<SetRequiredApprovals>, required_approvals: u8) -> Result<()> {
+        require!(required_approvals as usize <= Bounty::MAX_APPROVALS, ContractError::TooManyApprovals);
+        require!(required_approvals as usize <= ctx.accounts.bounty.reviewers.len(), ContractError::NotEnoughReviewers);
+        ctx.accounts.bounty.required_approvals = required_approvals;
+        Ok(())
+    }
 
-        emit!(BountyCompleted {
-            bounty_id,
-            contributor: ctx.accounts.contributor.key(),
-            amount: bounty.amount,
-        });
-        
-        bounty.state = BountyState::Completed;
+    // Replaces the keys allowed to approve the contributor's work
+    pub fn set_reviewers(ctx: Context<SetReviewers>, reviewers: Vec<Pubkey>) -> Result<()> {
+        let mut reviewers = reviewers;
+        reviewers.sort();
+        reviewers.dedup();
+        require!(reviewers.len() <= Bounty::MAX_APPROVALS, ContractError::TooManyApprovals);
+        require!(ctx.accounts.bounty.required_approvals as usize <= reviewers.len(), ContractError::NotEnoughReviewers);
+        ctx.accounts.bounty.reviewers = reviewers;
         Ok(())
     }
 
+    pub fn set_grace_period(ctx: Context<SetGracePeriod>, grace_period: i64) -> Result<()> {
+        require!(grace_period >= 0, ContractError::InvalidGracePeriod);
+        ctx.accounts.bounty.grace_period = grace_period;
+        Ok(())
+    }
+
+    // With the override off, admin_assign_and_release can never move this bounty's escrow
+    pub fn set_admin_override_allowed(ctx: Context<SetAdminOverrideAllowed>, allowed: bool) -> Result<()> {
+        ctx.accounts.bounty.admin_override_allowed = allowed;
+        Ok(())
+    }
 
-    pub fn cancel_bounty(ctx: Context<CancelBounty>) -> Result<()> {
+    // Returns an open bounty's escrow to its maintainer once the deadline and grace period have passed.
+    // Permissionless, so a keeper can sweep expired bounties.
+    pub fn reclaim_expired(ctx: Context<ReclaimExpired>, bounty_id: u64) -> Result<BountyStateChange> {
+        reentrancy::enter(&mut ctx.accounts.bounty)?;
+        escrow::assert_escrow_authority(&ctx.accounts.escrow_authority, &ctx.accounts.bounty.key(), ctx.accounts.bounty.bump)?;
         let bounty = &mut ctx.accounts.bounty;
+        require!(bounty.bounty_id == bounty_id, ContractError::BountyIdMismatch);
+        require!(bounty.funder_count == 0, ContractError::FundersNotRefunded);
+        require!(bounty.yield_principal == 0, ContractError::YieldNotWithdrawn);
+        let expires_at = bounty.deadline.checked_add(bounty.grace_period).ok_or(ContractError::ArithmeticOverflow)?;
+        require!(Clock::get()?.unix_timestamp > expires_at, ContractError::BountyNotExpired);
+
         let bounty_key = bounty.key();
         let bump = bounty.bump;
-
-        require!(bounty.state != BountyState::Completed, ContractError::BountyAlreadyCompleted);
-        require!(bounty.state != BountyState::Cancelled, ContractError::BountyAlreadyCancelled);
-        require!(bounty.maintainer == ctx.accounts.maintainer.key(), ContractError::MaintainerMismatch);
-        require!(bounty.mint == ctx.accounts.maintainer_token_account.mint, ContractError::InvalidMint);
-        require!(bounty.mint == ctx.accounts.escrow_token_account.mint, ContractError::InvalidMint);
-    
-    
-        // Seeds for the PDA authority
-        let seeds = &[
-            b"escrow_auth",
-            bounty_key.as_ref(),
-            &[bump]
-        ];
+        let seeds = escrow::escrow_signer_seeds(&bounty_key, &bump);
         let signer = &[&seeds[..]];
-    
-        // First, transfer the tokens from the escrow back to the maintainer
-        let cpi_ctx = CpiContext::new_with_signer(
-            ctx.accounts.token_program.to_account_info(),
-            Transfer {
-                from: ctx.accounts.escrow_token_account.to_account_info(),
-                to: ctx.accounts.maintainer_token_account.to_account_info(),
-                authority: ctx.accounts.escrow_authority.to_account_info(),
-            },
-            signer
-        );
-    
-        transfer(cpi_ctx, bounty.amount)?;
-    
-        // Now, close the escrow token account using a CPI to the token program
-        // The rent will be sent to the maintainer as specified in the context
-        let cpi_ctx = CpiContext::new_with_signer(
+        let escrow_authority = ctx.accounts.escrow_authority.to_account_info();
+
+        let refund = ctx.accounts.escrow_token_account.amount;
+        transfer_from_escrow(&ctx.accounts.token_program, &ctx.accounts.escrow_token_account, &ctx.accounts.mint, &ctx.accounts.maintainer_token_account, &escrow_authority, signer, refund)?;
+        close_account(CpiContext::new_with_signer(
             ctx.accounts.token_program.to_account_info(),
             CloseAccount {
                 account: ctx.accounts.escrow_token_account.to_account_info(),
                 destination: ctx.accounts.maintainer.to_account_info(),
-                authority: ctx.accounts.escrow_authority.to_account_info(),
+                authority: escrow_authority,
             },
-            signer
-        );
-    
-        close_account(cpi_ctx)?;
-    
-        // The bounty account will be closed automatically by Anchor due to its 'close' constraint.
-        // The rent from the bounty account will also go to the maintainer.
-    
+            signer,
+        ))?;
+
         emit!(BountyCancelled {
-            bounty_id: bounty.bounty_id,
+            bounty_id,
             maintainer: ctx.accounts.maintainer.key(),
-            amount: bounty.amount,
+            amount: refund,
+            reason: CancelReason::ExpiryReclaim as u8,
+            milestones_paid: bounty.milestones_paid,
         });
-        
-        bounty.state = BountyState::Cancelled;
-        
+        emit!(BountyExpired {
+            bounty_id,
+            refunded: refund,
+            caller: ctx.accounts.caller.key(),
+        });
+
+        ctx.accounts.count_by_state
+            .record(Some(&bounty.state), &BountyState::Cancelled)
+            .ok_or(ContractError::ArithmeticOverflow)?;
+        emit!(StateTransition {
+            bounty_id,
+            from_state: bounty.state.clone(),
+            to_state: BountyState::Cancelled,
+        });
+        bounty.transition(BountyState::Cancelled)?;
+        emit!(BountyFinalSnapshot::new(bounty_key, bounty));
+
+        Ok(bounty.state_change())
+    }
+
+    // Replaces the set of keepers allowed to settle the bounty, an empty set lifts the restriction
+    pub fn set_allowed_keepers(ctx: Context<SetAllowedKeepers>, keepers: Vec<Pubkey>) -> Result<()> {
+        require!(keepers.len() <= Bounty::MAX_KEEPERS, ContractError::TooManyKeepers);
+        ctx.accounts.bounty.allowed_keepers = keepers;
         Ok(())
     }
-    pub fn update_admin(ctx: Context<UpdateAdmin>, new_admin: Pubkey) -> Result<()> {
-        // Security checks
-        require!(new_admin != Pubkey::default(), ContractError::InvalidBountyState);
-        require!(new_admin != ctx.accounts.admin.key(), ContractError::InvalidBountyState);
-        
-        let config = &mut ctx.accounts.config;
-        let old_admin = config.admin;
-        config.admin = new_admin; // Update to the new admin key
-        
-        emit!(AdminUpdated {
-            old_admin,
-            new_admin,
+
+    // A reviewer signs off on the contributor's work
+    pub fn approve_work(ctx: Context<ApproveWork>, bounty_id: u64) -> Result<()> {
+        let bounty = &mut ctx.accounts.bounty;
+        let reviewer = ctx.accounts.reviewer.key();
+        require!(bounty.bounty_id == bounty_id, ContractError::BountyIdMismatch);
+        require!(bounty.contributor != Some(reviewer), ContractError::ContributorCannotApprove);
+        require!(bounty.reviewers.contains(&reviewer), ContractError::ReviewerNotAllowed);
+        require!(!bounty.approvals.contains(&reviewer), ContractError::DuplicateApproval);
+        require!(bounty.approvals.len() < Bounty::MAX_APPROVALS, ContractError::TooManyApprovals);
+
+        bounty.approvals.push(reviewer);
+
+        emit!(WorkApproved {
+            bounty_id,
+            reviewer,
+            approvals: bounty.approvals.len() as u8,
         });
-        
+
+        Ok(())
+    }
+
+    pub fn set_contributor_merkle_root(ctx: Context<SetContributorMerkleRoot>, merkle_root: [u8; 32]) -> Result<()> {
+        ctx.accounts.bounty.merkle_root = merkle_root;
         Ok(())
     }
 
-    pub fn admin_assign_and_release(ctx: Context<AdminAssignAndRelease>, bounty_id: u64) -> Result<()> {
+    // A contributor on the bounty's allowlist assigns themselves
+    pub fn assign_with_proof(ctx: Context<AssignWithProof>, bounty_id: u64, proof: Vec<[u8; 32]>) -> Result<BountyStateChange> {
         let bounty = &mut ctx.accounts.bounty;
+        let contributor_key = ctx.accounts.contributor.key();
 
-        // Security checks
-        require!(bounty.bounty_id == bounty_id, ContractError::InvalidBountyState);
-        require!(bounty.mint == ctx.accounts.contributor_token_account.mint, ContractError::InvalidMint);
-        require!(bounty.mint == ctx.accounts.escrow_token_account.mint, ContractError::InvalidMint);
+        require!(bounty.bounty_id == bounty_id, ContractError::BountyIdMismatch);
+        require!(contributor_key != bounty.maintainer, ContractError::ContributorCannotBeMaintainer);
+        require!(bounty.merkle_root != [0u8; 32], ContractError::MerkleRootNotSet);
+        require!(
+            merkle::verify_proof(&proof, bounty.merkle_root, merkle::leaf(contributor_key.as_ref())),
+            ContractError::InvalidMerkleProof
+        );
 
-        // Get the new contributor key
-        let new_contributor_key = ctx.accounts.contributor.key();
-        
+        bounty.contributor = Some(contributor_key);
+        bounty.contributor_is_pda = false;
+        bounty.transition(BountyState::InProgress)?;
+        ctx.accounts.count_by_state
+            .record(Some(&BountyState::Created), &BountyState::InProgress)
+            .ok_or(ContractError::ArithmeticOverflow)?;
+        emit!(StateTransition {
+            bounty_id: bounty.bounty_id,
+            from_state: BountyState::Created,
+            to_state: BountyState::InProgress,
+        });
 
-        // Override with new contributor (admin super power)
-        bounty.contributor = Some(new_contributor_key);
-        bounty.state = BountyState::InProgress;
-        
-        // Emit event for contributor assignment
-        emit!(ContributorAssigned { bounty_id: bounty.bounty_id, contributor: new_contributor_key });
+        emit!(ContributorAssigned {
+            bounty_id: bounty.bounty_id,
+            contributor: contributor_key,
+            assigned_by: contributor_key,
+            via_admin: false,
+        });
 
-        // Release funds from escrow to new contributor
-        let bounty_key = bounty.key();
-        let bump = bounty.bump;
-        let seeds = &[b"escrow_auth", bounty_key.as_ref(), &[bump]];
-        let signer = &[&seeds[..]];
+        Ok(bounty.state_change())
+    }
 
-        let cpi_ctx = CpiContext::new_with_signer(
+
+    // Maintainer tops up the escrow of an open bounty
+    pub fn increase_bounty(ctx: Context<IncreaseBounty>, bounty_id: u64, amount: u64) -> Result<()> {
+        reentrancy::enter(&mut ctx.accounts.bounty)?;
+        let bounty = &mut ctx.accounts.bounty;
+        require!(bounty.bounty_id == bounty_id, ContractError::BountyIdMismatch);
+        require!(amount > 0, ContractError::InvalidAmount);
+        // Changing the amount would break the milestone schedule
+        require!(bounty.milestones.is_empty(), ContractError::InvalidMilestones);
+
+        let new_amount = bounty.amount.checked_add(amount).ok_or(ContractError::AmountExceedsSupply)?;
+        require!(new_amount <= ctx.accounts.mint.supply, ContractError::AmountExceedsSupply);
+
+        let cpi_ctx = CpiContext::new(
             ctx.accounts.token_program.to_account_info(),
-            Transfer {
-                from: ctx.accounts.escrow_token_account.to_account_info(),
-                to: ctx.accounts.contributor_token_account.to_account_info(),
-                authority: ctx.accounts.escrow_authority.to_account_info(),
+            TransferChecked {
+                from: ctx.accounts.maintainer_token_account.to_account_info(),
+                mint: ctx.accounts.mint.to_account_info(),
+                to: ctx.accounts.escrow_token_account.to_account_info(),
+                authority: ctx.accounts.maintainer.to_account_info(),
             },
-            signer,
         );
-        transfer(cpi_ctx, bounty.amount)?;
+        transfer_checked(cpi_ctx, amount, ctx.accounts.mint.decimals)?;
 
-        // Now, close the escrow token account using a CPI to the token program
-        // The rent will be sent to the maintainer as specified in the context
-        let cpi_ctx = CpiContext::new_with_signer(
+        bounty.amount = new_amount;
+
+        emit!(BountyIncreased {
+            bounty_id,
+            added: amount,
+            new_amount,
+        });
+
+        reentrancy::leave(bounty);
+        Ok(())
+    }
+
+    // Anyone can add to a bounty's reward; each funder's share is tracked so a cancel can refund it
+    pub fn fund_bounty(ctx: Context<FundBounty>, bounty_id: u64, amount: u64) -> Result<()> {
+        reentrancy::enter(&mut ctx.accounts.bounty)?;
+        let bounty = &mut ctx.accounts.bounty;
+        require!(bounty.bounty_id == bounty_id, ContractError::BountyIdMismatch);
+        require!(amount > 0, ContractError::InvalidAmount);
+        require!(bounty.milestones.is_empty(), ContractError::InvalidMilestones);
+
+        let new_amount = bounty.amount.checked_add(amount).ok_or(ContractError::AmountExceedsSupply)?;
+        require!(new_amount <= ctx.accounts.mint.supply, ContractError::AmountExceedsSupply);
+
+        let cpi_ctx = CpiContext::new(
             ctx.accounts.token_program.to_account_info(),
-            CloseAccount {
-                account: ctx.accounts.escrow_token_account.to_account_info(),
-                destination: ctx.accounts.maintainer.to_account_info(),
-                authority: ctx.accounts.escrow_authority.to_account_info(),
+            TransferChecked {
+                from: ctx.accounts.funder_token_account.to_account_info(),
+                mint: ctx.accounts.mint.to_account_info(),
+                to: ctx.accounts.escrow_token_account.to_account_info(),
+                authority: ctx.accounts.funder.to_account_info(),
             },
-            signer
         );
+        transfer_checked(cpi_ctx, amount, ctx.accounts.mint.decimals)?;
 
-        close_account(cpi_ctx)?;
+        let contribution = &mut ctx.accounts.contribution;
+        if contribution.amount == 0 {
+            contribution.bounty = bounty.key();
+            contribution.funder = ctx.accounts.funder.key();
+            contribution.bump = ctx.bumps.contribution;
+            bounty.funder_count = bounty.funder_count.checked_add(1).ok_or(ContractError::ArithmeticOverflow)?;
+        }
+        contribution.amount = contribution.amount.checked_add(amount).ok_or(ContractError::ArithmeticOverflow)?;
+        bounty.amount = new_amount;
 
-        // Emit completion event
-        emit!(BountyCompleted {
+        emit!(BountyFunded {
             bounty_id,
-            contributor: new_contributor_key,
-            amount: bounty.amount,
+            funder: ctx.accounts.funder.key(),
+            added: amount,
+            contribution: contribution.amount,
+            new_amount,
         });
 
-        bounty.state = BountyState::Completed;
+        reentrancy::leave(bounty);
         Ok(())
     }
-        
 
+    // Anyone may add to the reward as a tip. Unlike `fund_bounty` no Contribution is recorded, so
+    // the tipper is not a funder and a cancelled bounty's tips go back with the maintainer's share.
+    pub fn tip_bounty(ctx: Context<TipBounty>, bounty_id: u64, amount: u64) -> Result<()> {
+        reentrancy::enter(&mut ctx.accounts.bounty)?;
+        let bounty = &mut ctx.accounts.bounty;
+        require!(bounty.bounty_id == bounty_id, ContractError::BountyIdMismatch);
+        require!(amount > 0, ContractError::InvalidAmount);
+        require!(bounty.milestones.is_empty(), ContractError::InvalidMilestones);
+
+        let new_amount = bounty.amount.checked_add(amount).ok_or(ContractError::AmountExceedsSupply)?;
+        require!(new_amount <= ctx.accounts.mint.supply, ContractError::AmountExceedsSupply);
+
+        let cpi_ctx = CpiContext::new(
+            ctx.accounts.token_program.to_account_info(),
+            TransferChecked {
+                from: ctx.accounts.tipper_token_account.to_account_info(),
+                mint: ctx.accounts.mint.to_account_info(),
+                to: ctx.accounts.escrow_token_account.to_account_info(),
+                authority: ctx.accounts.tipper.to_account_info(),
+            },
+        );
+        transfer_checked(cpi_ctx, amount, ctx.accounts.mint.decimals)?;
+
+        bounty.tipped_amount = bounty.tipped_amount.checked_add(amount).ok_or(ContractError::ArithmeticOverflow)?;
+        bounty.amount = new_amount;
+
+        emit!(BountyTipped {
+            bounty_id,
+            tipper: ctx.accounts.tipper.key(),
+            amount,
+            new_amount,
+        });
+
+        reentrancy::leave(bounty);
+        Ok(())
+    }
+
+    // Cancels a crowd-funded bounty a few funders at a time, see RefundFundersBatch for the
+    // account layout. Each call picks up after the last; the call that refunds the final funder
+    // returns the rest to the maintainer and closes the escrow and bounty.
+    pub fn refund_funders_batch<'info>(ctx: Context<'_, '_, 'info, 'info, RefundFundersBatch<'info>>, bounty_id: u64) -> Result<BountyStateChange> {
+        ctx.accounts.config.require_power(AdminPower::Cancel)?;
+        reentrancy::lock(&mut ctx.accounts.bounty)?;
+        escrow::assert_escrow_authority(&ctx.accounts.escrow_authority, &ctx.accounts.bounty.key(), ctx.accounts.bounty.bump)?;
+        require!(ctx.remaining_accounts.len().is_multiple_of(3), ContractError::InvalidRefundAccounts);
+        let bounty = &mut ctx.accounts.bounty;
+        require!(bounty.bounty_id == bounty_id, ContractError::BountyIdMismatch);
+        require!(bounty.funder_count > 0, ContractError::NotCrowdFunded);
+        require!(bounty.yield_principal == 0, ContractError::YieldNotWithdrawn);
+
+        let bounty_key = bounty.key();
+        let bump = bounty.bump;
+        let seeds = escrow::escrow_signer_seeds(&bounty_key, &bump);
+        let signer = &[&seeds[..]];
+        let escrow_authority = ctx.accounts.escrow_authority.to_account_info();
+
+        let mut escrow_balance = ctx.accounts.escrow_token_account.amount;
+        for chunk in ctx.remaining_accounts.chunks(3) {
+            let (contribution_info, funder_token_info, funder_info) = (&chunk[0], &chunk[1], &chunk[2]);
+            // A contribution refunded earlier in the batch is already closed and fails to load here
+            let contribution = Account::<Contribution>::try_from(contribution_info)?;
+            require_keys_eq!(contribution.bounty, bounty_key, ContractError::InvalidContribution);
+            require_keys_eq!(funder_info.key(), contribution.funder, ContractError::InvalidContribution);
+            let funder_token_account = InterfaceAccount::<TokenAccount>::try_from(funder_token_info)?;
+            require!(validate::mint_matches(&funder_token_account, &bounty.mint), ContractError::InvalidMint);
+            require!(validate::owner_matches(&funder_token_account, &contribution.funder), ContractError::InvalidTokenAccount);
+
+            // Partial releases are paid out of the pooled escrow, so a refund is capped by what is left
+            let amount = contribution.amount.min(escrow_balance);
+            transfer_from_escrow(&ctx.accounts.token_program, &ctx.accounts.escrow_token_account, &ctx.accounts.mint, &funder_token_account, &escrow_authority, signer, amount)?;
+            escrow_balance -= amount;
+            bounty.amount = bounty.amount.saturating_sub(amount);
+            contribution.close(funder_info.clone())?;
+            bounty.refunded_count = bounty.refunded_count.checked_add(1).ok_or(ContractError::ArithmeticOverflow)?;
+
+            emit!(FunderRefunded {
+                bounty_id,
+                funder: contribution.funder,
+                amount,
+                refunded_count: bounty.refunded_count,
+                funder_count: bounty.funder_count,
+            });
+        }
+
+        if bounty.refunded_count < bounty.funder_count {
+            reentrancy::leave(bounty);
+            return Ok(bounty.state_change());
+        }
+
+        // Every funder is refunded: what remains is the maintainer's own share
+        transfer_from_escrow(&ctx.accounts.token_program, &ctx.accounts.escrow_token_account, &ctx.accounts.mint, &ctx.accounts.maintainer_token_account, &escrow_authority, signer, escrow_balance)?;
+        close_account(CpiContext::new_with_signer(
+            ctx.accounts.token_program.to_account_info(),
+            CloseAccount {
+                account: ctx.accounts.escrow_token_account.to_account_info(),
+                destination: ctx.accounts.maintainer.to_account_info(),
+                authority: escrow_authority,
+            },
+            signer,
+        ))?;
 
+        emit!(BountyCancelled {
+            bounty_id,
+            maintainer: ctx.accounts.maintainer.key(),
+            amount: escrow_balance,
+            reason: CancelReason::AdminCancel as u8,
+            milestones_paid: bounty.milestones_paid,
+        });
 
-}
\ No newline at end of file
+        ctx.accounts.count_by_state
+            .record(Some(&bounty.state), &BountyState::Cancelled)
+            .ok_or(ContractError::ArithmeticOverflow)?;
+        emit!(StateTransition {
+            bounty_id,
+            from_state: bounty.state.clone(),
+            to_state: BountyState::Cancelled,
+        });
+        bounty.transition(BountyState::Cancelled)?;
+        emit!(BountyFinalSnapshot::new(bounty_key, bounty));
+
+        let state_change = bounty.state_change();
+        bounty.close(ctx.accounts.rent_payer.to_account_info())?;
+        Ok(state_change)
+    }
+
+    // Lets a contributor check the escrow actually holds the reward before starting work
+    pub fn verify_funded(ctx: Context<VerifyFunded>, bounty_id: u64) -> Result<bool> {
+        let bounty = &ctx.accounts.bounty;
+        require!(bounty.bounty_id == bounty_id, ContractError::BountyIdMismatch);
+
+        let assignable = bounty.state == BountyState::Created && bounty.contributor.is_none();
+        Ok(assignable && ctx.accounts.escrow_token_account.amount >= bounty.amount)
+    }
+
+
+    // Contributor applies to an open bounty
+    pub fn apply_to_bounty(ctx: Context<ApplyToBounty>) -> Result<()> {
+        let list = &mut ctx.accounts.applicant_list;
+        let contributor_key = ctx.accounts.contributor.key();
+
+        if list.bounty == Pubkey::default() {
+            list.bounty = ctx.accounts.bounty.key();
+            list.bump = ctx.bumps.applicant_list;
+        }
+        require!(!list.applicants.contains(&contributor_key), ContractError::AlreadyApplied);
+        require!(list.applicants.len() < ApplicantList::MAX_APPLICANTS, ContractError::ApplicantListFull);
+
+        list.applicants.push(contributor_key);
+
+        emit!(ContributorApplied {
+            bounty_id: ctx.accounts.bounty.bounty_id,
+            contributor: contributor_key,
+            index: (list.applicants.len() - 1) as u8,
+        });
+
+        Ok(())
+    }
+
+    // Maintainer picks one of the applicants by position in the list
+    pub fn assign_from_applicants(ctx: Context<AssignFromApplicants>, index: u8) -> Result<BountyStateChange> {
+        let contributor_key = *ctx.accounts.applicant_list.applicants
+            .get(index as usize)
+            .ok_or(ContractError::InvalidApplicantIndex)?;
+
+        let bounty = &mut ctx.accounts.bounty;
+        require!(contributor_key != bounty.maintainer, ContractError::ContributorCannotBeMaintainer);
+        bounty.contributor = Some(contributor_key);
+        bounty.contributor_is_pda = false;
+        bounty.transition(BountyState::InProgress)?;
+        ctx.accounts.count_by_state
+            .record(Some(&BountyState::Created), &BountyState::InProgress)
+            .ok_or(ContractError::ArithmeticOverflow)?;
+        emit!(StateTransition {
+            bounty_id: bounty.bounty_id,
+            from_state: BountyState::Created,
+            to_state: BountyState::InProgress,
+        });
+
+        emit!(ContributorAssigned {
+            bounty_id: bounty.bounty_id,
+            contributor: contributor_key,
+            assigned_by: ctx.accounts.maintainer.key(),
+            via_admin: false,
+        });
+
+        Ok(bounty.state_change())
+    }
+
+
+    // Maintainer swaps the bounty to a different token in one transaction:
+    // refund and close the old escrow, then fund a new one. Id and contributor are kept.
+    pub fn migrate_bounty_mint(ctx: Context<MigrateBountyMint>, bounty_id: u64, new_amount: u64) -> Result<()> {
+        reentrancy::enter(&mut ctx.accounts.bounty)?;
+        let bounty = &ctx.accounts.bounty;
+        require!(bounty.bounty_id == bounty_id, ContractError::BountyIdMismatch);
+        require!(new_amount > 0, ContractError::InvalidAmount);
+        require!(new_amount <= ctx.accounts.new_mint.supply, ContractError::AmountExceedsSupply);
+        require!(bounty.milestones.is_empty(), ContractError::InvalidMilestones);
+        require!(bounty.yield_principal == 0, ContractError::YieldNotWithdrawn);
+        bounty.assert_mint(&ctx.accounts.escrow_token_account.mint, &ctx.accounts.maintainer_token_account.mint)?;
+
+        let bounty_key = bounty.key();
+        let bump = bounty.bump;
+        let seeds = escrow::escrow_signer_seeds(&bounty_key, &bump);
+        let signer = &[&seeds[..]];
+
+        let refunded_amount = ctx.accounts.escrow_token_account.amount;
+        let cpi_ctx = CpiContext::new_with_signer(
+            ctx.accounts.token_program.to_account_info(),
+            TransferChecked {
+                from: ctx.accounts.escrow_token_account.to_account_info(),
+                mint: ctx.accounts.mint.to_account_info(),
+                to: ctx.accounts.maintainer_token_account.to_account_info(),
+                authority: ctx.accounts.escrow_authority.to_account_info(),
+            },
+            signer,
+        );
+        transfer_checked(cpi_ctx, refunded_amount, ctx.accounts.mint.decimals)?;
+
+        let cpi_ctx = CpiContext::new_with_signer(
+            ctx.accounts.token_program.to_account_info(),
+            CloseAccount {
+                account: ctx.accounts.escrow_token_account.to_account_info(),
+                destination: ctx.accounts.maintainer.to_account_info(),
+                authority: ctx.accounts.escrow_authority.to_account_info(),
+            },
+            signer,
+        );
+        close_account(cpi_ctx)?;
+
+        let cpi_ctx = CpiContext::new(
+            ctx.accounts.token_program.to_account_info(),
+            TransferChecked {
+                from: ctx.accounts.new_maintainer_token_account.to_account_info(),
+                mint: ctx.accounts.new_mint.to_account_info(),
+                to: ctx.accounts.new_escrow_token_account.to_account_info(),
+                authority: ctx.accounts.maintainer.to_account_info(),
+            },
+        );
+        transfer_checked(cpi_ctx, new_amount, ctx.accounts.new_mint.decimals)?;
+
+        let old_mint = ctx.accounts.mint.key();
+        let new_mint = ctx.accounts.new_mint.key();
+        let bounty = &mut ctx.accounts.bounty;
+        bounty.mint = new_mint;
+        bounty.amount = new_amount;
+
+        emit!(BountyMintMigrated {
+            bounty_id,
+            old_mint,
+            new_mint,
+            refunded_amount,
+            new_amount,
+        });
+
+        reentrancy::leave(bounty);
+        Ok(())
+    }
+
+
+    // Where the maintainer's bounty with `bounty_id` lives (or will be created), so clients can
+    // check their own derivation against the program's
+    pub fn derive_bounty_address(_ctx: Context<DeriveBountyAddress>, maintainer: Pubkey, bounty_id: u64) -> Result<BountyAddress> {
+        let (address, bump) = Bounty::find_address(&maintainer, bounty_id);
+        Ok(BountyAddress { address, bump })
+    }
+
+
+    // Maintainer completes bounty and pays contributor
+    // `deliverable_hash` must match the contributor's commitment when they made one, see `commit_deliverable`
+    pub fn complete_bounty<'info>(
+        ctx: Context<'_, '_, 'info, 'info, CompleteBounty<'info>>,
+        bounty_id: u64,
+        rating: u8,
+        deliverable_hash: Option<[u8; 32]>,
+    ) -> Result<BountyStateChange> {
+        reentrancy::enter(&mut ctx.accounts.bounty)?;
+        escrow::assert_escrow_authority(&ctx.accounts.escrow_authority, &ctx.accounts.bounty.key(), ctx.accounts.bounty.bump)?;
+        let bounty = &mut ctx.accounts.bounty;
+        
+        // Security checks
+        require!(bounty.bounty_id == bounty_id, ContractError::BountyIdMismatch);
+        require!((UserStats::MIN_RATING..=UserStats::MAX_RATING).contains(&rating), ContractError::InvalidRating);
+        require!(bounty.state == BountyState::InProgress, ContractError::InvalidBountyStateForOperation);
+        require!(bounty.assigned_contributor()? == ctx.accounts.contributor.key(), ContractError::InvalidContributor);
+        require_keys_eq!(ctx.accounts.mint.key(), bounty.mint, ContractError::InvalidMint);
+        // The contributor account is pinned to their ATA for bounty.mint, so only the escrow needs checking
+        require_keys_eq!(ctx.accounts.escrow_token_account.mint, bounty.mint, ContractError::InvalidMint);
+        require!(bounty.yield_principal == 0, ContractError::YieldNotWithdrawn);
+        bounty.check_release_gates(ctx.accounts.contributor.is_signer, deliverable_hash)?;
+        require!(
+            ctx.accounts.config.allow_transfer_hooks || !token_ext::has_transfer_hook(&ctx.accounts.mint.to_account_info())?,
+            ContractError::TransferHookNotAllowed
+        );
+
+        let ata_created = ctx.accounts.contributor_token_account.data_is_empty();
+        if ata_created {
+            associated_token::create(CpiContext::new(
+                ctx.accounts.associated_token_program.to_account_info(),
+                associated_token::Create {
+                    payer: ctx.accounts.admin.to_account_info(),
+                    associated_token: ctx.accounts.contributor_token_account.to_account_info(),
+                    authority: ctx.accounts.contributor.to_account_info(),
+                    mint: ctx.accounts.mint.to_account_info(),
+                    system_program: ctx.accounts.system_program.to_account_info(),
+                    token_program: ctx.accounts.token_program.to_account_info(),
+                },
+            ))?;
+        }
+      
+        let bounty_key = bounty.key();
+        let bump = bounty.bump;
+        let seeds = escrow::escrow_signer_seeds(&bounty_key, &bump);
+        let binding = &[&seeds[..]];
+
+        // Protocol fee is split between the treasury and the staker pool, the rest goes to the contributor
+        let fee_bps = fees::discounted_fee_bps(
+            fees::base_fee_bps(ctx.accounts.config.fee_bps, &ctx.accounts.mint_fee_config)?,
+            ctx.accounts.config.fee_discount_max_bps,
+            bounty.created_at,
+            bounty.deadline,
+            Clock::get()?.unix_timestamp,
+        );
+        let fee = fees::apply_bps(bounty.amount, fee_bps)?;
+        // A bounty may send its whole fee to a recipient of its choosing instead of the protocol
+        let (treasury_fee, staker_fee, treasury_account) = match &ctx.accounts.fee_recipient {
+            Some(fee_recipient) => (fee, 0, Some(fee_recipient)),
+            None => {
+                let (treasury_fee, staker_fee) = fees::split_fee(fee, ctx.accounts.config.fee_treasury_bps)?;
+                (treasury_fee, staker_fee, ctx.accounts.treasury_token_account.as_ref())
+            }
+        };
+
+        let fee_stats = &mut ctx.accounts.fee_epoch_stats;
+        fee_stats.mint = ctx.accounts.mint.key();
+        fee_stats.bump = ctx.bumps.fee_epoch_stats;
+        fee_stats.record(Clock::get()?.epoch, fee).ok_or(ContractError::ArithmeticOverflow)?;
+
+        let escrow_authority = ctx.accounts.escrow_authority.to_account_info();
+        let dust = fees::escrow_dust(ctx.accounts.escrow_token_account.amount, bounty.amount);
+        let payout = EscrowPayout {
+            bounty_id,
+            contributor: ctx.accounts.contributor.key(),
+            burn_bps: ctx.accounts.config.burn_bps,
+            donate_bps: bounty.donate_bps,
+            token_program: &ctx.accounts.token_program,
+            mint: &ctx.accounts.mint,
+            escrow_token_account: &ctx.accounts.escrow_token_account,
+            escrow_authority: &escrow_authority,
+            signer: binding,
+            treasury_token_account: treasury_account,
+            staker_token_account: ctx.accounts.staker_token_account.as_ref(),
+            charity_token_account: ctx.accounts.charity_token_account.as_ref(),
+        };
+
+        let contributor_amount = if let Some(sponsor_token_account) = &ctx.accounts.sponsor_token_account {
+            // Sponsor covers the fee, the contributor receives the full amount
+            let sponsor = ctx.accounts.sponsor.as_ref().ok_or(ContractError::SponsorMissing)?;
+            require_keys_eq!(sponsor_token_account.owner, sponsor.key(), ContractError::InvalidTokenAccount);
+
+            for (destination, share) in [
+                (treasury_account, treasury_fee),
+                (ctx.accounts.staker_token_account.as_ref(), staker_fee),
+            ] {
+                if share == 0 {
+                    continue;
+                }
+                let destination = destination.ok_or(ContractError::FeeAccountMissing)?;
+                let cpi_ctx = CpiContext::new(
+                    ctx.accounts.token_program.to_account_info(),
+                    TransferChecked {
+                        from: sponsor_token_account.to_account_info(),
+                        mint: ctx.accounts.mint.to_account_info(),
+                        to: destination.to_account_info(),
+                        authority: sponsor.to_account_info(),
+                    },
+                );
+                transfer_checked(cpi_ctx, share, ctx.accounts.mint.decimals)?;
+            }
+
+            emit!(FeeSponsored {
+                bounty_id,
+                sponsor: sponsor.key(),
+                fee,
+            });
+            bounty.amount
+        } else {
+            payout.pay_fee(treasury_fee, staker_fee)?;
+            bounty.amount - fee
+        };
+
+        // Deflationary share of the payout is destroyed before the contributor is paid
+        let mut contributor_amount = payout.burn_and_donate(contributor_amount)?;
+        // The admin paid rent for the contributor's new ATA; the treasury recoups it from their payout
+        if ata_created && ctx.accounts.config.ata_creation_fee > 0 {
+            let ata_fee = ctx.accounts.config.ata_creation_fee.min(contributor_amount);
+            let treasury_token_account = ctx.accounts.treasury_token_account.as_ref().ok_or(ContractError::FeeAccountMissing)?;
+            transfer_from_escrow(&ctx.accounts.token_program, &ctx.accounts.escrow_token_account, &ctx.accounts.mint, treasury_token_account, &escrow_authority, binding, ata_fee)?;
+            contributor_amount -= ata_fee;
+            emit!(AtaCreationFeeCharged {
+                bounty_id,
+                contributor: ctx.accounts.contributor.key(),
+                amount: ata_fee,
+            });
+        }
+        let contributor_amount = contributor_amount + dust;
+        if dust > 0 {
+            emit!(DustSwept {
+                bounty_id,
+                contributor: ctx.accounts.contributor.key(),
+                amount: dust,
+            });
+        }
+
+        if let Some(payout_mint) = bounty.payout_mint {
+            // The contributor asked to be paid in another token; the swap has to clear their min_out
+            let amm_program = ctx.accounts.amm_program.as_ref().ok_or(ContractError::PayoutSwapAccountsMissing)?;
+            require!(ctx.accounts.config.amm_program == Some(amm_program.key()), ContractError::InvalidAmmProgram);
+            let destination = ctx.accounts.contributor_underlying_token_account.as_mut().ok_or(ContractError::PayoutSwapAccountsMissing)?;
+            require_keys_eq!(destination.mint, payout_mint, ContractError::InvalidMint);
+
+            let escrow_before = ctx.accounts.escrow_token_account.amount;
+            let out_before = destination.amount;
+            let pool_accounts: Vec<AccountInfo<'info>> = ctx.remaining_accounts
+                .iter()
+                .filter(|acc| Some(acc.key()) != ctx.accounts.config.notification_program)
+                .cloned()
+                .collect();
+            swap::swap_tokens(
+                &amm_program.to_account_info(),
+                &escrow_authority,
+                &ctx.accounts.escrow_token_account.to_account_info(),
+                &destination.to_account_info(),
+                &ctx.accounts.token_program.to_account_info(),
+                &pool_accounts,
+                contributor_amount,
+                bounty.payout_min_out,
+                binding,
+            )?;
+
+            ctx.accounts.escrow_token_account.reload()?;
+            destination.reload()?;
+            require!(
+                escrow_before.checked_sub(ctx.accounts.escrow_token_account.amount) == Some(contributor_amount),
+                ContractError::SwapFailed
+            );
+            let amount_out = destination.amount.saturating_sub(out_before);
+            require!(amount_out >= bounty.payout_min_out, ContractError::SlippageExceeded);
+
+            emit!(PayoutSwapped {
+                bounty_id,
+                contributor: ctx.accounts.contributor.key(),
+                payout_mint,
+                amount_in: contributor_amount,
+                amount_out,
+            });
+        } else {
+            match (bounty.unwrap_on_complete, &ctx.accounts.amm_program, &mut ctx.accounts.contributor_underlying_token_account) {
+                (true, Some(amm_program), Some(destination)) => {
+                    require!(ctx.accounts.config.amm_program == Some(amm_program.key()), ContractError::InvalidAmmProgram);
+
+                    // A failing unwrap reverts the whole completion, so the LP principal never leaves the
+                    // escrow unpaid; completing again without the AMM accounts pays out the LP tokens instead
+                    let escrow_before = ctx.accounts.escrow_token_account.amount;
+                    let underlying_before = destination.amount;
+                    let pool_accounts: Vec<AccountInfo<'info>> = ctx.remaining_accounts
+                        .iter()
+                        .filter(|acc| Some(acc.key()) != ctx.accounts.config.notification_program)
+                        .cloned()
+                        .collect();
+                    unwrap::unwrap(
+                        &amm_program.to_account_info(),
+                        &escrow_authority,
+                        &ctx.accounts.escrow_token_account.to_account_info(),
+                        &destination.to_account_info(),
+                        &ctx.accounts.token_program.to_account_info(),
+                        &pool_accounts,
+                        contributor_amount,
+                        binding,
+                    )?;
+
+                    ctx.accounts.escrow_token_account.reload()?;
+                    destination.reload()?;
+                    require!(
+                        escrow_before.checked_sub(ctx.accounts.escrow_token_account.amount) == Some(contributor_amount),
+                        ContractError::UnwrapFailed
+                    );
+                    let underlying_amount = destination.amount.saturating_sub(underlying_before);
+                    require!(underlying_amount > 0, ContractError::UnwrapFailed);
+
+                    emit!(LpUnwrapped {
+                        bounty_id,
+                        contributor: ctx.accounts.contributor.key(),
+                        lp_amount: contributor_amount,
+                        underlying_amount,
+                    });
+                }
+                _ => transfer_checked(
+                    CpiContext::new_with_signer(
+                        ctx.accounts.token_program.to_account_info(),
+                        TransferChecked {
+                            from: ctx.accounts.escrow_token_account.to_account_info(),
+                            mint: ctx.accounts.mint.to_account_info(),
+                            to: ctx.accounts.contributor_token_account.to_account_info(),
+                            authority: escrow_authority.clone(),
+                        },
+                        binding,
+                    ),
+                    contributor_amount,
+                    ctx.accounts.mint.decimals,
+                )?,
+            }
+        }
+
+        // Now, close the escrow token account using a CPI to the token program
+        // The rent will be sent to the maintainer as specified in the context
+        let cpi_ctx = CpiContext::new_with_signer(
+            ctx.accounts.token_program.to_account_info(),
+            CloseAccount {
+                account: ctx.accounts.escrow_token_account.to_account_info(),
+                destination: ctx.accounts.maintainer.to_account_info(),
+                authority: ctx.accounts.escrow_authority.to_account_info(),
+            },
+            binding
+        );
+
+        close_account(cpi_ctx)?;
+
+        emit!(BountyCompleted {
+            bounty_id,
+            contributor: ctx.accounts.contributor.key(),
+            amount: bounty.amount,
+            fee,
+        });
+        let global_stats = &mut ctx.accounts.global_stats;
+        global_stats.bump = ctx.bumps.global_stats;
+        global_stats
+            .record_receipt(bounty_id, &ctx.accounts.contributor.key(), bounty.amount)
+            .ok_or(ContractError::ArithmeticOverflow)?;
+
+        // Onboarding bonus, skipped rather than failing the payout when the reserve runs dry
+        let bonus = ctx.accounts.config.first_completion_bonus_lamports;
+        if bonus > 0 && ctx.accounts.contributor_stats.bounties_completed == 0 {
+            if let (Some(reserve), Some(reserve_bump)) = (&ctx.accounts.bonus_reserve, ctx.bumps.bonus_reserve) {
+                let spendable = reserve.lamports().saturating_sub(Rent::get()?.minimum_balance(0));
+                if spendable >= bonus {
+                    system_program::transfer(
+                        CpiContext::new_with_signer(
+                            ctx.accounts.system_program.to_account_info(),
+                            system_program::Transfer {
+                                from: reserve.to_account_info(),
+                                to: ctx.accounts.contributor.to_account_info(),
+                            },
+                            &[&[b"bonus_reserve", &[reserve_bump]]],
+                        ),
+                        bonus,
+                    )?;
+                    emit!(FirstCompletionBonusPaid {
+                        bounty_id,
+                        contributor: ctx.accounts.contributor.key(),
+                        lamports: bonus,
+                    });
+                }
+            }
+        }
+
+        let stats = &mut ctx.accounts.contributor_stats;
+        stats.user = ctx.accounts.contributor.key();
+        stats.bump = ctx.bumps.contributor_stats;
+        stats.bounties_completed = stats.bounties_completed.checked_add(1).ok_or(ContractError::ArithmeticOverflow)?;
+        stats.record_rating(rating).ok_or(ContractError::InvalidRating)?;
+
+        emit!(ContributorRated {
+            bounty_id,
+            contributor: stats.user,
+            rating,
+            average_rating_x100: stats.average_rating_x100,
+            rating_count: stats.rating_count,
+        });
+        
+        ctx.accounts.count_by_state
+            .record(Some(&bounty.state), &BountyState::Completed)
+            .ok_or(ContractError::ArithmeticOverflow)?;
+        emit!(StateTransition {
+            bounty_id: bounty.bounty_id,
+            from_state: bounty.state.clone(),
+            to_state: BountyState::Completed,
+        });
+        bounty.transition(BountyState::Completed)?;
+        emit!(BountyFinalSnapshot::new(bounty.key(), bounty));
+
+        notify::notify(
+            ctx.accounts.config.notification_program,
+            ctx.remaining_accounts,
+            notify::KIND_COMPLETED,
+            bounty_id,
+            ctx.accounts.contributor.key(),
+        )?;
+        Ok(bounty.state_change())
+    }
+
+
+    // Contributor commits up front to what they will deliver; completion must then present the same hash
+    pub fn commit_deliverable(ctx: Context<CommitDeliverable>, bounty_id: u64, hash: [u8; 32]) -> Result<()> {
+        let bounty = &mut ctx.accounts.bounty;
+        require!(bounty.bounty_id == bounty_id, ContractError::BountyIdMismatch);
+        require!(bounty.deliverable_commitment.is_none(), ContractError::DeliverableAlreadyCommitted);
+        bounty.deliverable_commitment = Some(hash);
+        emit!(DeliverableCommitted {
+            bounty_id,
+            contributor: ctx.accounts.contributor.key(),
+            hash,
+        });
+        Ok(())
+    }
+
+    // Starts the auto-release window, after which anyone may settle the bounty with `crank_auto_release`
+    pub fn submit_work(ctx: Context<SubmitWork>, bounty_id: u64) -> Result<()> {
+        let bounty = &mut ctx.accounts.bounty;
+        require!(bounty.bounty_id == bounty_id, ContractError::BountyIdMismatch);
+        bounty.submitted_at = Clock::get()?.unix_timestamp;
+        emit!(WorkSubmitted {
+            bounty_id,
+            contributor: ctx.accounts.contributor.key(),
+            submitted_at: bounty.submitted_at,
+        });
+        Ok(())
+    }
+
+    // Settles submitted work nobody reviewed within config.auto_release_window; the cranker
+    // is paid config.crank_reward_bps of the protocol fee. The bounty's approvals, contributor
+    // ack and deliverable commitment still apply, `deliverable_hash` is checked against the latter.
+    pub fn crank_auto_release(ctx: Context<CrankAutoRelease>, bounty_id: u64, deliverable_hash: Option<[u8; 32]>) -> Result<BountyStateChange> {
+        reentrancy::enter(&mut ctx.accounts.bounty)?;
+        escrow::assert_escrow_authority(&ctx.accounts.escrow_authority, &ctx.accounts.bounty.key(), ctx.accounts.bounty.bump)?;
+        let bounty = &mut ctx.accounts.bounty;
+        let config = &ctx.accounts.config;
+
+        require!(bounty.bounty_id == bounty_id, ContractError::BountyIdMismatch);
+        require!(config.auto_release_window > 0, ContractError::AutoReleaseDisabled);
+        require!(bounty.submitted_at != 0, ContractError::WorkNotSubmitted);
+        let now = Clock::get()?.unix_timestamp;
+        let releasable_at = bounty.submitted_at.checked_add(config.auto_release_window).ok_or(ContractError::ArithmeticOverflow)?;
+        require!(now >= releasable_at, ContractError::AutoReleaseNotReady);
+        let contributor = bounty.assigned_contributor()?;
+        bounty.assert_mint(&ctx.accounts.escrow_token_account.mint, &ctx.accounts.contributor_token_account.mint)?;
+        require!(bounty.yield_principal == 0, ContractError::YieldNotWithdrawn);
+        bounty.check_release_gates(ctx.accounts.contributor.is_signer, deliverable_hash)?;
+        require!(
+            config.allow_transfer_hooks || !token_ext::has_transfer_hook(&ctx.accounts.mint.to_account_info())?,
+            ContractError::TransferHookNotAllowed
+        );
+
+        let bounty_key = bounty.key();
+        let bump = bounty.bump;
+        let seeds = escrow::escrow_signer_seeds(&bounty_key, &bump);
+        let signer = &[&seeds[..]];
+
+        let fee_bps = fees::discounted_fee_bps(
+            fees::base_fee_bps(config.fee_bps, &ctx.accounts.mint_fee_config)?,
+            config.fee_discount_max_bps,
+            bounty.created_at,
+            bounty.deadline,
+            now,
+        );
+        let fee = fees::apply_bps(bounty.amount, fee_bps)?;
+        let crank_reward = fees::apply_bps(fee, config.crank_reward_bps)?;
+        let (treasury_fee, staker_fee) = fees::split_fee(fee - crank_reward, config.fee_treasury_bps)?;
+
+        let fee_stats = &mut ctx.accounts.fee_epoch_stats;
+        fee_stats.mint = ctx.accounts.mint.key();
+        fee_stats.bump = ctx.bumps.fee_epoch_stats;
+        fee_stats.record(Clock::get()?.epoch, fee).ok_or(ContractError::ArithmeticOverflow)?;
+
+        let escrow_authority = ctx.accounts.escrow_authority.to_account_info();
+        let dust = fees::escrow_dust(ctx.accounts.escrow_token_account.amount, bounty.amount);
+
+        EscrowPayout {
+            bounty_id,
+            contributor,
+            burn_bps: config.burn_bps,
+            donate_bps: bounty.donate_bps,
+            token_program: &ctx.accounts.token_program,
+            mint: &ctx.accounts.mint,
+            escrow_token_account: &ctx.accounts.escrow_token_account,
+            escrow_authority: &escrow_authority,
+            signer,
+            treasury_token_account: ctx.accounts.treasury_token_account.as_ref(),
+            staker_token_account: ctx.accounts.staker_token_account.as_ref(),
+            charity_token_account: ctx.accounts.charity_token_account.as_ref(),
+        }
+        .settle(&ctx.accounts.contributor_token_account, bounty.amount - fee, treasury_fee, staker_fee, dust)?;
+        if crank_reward > 0 {
+            let cranker_token_account = ctx.accounts.cranker_token_account.as_ref().ok_or(ContractError::FeeAccountMissing)?;
+            transfer_from_escrow(&ctx.accounts.token_program, &ctx.accounts.escrow_token_account, &ctx.accounts.mint, cranker_token_account, &escrow_authority, signer, crank_reward)?;
+            emit!(CrankRewardPaid {
+                bounty_id,
+                cranker: ctx.accounts.cranker.key(),
+                amount: crank_reward,
+            });
+        }
+
+        close_account(CpiContext::new_with_signer(
+            ctx.accounts.token_program.to_account_info(),
+            CloseAccount {
+                account: ctx.accounts.escrow_token_account.to_account_info(),
+                destination: ctx.accounts.maintainer.to_account_info(),
+                authority: escrow_authority,
+            },
+            signer,
+        ))?;
+
+        emit!(BountyCompleted {
+            bounty_id,
+            contributor,
+            amount: bounty.amount,
+            fee,
+        });
+        let global_stats = &mut ctx.accounts.global_stats;
+        global_stats.bump = ctx.bumps.global_stats;
+        global_stats
+            .record_receipt(bounty_id, &contributor, bounty.amount)
+            .ok_or(ContractError::ArithmeticOverflow)?;
+
+        ctx.accounts.count_by_state
+            .record(Some(&bounty.state), &BountyState::Completed)
+            .ok_or(ContractError::ArithmeticOverflow)?;
+        emit!(StateTransition {
+            bounty_id,
+            from_state: bounty.state.clone(),
+            to_state: BountyState::Completed,
+        });
+        bounty.transition(BountyState::Completed)?;
+        emit!(BountyFinalSnapshot::new(bounty_key, bounty));
+
+        Ok(bounty.state_change())
+    }
+
+    // Maintainer pays out part of the bounty for partial work; the bounty stays InProgress
+    pub fn release_partial(ctx: Context<ReleasePartial>, bounty_id: u64, amount: u64) -> Result<BountyStateChange> {
+        reentrancy::enter(&mut ctx.accounts.bounty)?;
+        let bounty = &mut ctx.accounts.bounty;
+
+        require!(bounty.bounty_id == bounty_id, ContractError::BountyIdMismatch);
+        require!(amount > 0, ContractError::InvalidAmount);
+        require!(amount < bounty.amount, ContractError::PartialReleaseTooLarge);
+        require!(bounty.milestones.is_empty(), ContractError::InvalidMilestones);
+        require_keys_eq!(ctx.accounts.mint.key(), bounty.mint, ContractError::InvalidMint);
+        bounty.assert_mint(&ctx.accounts.escrow_token_account.mint, &ctx.accounts.contributor_token_account.mint)?;
+        require!(bounty.yield_principal == 0, ContractError::YieldNotWithdrawn);
+        require!(
+            ctx.accounts.config.allow_transfer_hooks || !token_ext::has_transfer_hook(&ctx.accounts.mint.to_account_info())?,
+            ContractError::TransferHookNotAllowed
+        );
+
+        let bounty_key = bounty.key();
+        let bump = bounty.bump;
+        let seeds = escrow::escrow_signer_seeds(&bounty_key, &bump);
+        let signer = &[&seeds[..]];
+
+        // Partial payouts carry the same protocol fee as a full completion
+        let fee_bps = fees::discounted_fee_bps(
+            fees::base_fee_bps(ctx.accounts.config.fee_bps, &ctx.accounts.mint_fee_config)?,
+            ctx.accounts.config.fee_discount_max_bps,
+            bounty.created_at,
+            bounty.deadline,
+            Clock::get()?.unix_timestamp,
+        );
+        let fee = fees::apply_bps(amount, fee_bps)?;
+        let (treasury_fee, staker_fee) = fees::split_fee(fee, ctx.accounts.config.fee_treasury_bps)?;
+
+        let fee_stats = &mut ctx.accounts.fee_epoch_stats;
+        fee_stats.mint = ctx.accounts.mint.key();
+        fee_stats.bump = ctx.bumps.fee_epoch_stats;
+        fee_stats.record(Clock::get()?.epoch, fee).ok_or(ContractError::ArithmeticOverflow)?;
+
+        let escrow_authority = ctx.accounts.escrow_authority.to_account_info();
+
+        EscrowPayout {
+            bounty_id,
+            contributor: ctx.accounts.contributor.key(),
+            burn_bps: ctx.accounts.config.burn_bps,
+            donate_bps: bounty.donate_bps,
+            token_program: &ctx.accounts.token_program,
+            mint: &ctx.accounts.mint,
+            escrow_token_account: &ctx.accounts.escrow_token_account,
+            escrow_authority: &escrow_authority,
+            signer,
+            treasury_token_account: ctx.accounts.treasury_token_account.as_ref(),
+            staker_token_account: ctx.accounts.staker_token_account.as_ref(),
+            charity_token_account: ctx.accounts.charity_token_account.as_ref(),
+        }
+        .settle(&ctx.accounts.contributor_token_account, amount - fee, treasury_fee, staker_fee, 0)?;
+
+        bounty.amount -= amount;
+
+        emit!(PartialReleased {
+            bounty_id,
+            contributor: ctx.accounts.contributor.key(),
+            amount,
+            fee,
+            remaining: bounty.amount,
+        });
+
+        reentrancy::leave(bounty);
+        Ok(bounty.state_change())
+    }
+
+    // Maintainer pays out the next milestone. The last one is paid by completing the bounty.
+    pub fn release_milestone(ctx: Context<ReleasePartial>, bounty_id: u64) -> Result<BountyStateChange> {
+        reentrancy::enter(&mut ctx.accounts.bounty)?;
+        let bounty = &mut ctx.accounts.bounty;
+
+        require!(bounty.bounty_id == bounty_id, ContractError::BountyIdMismatch);
+        let index = bounty.milestones_paid as usize;
+        require!(index + 1 < bounty.milestones.len(), ContractError::InvalidMilestones);
+        let amount = bounty.milestones[index];
+        require_keys_eq!(ctx.accounts.mint.key(), bounty.mint, ContractError::InvalidMint);
+        bounty.assert_mint(&ctx.accounts.escrow_token_account.mint, &ctx.accounts.contributor_token_account.mint)?;
+        require!(bounty.yield_principal == 0, ContractError::YieldNotWithdrawn);
+        require!(
+            ctx.accounts.config.allow_transfer_hooks || !token_ext::has_transfer_hook(&ctx.accounts.mint.to_account_info())?,
+            ContractError::TransferHookNotAllowed
+        );
+
+        let bounty_key = bounty.key();
+        let bump = bounty.bump;
+        let seeds = escrow::escrow_signer_seeds(&bounty_key, &bump);
+        let signer = &[&seeds[..]];
+
+        let fee_bps = fees::discounted_fee_bps(
+            fees::base_fee_bps(ctx.accounts.config.fee_bps, &ctx.accounts.mint_fee_config)?,
+            ctx.accounts.config.fee_discount_max_bps,
+            bounty.created_at,
+            bounty.deadline,
+            Clock::get()?.unix_timestamp,
+        );
+        let fee = fees::apply_bps(amount, fee_bps)?;
+        let (treasury_fee, staker_fee) = fees::split_fee(fee, ctx.accounts.config.fee_treasury_bps)?;
+
+        let fee_stats = &mut ctx.accounts.fee_epoch_stats;
+        fee_stats.mint = ctx.accounts.mint.key();
+        fee_stats.bump = ctx.bumps.fee_epoch_stats;
+        fee_stats.record(Clock::get()?.epoch, fee).ok_or(ContractError::ArithmeticOverflow)?;
+
+        let escrow_authority = ctx.accounts.escrow_authority.to_account_info();
+
+        EscrowPayout {
+            bounty_id,
+            contributor: ctx.accounts.contributor.key(),
+            burn_bps: ctx.accounts.config.burn_bps,
+            donate_bps: bounty.donate_bps,
+            token_program: &ctx.accounts.token_program,
+            mint: &ctx.accounts.mint,
+            escrow_token_account: &ctx.accounts.escrow_token_account,
+            escrow_authority: &escrow_authority,
+            signer,
+            treasury_token_account: ctx.accounts.treasury_token_account.as_ref(),
+            staker_token_account: ctx.accounts.staker_token_account.as_ref(),
+            charity_token_account: ctx.accounts.charity_token_account.as_ref(),
+        }
+        .settle(&ctx.accounts.contributor_token_account, amount - fee, treasury_fee, staker_fee, 0)?;
+
+        bounty.amount -= amount;
+        bounty.milestones_paid += 1;
+
+        emit!(MilestoneReleased {
+            bounty_id,
+            contributor: ctx.accounts.contributor.key(),
+            milestone: index as u8,
+            amount,
+            fee,
+            remaining: bounty.amount,
+        });
+
+        reentrancy::leave(bounty);
+        Ok(bounty.state_change())
+    }
+
+    // `expected_amount` is the bounty amount the caller last read; cancelling fails if it has since changed
+    pub fn cancel_bounty(ctx: Context<CancelBounty>, expected_amount: Option<u64>, reason: [u8; 64]) -> Result<BountyStateChange> {
+        ctx.accounts.config.require_power(AdminPower::Cancel)?;
+        ctx.accounts.config.check_admin_reason(&reason)?;
+        reentrancy::enter(&mut ctx.accounts.bounty)?;
+        escrow::assert_escrow_authority(&ctx.accounts.escrow_authority, &ctx.accounts.bounty.key(), ctx.accounts.bounty.bump)?;
+        let bounty = &mut ctx.accounts.bounty;
+        let bounty_key = bounty.key();
+        let bump = bounty.bump;
+
+        require!(bounty.state != BountyState::Completed, ContractError::BountyAlreadyCompleted);
+        require!(bounty.state != BountyState::Cancelled, ContractError::BountyAlreadyCancelled);
+        require!(bounty.maintainer == ctx.accounts.maintainer.key(), ContractError::MaintainerMismatch);
+        require!(bounty.funder_count == 0, ContractError::FundersNotRefunded);
+        bounty.assert_mint(&ctx.accounts.escrow_token_account.mint, &ctx.accounts.maintainer_token_account.mint)?;
+        require!(bounty.yield_principal == 0, ContractError::YieldNotWithdrawn);
+        if let Some(expected_amount) = expected_amount {
+            require!(expected_amount == bounty.amount, ContractError::AmountMismatch);
+        }
+    
+    
+        // Seeds for the PDA authority
+        let seeds = escrow::escrow_signer_seeds(&bounty_key, &bump);
+        let signer = &[&seeds[..]];
+    
+        // First, transfer the tokens from the escrow back to the maintainer
+        let cpi_ctx = CpiContext::new_with_signer(
+            ctx.accounts.token_program.to_account_info(),
+            TransferChecked {
+                from: ctx.accounts.escrow_token_account.to_account_info(),
+                mint: ctx.accounts.mint.to_account_info(),
+                to: ctx.accounts.maintainer_token_account.to_account_info(),
+                authority: ctx.accounts.escrow_authority.to_account_info(),
+            },
+            signer
+        );
+    
+        // Refund whatever is left in escrow, which after milestone payouts is only the unpaid remainder
+        let refund = ctx.accounts.escrow_token_account.amount;
+        transfer_checked(cpi_ctx, refund, ctx.accounts.mint.decimals)?;
+    
+        // Now, close the escrow token account using a CPI to the token program
+        // The rent will be sent to the maintainer as specified in the context
+        let cpi_ctx = CpiContext::new_with_signer(
+            ctx.accounts.token_program.to_account_info(),
+            CloseAccount {
+                account: ctx.accounts.escrow_token_account.to_account_info(),
+                destination: ctx.accounts.maintainer.to_account_info(),
+                authority: ctx.accounts.escrow_authority.to_account_info(),
+            },
+            signer
+        );
+    
+        close_account(cpi_ctx)?;
+    
+        // The bounty account will be closed automatically by Anchor due to its 'close' constraint.
+        // The bounty account rent goes to its rent payer.
+    
+        emit!(BountyCancelled {
+            bounty_id: bounty.bounty_id,
+            maintainer: ctx.accounts.maintainer.key(),
+            amount: refund,
+            reason: CancelReason::AdminCancel as u8,
+            milestones_paid: bounty.milestones_paid,
+        });
+        emit!(AdminIntervention {
+            bounty_id: bounty.bounty_id,
+            admin: ctx.accounts.admin.key(),
+            power: AdminPower::Cancel as u8,
+            reason,
+        });
+        
+        ctx.accounts.count_by_state
+            .record(Some(&bounty.state), &BountyState::Cancelled)
+            .ok_or(ContractError::ArithmeticOverflow)?;
+        emit!(StateTransition {
+            bounty_id: bounty.bounty_id,
+            from_state: bounty.state.clone(),
+            to_state: BountyState::Cancelled,
+        });
+        bounty.transition(BountyState::Cancelled)?;
+        emit!(BountyFinalSnapshot::new(bounty.key(), bounty));
+        
+        Ok(bounty.state_change())
+    }
+    // The maintainer unwinds their own bounty, only possible before anyone has been assigned
+    pub fn maintainer_cancel(ctx: Context<MaintainerCancel>, bounty_id: u64) -> Result<BountyStateChange> {
+        reentrancy::enter(&mut ctx.accounts.bounty)?;
+        escrow::assert_escrow_authority(&ctx.accounts.escrow_authority, &ctx.accounts.bounty.key(), ctx.accounts.bounty.bump)?;
+        let bounty = &mut ctx.accounts.bounty;
+        require!(bounty.bounty_id == bounty_id, ContractError::BountyIdMismatch);
+        require!(bounty.funder_count == 0, ContractError::FundersNotRefunded);
+        require!(bounty.yield_principal == 0, ContractError::YieldNotWithdrawn);
+
+        let bounty_key = bounty.key();
+        let bump = bounty.bump;
+        let seeds = escrow::escrow_signer_seeds(&bounty_key, &bump);
+        let signer = &[&seeds[..]];
+        let escrow_authority = ctx.accounts.escrow_authority.to_account_info();
+
+        let refund = ctx.accounts.escrow_token_account.amount;
+        transfer_from_escrow(&ctx.accounts.token_program, &ctx.accounts.escrow_token_account, &ctx.accounts.mint, &ctx.accounts.maintainer_token_account, &escrow_authority, signer, refund)?;
+        close_account(CpiContext::new_with_signer(
+            ctx.accounts.token_program.to_account_info(),
+            CloseAccount {
+                account: ctx.accounts.escrow_token_account.to_account_info(),
+                destination: ctx.accounts.maintainer.to_account_info(),
+                authority: escrow_authority,
+            },
+            signer,
+        ))?;
+
+        emit!(BountyCancelled {
+            bounty_id,
+            maintainer: ctx.accounts.maintainer.key(),
+            amount: refund,
+            reason: CancelReason::MaintainerCancel as u8,
+            milestones_paid: bounty.milestones_paid,
+        });
+
+        ctx.accounts.count_by_state
+            .record(Some(&bounty.state), &BountyState::Cancelled)
+            .ok_or(ContractError::ArithmeticOverflow)?;
+        emit!(StateTransition {
+            bounty_id,
+            from_state: bounty.state.clone(),
+            to_state: BountyState::Cancelled,
+        });
+        bounty.transition(BountyState::Cancelled)?;
+        emit!(BountyFinalSnapshot::new(bounty_key, bounty));
+
+        Ok(bounty.state_change())
+    }
+
+    // First half of an admin handover, nothing changes until `new_admin` calls `accept_admin`
+    pub fn propose_admin(ctx: Context<ProposeAdmin>, new_admin: Pubkey) -> Result<()> {
+        // Security checks
+        require!(new_admin != Pubkey::default(), ContractError::InvalidNewAdmin);
+        require!(new_admin != ctx.accounts.admin.key(), ContractError::AdminUnchanged);
+        
+        let config = &mut ctx.accounts.config;
+        config.pending_admin = Some(new_admin);
+        
+        emit!(AdminTransferProposed {
+            admin: config.admin,
+            pending_admin: new_admin,
+        });
+        
+        Ok(())
+    }
+
+    pub fn accept_admin(ctx: Context<AcceptAdmin>) -> Result<()> {
+        let config = &mut ctx.accounts.config;
+        let old_admin = config.admin;
+        config.admin = ctx.accounts.new_admin.key();
+        config.pending_admin = None;
+
+        emit!(AdminTransferAccepted {
+            old_admin,
+            new_admin: config.admin,
+        });
+
+        Ok(())
+    }
+
+    // Withdraws a proposal that hasn't been accepted yet
+    pub fn cancel_admin_transfer(ctx: Context<ProposeAdmin>) -> Result<()> {
+        let config = &mut ctx.accounts.config;
+        let pending_admin = config.pending_admin.take().ok_or(ContractError::NoPendingAdmin)?;
+
+        emit!(AdminTransferCancelled {
+            admin: config.admin,
+            pending_admin,
+        });
+
+        Ok(())
+    }
+
+    pub fn admin_assign_and_release(ctx: Context<AdminAssignAndRelease>, bounty_id: u64, reason: [u8; 64]) -> Result<BountyStateChange> {
+        ctx.accounts.config.require_power(AdminPower::ForceRelease)?;
+        ctx.accounts.config.check_admin_reason(&reason)?;
+        reentrancy::enter(&mut ctx.accounts.bounty)?;
+        escrow::assert_escrow_authority(&ctx.accounts.escrow_authority, &ctx.accounts.bounty.key(), ctx.accounts.bounty.bump)?;
+        let bounty = &mut ctx.accounts.bounty;
+
+        // Security checks
+        require!(bounty.bounty_id == bounty_id, ContractError::BountyIdMismatch);
+        require_keys_eq!(ctx.accounts.mint.key(), bounty.mint, ContractError::InvalidMint);
+        bounty.assert_mint(&ctx.accounts.escrow_token_account.mint, &ctx.accounts.contributor_token_account.mint)?;
+        require!(bounty.yield_principal == 0, ContractError::YieldNotWithdrawn);
+        require!(
+            ctx.accounts.config.allow_transfer_hooks || !token_ext::has_transfer_hook(&ctx.accounts.mint.to_account_info())?,
+            ContractError::TransferHookNotAllowed
+        );
+        require!(bounty.state != BountyState::Draft, ContractError::BountyIsDraft);
+        require!(bounty.admin_override_allowed, ContractError::AdminOverrideDisabled);
+        require!(
+            bounty.state == BountyState::InProgress || ctx.accounts.config.allow_admin_force_on_created,
+            ContractError::AdminForceOnCreatedDisabled
+        );
+
+        // Get the new contributor key
+        let new_contributor_key = ctx.accounts.contributor.key();
+        // The admin path must not become a way for the maintainer to pay themselves
+        require!(new_contributor_key != bounty.maintainer, ContractError::ContributorCannotBeMaintainer);
+        require_keys_neq!(new_contributor_key, Pubkey::default(), ContractError::InvalidContributor);
+
+
+        // Counted straight from whichever open state the bounty was in
+        ctx.accounts.count_by_state
+            .record(Some(&bounty.state), &BountyState::Completed)
+            .ok_or(ContractError::ArithmeticOverflow)?;
+        emit!(StateTransition {
+            bounty_id: bounty.bounty_id,
+            from_state: bounty.state.clone(),
+            to_state: BountyState::Completed,
+        });
+
+        // Override with new contributor (admin super power)
+        bounty.contributor = Some(new_contributor_key);
+        bounty.contributor_is_pda = ctx.accounts.contributor.owner != &System::id();
+        
+        // Emit event for contributor assignment
+        emit!(ContributorAssigned {
+            bounty_id: bounty.bounty_id,
+            contributor: new_contributor_key,
+            assigned_by: ctx.accounts.admin.key(),
+            via_admin: true,
+        });
+        emit!(AdminIntervention {
+            bounty_id: bounty.bounty_id,
+            admin: ctx.accounts.admin.key(),
+            power: AdminPower::ForceRelease as u8,
+            reason,
+        });
+
+        // Release funds from escrow to new contributor
+        let bounty_key = bounty.key();
+        let bump = bounty.bump;
+        let seeds = escrow::escrow_signer_seeds(&bounty_key, &bump);
+        let signer = &[&seeds[..]];
+
+        let fee_bps = fees::discounted_fee_bps(
+            fees::base_fee_bps(ctx.accounts.config.fee_bps, &ctx.accounts.mint_fee_config)?,
+            ctx.accounts.config.fee_discount_max_bps,
+            bounty.created_at,
+            bounty.deadline,
+            Clock::get()?.unix_timestamp,
+        );
+        let fee = fees::apply_bps(bounty.amount, fee_bps)?;
+        let (treasury_fee, staker_fee) = fees::split_fee(fee, ctx.accounts.config.fee_treasury_bps)?;
+
+        // Admin intervention surcharge on top of the regular fee, all of it to the treasury
+        let intervention_fee = fees::apply_bps(bounty.amount, ctx.accounts.config.admin_intervention_bps)?;
+        let treasury_fee = treasury_fee + intervention_fee;
+        let fee = fee + intervention_fee;
+        require!(fee <= bounty.amount, ContractError::InvalidFeeBps);
+
+        let fee_stats = &mut ctx.accounts.fee_epoch_stats;
+        fee_stats.mint = ctx.accounts.mint.key();
+        fee_stats.bump = ctx.bumps.fee_epoch_stats;
+        fee_stats.record(Clock::get()?.epoch, fee).ok_or(ContractError::ArithmeticOverflow)?;
+
+        let escrow_authority = ctx.accounts.escrow_authority.to_account_info();
+        let dust = fees::escrow_dust(ctx.accounts.escrow_token_account.amount, bounty.amount);
+
+        EscrowPayout {
+            bounty_id,
+            contributor: new_contributor_key,
+            burn_bps: ctx.accounts.config.burn_bps,
+            donate_bps: bounty.donate_bps,
+            token_program: &ctx.accounts.token_program,
+            mint: &ctx.accounts.mint,
+            escrow_token_account: &ctx.accounts.escrow_token_account,
+            escrow_authority: &escrow_authority,
+            signer,
+            treasury_token_account: ctx.accounts.treasury_token_account.as_ref(),
+            staker_token_account: ctx.accounts.staker_token_account.as_ref(),
+            charity_token_account: ctx.accounts.charity_token_account.as_ref(),
+        }
+        .settle(&ctx.accounts.contributor_token_account, bounty.amount - fee, treasury_fee, staker_fee, dust)?;
+
+        // Now, close the escrow token account using a CPI to the token program
+        // The rent will be sent to the maintainer as specified in the context
+        let cpi_ctx = CpiContext::new_with_signer(
+            ctx.accounts.token_program.to_account_info(),
+            CloseAccount {
+                account: ctx.accounts.escrow_token_account.to_account_info(),
+                destination: ctx.accounts.maintainer.to_account_info(),
+                authority: ctx.accounts.escrow_authority.to_account_info(),
+            },
+            signer
+        );
+
+        close_account(cpi_ctx)?;
+
+        // Emit completion event
+        emit!(BountyCompleted {
+            bounty_id,
+            contributor: new_contributor_key,
+            amount: bounty.amount,
+            fee,
+        });
+        let global_stats = &mut ctx.accounts.global_stats;
+        global_stats.bump = ctx.bumps.global_stats;
+        global_stats
+            .record_receipt(bounty_id, &new_contributor_key, bounty.amount)
+            .ok_or(ContractError::ArithmeticOverflow)?;
+
+        bounty.transition(BountyState::Completed)?;
+        emit!(BountyFinalSnapshot::new(bounty.key(), bounty));
+        Ok(bounty.state_change())
+    }
+
+    // Releases the bounty on the oracle's Ed25519 signature over (bounty_id, pr_id, contributor),
+    // verified by an Ed25519 program instruction placed right before this one
+    pub fn complete_via_attestation(
+        ctx: Context<CompleteViaAttestation>,
+        bounty_id: u64,
+        pr_id: u64,
+        signature: [u8; 64],
+        deliverable_hash: Option<[u8; 32]>,
+    ) -> Result<BountyStateChange> {
+        reentrancy::enter(&mut ctx.accounts.bounty)?;
+        escrow::assert_escrow_authority(&ctx.accounts.escrow_authority, &ctx.accounts.bounty.key(), ctx.accounts.bounty.bump)?;
+        let bounty = &mut ctx.accounts.bounty;
+        require!(bounty.bounty_id == bounty_id, ContractError::BountyIdMismatch);
+        require!(bounty.assigned_contributor()? == ctx.accounts.contributor.key(), ContractError::InvalidContributor);
+        require!(bounty.yield_principal == 0, ContractError::YieldNotWithdrawn);
+        bounty.check_release_gates(ctx.accounts.contributor.is_signer, deliverable_hash)?;
+        // The oracle signature stands in for the keeper, but a bounty pinned to a keeper set
+        // still only settles through one of them
+        require!(
+            bounty.allowed_keepers.is_empty() || bounty.allowed_keepers.contains(&ctx.accounts.payer.key()),
+            ContractError::KeeperNotAllowed
+        );
+        require!(
+            ctx.accounts.config.allow_transfer_hooks || !token_ext::has_transfer_hook(&ctx.accounts.mint.to_account_info())?,
+            ContractError::TransferHookNotAllowed
+        );
+
+        let contributor_key = ctx.accounts.contributor.key();
+        attestation::verify_ed25519(
+            &ctx.accounts.instructions.to_account_info(),
+            &ctx.accounts.config.admin,
+            &attestation::merge_message(bounty_id, pr_id, &contributor_key),
+            &signature,
+        )?;
+        emit!(MergeAttested {
+            bounty_id,
+            pr_id,
+            contributor: contributor_key,
+        });
+
+        let bounty_key = bounty.key();
+        let bump = bounty.bump;
+        let seeds = escrow::escrow_signer_seeds(&bounty_key, &bump);
+        let signer = &[&seeds[..]];
+
+        let fee_bps = fees::discounted_fee_bps(
+            fees::base_fee_bps(ctx.accounts.config.fee_bps, &ctx.accounts.mint_fee_config)?,
+            ctx.accounts.config.fee_discount_max_bps,
+            bounty.created_at,
+            bounty.deadline,
+            Clock::get()?.unix_timestamp,
+        );
+        let fee = fees::apply_bps(bounty.amount, fee_bps)?;
+        let (treasury_fee, staker_fee) = fees::split_fee(fee, ctx.accounts.config.fee_treasury_bps)?;
+
+        let fee_stats = &mut ctx.accounts.fee_epoch_stats;
+        fee_stats.mint = ctx.accounts.mint.key();
+        fee_stats.bump = ctx.bumps.fee_epoch_stats;
+        fee_stats.record(Clock::get()?.epoch, fee).ok_or(ContractError::ArithmeticOverflow)?;
+
+        let escrow_authority = ctx.accounts.escrow_authority.to_account_info();
+        let dust = fees::escrow_dust(ctx.accounts.escrow_token_account.amount, bounty.amount);
+
+        EscrowPayout {
+            bounty_id,
+            contributor: contributor_key,
+            burn_bps: ctx.accounts.config.burn_bps,
+            donate_bps: bounty.donate_bps,
+            token_program: &ctx.accounts.token_program,
+            mint: &ctx.accounts.mint,
+            escrow_token_account: &ctx.accounts.escrow_token_account,
+            escrow_authority: &escrow_authority,
+            signer,
+            treasury_token_account: ctx.accounts.treasury_token_account.as_ref(),
+            staker_token_account: ctx.accounts.staker_token_account.as_ref(),
+            charity_token_account: ctx.accounts.charity_token_account.as_ref(),
+        }
+        .settle(&ctx.accounts.contributor_token_account, bounty.amount - fee, treasury_fee, staker_fee, dust)?;
+
+        let cpi_ctx = CpiContext::new_with_signer(
+            ctx.accounts.token_program.to_account_info(),
+            CloseAccount {
+                account: ctx.accounts.escrow_token_account.to_account_info(),
+                destination: ctx.accounts.maintainer.to_account_info(),
+                authority: ctx.accounts.escrow_authority.to_account_info(),
+            },
+            signer
+        );
+        close_account(cpi_ctx)?;
+
+        emit!(BountyCompleted {
+            bounty_id,
+            contributor: contributor_key,
+            amount: bounty.amount,
+            fee,
+        });
+        let global_stats = &mut ctx.accounts.global_stats;
+        global_stats.bump = ctx.bumps.global_stats;
+        global_stats
+            .record_receipt(bounty_id, &contributor_key, bounty.amount)
+            .ok_or(ContractError::ArithmeticOverflow)?;
+
+        ctx.accounts.count_by_state
+            .record(Some(&bounty.state), &BountyState::Completed)
+            .ok_or(ContractError::ArithmeticOverflow)?;
+        emit!(StateTransition {
+            bounty_id: bounty.bounty_id,
+            from_state: bounty.state.clone(),
+            to_state: BountyState::Completed,
+        });
+        bounty.transition(BountyState::Completed)?;
+        emit!(BountyFinalSnapshot::new(bounty.key(), bounty));
+        Ok(bounty.state_change())
+    }
+
+    // Support tool: undo an erroneous assignment, funds stay in escrow
+    pub fn admin_unassign(ctx: Context<AdminUnassign>, bounty_id: u64, reason: [u8; 64]) -> Result<BountyStateChange> {
+        ctx.accounts.config.require_power(AdminPower::Unassign)?;
+        ctx.accounts.config.check_admin_reason(&reason)?;
+        let bounty = &mut ctx.accounts.bounty;
+        require!(bounty.bounty_id == bounty_id, ContractError::BountyIdMismatch);
+
+        let previous_contributor = bounty.contributor.take().ok_or(ContractError::InvalidContributor)?;
+        bounty.contributor_is_pda = false;
+        // Sign-offs and the donation pledge belonged to the previous contributor
+        bounty.approvals.clear();
+        bounty.donate_bps = 0;
+        bounty.payout_mint = None;
+        bounty.payout_min_out = 0;
+        bounty.submitted_at = 0;
+        bounty.deliverable_commitment = None;
+        bounty.transition(BountyState::Created)?;
+        ctx.accounts.count_by_state
+            .record(Some(&BountyState::InProgress), &BountyState::Created)
+            .ok_or(ContractError::ArithmeticOverflow)?;
+        emit!(StateTransition {
+            bounty_id: bounty.bounty_id,
+            from_state: BountyState::InProgress,
+            to_state: BountyState::Created,
+        });
+
+        emit!(AssignmentCleared {
+            bounty_id,
+            previous_contributor,
+            admin: ctx.accounts.admin.key(),
+        });
+        emit!(AdminIntervention {
+            bounty_id,
+            admin: ctx.accounts.admin.key(),
+            power: AdminPower::Unassign as u8,
+            reason,
+        });
+
+        Ok(bounty.state_change())
+    }
+
+    pub fn set_yield_config(ctx: Context<UpdateConfig>, yield_enabled: bool, yield_program: Pubkey) -> Result<()> {
+        let config = &mut ctx.accounts.config;
+
+        // Outstanding principal must always be withdrawable from the program it went into
+        require!(
+            config.yield_program == yield_program || config.yield_deployed == 0,
+            ContractError::YieldPositionsOpen
+        );
+
+        config.yield_enabled = yield_enabled;
+        config.yield_program = yield_program;
+        Ok(())
+    }
+
+    pub fn set_allow_transfer_hooks(ctx: Context<UpdateConfig>, allow_transfer_hooks: bool) -> Result<()> {
+        ctx.accounts.config.allow_transfer_hooks = allow_transfer_hooks;
+        Ok(())
+    }
+
+    pub fn set_fee(ctx: Context<UpdateConfig>, fee_bps: u16, fee_treasury_bps: u16) -> Result<()> {
+        ctx.accounts.config.set_fee_terms(Some(fee_bps), Some(fee_treasury_bps), None, None)
+    }
+
+    // Platform fee rate and the wallet whose token accounts receive it
+    pub fn update_fee(ctx: Context<UpdateConfig>, fee_bps: u16, treasury: Pubkey) -> Result<()> {
+        ctx.accounts.config.set_fee_terms(Some(fee_bps), None, Some(treasury), None)
+    }
+
+    // Grows a config created with the original admin + bump layout to `ConfigState::LEN` and fills
+    // the fields after `bump` with the defaults `initialize_config` uses, so a live deployment needs
+    // no redeploy. The admin pays the extra rent
+    pub fn migrate_config(ctx: Context<MigrateConfig>, fee_bps: u16, treasury: Pubkey) -> Result<()> {
+        let mut config = ConfigState::new(ctx.accounts.admin.key(), ctx.bumps.config);
+        config.set_fee_terms(Some(fee_bps), None, Some(treasury), None)?;
+        let info = ctx.accounts.config.to_account_info();
+        let rent_shortfall = Rent::get()?.minimum_balance(ConfigState::LEN).saturating_sub(info.lamports());
+        if rent_shortfall > 0 {
+            system_program::transfer(
+                CpiContext::new(
+                    ctx.accounts.system_program.to_account_info(),
+                    system_program::Transfer {
+                        from: ctx.accounts.admin.to_account_info(),
+                        to: info.clone(),
+                    },
+                ),
+                rent_shortfall,
+            )?;
+        }
+        info.realloc(ConfigState::LEN, false)?;
+
+        let mut data = info.try_borrow_mut_data()?;
+        config.try_serialize(&mut &mut data[..])?;
+        Ok(())
+    }
+
+    pub fn set_fee_discount(ctx: Context<UpdateConfig>, fee_discount_max_bps: u16) -> Result<()> {
+        require!(fee_discount_max_bps as u64 <= fees::BPS_DENOMINATOR, ContractError::InvalidFeeBps);
+        ctx.accounts.config.fee_discount_max_bps = fee_discount_max_bps;
+        Ok(())
+    }
+
+    pub fn set_burn_bps(ctx: Context<UpdateConfig>, burn_bps: u16) -> Result<()> {
+        require!(burn_bps as u64 <= fees::BPS_DENOMINATOR, ContractError::InvalidBurnBps);
+        ctx.accounts.config.burn_bps = burn_bps;
+        Ok(())
+    }
+
+    // A zero window disables `crank_auto_release`
+    pub fn set_auto_release(ctx: Context<UpdateConfig>, auto_release_window: i64, crank_reward_bps: u16) -> Result<()> {
+        require!(auto_release_window >= 0, ContractError::InvalidAutoReleaseWindow);
+        require!(crank_reward_bps as u64 <= fees::BPS_DENOMINATOR, ContractError::InvalidFeeBps);
+        ctx.accounts.config.auto_release_window = auto_release_window;
+        ctx.accounts.config.crank_reward_bps = crank_reward_bps;
+        Ok(())
+    }
+
+    pub fn set_mint_fee(ctx: Context<SetMintFee>, fee_bps: u16) -> Result<()> {
+        require!(fee_bps as u64 <= fees::BPS_DENOMINATOR, ContractError::InvalidFeeBps);
+        require!(fee_bps <= fees::MAX_FEE_BPS, ContractError::FeeTooHigh);
+        let mint_fee_config = &mut ctx.accounts.mint_fee_config;
+        mint_fee_config.mint = ctx.accounts.mint.key();
+        mint_fee_config.fee_bps = fee_bps;
+        mint_fee_config.bump = ctx.bumps.mint_fee_config;
+        Ok(())
+    }
+
+    // Closing the override makes the mint fall back to the global fee
+    pub fn clear_mint_fee(_ctx: Context<ClearMintFee>) -> Result<()> {
+        Ok(())
+    }
+
+    pub fn set_mint_whitelist_enabled(ctx: Context<UpdateConfig>, enabled: bool) -> Result<()> {
+        ctx.accounts.config.mint_whitelist_enabled = enabled;
+        Ok(())
+    }
+
+    pub fn whitelist_mint(ctx: Context<WhitelistMint>) -> Result<()> {
+        let entry = &mut ctx.accounts.mint_whitelist;
+        entry.mint = ctx.accounts.mint.key();
+        entry.bump = ctx.bumps.mint_whitelist;
+        Ok(())
+    }
+
+    // Closing the entry removes the mint from the whitelist
+    pub fn unwhitelist_mint(_ctx: Context<UnwhitelistMint>) -> Result<()> {
+        Ok(())
+    }
+
+    // Whether `initialize_bounty` would currently accept the mint
+    pub fn is_mint_allowed(ctx: Context<IsMintAllowed>) -> Result<bool> {
+        Ok(ctx.accounts.config.mint_allowed(&ctx.accounts.mint_whitelist))
+    }
+
+    pub fn set_notification_program(ctx: Context<UpdateConfig>, notification_program: Option<Pubkey>) -> Result<()> {
+        ctx.accounts.config.notification_program = notification_program;
+        Ok(())
+    }
+
+    // Optional step after a cancel: the maintainer swaps the SOL rent they got back into the
+    // bounty's mint through the configured AMM. Only the maintainer's own lamports are spent.
+    pub fn swap_reclaimed_rent<'info>(
+        ctx: Context<'_, '_, '_, 'info, SwapReclaimedRent<'info>>,
+        lamports: u64,
+        min_out: u64,
+    ) -> Result<()> {
+        require!(lamports > 0, ContractError::InvalidAmount);
+        require!(ctx.accounts.config.amm_program == Some(ctx.accounts.amm_program.key()), ContractError::InvalidAmmProgram);
+
+        let lamports_before = ctx.accounts.maintainer.lamports();
+        let tokens_before = ctx.accounts.maintainer_token_account.amount;
+
+        swap::swap_sol(
+            &ctx.accounts.amm_program.to_account_info(),
+            &ctx.accounts.maintainer.to_account_info(),
+            &ctx.accounts.maintainer_token_account.to_account_info(),
+            &ctx.accounts.token_program.to_account_info(),
+            &ctx.accounts.system_program.to_account_info(),
+            ctx.remaining_accounts,
+            lamports,
+            min_out,
+        )?;
+
+        ctx.accounts.maintainer_token_account.reload()?;
+        require!(
+            lamports_before.saturating_sub(ctx.accounts.maintainer.lamports()) <= lamports,
+            ContractError::SlippageExceeded
+        );
+        let amount_out = ctx.accounts.maintainer_token_account.amount.saturating_sub(tokens_before);
+        require!(amount_out >= min_out, ContractError::SlippageExceeded);
+
+        emit!(RentSwapped {
+            maintainer: ctx.accounts.maintainer.key(),
+            mint: ctx.accounts.mint.key(),
+            lamports,
+            amount_out,
+        });
+        Ok(())
+    }
+
+    pub fn set_amm_program(ctx: Context<UpdateConfig>, amm_program: Option<Pubkey>) -> Result<()> {
+        ctx.accounts.config.amm_program = amm_program;
+        Ok(())
+    }
+
+    pub fn set_archive_tree(ctx: Context<UpdateConfig>, archive_tree: Option<Pubkey>) -> Result<()> {
+        ctx.accounts.config.archive_tree = archive_tree;
+        Ok(())
+    }
+
+    pub fn set_charity(ctx: Context<UpdateConfig>, charity: Option<Pubkey>) -> Result<()> {
+        ctx.accounts.config.charity = charity;
+        Ok(())
+    }
+
+    // Permanently gives up an admin power, see AdminPower for the codes. There is no way back.
+    pub fn renounce_power(ctx: Context<UpdateConfig>, power_id: u8) -> Result<()> {
+        let power = AdminPower::from_code(power_id).ok_or(ContractError::InvalidPower)?;
+        ctx.accounts.config.renounced_powers |= 1 << power as u8;
+
+        emit!(PowerRenounced {
+            admin: ctx.accounts.admin.key(),
+            power_id,
+        });
+        Ok(())
+    }
+
+    // Publishes an upcoming upgrade authority handoff so clients can warn users ahead of it
+    pub fn announce_upgrade(ctx: Context<UpdateConfig>, new_authority: Pubkey, effective_at: i64) -> Result<()> {
+        let now = Clock::get()?.unix_timestamp;
+        require!(effective_at > now, ContractError::InvalidEffectiveTime);
+
+        let config = &mut ctx.accounts.config;
+        config.announced_upgrade_authority = Some(new_authority);
+        config.upgrade_effective_at = effective_at;
+
+        emit!(UpgradeAnnounced {
+            new_authority,
+            effective_at,
+            announced_at: now,
+        });
+        Ok(())
+    }
+
+    // The reserve is the [b"bonus_reserve"] PDA, funded by plain SOL transfers
+    pub fn set_first_completion_bonus(ctx: Context<UpdateConfig>, lamports: u64) -> Result<()> {
+        ctx.accounts.config.first_completion_bonus_lamports = lamports;
+        Ok(())
+    }
+
+    pub fn set_max_deadline_duration(ctx: Context<UpdateConfig>, seconds: i64) -> Result<()> {
+        require!(seconds >= 0, ContractError::InvalidDeadline);
+        ctx.accounts.config.max_deadline_duration = seconds;
+        Ok(())
+    }
+
+    pub fn set_max_daily_volume(ctx: Context<UpdateConfig>, max_daily_volume: u64) -> Result<()> {
+        ctx.accounts.config.max_daily_volume = max_daily_volume;
+        Ok(())
+    }
+
+    pub fn set_ata_creation_fee(ctx: Context<UpdateConfig>, ata_creation_fee: u64) -> Result<()> {
+        ctx.accounts.config.ata_creation_fee = ata_creation_fee;
+        Ok(())
+    }
+
+    pub fn set_require_admin_reason(ctx: Context<UpdateConfig>, required: bool) -> Result<()> {
+        ctx.accounts.config.require_admin_reason = required;
+        Ok(())
+    }
+
+    pub fn set_default_keeper(ctx: Context<UpdateConfig>, default_keeper: Pubkey) -> Result<()> {
+        ctx.accounts.config.default_keeper = default_keeper;
+        Ok(())
+    }
+
+    pub fn set_fee_recipients(ctx: Context<UpdateConfig>, treasury: Pubkey, staker_pool: Pubkey) -> Result<()> {
+        ctx.accounts.config.set_fee_terms(None, None, Some(treasury), Some(staker_pool))
+    }
+
+    // Permissionless rent recovery for Completed bounties whose escrow is already closed or empty.
+    // An empty escrow still open is closed along with its bounty, both rents going to the rent payer.
+    // Anything else in the batch (other states, funded escrows, already closed bounties) is skipped.
+    pub fn close_completed_batch<'info>(ctx: Context<'_, '_, 'info, 'info, CloseCompletedBatch<'info>>) -> Result<()> {
+        require!(ctx.remaining_accounts.len().is_multiple_of(4), ContractError::InvalidBatchAccounts);
+
+        let mut closed = 0u32;
+        for chunk in ctx.remaining_accounts.chunks(4) {
+            let (bounty_info, escrow_authority, escrow_info, rent_payer_info) = (&chunk[0], &chunk[1], &chunk[2], &chunk[3]);
+            if bounty_info.owner != &crate::ID || bounty_info.data_is_empty() {
+                continue;
+            }
+            let bounty = Account::<Bounty>::try_from(bounty_info)?;
+            if bounty.state != BountyState::Completed {
+                continue;
+            }
+            require_keys_eq!(rent_payer_info.key(), bounty.rent_payer, ContractError::RentPayerMismatch);
+
+            escrow::assert_escrow_authority(escrow_authority, bounty_info.key, bounty.bump)?;
+            let expected_escrow = get_associated_token_address_with_program_id(
+                escrow_authority.key,
+                &bounty.mint,
+                ctx.accounts.token_program.key,
+            );
+            require_keys_eq!(escrow_info.key(), expected_escrow, ContractError::InvalidTokenAccount);
+            if !escrow_info.data_is_empty() {
+                if InterfaceAccount::<TokenAccount>::try_from(escrow_info)?.amount > 0 {
+                    continue;
+                }
+                let seeds = escrow::escrow_signer_seeds(bounty_info.key, &bounty.bump);
+                close_account(CpiContext::new_with_signer(
+                    ctx.accounts.token_program.to_account_info(),
+                    CloseAccount {
+                        account: escrow_info.clone(),
+                        destination: rent_payer_info.clone(),
+                        authority: escrow_authority.clone(),
+                    },
+                    &[&seeds[..]],
+                ))?;
+            }
+
+            bounty.close(rent_payer_info.clone())?;
+            closed += 1;
+        }
+
+        emit!(CompletedBountiesClosed { closed });
+
+        Ok(())
+    }
+
+    // Keeper settles several approved bounties of one mint in a single transaction. Bounties it may
+    // not settle, or that need accounts or signatures a batch can't carry (payout swap, LP unwrap,
+    // contributor ack, deliverable commitment, a donation without the charity account), are skipped. Each escrow is closed into
+    // its bounty account, which stays Completed for `close_completed_batch` to return its rent.
+    // Returns how many bounties were settled.
+    pub fn complete_batch<'info>(ctx: Context<'_, '_, 'info, 'info, CompleteBatch<'info>>) -> Result<u32> {
+        require!(ctx.remaining_accounts.len().is_multiple_of(4), ContractError::InvalidBatchAccounts);
+        require!(
+            ctx.accounts.config.allow_transfer_hooks || !token_ext::has_transfer_hook(&ctx.accounts.mint.to_account_info())?,
+            ContractError::TransferHookNotAllowed
+        );
+        let keeper = ctx.accounts.keeper.key();
+        let mint_key = ctx.accounts.mint.key();
+        let token_program_key = ctx.accounts.token_program.key();
+        let now = Clock::get()?.unix_timestamp;
+        let base_fee_bps = fees::base_fee_bps(ctx.accounts.config.fee_bps, &ctx.accounts.mint_fee_config)?;
+        ctx.accounts.fee_epoch_stats.mint = mint_key;
+        ctx.accounts.fee_epoch_stats.bump = ctx.bumps.fee_epoch_stats;
+        ctx.accounts.global_stats.bump = ctx.bumps.global_stats;
+
+        let mut settled = 0u32;
+        for chunk in ctx.remaining_accounts.chunks(4) {
+            let (bounty_info, escrow_authority, escrow_info, contributor_token_info) = (&chunk[0], &chunk[1], &chunk[2], &chunk[3]);
+            let Ok(mut bounty) = Account::<Bounty>::try_from(bounty_info) else { continue };
+            let Ok(contributor) = bounty.assigned_contributor() else { continue };
+            if bounty.state != BountyState::InProgress
+                || bounty.locked
+                || bounty.refunded_count != 0
+                || bounty.mint != mint_key
+                || bounty.token_program != token_program_key
+                || !bounty.keeper_allowed(&keeper)
+                || bounty.approvals.len() < bounty.required_approvals as usize
+                || bounty.yield_principal != 0
+                || bounty.require_contributor_ack
+                || bounty.deliverable_commitment.is_some()
+                || (bounty.donate_bps != 0 && ctx.accounts.charity_token_account.is_none())
+                || bounty.payout_mint.is_some()
+                || bounty.unwrap_on_complete
+            {
+                continue;
+            }
+            if escrow::assert_escrow_authority(escrow_authority, bounty_info.key, bounty.bump).is_err() {
+                continue;
+            }
+            let expected_escrow = get_associated_token_address_with_program_id(escrow_authority.key, &mint_key, &token_program_key);
+            if escrow_info.key() != expected_escrow {
+                continue;
+            }
+            let Ok(escrow_token_account) = InterfaceAccount::<TokenAccount>::try_from(escrow_info) else { continue };
+            let Ok(contributor_token_account) = InterfaceAccount::<TokenAccount>::try_from(contributor_token_info) else { continue };
+            if !validate::mint_matches(&contributor_token_account, &mint_key) || !validate::owner_matches(&contributor_token_account, &contributor) {
+                continue;
+            }
+
+            let bounty_id = bounty.bounty_id;
+            let seeds = escrow::escrow_signer_seeds(bounty_info.key, &bounty.bump);
+            let signer = &[&seeds[..]];
+
+            let fee_bps = fees::discounted_fee_bps(base_fee_bps, ctx.accounts.config.fee_discount_max_bps, bounty.created_at, bounty.deadline, now);
+            let fee = fees::apply_bps(bounty.amount, fee_bps)?;
+            let (treasury_fee, staker_fee) = fees::split_fee(fee, ctx.accounts.config.fee_treasury_bps)?;
+            let dust = fees::escrow_dust(escrow_token_account.amount, bounty.amount);
+
+            EscrowPayout {
+                bounty_id,
+                contributor,
+                burn_bps: ctx.accounts.config.burn_bps,
+                donate_bps: bounty.donate_bps,
+                token_program: &ctx.accounts.token_program,
+                mint: &ctx.accounts.mint,
+                escrow_token_account: &escrow_token_account,
+                escrow_authority,
+                signer,
+                treasury_token_account: ctx.accounts.treasury_token_account.as_ref(),
+                staker_token_account: ctx.accounts.staker_token_account.as_ref(),
+                charity_token_account: ctx.accounts.charity_token_account.as_ref(),
+            }
+            .settle(&contributor_token_account, bounty.amount - fee, treasury_fee, staker_fee, dust)?;
+            close_account(CpiContext::new_with_signer(
+                ctx.accounts.token_program.to_account_info(),
+                CloseAccount {
+                    account: escrow_info.clone(),
+                    destination: bounty_info.clone(),
+                    authority: escrow_authority.clone(),
+                },
+                signer,
+            ))?;
+
+            ctx.accounts.fee_epoch_stats.record(Clock::get()?.epoch, fee).ok_or(ContractError::ArithmeticOverflow)?;
+            emit!(BountyCompleted {
+                bounty_id,
+                contributor,
+                amount: bounty.amount,
+                fee,
+            });
+            ctx.accounts.global_stats
+                .record_receipt(bounty_id, &contributor, bounty.amount)
+                .ok_or(ContractError::ArithmeticOverflow)?;
+            ctx.accounts.count_by_state
+                .record(Some(&bounty.state), &BountyState::Completed)
+                .ok_or(ContractError::ArithmeticOverflow)?;
+            emit!(StateTransition {
+                bounty_id,
+                from_state: bounty.state.clone(),
+                to_state: BountyState::Completed,
+            });
+            bounty.transition(BountyState::Completed)?;
+            emit!(BountyFinalSnapshot::new(bounty_info.key(), &bounty));
+            bounty.exit(&crate::ID)?;
+            settled += 1;
+        }
+
+        Ok(settled)
+    }
+
+    // Applies several config changes in one transaction
+    pub fn admin_update_config(ctx: Context<UpdateConfig>, params: ConfigUpdateParams) -> Result<()> {
+        let config = &mut ctx.accounts.config;
+        let mut changed = Vec::new();
+
+        config.set_fee_terms(params.fee_bps, params.fee_treasury_bps, None, None)?;
+        if params.fee_bps.is_some() {
+            changed.push("fee_bps".to_string());
+        }
+        if params.fee_treasury_bps.is_some() {
+            changed.push("fee_treasury_bps".to_string());
+        }
+        if let Some(fee_discount_max_bps) = params.fee_discount_max_bps {
+            require!(fee_discount_max_bps as u64 <= fees::BPS_DENOMINATOR, ContractError::InvalidFeeBps);
+            config.fee_discount_max_bps = fee_discount_max_bps;
+            changed.push("fee_discount_max_bps".to_string());
+        }
+        if let Some(min_bounty_amount) = params.min_bounty_amount {
+            config.min_bounty_amount = min_bounty_amount;
+            changed.push("min_bounty_amount".to_string());
+        }
+        if let Some(max_bounty_amount) = params.max_bounty_amount {
+            config.max_bounty_amount = max_bounty_amount;
+            changed.push("max_bounty_amount".to_string());
+        }
+        if let Some(paused) = params.paused {
+            config.paused = paused;
+            changed.push("paused".to_string());
+        }
+        if let Some(allow_transfer_hooks) = params.allow_transfer_hooks {
+            config.allow_transfer_hooks = allow_transfer_hooks;
+            changed.push("allow_transfer_hooks".to_string());
+        }
+        if let Some(default_keeper) = params.default_keeper {
+            config.default_keeper = default_keeper;
+            changed.push("default_keeper".to_string());
+        }
+        if let Some(allow_admin_force_on_created) = params.allow_admin_force_on_created {
+            config.allow_admin_force_on_created = allow_admin_force_on_created;
+            changed.push("allow_admin_force_on_created".to_string());
+        }
+        if let Some(admin_intervention_bps) = params.admin_intervention_bps {
+            require!(admin_intervention_bps as u64 <= fees::BPS_DENOMINATOR, ContractError::InvalidFeeBps);
+            require!(admin_intervention_bps <= fees::MAX_FEE_BPS, ContractError::FeeTooHigh);
+            config.admin_intervention_bps = admin_intervention_bps;
+            changed.push("admin_intervention_bps".to_string());
+        }
+
+        require!(
+            config.max_bounty_amount == 0 || config.min_bounty_amount <= config.max_bounty_amount,
+            ContractError::InvalidAmountLimits
+        );
+
+        emit!(ConfigChanged {
+            admin: ctx.accounts.admin.key(),
+            changed,
+        });
+
+        Ok(())
+    }
+
+    // Replaces a completed bounty account with a leaf in the archive tree: the leaf is the
+    // sha256 of its final snapshot, and the account's rent goes back to its rent payer
+    pub fn archive_compressed(ctx: Context<ArchiveCompressed>, bounty_id: u64) -> Result<()> {
+        let bounty = &ctx.accounts.bounty;
+        require!(bounty.bounty_id == bounty_id, ContractError::BountyIdMismatch);
+
+        let snapshot = BountyFinalSnapshot::new(bounty.key(), bounty);
+        let leaf = anchor_lang::solana_program::hash::hash(&snapshot.try_to_vec()?).to_bytes();
+
+        let seeds = &[b"archive_authority".as_ref(), &[ctx.bumps.archive_authority]];
+        compression::append_leaf(
+            &ctx.accounts.compression_program.to_account_info(),
+            &ctx.accounts.merkle_tree.to_account_info(),
+            &ctx.accounts.archive_authority.to_account_info(),
+            &ctx.accounts.noop_program.to_account_info(),
+            leaf,
+            &[&seeds[..]],
+        )?;
+
+        emit!(BountyArchived {
+            bounty_id,
+            merkle_tree: ctx.accounts.merkle_tree.key(),
+            leaf,
+        });
+
+        Ok(())
+    }
+
+    // Identifies the deployment a client is talking to
+    pub fn program_info(ctx: Context<GetProgramInfo>) -> Result<ProgramInfo> {
+        Ok(ProgramInfo {
+            admin: ctx.accounts.config.admin,
+            version: PROGRAM_VERSION,
+            config_bump: ctx.accounts.config.bump,
+        })
+    }
+
+    // What completing the bounty now would pay out, assuming no sponsor covers the fee
+    pub fn preview_payout(ctx: Context<PreviewPayout>, bounty_id: u64) -> Result<PayoutPreview> {
+        let bounty = &ctx.accounts.bounty;
+        require!(bounty.bounty_id == bounty_id, ContractError::BountyIdMismatch);
+        let fee_bps = fees::discounted_fee_bps(
+            fees::base_fee_bps(ctx.accounts.config.fee_bps, &ctx.accounts.mint_fee_config)?,
+            ctx.accounts.config.fee_discount_max_bps,
+            bounty.created_at,
+            bounty.deadline,
+            Clock::get()?.unix_timestamp,
+        );
+        let fee = fees::apply_bps(bounty.amount, fee_bps)?;
+        let (burned, donation, net) = fees::split_payout(bounty.amount - fee, ctx.accounts.config.burn_bps, bounty.donate_bps)?;
+        let dust = fees::escrow_dust(ctx.accounts.escrow_token_account.amount, bounty.amount);
+        Ok(PayoutPreview {
+            gross: bounty.amount,
+            fee,
+            burned,
+            donation,
+            net: net + dust,
+        })
+    }
+
+    // Bounty totals per state, for UIs that page without scanning accounts
+    pub fn get_count_by_state(ctx: Context<GetCountByState>) -> Result<CountByState> {
+        Ok((*ctx.accounts.count_by_state).clone())
+    }
+
+    // Fees collected for a mint in the current and last active epoch
+    pub fn get_fee_epoch_stats(ctx: Context<GetFeeEpochStats>) -> Result<FeeEpochStats> {
+        let mut stats = (*ctx.accounts.fee_epoch_stats).clone();
+        stats.roll(Clock::get()?.epoch);
+        Ok(stats)
+    }
+
+    // Admin sweeps and closes an escrow ATA whose bounty account no longer exists
+    pub fn reclaim_orphan_escrow(ctx: Context<ReclaimOrphanEscrow>) -> Result<()> {
+        let bounty_info = ctx.accounts.bounty.to_account_info();
+        require!(
+            bounty_info.data_is_empty() && bounty_info.lamports() == 0,
+            ContractError::EscrowNotOrphaned
+        );
+
+        let bounty_key = bounty_info.key();
+        let bump = ctx.bumps.escrow_authority;
+        let seeds = escrow::escrow_signer_seeds(&bounty_key, &bump);
+        let signer = &[&seeds[..]];
+
+        let amount = ctx.accounts.escrow_token_account.amount;
+        if amount > 0 {
+            let cpi_ctx = CpiContext::new_with_signer(
+                ctx.accounts.token_program.to_account_info(),
+                TransferChecked {
+                    from: ctx.accounts.escrow_token_account.to_account_info(),
+                    mint: ctx.accounts.mint.to_account_info(),
+                    to: ctx.accounts.treasury_token_account.to_account_info(),
+                    authority: ctx.accounts.escrow_authority.to_account_info(),
+                },
+                signer,
+            );
+            transfer_checked(cpi_ctx, amount, ctx.accounts.mint.decimals)?;
+        }
+
+        // Rent goes to the protocol treasury
+        let cpi_ctx = CpiContext::new_with_signer(
+            ctx.accounts.token_program.to_account_info(),
+            CloseAccount {
+                account: ctx.accounts.escrow_token_account.to_account_info(),
+                destination: ctx.accounts.treasury.to_account_info(),
+                authority: ctx.accounts.escrow_authority.to_account_info(),
+            },
+            signer,
+        );
+        close_account(cpi_ctx)?;
+
+        emit!(OrphanEscrowReclaimed {
+            escrow_token_account: ctx.accounts.escrow_token_account.key(),
+            amount,
+        });
+
+        Ok(())
+    }
+
+    // Admin moves idle escrow funds into the configured lending protocol
+    pub fn deposit_to_yield<'info>(ctx: Context<'_, '_, '_, 'info, DepositToYield<'info>>, amount: u64) -> Result<()> {
+        reentrancy::enter(&mut ctx.accounts.bounty)?;
+        let config = &mut ctx.accounts.config;
+        let bounty = &mut ctx.accounts.bounty;
+
+        require!(config.yield_enabled, ContractError::YieldDisabled);
+        require!(amount > 0, ContractError::InvalidAmount);
+        require!(
+            bounty.state == BountyState::Created || bounty.state == BountyState::InProgress,
+            ContractError::InvalidBountyStateForOperation
+        );
+        require!(amount <= ctx.accounts.escrow_token_account.amount, ContractError::InsufficientAmount);
+
+        let principal = bounty.yield_principal.checked_add(amount).ok_or(ContractError::ArithmeticOverflow)?;
+        require!(principal <= bounty.amount, ContractError::InsufficientAmount);
+
+        let bounty_key = bounty.key();
+        let bump = bounty.bump;
+        let seeds = escrow::escrow_signer_seeds(&bounty_key, &bump);
+        let signer = &[&seeds[..]];
+
+        yield_hook::deposit(
+            &ctx.accounts.yield_program.to_account_info(),
+            &ctx.accounts.escrow_authority.to_account_info(),
+            &ctx.accounts.escrow_token_account.to_account_info(),
+            &ctx.accounts.token_program.to_account_info(),
+            ctx.remaining_accounts,
+            amount,
+            signer,
+        )?;
+
+        bounty.yield_principal = principal;
+        config.yield_deployed = config.yield_deployed.checked_add(amount).ok_or(ContractError::ArithmeticOverflow)?;
+
+        emit!(YieldDeposited {
+            bounty_id: bounty.bounty_id,
+            amount,
+        });
+
+        reentrancy::leave(bounty);
+        Ok(())
+    }
+
+    // Admin pulls the position back into escrow; anything above principal goes to the treasury
+    pub fn withdraw_from_yield<'info>(ctx: Context<'_, '_, '_, 'info, WithdrawFromYield<'info>>) -> Result<()> {
+        reentrancy::enter(&mut ctx.accounts.bounty)?;
+        let principal = ctx.accounts.bounty.yield_principal;
+        require!(principal > 0, ContractError::InvalidAmount);
+        ctx.accounts.bounty.assert_mint(&ctx.accounts.escrow_token_account.mint, &ctx.accounts.treasury_token_account.mint)?;
+
+        let bounty_key = ctx.accounts.bounty.key();
+        let bump = ctx.accounts.bounty.bump;
+        let seeds = escrow::escrow_signer_seeds(&bounty_key, &bump);
+        let signer = &[&seeds[..]];
+
+        let balance_before = ctx.accounts.escrow_token_account.amount;
+
+        // Withdrawing is allowed even if yield was disabled since the deposit
+        yield_hook::withdraw(
+            &ctx.accounts.yield_program.to_account_info(),
+            &ctx.accounts.escrow_authority.to_account_info(),
+            &ctx.accounts.escrow_token_account.to_account_info(),
+            &ctx.accounts.token_program.to_account_info(),
+            ctx.remaining_accounts,
+            principal,
+            signer,
+        )?;
+
+        ctx.accounts.escrow_token_account.reload()?;
+        let received = ctx.accounts.escrow_token_account.amount
+            .checked_sub(balance_before)
+            .ok_or(ContractError::YieldPrincipalShortfall)?;
+        require!(received >= principal, ContractError::YieldPrincipalShortfall);
+
+        let yield_amount = received - principal;
+        if yield_amount > 0 {
+            let cpi_ctx = CpiContext::new_with_signer(
+                ctx.accounts.token_program.to_account_info(),
+                TransferChecked {
+                    from: ctx.accounts.escrow_token_account.to_account_info(),
+                    mint: ctx.accounts.mint.to_account_info(),
+                    to: ctx.accounts.treasury_token_account.to_account_info(),
+                    authority: ctx.accounts.escrow_authority.to_account_info(),
+                },
+                signer,
+            );
+            transfer_checked(cpi_ctx, yield_amount, ctx.accounts.mint.decimals)?;
+        }
+
+        let bounty = &mut ctx.accounts.bounty;
+        let config = &mut ctx.accounts.config;
+        bounty.yield_principal = 0;
+        config.yield_deployed = config.yield_deployed.saturating_sub(principal);
+
+        emit!(YieldWithdrawn {
+            bounty_id: bounty.bounty_id,
+            principal,
+            yield_amount,
+        });
+
+        reentrancy::leave(bounty);
+        Ok(())
+    }
+}
+
+// Shared by `initialize_bounty` and `relaunch_bounty`
+fn create_bounty(
+    ctx: &mut Context<InitializeBounty>,
+    bounty_id: u64,
+    amount: u64,
+    priority: u8,
+    deadline: i64,
+    keeper: Option<Pubkey>,
+    milestones: Vec<u64>,
+) -> Result<()> {
+    write_bounty(ctx, bounty_id, amount, priority, deadline, keeper, milestones)?;
+
+    let counts = &mut ctx.accounts.count_by_state;
+    counts.bump = ctx.bumps.count_by_state;
+    counts.record(None, &BountyState::Created).ok_or(ContractError::ArithmeticOverflow)?;
+
+    let volume_window = &mut ctx.accounts.volume_window;
+    volume_window.mint = ctx.accounts.mint.key();
+    volume_window.bump = ctx.bumps.volume_window;
+    volume_window.admit(Clock::get()?.unix_timestamp, amount, ctx.accounts.config.max_daily_volume)?;
+
+    // Transfer tokens from maintainer to escrow
+    let cpi_accounts = TransferChecked {
+        from: ctx.accounts.maintainer_token_account.to_account_info(),
+        mint: ctx.accounts.mint.to_account_info(),
+        to: ctx.accounts.escrow_token_account.to_account_info(),
+        authority: ctx.accounts.maintainer.to_account_info(),
+    };
+    let cpi_program = ctx.accounts.token_program.to_account_info();
+    let cpi_ctx = CpiContext::new(cpi_program, cpi_accounts);
+    transfer_checked(cpi_ctx, amount, ctx.accounts.mint.decimals)?;
+
+    emit!(BountyCreated {
+        bounty_id,
+        maintainer: ctx.accounts.maintainer.key(),
+        amount,
+        priority,
+        keeper: ctx.accounts.bounty.keeper,
+    });
+
+    Ok(())
+}
+
+// Validates and writes a new bounty record in the Created state; no tokens move
+fn write_bounty(
+    ctx: &mut Context<InitializeBounty>,
+    bounty_id: u64,
+    amount: u64,
+    priority: u8,
+    deadline: i64,
+    keeper: Option<Pubkey>,
+    milestones: Vec<u64>,
+) -> Result<()> {
+    // Only an account `init_if_needed` just created is still zeroed
+    require!(ctx.accounts.bounty.maintainer == Pubkey::default(), ContractError::BountyAlreadyExists);
+    let now = Clock::get()?.unix_timestamp;
+    ctx.accounts.config.check_new_bounty(amount, ctx.accounts.mint.supply, &ctx.accounts.mint_whitelist, deadline, now)?;
+    escrow::assert_fresh_escrow(&ctx.accounts.escrow_token_account, &ctx.accounts.escrow_authority.key())?;
+    require!(milestones.len() <= Bounty::MAX_MILESTONES, ContractError::TooManyMilestones);
+    if !milestones.is_empty() {
+        let total = milestones.iter().try_fold(0u64, |sum, m| sum.checked_add(*m));
+        require!(
+            !milestones.contains(&0) && total == Some(amount),
+            ContractError::InvalidMilestones
+        );
+    }
+
+    let bounty = &mut ctx.accounts.bounty;
+    bounty.maintainer = ctx.accounts.maintainer.key();
+    bounty.contributor = None;
+    bounty.mint = ctx.accounts.mint.key();
+    bounty.amount = amount;
+    bounty.bump = ctx.bumps.escrow_authority;
+    bounty.bounty_id = bounty_id;
+    bounty.state = BountyState::Created;
+    bounty.yield_principal = 0;
+    bounty.priority = priority;
+    bounty.merkle_root = [0u8; 32];
+    bounty.created_at = now;
+    bounty.deadline = deadline;
+    bounty.keeper = keeper.unwrap_or(ctx.accounts.config.default_keeper);
+    bounty.contributor_is_pda = false;
+    bounty.milestones = milestones;
+    bounty.milestones_paid = 0;
+    bounty.unwrap_on_complete = false;
+    bounty.rent_payer = ctx.accounts.maintainer.key();
+    bounty.allow_custom_fee_recipient = false;
+    bounty.locked = false;
+    bounty.required_approvals = 0;
+    bounty.approvals = Vec::new();
+    bounty.reviewers = Vec::new();
+    bounty.donate_bps = 0;
+    bounty.payout_mint = None;
+    bounty.payout_min_out = 0;
+    bounty.require_contributor_ack = false;
+    bounty.relaunched_from = None;
+    bounty.allowed_keepers = Vec::new();
+    bounty.funder_count = 0;
+    bounty.refunded_count = 0;
+    bounty.grace_period = 0;
+    bounty.submitted_at = 0;
+    bounty.token_program = ctx.accounts.token_program.key();
+    bounty.admin_override_allowed = true;
+    bounty.deliverable_commitment = None;
+    bounty.tipped_amount = 0;
+    bounty.state_version = 0;
+    bounty.custom_fee_recipient = None;
+
+    // A LEN that undercounts a field would only surface once a vec fills up, so fail loudly at creation
+    let bounty_info = ctx.accounts.bounty.to_account_info();
+    require!(
+        bounty_info.data_len() == Bounty::LEN && Rent::get()?.is_exempt(bounty_info.lamports(), bounty_info.data_len()),
+        ContractError::BountyAccountSize
+    );
+
+    // Anchor can only init with a fixed payer, so a separate rent payer reimburses the maintainer
+    if let Some(rent_payer) = &ctx.accounts.rent_payer {
+        if rent_payer.key() != ctx.accounts.maintainer.key() {
+            let rent = ctx.accounts.bounty.to_account_info().lamports();
+            system_program::transfer(
+                CpiContext::new(
+                    ctx.accounts.system_program.to_account_info(),
+                    system_program::Transfer {
+                        from: rent_payer.to_account_info(),
+                        to: ctx.accounts.maintainer.to_account_info(),
+                    },
+                ),
+                rent,
+            )?;
+            ctx.accounts.bounty.rent_payer = rent_payer.key();
+        }
+    }
+
+    Ok(())
+}
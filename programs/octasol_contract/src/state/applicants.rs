@@ -0,0 +1,18 @@
+use anchor_lang::prelude::*;
+
+// Contributors who applied to an open bounty, in application order
+#[account]
+pub struct ApplicantList {
+    pub bounty: Pubkey,
+    pub applicants: Vec<Pubkey>,
+    pub bump: u8,
+}
+
+impl ApplicantList {
+    pub const MAX_APPLICANTS: usize = 16;
+
+    pub const LEN: usize = 8 + // discriminator
+        32 + // bounty
+        4 + 32 * Self::MAX_APPLICANTS + // applicants vec
+        1;  // bump
+}
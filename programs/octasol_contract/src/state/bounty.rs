@@ -1,14 +1,78 @@
 use anchor_lang::prelude::*;
 
-#[derive(AnchorSerialize, AnchorDeserialize, Clone, PartialEq)]
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, PartialEq, Debug, Default)]
 pub enum BountyState {
+    #[default]
     Created,
     InProgress,
     Completed,
     Cancelled,
+    // Described but not funded yet, see `initialize_draft` and `fund_draft`
+    Draft,
+}
+
+impl BountyState {
+    // Position in the lifecycle; Completed and Cancelled are both terminal
+    fn rank(&self) -> u8 {
+        match self {
+            BountyState::Draft => 0,
+            BountyState::Created => 1,
+            BountyState::InProgress => 2,
+            BountyState::Completed | BountyState::Cancelled => 3,
+        }
+    }
+
+    // Transitions only move forward, with one sanctioned step back: `admin_unassign` returns an
+    // InProgress bounty to Created. A draft is either funded or discarded, and terminal states
+    // and Draft can never be re-entered.
+    pub fn can_transition_to(&self, to: &BountyState) -> bool {
+        match (self, to) {
+            (BountyState::Draft, BountyState::Created | BountyState::Cancelled) => true,
+            (BountyState::Draft, _) | (_, BountyState::Draft) => false,
+            (BountyState::InProgress, BountyState::Created) => true,
+            _ => self.rank() < 3 && to.rank() > self.rank(),
+        }
+    }
+}
+
+// Why a bounty was cancelled, emitted as a `u8` code in `BountyCancelled`
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Copy, PartialEq)]
+#[repr(u8)]
+pub enum CancelReason {
+    MaintainerCancel = 0,
+    AdminCancel = 1,
+    ExpiryReclaim = 2,
+    DisputeRefund = 3,
+    DraftDiscarded = 4,
+}
+
+// Returned by `derive_bounty_address`
+#[derive(AnchorSerialize, AnchorDeserialize, Clone)]
+pub struct BountyAddress {
+    pub address: Pubkey,
+    pub bump: u8,
+}
+
+// Returned by `preview_payout`; `net` is what the contributor would receive
+#[derive(AnchorSerialize, AnchorDeserialize, Clone)]
+pub struct PayoutPreview {
+    pub gross: u64,
+    pub fee: u64,
+    pub burned: u64,
+    pub donation: u64,
+    pub net: u64,
+}
+
+// Returned by instructions that move a bounty between states, so clients
+// don't have to re-fetch the account (which may already be closed)
+#[derive(AnchorSerialize, AnchorDeserialize, Clone)]
+pub struct BountyStateChange {
+    pub bounty_id: u64,
+    pub new_state: BountyState,
 }
 
 #[account]
+#[derive(Default)]
 pub struct Bounty {
     pub maintainer: Pubkey,
     pub contributor: Option<Pubkey>,
@@ -17,16 +81,273 @@ pub struct Bounty {
     pub amount: u64,
     pub state: BountyState,
     pub bounty_id: u64,
+    pub yield_principal: u64,
+    // Keeper ordering hint, higher is more urgent
+    pub priority: u8,
+    // Root of the contributor allowlist, all zeroes when unset
+    pub merkle_root: [u8; 32],
+    pub created_at: i64,
+    pub deadline: i64,
+    // Settlement keeper for this bounty
+    pub keeper: Pubkey,
+    // Contributor is a program-owned address (e.g. a DAO treasury PDA) rather than a wallet
+    pub contributor_is_pda: bool,
+    // Payout schedule, empty for a single payout; amounts sum to `amount` at creation
+    pub milestones: Vec<u64>,
+    pub milestones_paid: u8,
+    // Escrowed mint is an LP token to be redeemed into its underlying for the contributor
+    pub unwrap_on_complete: bool,
+    // Who paid the bounty account's rent, and gets it back when the account closes
+    pub rent_payer: Pubkey,
+    // Completion may route the fee to a caller-supplied recipient (e.g. a charity)
+    pub allow_custom_fee_recipient: bool,
+    // Set while a fund-moving instruction runs, see util::reentrancy
+    pub locked: bool,
+    // Distinct reviewer sign-offs completion needs, see `approve_work`
+    pub required_approvals: u8,
+    pub approvals: Vec<Pubkey>,
+    // Share of the contributor's payout donated to config.charity, chosen by the contributor
+    pub donate_bps: u16,
+    // Token the contributor wants to be paid in, swapped from the escrow mint on completion
+    pub payout_mint: Option<Pubkey>,
+    pub payout_min_out: u64,
+    // Completion needs the contributor's signature, confirming they consider the work settled
+    pub require_contributor_ack: bool,
+    // Id of the earlier bounty for the same issue this one relaunches, see `relaunch_bounty`
+    pub relaunched_from: Option<u64>,
+    // Keepers allowed to co-sign completion, empty for no restriction
+    pub allowed_keepers: Vec<Pubkey>,
+    // Third-party funders with a Contribution, and how many of them a crowd-funded cancel has refunded
+    pub funder_count: u32,
+    pub refunded_count: u32,
+    // Seconds after the deadline the contributor may still deliver before the maintainer can reclaim
+    pub grace_period: i64,
+    // When the contributor last called `submit_work`, 0 if they haven't
+    pub submitted_at: i64,
+    // Token program the escrow was created under; every escrow movement must go through it
+    pub token_program: Pubkey,
+    // Whether admin may force-release this bounty, chosen by the maintainer before assignment
+    pub admin_override_allowed: bool,
+    // Hash the contributor committed to deliver, checked at completion when set
+    pub deliverable_commitment: Option<[u8; 32]>,
+    // Part of `amount` added by `tip_bounty`; never refunded to the tippers
+    pub tipped_amount: u64,
+    // Bumped by every `transition`, so clients can tell two reads of the same state apart
+    pub state_version: u16,
+    // Keys the maintainer trusts to sign `approve_work`, see `set_reviewers`
+    pub reviewers: Vec<Pubkey>,
+    // Owner of the token account completion may route the fee to, approved by the maintainer
+    pub custom_fee_recipient: Option<Pubkey>,
 }
 
 impl Bounty {
+    pub const SEED_PREFIX: &'static [u8] = b"bounty";
+    pub const MAX_MILESTONES: usize = 10;
+    pub const MAX_APPROVALS: usize = 5;
+    pub const MAX_KEEPERS: usize = 5;
+
+    // Borsh size of a bounty with every Option set and every Vec at its cap, in field order.
+    // A new field goes at the end of the struct and gets its own line here.
     pub const LEN: usize = 8 + // discriminator
-        32 + // maintainer pubkey
-        33 + // contributor option pubkey
-        8 + // amount
-        1 + // state
-        8 + // bounty_id
-        32 + // mint address
-        8;  // bump
+        32 + // maintainer: Pubkey
+        1 + 32 + // contributor: Option<Pubkey>
+        32 + // mint: Pubkey
+        1 + // bump: u8
+        8 + // amount: u64
+        1 + // state: BountyState (unit variants only)
+        8 + // bounty_id: u64
+        8 + // yield_principal: u64
+        1 + // priority: u8
+        32 + // merkle_root: [u8; 32]
+        8 + // created_at: i64
+        8 + // deadline: i64
+        32 + // keeper: Pubkey
+        1 + // contributor_is_pda: bool
+        4 + 8 * Self::MAX_MILESTONES + // milestones: Vec<u64>
+        1 + // milestones_paid: u8
+        1 + // unwrap_on_complete: bool
+        32 + // rent_payer: Pubkey
+        1 + // allow_custom_fee_recipient: bool
+        1 + // locked: bool
+        1 + // required_approvals: u8
+        4 + 32 * Self::MAX_APPROVALS + // approvals: Vec<Pubkey>
+        2 + // donate_bps: u16
+        1 + 32 + // payout_mint: Option<Pubkey>
+        8 + // payout_min_out: u64
+        1 + // require_contributor_ack: bool
+        1 + 8 + // relaunched_from: Option<u64>
+        4 + 32 * Self::MAX_KEEPERS + // allowed_keepers: Vec<Pubkey>
+        4 + // funder_count: u32
+        4 + // refunded_count: u32
+        8 + // grace_period: i64
+        8 + // submitted_at: i64
+        32 + // token_program: Pubkey
+        1 + // admin_override_allowed: bool
+        1 + 32 + // deliverable_commitment: Option<[u8; 32]>
+        8 + // tipped_amount: u64
+        2 + // state_version: u16
+        4 + 32 * Self::MAX_APPROVALS + // reviewers: Vec<Pubkey>
+        1 + 32; // custom_fee_recipient: Option<Pubkey>
+
+    // Address `InitializeBounty` creates a bounty at: seeds ["bounty", maintainer, bounty_id (le)]
+    pub fn find_address(maintainer: &Pubkey, bounty_id: u64) -> (Pubkey, u8) {
+        Pubkey::find_program_address(
+            &[Self::SEED_PREFIX, maintainer.as_ref(), &bounty_id.to_le_bytes()],
+            &crate::ID,
+        )
+    }
+
+    // Funds may only move between accounts of the bounty's current mint
+    // require_keys_eq! logs both keys on failure
+    pub fn assert_mint(&self, escrow_mint: &Pubkey, payout_mint: &Pubkey) -> Result<()> {
+        require_keys_eq!(self.mint, *escrow_mint, crate::util::errors::ContractError::InvalidMint);
+        require_keys_eq!(self.mint, *payout_mint, crate::util::errors::ContractError::InvalidMint);
+        Ok(())
+    }
+
+    // Errors rather than panicking if an InProgress bounty ever lacks a contributor, and never
+    // hands out the default pubkey as a payout destination
+    pub fn assigned_contributor(&self) -> Result<Pubkey> {
+        let contributor = self.contributor.ok_or(crate::util::errors::ContractError::InvalidContributor)?;
+        require_keys_neq!(contributor, Pubkey::default(), crate::util::errors::ContractError::InvalidContributor);
+        Ok(contributor)
+    }
+
+    // Whether `settler` may settle this bounty: its keeper, or once the maintainer has set
+    // allowed_keepers, any member of that set instead
+    pub fn keeper_allowed(&self, settler: &Pubkey) -> bool {
+        if self.allowed_keepers.is_empty() {
+            *settler == self.keeper
+        } else {
+            self.allowed_keepers.contains(settler)
+        }
+    }
+
+    // Reviewer approvals, contributor sign-off and deliverable commitment every release to the
+    // assigned contributor has to satisfy, whoever triggers it
+    pub fn check_release_gates(&self, contributor_signed: bool, deliverable_hash: Option<[u8; 32]>) -> Result<()> {
+        require!(self.approvals.len() >= self.required_approvals as usize, crate::util::errors::ContractError::InsufficientApprovals);
+        require!(!self.require_contributor_ack || contributor_signed, crate::util::errors::ContractError::ContributorAckRequired);
+        if let Some(commitment) = self.deliverable_commitment {
+            require!(deliverable_hash == Some(commitment), crate::util::errors::ContractError::DeliverableMismatch);
+        }
+        Ok(())
+    }
+
+    // Moves the bounty to `to`, rejecting anything the lifecycle does not allow
+    pub fn transition(&mut self, to: BountyState) -> Result<()> {
+        require!(self.state.can_transition_to(&to), crate::util::errors::ContractError::InvalidStateTransition);
+        self.state = to;
+        self.state_version = self.state_version.checked_add(1).ok_or(crate::util::errors::ContractError::ArithmeticOverflow)?;
+        Ok(())
+    }
+
+    pub fn state_change(&self) -> BountyStateChange {
+        BountyStateChange {
+            bounty_id: self.bounty_id,
+            new_state: self.state.clone(),
+        }
+    }
 }
 
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::util::errors::ContractError;
+
+    #[test]
+    fn assigned_contributor_errors_without_a_contributor() {
+        let bounty = Bounty::default();
+        assert_eq!(bounty.assigned_contributor().unwrap_err(), ContractError::InvalidContributor.into());
+    }
+
+    #[test]
+    fn assigned_contributor_rejects_the_default_pubkey() {
+        let bounty = Bounty { contributor: Some(Pubkey::default()), ..Bounty::default() };
+        assert_eq!(bounty.assigned_contributor().unwrap_err(), ContractError::InvalidContributor.into());
+    }
+
+    #[test]
+    fn len_matches_a_bounty_with_every_field_at_its_largest() {
+        let key = Pubkey::new_unique();
+        let bounty = Bounty {
+            contributor: Some(key),
+            milestones: vec![u64::MAX; Bounty::MAX_MILESTONES],
+            approvals: vec![key; Bounty::MAX_APPROVALS],
+            payout_mint: Some(key),
+            relaunched_from: Some(u64::MAX),
+            allowed_keepers: vec![key; Bounty::MAX_KEEPERS],
+            deliverable_commitment: Some([u8::MAX; 32]),
+            reviewers: vec![key; Bounty::MAX_APPROVALS],
+            custom_fee_recipient: Some(key),
+            ..Bounty::default()
+        };
+        let mut data = Vec::new();
+        bounty.try_serialize(&mut data).unwrap();
+        // try_serialize writes the discriminator too
+        assert_eq!(data.len(), Bounty::LEN);
+    }
+
+    #[test]
+    fn keeper_allowed_prefers_the_allowed_set_over_the_single_keeper() {
+        let (keeper, member, outsider) = (Pubkey::new_unique(), Pubkey::new_unique(), Pubkey::new_unique());
+        let mut bounty = Bounty { keeper, ..Bounty::default() };
+        assert!(bounty.keeper_allowed(&keeper));
+        assert!(!bounty.keeper_allowed(&outsider));
+
+        bounty.allowed_keepers = vec![member];
+        assert!(bounty.keeper_allowed(&member));
+        assert!(!bounty.keeper_allowed(&keeper));
+    }
+
+    #[test]
+    fn transitions_follow_the_lifecycle() {
+        use BountyState::*;
+        let all = [Draft, Created, InProgress, Completed, Cancelled];
+        let allowed = [
+            (Draft, Created),
+            (Draft, Cancelled),
+            (Created, InProgress),
+            (Created, Completed),
+            (Created, Cancelled),
+            (InProgress, Created),
+            (InProgress, Completed),
+            (InProgress, Cancelled),
+        ];
+        for (i, from) in all.iter().enumerate() {
+            for (j, to) in all.iter().enumerate() {
+                let expected = allowed.contains(&(from.clone(), to.clone()));
+                assert_eq!(from.can_transition_to(to), expected, "transition {} -> {}", i, j);
+            }
+        }
+    }
+
+    #[test]
+    fn transition_bumps_the_version() {
+        let mut bounty = Bounty::default();
+        bounty.transition(BountyState::InProgress).unwrap();
+        bounty.transition(BountyState::Created).unwrap();
+        bounty.transition(BountyState::InProgress).unwrap();
+        bounty.transition(BountyState::Completed).unwrap();
+        assert_eq!(bounty.state, BountyState::Completed);
+        assert_eq!(bounty.state_version, 4);
+    }
+
+    #[test]
+    fn transition_rejects_regressions_without_bumping_the_version() {
+        let mut bounty = Bounty { state: BountyState::Completed, state_version: 3, ..Bounty::default() };
+        for to in [BountyState::InProgress, BountyState::Created, BountyState::Cancelled, BountyState::Completed] {
+            assert_eq!(bounty.transition(to).unwrap_err(), ContractError::InvalidStateTransition.into());
+        }
+        assert_eq!(bounty.state, BountyState::Completed);
+        assert_eq!(bounty.state_version, 3);
+    }
+
+    #[test]
+    fn assigned_contributor_returns_the_contributor() {
+        let contributor = Pubkey::new_unique();
+        let bounty = Bounty { contributor: Some(contributor), ..Bounty::default() };
+        assert_eq!(bounty.assigned_contributor().unwrap(), contributor);
+    }
+}
@@ -1,6 +1,7 @@
 // In state/config.rs or your main state file
 
 use anchor_lang::prelude::*;
+use crate::util::errors::ContractError;
 
 #[account]
 pub struct ConfigState {
@@ -8,9 +9,334 @@ pub struct ConfigState {
     pub admin: Pubkey,
     // The bump seed for this PDA.
     pub bump: u8,
+    // Wallet that receives protocol revenue (e.g. yield earned on escrows).
+    pub treasury: Pubkey,
+    // Whether escrowed funds may be deposited into the yield program.
+    pub yield_enabled: bool,
+    // The lending program escrow deposits are CPI'd into.
+    pub yield_program: Pubkey,
+    // Total principal currently deployed to the yield program across all bounties.
+    pub yield_deployed: u64,
+    // Whether payouts may go through Token-2022 mints with a transfer hook.
+    pub allow_transfer_hooks: bool,
+    // Protocol fee taken from each payout, in basis points.
+    pub fee_bps: u16,
+    // Share of the fee routed to the treasury, the rest goes to the staker pool.
+    pub fee_treasury_bps: u16,
+    // Wallet that receives the stakers' share of fees.
+    pub staker_pool: Pubkey,
+    // Largest share of the fee waived for completing right after creation,
+    // shrinking linearly to zero at the deadline.
+    pub fee_discount_max_bps: u16,
+    // Settlement keeper new bounties get unless the maintainer picks one.
+    pub default_keeper: Pubkey,
+    // Smallest amount a new bounty may escrow.
+    pub min_bounty_amount: u64,
+    // Largest amount a new bounty may escrow, 0 for no limit.
+    pub max_bounty_amount: u64,
+    // Blocks creating new bounties while set.
+    pub paused: bool,
+    // Program CPI'd on assignment and completion, if any.
+    pub notification_program: Option<Pubkey>,
+    // Whether admin may assign-and-pay a bounty nobody has been assigned to yet.
+    pub allow_admin_force_on_created: bool,
+    // AMM that LP-token escrows are redeemed through on completion, if any.
+    pub amm_program: Option<Pubkey>,
+    // Surcharge on admin force-releases, in basis points, paid to the treasury.
+    pub admin_intervention_bps: u16,
+    // Concurrent merkle tree completed bounties are archived into, if any.
+    pub archive_tree: Option<Pubkey>,
+    // SOL paid from the bonus reserve on a contributor's first completion, 0 disables it.
+    pub first_completion_bonus_lamports: u64,
+    // Informational only: the loader's upgrade authority is what actually gates upgrades.
+    pub announced_upgrade_authority: Option<Pubkey>,
+    pub upgrade_effective_at: i64,
+    // Wallet whose token accounts receive contributor donations, if any.
+    pub charity: Option<Pubkey>,
+    // Restrict new bounties to mints with a MintWhitelist entry.
+    pub mint_whitelist_enabled: bool,
+    // Bitmask of AdminPower codes the admin has given up, never cleared.
+    pub renounced_powers: u32,
+    // Longest a new bounty's deadline may be from its creation, in seconds, 0 for no limit.
+    pub max_deadline_duration: i64,
+    // Share of each completed payout burned from escrow, in basis points.
+    pub burn_bps: u16,
+    // Seconds after submitted work anyone may settle it with `crank_auto_release`, 0 disables it.
+    pub auto_release_window: i64,
+    // Share of the protocol fee paid to whoever cranks an auto-release, in basis points.
+    pub crank_reward_bps: u16,
+    // Most a single mint may escrow into new bounties per UTC day, 0 for no limit.
+    pub max_daily_volume: u64,
+    // Reject admin interventions that don't give a reason.
+    pub require_admin_reason: bool,
+    // Token amount taken from a payout whose completion had to create the contributor's ATA, 0 disables it.
+    pub ata_creation_fee: u64,
+    // Admin proposed by `propose_admin`, who takes over once they call `accept_admin`.
+    pub pending_admin: Option<Pubkey>,
+}
+
+// Admin powers that can be permanently renounced with `renounce_power`
+#[derive(Clone, Copy, PartialEq)]
+#[repr(u8)]
+pub enum AdminPower {
+    ForceRelease = 0,
+    Unassign = 1,
+    Cancel = 2,
+}
+
+impl AdminPower {
+    pub fn from_code(code: u8) -> Option<Self> {
+        match code {
+            0 => Some(Self::ForceRelease),
+            1 => Some(Self::Unassign),
+            2 => Some(Self::Cancel),
+            _ => None,
+        }
+    }
+}
+
+// Outcome of the bounty creation policy checks, returned by `validate_create`
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Default)]
+pub struct CreateReadiness {
+    pub ready: bool,
+    pub zero_amount: bool,
+    pub paused: bool,
+    pub below_minimum: bool,
+    pub above_maximum: bool,
+    pub exceeds_supply: bool,
+}
+
+impl CreateReadiness {
+    // Fails with the error `initialize_bounty` would hit first
+    pub fn check(&self) -> Result<()> {
+        require!(!self.zero_amount, ContractError::InvalidAmount);
+        require!(!self.paused, ContractError::ProtocolPaused);
+        require!(!self.below_minimum, ContractError::AmountBelowMinimum);
+        require!(!self.above_maximum, ContractError::AmountAboveMaximum);
+        require!(!self.exceeds_supply, ContractError::AmountExceedsSupply);
+        Ok(())
+    }
+}
+
+// Every config limit a new bounty must satisfy, returned by `creation_policy` so clients can
+// validate a form without reading the config account themselves
+#[derive(AnchorSerialize, AnchorDeserialize, Clone)]
+pub struct CreationPolicy {
+    pub paused: bool,
+    // 0 for no limit
+    pub min_bounty_amount: u64,
+    pub max_bounty_amount: u64,
+    pub max_deadline_duration: i64,
+    pub max_daily_volume: u64,
+    pub mint_whitelist_enabled: bool,
+    // Whether the queried mint may be escrowed, always true while the whitelist is off
+    pub mint_allowed: bool,
+}
+
+// Returned by `program_info`
+#[derive(AnchorSerialize, AnchorDeserialize, Clone)]
+pub struct ProgramInfo {
+    pub admin: Pubkey,
+    pub version: u8,
+    pub config_bump: u8,
+}
+
+// Arguments for `admin_update_config`, only the `Some` fields are applied
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Default)]
+pub struct ConfigUpdateParams {
+    pub fee_bps: Option<u16>,
+    pub fee_treasury_bps: Option<u16>,
+    pub fee_discount_max_bps: Option<u16>,
+    pub min_bounty_amount: Option<u64>,
+    pub max_bounty_amount: Option<u64>,
+    pub paused: Option<bool>,
+    pub allow_transfer_hooks: Option<bool>,
+    pub default_keeper: Option<Pubkey>,
+    pub allow_admin_force_on_created: Option<bool>,
+    pub admin_intervention_bps: Option<u16>,
 }
 
 impl ConfigState {
-    // 8 bytes for discriminator + 32 for the pubkey + 1 for the bump
-    pub const LEN: usize = 8 + 32 + 1;
-}
\ No newline at end of file
+    // Config as `initialize_config` creates it, also what `migrate_config` fills an old layout with.
+    // Callers set the fee rate and treasury through `set_fee_terms`
+    pub fn new(admin: Pubkey, bump: u8) -> Self {
+        Self {
+            admin,
+            bump,
+            treasury: admin,
+            yield_enabled: false,
+            yield_program: Pubkey::default(),
+            yield_deployed: 0,
+            allow_transfer_hooks: false,
+            fee_bps: 0,
+            fee_treasury_bps: crate::util::fees::BPS_DENOMINATOR as u16,
+            staker_pool: admin,
+            fee_discount_max_bps: 0,
+            default_keeper: admin,
+            min_bounty_amount: 0,
+            max_bounty_amount: 0,
+            paused: false,
+            notification_program: None,
+            allow_admin_force_on_created: true,
+            amm_program: None,
+            admin_intervention_bps: 0,
+            archive_tree: None,
+            first_completion_bonus_lamports: 0,
+            announced_upgrade_authority: None,
+            upgrade_effective_at: 0,
+            charity: None,
+            mint_whitelist_enabled: false,
+            renounced_powers: 0,
+            max_deadline_duration: 0,
+            burn_bps: 0,
+            auto_release_window: 0,
+            crank_reward_bps: 0,
+            max_daily_volume: 0,
+            require_admin_reason: false,
+            ata_creation_fee: 0,
+            pending_admin: None,
+        }
+    }
+
+    // Every write of the fee rate, its treasury split or where it goes comes through here, so the
+    // MAX_FEE_BPS cap and the recipient checks are the same for each admin instruction
+    pub fn set_fee_terms(
+        &mut self,
+        fee_bps: Option<u16>,
+        fee_treasury_bps: Option<u16>,
+        treasury: Option<Pubkey>,
+        staker_pool: Option<Pubkey>,
+    ) -> Result<()> {
+        if let Some(fee_bps) = fee_bps {
+            crate::util::fees::check_fee_bps(fee_bps)?;
+            self.fee_bps = fee_bps;
+        }
+        if let Some(fee_treasury_bps) = fee_treasury_bps {
+            require!(fee_treasury_bps as u64 <= crate::util::fees::BPS_DENOMINATOR, ContractError::InvalidFeeBps);
+            self.fee_treasury_bps = fee_treasury_bps;
+        }
+        // Token accounts owned by the default key can't be signed for, so fees sent there are lost
+        if let Some(treasury) = treasury {
+            require!(treasury != Pubkey::default(), ContractError::InvalidFeeRecipient);
+            self.treasury = treasury;
+        }
+        if let Some(staker_pool) = staker_pool {
+            require!(staker_pool != Pubkey::default(), ContractError::InvalidFeeRecipient);
+            self.staker_pool = staker_pool;
+        }
+        Ok(())
+    }
+
+    pub fn require_power(&self, power: AdminPower) -> Result<()> {
+        require!(self.renounced_powers & (1 << power as u8) == 0, ContractError::PowerRenounced);
+        Ok(())
+    }
+
+    // An all-zero reason counts as none given
+    pub fn check_admin_reason(&self, reason: &[u8; 64]) -> Result<()> {
+        require!(!self.require_admin_reason || reason.iter().any(|b| *b != 0), ContractError::ReasonRequired);
+        Ok(())
+    }
+
+    // Whether new bounties may escrow the mint whose whitelist PDA is `entry`
+    pub fn mint_allowed(&self, entry: &AccountInfo) -> bool {
+        !self.mint_whitelist_enabled || crate::state::MintWhitelist::is_listed(entry)
+    }
+
+    // 8 discriminator + 32 admin + 1 bump + 32 treasury + 1 yield_enabled
+    // + 32 yield_program + 8 yield_deployed + 1 allow_transfer_hooks
+    // + 2 fee_bps + 2 fee_treasury_bps + 32 staker_pool + 2 fee_discount_max_bps
+    // + 32 default_keeper + 8 min_bounty_amount + 8 max_bounty_amount + 1 paused
+    // + 33 notification_program + 1 allow_admin_force_on_created + 33 amm_program
+    // + 2 admin_intervention_bps + 33 archive_tree + 8 first_completion_bonus_lamports
+    // + 33 announced_upgrade_authority + 8 upgrade_effective_at + 33 charity
+    // + 1 mint_whitelist_enabled + 4 renounced_powers + 8 max_deadline_duration + 2 burn_bps
+    // + 8 auto_release_window + 2 crank_reward_bps + 8 max_daily_volume + 1 require_admin_reason
+    // + 8 ata_creation_fee + 33 pending_admin
+    pub const LEN: usize = 8 + 32 + 1 + 32 + 1 + 32 + 8 + 1 + 2 + 2 + 32 + 2 + 32 + 8 + 8 + 1 + 33 + 1 + 33 + 2 + 33 + 8 + 33 + 8 + 33 + 1 + 4 + 8 + 2 + 8 + 2 + 8 + 1 + 8 + 33;
+
+    // Limits for a new bounty escrowing the mint whose whitelist PDA is `mint_whitelist`
+    pub fn creation_policy(&self, mint_whitelist: &AccountInfo) -> CreationPolicy {
+        CreationPolicy {
+            paused: self.paused,
+            min_bounty_amount: self.min_bounty_amount,
+            max_bounty_amount: self.max_bounty_amount,
+            max_deadline_duration: self.max_deadline_duration,
+            max_daily_volume: self.max_daily_volume,
+            mint_whitelist_enabled: self.mint_whitelist_enabled,
+            mint_allowed: self.mint_allowed(mint_whitelist),
+        }
+    }
+
+    // Policy checks a new bounty of `amount` must pass
+    pub fn create_readiness(&self, amount: u64, mint_supply: u64) -> CreateReadiness {
+        let mut readiness = CreateReadiness {
+            ready: false,
+            zero_amount: amount == 0,
+            paused: self.paused,
+            below_minimum: amount < self.min_bounty_amount,
+            above_maximum: self.max_bounty_amount != 0 && amount > self.max_bounty_amount,
+            exceeds_supply: amount > mint_supply,
+        };
+        readiness.ready = readiness.check().is_ok();
+        readiness
+    }
+
+    // Everything a bounty must meet when it is funded, whether created directly or from a draft
+    pub fn check_new_bounty(&self, amount: u64, mint_supply: u64, mint_whitelist: &AccountInfo, deadline: i64, now: i64) -> Result<()> {
+        self.create_readiness(amount, mint_supply).check()?;
+        require!(self.mint_allowed(mint_whitelist), ContractError::MintNotWhitelisted);
+        require!(deadline > now, ContractError::InvalidDeadline);
+        require!(
+            self.max_deadline_duration == 0
+                || deadline <= now.checked_add(self.max_deadline_duration).ok_or(ContractError::ArithmeticOverflow)?,
+            ContractError::DeadlineTooFar
+        );
+        Ok(())
+    }
+}
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn len_matches_a_config_with_every_option_set() {
+        let admin = Pubkey::new_unique();
+        let mut config = ConfigState::new(admin, 254);
+        config.notification_program = Some(admin);
+        config.amm_program = Some(admin);
+        config.archive_tree = Some(admin);
+        config.announced_upgrade_authority = Some(admin);
+        config.charity = Some(admin);
+        config.pending_admin = Some(admin);
+
+        let mut data = Vec::new();
+        config.try_serialize(&mut data).unwrap();
+        assert_eq!(data.len(), ConfigState::LEN);
+        // `migrate_config` reads the admin of an old layout from these bytes
+        assert_eq!(&data[8..40], admin.as_ref());
+    }
+
+    #[test]
+    fn set_fee_terms_checks_every_field_it_is_given() {
+        let admin = Pubkey::new_unique();
+        let mut config = ConfigState::new(admin, 254);
+        let treasury = Pubkey::new_unique();
+        config.set_fee_terms(Some(crate::util::fees::MAX_FEE_BPS), Some(5_000), Some(treasury), None).unwrap();
+        assert_eq!(config.fee_bps, crate::util::fees::MAX_FEE_BPS);
+        assert_eq!(config.fee_treasury_bps, 5_000);
+        assert_eq!(config.treasury, treasury);
+        assert_eq!(config.staker_pool, admin);
+
+        assert_eq!(
+            config.set_fee_terms(Some(crate::util::fees::MAX_FEE_BPS + 1), None, None, None).unwrap_err(),
+            ContractError::FeeTooHigh.into()
+        );
+        assert_eq!(config.set_fee_terms(None, Some(10_001), None, None).unwrap_err(), ContractError::InvalidFeeBps.into());
+        assert_eq!(
+            config.set_fee_terms(None, None, None, Some(Pubkey::default())).unwrap_err(),
+            ContractError::InvalidFeeRecipient.into()
+        );
+        assert_eq!(config.staker_pool, admin);
+    }
+}
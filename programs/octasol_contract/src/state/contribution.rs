@@ -0,0 +1,21 @@
+use anchor_lang::prelude::*;
+
+// One funder's share of a crowd-funded bounty, PDA seeded by [b"contribution", bounty, funder].
+// Closed to the funder when they are refunded on cancel.
+#[account]
+pub struct Contribution {
+    pub bounty: Pubkey,
+    pub funder: Pubkey,
+    pub amount: u64,
+    pub bump: u8,
+}
+
+impl Contribution {
+    pub const SEED_PREFIX: &'static [u8] = b"contribution";
+
+    pub const LEN: usize = 8 + // discriminator
+        32 + // bounty
+        32 + // funder
+        8 + // amount
+        1; // bump
+}
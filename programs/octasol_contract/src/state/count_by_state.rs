@@ -0,0 +1,46 @@
+use anchor_lang::prelude::*;
+use crate::state::BountyState;
+
+// Number of bounties per state, PDA seeded by [b"count_by_state"].
+// Open states count live bounties; completed and cancelled are running totals,
+// since those bounty accounts are closed.
+#[account]
+pub struct CountByState {
+    pub created: u64,
+    pub in_progress: u64,
+    pub completed: u64,
+    pub cancelled: u64,
+    pub bump: u8,
+    pub draft: u64,
+}
+
+impl CountByState {
+    pub const LEN: usize = 8 + // discriminator
+        8 + // created
+        8 + // in_progress
+        8 + // completed
+        8 + // cancelled
+        1 + // bump
+        8; // draft
+
+    fn count_mut(&mut self, state: &BountyState) -> &mut u64 {
+        match state {
+            BountyState::Created => &mut self.created,
+            BountyState::InProgress => &mut self.in_progress,
+            BountyState::Completed => &mut self.completed,
+            BountyState::Cancelled => &mut self.cancelled,
+            BountyState::Draft => &mut self.draft,
+        }
+    }
+
+    // Moves one bounty from `from` (None for a new bounty) to `to`
+    pub fn record(&mut self, from: Option<&BountyState>, to: &BountyState) -> Option<()> {
+        if let Some(from) = from {
+            let count = self.count_mut(from);
+            *count = count.checked_sub(1)?;
+        }
+        let count = self.count_mut(to);
+        *count = count.checked_add(1)?;
+        Some(())
+    }
+}
@@ -0,0 +1,41 @@
+use anchor_lang::prelude::*;
+
+// Fees collected per epoch for one mint, PDA seeded by [b"fee_epoch_stats", mint].
+// Keeps the current epoch and the last epoch that collected fees.
+#[account]
+pub struct FeeEpochStats {
+    pub mint: Pubkey,
+    pub epoch: u64,
+    pub fees_collected: u64,
+    pub previous_epoch: u64,
+    pub previous_fees_collected: u64,
+    pub bump: u8,
+}
+
+impl FeeEpochStats {
+    pub const LEN: usize = 8 + // discriminator
+        32 + // mint
+        8 + // epoch
+        8 + // fees_collected
+        8 + // previous_epoch
+        8 + // previous_fees_collected
+        1; // bump
+
+    // Rolls the current totals into `previous_*` once the epoch advances
+    pub fn roll(&mut self, epoch: u64) {
+        if epoch > self.epoch {
+            if self.fees_collected > 0 {
+                self.previous_epoch = self.epoch;
+                self.previous_fees_collected = self.fees_collected;
+            }
+            self.epoch = epoch;
+            self.fees_collected = 0;
+        }
+    }
+
+    pub fn record(&mut self, epoch: u64, fee: u64) -> Option<()> {
+        self.roll(epoch);
+        self.fees_collected = self.fees_collected.checked_add(fee)?;
+        Some(())
+    }
+}
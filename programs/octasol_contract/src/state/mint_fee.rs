@@ -0,0 +1,16 @@
+use anchor_lang::prelude::*;
+
+// Per-mint fee override, PDA seeded by [b"mint_fee", mint]. Takes precedence over config.fee_bps.
+#[account]
+pub struct MintFeeConfig {
+    pub mint: Pubkey,
+    pub fee_bps: u16,
+    pub bump: u8,
+}
+
+impl MintFeeConfig {
+    pub const LEN: usize = 8 + // discriminator
+        32 + // mint
+        2 + // fee_bps
+        1; // bump
+}
@@ -0,0 +1,20 @@
+use anchor_lang::prelude::*;
+
+// Marks a mint as allowed for new bounties, PDA seeded by [b"mint_whitelist", mint].
+// Only consulted while config.mint_whitelist_enabled is set.
+#[account]
+pub struct MintWhitelist {
+    pub mint: Pubkey,
+    pub bump: u8,
+}
+
+impl MintWhitelist {
+    pub const LEN: usize = 8 + // discriminator
+        32 + // mint
+        1; // bump
+
+    // The entry exists iff the PDA is initialized and owned by the program
+    pub fn is_listed(entry: &AccountInfo) -> bool {
+        entry.owner == &crate::ID && !entry.data_is_empty()
+    }
+}
@@ -1,4 +1,22 @@
 pub mod bounty;
-pub use bounty::{Bounty, BountyState};
+pub use bounty::{Bounty, BountyAddress, BountyState, BountyStateChange, CancelReason, PayoutPreview};
 pub mod config;
-pub use config::ConfigState;
\ No newline at end of file
+pub use config::{AdminPower, ConfigState, ConfigUpdateParams, CreateReadiness, CreationPolicy, ProgramInfo};
+pub mod user_stats;
+pub use user_stats::UserStats;
+pub mod applicants;
+pub use applicants::ApplicantList;
+pub mod fee_epoch_stats;
+pub use fee_epoch_stats::FeeEpochStats;
+pub mod mint_fee;
+pub use mint_fee::MintFeeConfig;
+pub mod count_by_state;
+pub use count_by_state::CountByState;
+pub mod mint_whitelist;
+pub use mint_whitelist::MintWhitelist;
+pub mod contribution;
+pub use contribution::Contribution;
+pub mod global_stats;
+pub use global_stats::GlobalStats;
+pub mod volume_window;
+pub use volume_window::VolumeWindow;
@@ -0,0 +1,33 @@
+use anchor_lang::prelude::*;
+
+// Per-contributor reputation, PDA seeded by [b"user_stats", user]
+#[account]
+pub struct UserStats {
+    pub user: Pubkey,
+    pub bounties_completed: u64,
+    pub rating_count: u64,
+    pub rating_sum: u64,
+    // Average rating scaled by 100 (e.g. 450 == 4.5 stars)
+    pub average_rating_x100: u16,
+    pub bump: u8,
+}
+
+impl UserStats {
+    pub const LEN: usize = 8 + // discriminator
+        32 + // user
+        8 + // bounties_completed
+        8 + // rating_count
+        8 + // rating_sum
+        2 + // average_rating_x100
+        1; // bump
+
+    pub const MIN_RATING: u8 = 1;
+    pub const MAX_RATING: u8 = 5;
+
+    pub fn record_rating(&mut self, rating: u8) -> Option<()> {
+        self.rating_count = self.rating_count.checked_add(1)?;
+        self.rating_sum = self.rating_sum.checked_add(rating as u64)?;
+        self.average_rating_x100 = u16::try_from(self.rating_sum.checked_mul(100)? / self.rating_count).ok()?;
+        Some(())
+    }
+}
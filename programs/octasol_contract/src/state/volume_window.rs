@@ -0,0 +1,113 @@
+use anchor_lang::prelude::*;
+use crate::util::errors::ContractError;
+
+// Escrowed volume for one mint over the last WINDOW_DAYS days, PDA seeded by
+// [b"volume_window", mint]. One bucket per UTC day, reused once it falls out of the window.
+#[account]
+pub struct VolumeWindow {
+    pub mint: Pubkey,
+    // Day number (unix time / SECONDS_PER_DAY) each bucket was last written for
+    pub bucket_days: [i64; Self::WINDOW_DAYS],
+    pub bucket_volumes: [u64; Self::WINDOW_DAYS],
+    pub bump: u8,
+}
+
+impl VolumeWindow {
+    pub const WINDOW_DAYS: usize = 30;
+    pub const SECONDS_PER_DAY: i64 = 86_400;
+
+    pub const LEN: usize = 8 + // discriminator
+        32 + // mint
+        8 * Self::WINDOW_DAYS + // bucket_days
+        8 * Self::WINDOW_DAYS + // bucket_volumes
+        1; // bump
+
+    fn bucket(day: i64) -> usize {
+        day.rem_euclid(Self::WINDOW_DAYS as i64) as usize
+    }
+
+    // Volume escrowed so far on the UTC day containing `now`
+    pub fn daily_volume(&self, now: i64) -> u64 {
+        let day = now.div_euclid(Self::SECONDS_PER_DAY);
+        let i = Self::bucket(day);
+        if self.bucket_days[i] == day { self.bucket_volumes[i] } else { 0 }
+    }
+
+    // Volume escrowed over the WINDOW_DAYS days ending with the one containing `now`
+    pub fn rolling_volume(&self, now: i64) -> u64 {
+        let day = now.div_euclid(Self::SECONDS_PER_DAY);
+        self.bucket_days
+            .iter()
+            .zip(self.bucket_volumes.iter())
+            .filter(|(d, _)| day - **d < Self::WINDOW_DAYS as i64 && **d <= day)
+            .fold(0u64, |sum, (_, v)| sum.saturating_add(*v))
+    }
+
+    // Adds `amount` to today's bucket, clearing it first if it still holds an older day
+    pub fn record(&mut self, now: i64, amount: u64) -> Option<()> {
+        let day = now.div_euclid(Self::SECONDS_PER_DAY);
+        let i = Self::bucket(day);
+        if self.bucket_days[i] != day {
+            self.bucket_days[i] = day;
+            self.bucket_volumes[i] = 0;
+        }
+        self.bucket_volumes[i] = self.bucket_volumes[i].checked_add(amount)?;
+        Some(())
+    }
+
+    // Circuit breaker: records `amount` unless it would take today's volume past `max_daily_volume` (0 for no limit)
+    pub fn admit(&mut self, now: i64, amount: u64, max_daily_volume: u64) -> Result<()> {
+        let daily = self.daily_volume(now).checked_add(amount).ok_or(ContractError::ArithmeticOverflow)?;
+        require!(max_daily_volume == 0 || daily <= max_daily_volume, ContractError::DailyVolumeExceeded);
+        self.record(now, amount).ok_or(ContractError::ArithmeticOverflow)?;
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn empty() -> VolumeWindow {
+        VolumeWindow {
+            mint: Pubkey::default(),
+            bucket_days: [0; VolumeWindow::WINDOW_DAYS],
+            bucket_volumes: [0; VolumeWindow::WINDOW_DAYS],
+            bump: 0,
+        }
+    }
+
+    #[test]
+    fn daily_volume_resets_on_the_next_day() {
+        let mut window = empty();
+        let day = 20_000 * VolumeWindow::SECONDS_PER_DAY;
+        window.record(day + 10, 600).unwrap();
+        window.record(day + 20, 400).unwrap();
+        assert_eq!(window.daily_volume(day + 30), 1_000);
+        assert_eq!(window.daily_volume(day + VolumeWindow::SECONDS_PER_DAY), 0);
+        assert_eq!(window.rolling_volume(day + VolumeWindow::SECONDS_PER_DAY), 1_000);
+    }
+
+    #[test]
+    fn admit_trips_past_the_daily_limit_and_resets_the_next_day() {
+        let mut window = empty();
+        let day = 20_000 * VolumeWindow::SECONDS_PER_DAY;
+        window.admit(day, 1_000, 1_500).unwrap();
+        window.admit(day + 1, 500, 1_500).unwrap();
+        assert_eq!(window.admit(day + 2, 1, 1_500).unwrap_err(), ContractError::DailyVolumeExceeded.into());
+        window.admit(day + VolumeWindow::SECONDS_PER_DAY, 1_500, 1_500).unwrap();
+        window.admit(day + 3, u64::MAX, 0).unwrap_err();
+    }
+
+    #[test]
+    fn rolling_volume_drops_days_older_than_the_window() {
+        let mut window = empty();
+        let day = 20_000 * VolumeWindow::SECONDS_PER_DAY;
+        window.record(day, 500).unwrap();
+        let later = day + VolumeWindow::WINDOW_DAYS as i64 * VolumeWindow::SECONDS_PER_DAY;
+        window.record(later, 700).unwrap();
+        // Same bucket, so the old day's volume is overwritten rather than added to
+        assert_eq!(window.daily_volume(later), 700);
+        assert_eq!(window.rolling_volume(later), 700);
+    }
+}
@@ -0,0 +1,71 @@
+use anchor_lang::prelude::*;
+use anchor_lang::solana_program::{
+    ed25519_program,
+    sysvar::instructions::{load_current_index_checked, load_instruction_at_checked},
+};
+use crate::util::errors::ContractError;
+
+// Ed25519 program data: [num_signatures u8, padding u8] then one 14 byte offsets block per signature
+const HEADER_LEN: usize = 2;
+const OFFSETS_LEN: usize = 14;
+// `instruction_index` value meaning "the data of the Ed25519 instruction itself"
+const THIS_INSTRUCTION: u16 = u16::MAX;
+
+// Message the oracle signs to attest that `pr_id` was merged for the bounty by `contributor`
+pub fn merge_message(bounty_id: u64, pr_id: u64, contributor: &Pubkey) -> Vec<u8> {
+    let mut message = Vec::with_capacity(8 + 8 + 32);
+    message.extend_from_slice(&bounty_id.to_le_bytes());
+    message.extend_from_slice(&pr_id.to_le_bytes());
+    message.extend_from_slice(contributor.as_ref());
+    message
+}
+
+fn read_u16(data: &[u8], at: usize) -> Result<u16> {
+    let bytes = data.get(at..at + 2).ok_or(ContractError::InvalidAttestation)?;
+    Ok(u16::from_le_bytes([bytes[0], bytes[1]]))
+}
+
+fn slice(data: &[u8], offset: u16, len: usize) -> Result<&[u8]> {
+    let start = offset as usize;
+    data.get(start..start + len).ok_or(ContractError::InvalidAttestation.into())
+}
+
+// The runtime has already checked the signature by the time this instruction runs, as long as
+// the instruction right before it is an Ed25519 program call; all that is left is to make sure
+// that call verified exactly `signer` signing `message` with `signature`
+pub fn verify_ed25519(
+    instructions_sysvar: &AccountInfo,
+    signer: &Pubkey,
+    message: &[u8],
+    signature: &[u8; 64],
+) -> Result<()> {
+    let current = load_current_index_checked(instructions_sysvar)?;
+    require!(current > 0, ContractError::InvalidAttestation);
+    let ix = load_instruction_at_checked((current - 1) as usize, instructions_sysvar)?;
+    require_keys_eq!(ix.program_id, ed25519_program::ID, ContractError::InvalidAttestation);
+
+    let data = &ix.data;
+    require!(data.len() >= HEADER_LEN + OFFSETS_LEN && data[0] == 1, ContractError::InvalidAttestation);
+
+    let offsets = HEADER_LEN;
+    let signature_offset = read_u16(data, offsets)?;
+    let signature_ix = read_u16(data, offsets + 2)?;
+    let public_key_offset = read_u16(data, offsets + 4)?;
+    let public_key_ix = read_u16(data, offsets + 6)?;
+    let message_offset = read_u16(data, offsets + 8)?;
+    let message_size = read_u16(data, offsets + 10)?;
+    let message_ix = read_u16(data, offsets + 12)?;
+
+    // Everything must live in the Ed25519 instruction's own data, not be pulled from elsewhere
+    require!(
+        signature_ix == THIS_INSTRUCTION && public_key_ix == THIS_INSTRUCTION && message_ix == THIS_INSTRUCTION,
+        ContractError::InvalidAttestation
+    );
+    require!(slice(data, public_key_offset, 32)? == signer.as_ref(), ContractError::InvalidAttestation);
+    require!(slice(data, signature_offset, 64)? == signature.as_ref(), ContractError::InvalidAttestation);
+    require!(
+        message_size as usize == message.len() && slice(data, message_offset, message.len())? == message,
+        ContractError::InvalidAttestation
+    );
+    Ok(())
+}
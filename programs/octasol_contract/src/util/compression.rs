@@ -0,0 +1,36 @@
+use anchor_lang::prelude::*;
+use anchor_lang::solana_program::{
+    instruction::{AccountMeta, Instruction},
+    program::invoke_signed,
+};
+use super::yield_hook::sighash;
+
+// The compression program is expected to expose spl-account-compression's
+// `append(leaf: [u8; 32])` instruction taking, in order:
+//   [merkle tree (mut), tree authority (signer), noop program]
+pub fn append_leaf<'info>(
+    compression_program: &AccountInfo<'info>,
+    merkle_tree: &AccountInfo<'info>,
+    authority: &AccountInfo<'info>,
+    noop_program: &AccountInfo<'info>,
+    leaf: [u8; 32],
+    signer: &[&[&[u8]]],
+) -> Result<()> {
+    let mut data = sighash("append").to_vec();
+    data.extend_from_slice(&leaf);
+
+    let ix = Instruction {
+        program_id: compression_program.key(),
+        accounts: vec![
+            AccountMeta::new(merkle_tree.key(), false),
+            AccountMeta::new_readonly(authority.key(), true),
+            AccountMeta::new_readonly(noop_program.key(), false),
+        ],
+        data,
+    };
+    invoke_signed(
+        &ix,
+        &[merkle_tree.clone(), authority.clone(), noop_program.clone(), compression_program.clone()],
+        signer,
+    ).map_err(Into::into)
+}
@@ -6,6 +6,7 @@ pub enum ContractError {
     InvalidAmount,
     #[msg("Insufficient bounty amount")]
     InsufficientAmount,
+    // No longer raised, kept so the error codes after it don't shift
     #[msg("Invalid bounty state")]
     InvalidBountyState,
     #[msg("Invalid contributor")]
@@ -26,6 +27,186 @@ pub enum ContractError {
     InvalidMint,
     #[msg("Invalid token account")]
     InvalidTokenAccount,
+    #[msg("Yield integration is disabled")]
+    YieldDisabled,
+    #[msg("Yield program does not match config")]
+    InvalidYieldProgram,
+    #[msg("Escrow funds must be withdrawn from the yield program first")]
+    YieldNotWithdrawn,
+    #[msg("Yield program returned less than the deposited principal")]
+    YieldPrincipalShortfall,
+    #[msg("Yield program cannot change while principal is deployed")]
+    YieldPositionsOpen,
+    #[msg("Mints with a transfer hook are not allowed")]
+    TransferHookNotAllowed,
+    #[msg("Escrow still belongs to a live bounty")]
+    EscrowNotOrphaned,
+    #[msg("Fee basis points must not exceed 10000")]
+    InvalidFeeBps,
+    #[msg("Fee destination token account is required")]
+    FeeAccountMissing,
+    #[msg("Bounty has no contributor allowlist")]
+    MerkleRootNotSet,
+    #[msg("Contributor is not in the allowlist")]
+    InvalidMerkleProof,
+    #[msg("Rating must be between 1 and 5")]
+    InvalidRating,
+    #[msg("Deadline must be in the future")]
+    InvalidDeadline,
+    #[msg("Applicant list is full")]
+    ApplicantListFull,
+    #[msg("Contributor has already applied")]
+    AlreadyApplied,
+    #[msg("No applicant at this index")]
+    InvalidApplicantIndex,
+    #[msg("Partial release must be less than the remaining bounty amount")]
+    PartialReleaseTooLarge,
+    #[msg("Escrow token account already holds funds")]
+    EscrowNotEmpty,
+    #[msg("Bounty amount is below the protocol minimum")]
+    AmountBelowMinimum,
+    #[msg("Bounty amount is above the protocol maximum")]
+    AmountAboveMaximum,
+    #[msg("Minimum bounty amount exceeds the maximum")]
+    InvalidAmountLimits,
+    #[msg("Protocol is paused")]
+    ProtocolPaused,
+    #[msg("Bounty amount exceeds the mint's total supply")]
+    AmountExceedsSupply,
+    #[msg("Contributor must be a wallet unless flagged as a PDA")]
+    ContributorNotWallet,
+    #[msg("Escrow token account has a delegate or close authority")]
+    EscrowAuthorityTampered,
+    #[msg("Bounty amount changed since it was read")]
+    AmountMismatch,
+    #[msg("Batch accounts must come in [bounty, escrow, rent payer] triples")]
+    InvalidBatchAccounts,
+    #[msg("Sponsor token account requires the sponsor to sign")]
+    SponsorMissing,
+    #[msg("Too many milestones")]
+    TooManyMilestones,
+    #[msg("Milestone amounts must be non-zero and sum to the bounty amount")]
+    InvalidMilestones,
+    #[msg("Admin may only force-release bounties that are already in progress")]
+    AdminForceOnCreatedDisabled,
+    #[msg("AMM program is not the configured one")]
+    InvalidAmmProgram,
+    #[msg("LP unwrap did not redeem exactly the escrowed amount")]
+    UnwrapFailed,
+    #[msg("Missing or invalid oracle attestation")]
+    InvalidAttestation,
+    #[msg("Rent payer does not match the bounty")]
+    RentPayerMismatch,
+    #[msg("Bounty does not allow a custom fee recipient")]
+    CustomFeeRecipientNotAllowed,
+    #[msg("Fee exceeds the protocol maximum")]
+    FeeTooHigh,
+    #[msg("Merkle tree is not the configured archive tree")]
+    InvalidArchiveTree,
+    #[msg("Bounty is already in the middle of a fund-moving instruction")]
+    Reentrancy,
+    #[msg("Required approvals exceed the maximum reviewer count")]
+    TooManyApprovals,
+    #[msg("Reviewer has already approved this bounty")]
+    DuplicateApproval,
+    #[msg("Contributor cannot approve their own work")]
+    ContributorCannotApprove,
+    #[msg("Bounty does not have enough reviewer approvals")]
+    InsufficientApprovals,
+    #[msg("Contributor cannot be the bounty's maintainer")]
+    ContributorCannotBeMaintainer,
+    #[msg("Effective time must be in the future")]
+    InvalidEffectiveTime,
+    #[msg("Donation share must be at most 10000 basis points")]
+    InvalidDonationBps,
+    #[msg("Charity token account is required for a donating contributor")]
+    CharityAccountMissing,
+    #[msg("New admin is already the admin")]
+    AdminUnchanged,
+    #[msg("New admin must not be the default pubkey")]
+    InvalidNewAdmin,
+    #[msg("Bounty id does not match the bounty account")]
+    BountyIdMismatch,
+    #[msg("Arithmetic overflow")]
+    ArithmeticOverflow,
+    #[msg("Mint is not whitelisted")]
+    MintNotWhitelisted,
+    #[msg("Swap returned less than the minimum output")]
+    SlippageExceeded,
+    #[msg("Escrow authority is not the bounty's escrow PDA")]
+    InvalidEscrowAuthority,
+    #[msg("Admin has renounced this power")]
+    PowerRenounced,
+    #[msg("Unknown admin power")]
+    InvalidPower,
+    #[msg("AMM program and payout token account are required to pay in the payout mint")]
+    PayoutSwapAccountsMissing,
+    #[msg("Swap did not take exactly the escrowed payout")]
+    SwapFailed,
+    #[msg("Contributor must sign to acknowledge completion")]
+    ContributorAckRequired,
+    #[msg("Deadline is further out than the configured maximum duration")]
+    DeadlineTooFar,
+    #[msg("Too many allowed keepers")]
+    TooManyKeepers,
+    #[msg("Keeper is not allowed to settle this bounty")]
+    KeeperNotAllowed,
+    #[msg("Funders are being refunded, the bounty can only be cancelled through refund_funders_batch")]
+    RefundInProgress,
+    #[msg("Crowd-funded bounties must be cancelled through refund_funders_batch")]
+    FundersNotRefunded,
+    #[msg("Bounty has no funders to refund")]
+    NotCrowdFunded,
+    #[msg("Refund accounts must come in [contribution, funder token account, funder] triples")]
+    InvalidRefundAccounts,
+    #[msg("Contribution does not belong to this bounty and funder")]
+    InvalidContribution,
+    #[msg("Bounty is still a draft and has not been funded")]
+    BountyIsDraft,
+    #[msg("Grace period must not be negative")]
+    InvalidGracePeriod,
+    #[msg("Bounty deadline and grace period have not passed")]
+    BountyNotExpired,
+    #[msg("Burn bps must not exceed 10000")]
+    InvalidBurnBps,
+    #[msg("Bounty account is not sized to Bounty::LEN or is not rent-exempt")]
+    BountyAccountSize,
+    #[msg("Auto-release window must not be negative")]
+    InvalidAutoReleaseWindow,
+    #[msg("Auto-release is disabled")]
+    AutoReleaseDisabled,
+    #[msg("Contributor has not submitted work")]
+    WorkNotSubmitted,
+    #[msg("Auto-release window has not passed")]
+    AutoReleaseNotReady,
+    #[msg("Token program does not match the one the bounty escrow was created with")]
+    TokenProgramMismatch,
+    #[msg("Maintainer has disabled admin override for this bounty")]
+    AdminOverrideDisabled,
+    #[msg("Bounty would take today's escrowed volume past config.max_daily_volume")]
+    DailyVolumeExceeded,
+    #[msg("Contributor has already committed to a deliverable")]
+    DeliverableAlreadyCommitted,
+    #[msg("Deliverable hash does not match the contributor's commitment")]
+    DeliverableMismatch,
+    #[msg("Bounty account has already been created")]
+    BountyAlreadyExists,
+    #[msg("Admin interventions must give a reason")]
+    ReasonRequired,
+    #[msg("Bounty cannot move from its current state to the requested one")]
+    InvalidStateTransition,
+    #[msg("Signer is not the pending admin")]
+    NotPendingAdmin,
+    #[msg("No admin transfer is pending")]
+    NoPendingAdmin,
+    #[msg("Signer is not one of the bounty's reviewers")]
+    ReviewerNotAllowed,
+    #[msg("Required approvals exceed the number of reviewers")]
+    NotEnoughReviewers,
+    #[msg("Config account already has the current layout")]
+    ConfigAlreadyMigrated,
+    #[msg("Fee recipient can't be the default pubkey")]
+    InvalidFeeRecipient,
 }
 
 
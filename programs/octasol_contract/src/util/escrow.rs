@@ -0,0 +1,74 @@
+use anchor_lang::prelude::*;
+use anchor_spl::token_interface::{transfer_checked, Mint, TokenAccount, TokenInterface, TransferChecked};
+use crate::util::errors::ContractError;
+
+// Signer seeds of a bounty's escrow authority PDA, for `CpiContext::new_with_signer`
+pub fn escrow_signer_seeds<'a>(bounty_key: &'a Pubkey, bump: &'a u8) -> [&'a [u8]; 3] {
+    [b"escrow_auth", bounty_key.as_ref(), std::slice::from_ref(bump)]
+}
+
+// An escrow ATA may already exist (anyone can create an ATA for any owner), so before
+// funding it must be empty, owned by the escrow authority and carry no delegate or close authority
+pub fn assert_fresh_escrow(escrow_token_account: &InterfaceAccount<TokenAccount>, escrow_authority: &Pubkey) -> Result<()> {
+    require_keys_eq!(escrow_token_account.owner, *escrow_authority, ContractError::InvalidTokenAccount);
+    require!(escrow_token_account.amount == 0, ContractError::EscrowNotEmpty);
+    require!(
+        escrow_token_account.delegate.is_none() && escrow_token_account.close_authority.is_none(),
+        ContractError::EscrowAuthorityTampered
+    );
+    Ok(())
+}
+
+// The escrow authority is an unchecked PDA that must only ever sign through its seeds inside a
+// CPI. A transaction-level signature, a key off the bounty's seeds, or any owner/data on it means
+// the caller passed something other than the PDA.
+pub fn assert_escrow_authority(escrow_authority: &AccountInfo, bounty: &Pubkey, bump: u8) -> Result<()> {
+    require!(!escrow_authority.is_signer, ContractError::InvalidEscrowAuthority);
+    let expected = Pubkey::create_program_address(&escrow_signer_seeds(bounty, &bump), &crate::ID)
+        .map_err(|_| ContractError::InvalidEscrowAuthority)?;
+    require_keys_eq!(escrow_authority.key(), expected, ContractError::InvalidEscrowAuthority);
+    require!(
+        escrow_authority.owner == &System::id() && escrow_authority.data_is_empty(),
+        ContractError::InvalidEscrowAuthority
+    );
+    Ok(())
+}
+
+// Moves `amount` out of a bounty escrow, signed by the escrow authority PDA
+pub fn transfer_from_escrow<'info>(
+    token_program: &Interface<'info, TokenInterface>,
+    escrow_token_account: &InterfaceAccount<'info, TokenAccount>,
+    mint: &InterfaceAccount<'info, Mint>,
+    to: &InterfaceAccount<'info, TokenAccount>,
+    escrow_authority: &AccountInfo<'info>,
+    signer: &[&[&[u8]]],
+    amount: u64,
+) -> Result<()> {
+    if amount == 0 {
+        return Ok(());
+    }
+    let cpi_ctx = CpiContext::new_with_signer(
+        token_program.to_account_info(),
+        TransferChecked {
+            from: escrow_token_account.to_account_info(),
+            mint: mint.to_account_info(),
+            to: to.to_account_info(),
+            authority: escrow_authority.clone(),
+        },
+        signer,
+    );
+    transfer_checked(cpi_ctx, amount, mint.decimals)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn escrow_signer_seeds_regenerate_the_escrow_pda() {
+        let bounty = Pubkey::new_unique();
+        let (expected, bump) = Pubkey::find_program_address(&[b"escrow_auth", bounty.as_ref()], &crate::ID);
+        let derived = Pubkey::create_program_address(&escrow_signer_seeds(&bounty, &bump), &crate::ID).unwrap();
+        assert_eq!(derived, expected);
+    }
+}
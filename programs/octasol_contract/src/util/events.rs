@@ -1,4 +1,5 @@
 use anchor_lang::prelude::*;
+use crate::state::{Bounty, BountyState};
 
 // Events for comprehensive tracking
 #[event]
@@ -6,12 +7,36 @@ pub struct BountyCreated {
     pub bounty_id: u64,
     pub maintainer: Pubkey,
     pub amount: u64,
+    pub priority: u8,
+    // Settlement keeper, config.default_keeper unless the maintainer chose one
+    pub keeper: Pubkey,
+}
+
+#[event]
+pub struct DraftCreated {
+    pub bounty_id: u64,
+    pub maintainer: Pubkey,
+    pub amount: u64,
+}
+
+#[event]
+pub struct BountyRelaunched {
+    pub bounty_id: u64,
+    pub old_bounty_id: u64,
+    // The new bounty account
+    pub bounty: Pubkey,
+    pub maintainer: Pubkey,
+    pub amount: u64,
+    pub deadline: i64,
 }
 
 #[event]
 pub struct ContributorAssigned {
     pub bounty_id: u64,
     pub contributor: Pubkey,
+    // Maintainer, admin, or the contributor themselves for allowlist claims
+    pub assigned_by: Pubkey,
+    pub via_admin: bool,
 }
 
 #[event]
@@ -19,6 +44,7 @@ pub struct BountyCompleted {
     pub bounty_id: u64,
     pub contributor: Pubkey,
     pub amount: u64,
+    pub fee: u64,
 }
 
 #[event]
@@ -26,10 +52,377 @@ pub struct BountyCancelled {
     pub bounty_id: u64,
     pub maintainer: Pubkey,
     pub amount: u64,
+    pub reason: u8, // CancelReason code
+    pub milestones_paid: u8,
 }
 
 #[event]
-pub struct AdminUpdated {
+pub struct BountyExpired {
+    pub bounty_id: u64,
+    pub refunded: u64,
+    pub caller: Pubkey,
+}
+
+#[event]
+pub struct AdminTransferProposed {
+    pub admin: Pubkey,
+    pub pending_admin: Pubkey,
+}
+
+#[event]
+pub struct AdminTransferAccepted {
     pub old_admin: Pubkey,
     pub new_admin: Pubkey,
 }
+
+#[event]
+pub struct AdminTransferCancelled {
+    pub admin: Pubkey,
+    pub pending_admin: Pubkey,
+}
+
+#[event]
+pub struct YieldDeposited {
+    pub bounty_id: u64,
+    pub amount: u64,
+}
+
+#[event]
+pub struct YieldWithdrawn {
+    pub bounty_id: u64,
+    pub principal: u64,
+    pub yield_amount: u64,
+}
+
+#[event]
+pub struct OrphanEscrowReclaimed {
+    pub escrow_token_account: Pubkey,
+    pub amount: u64,
+}
+
+#[event]
+pub struct ContributorRated {
+    pub bounty_id: u64,
+    pub contributor: Pubkey,
+    pub rating: u8,
+    pub average_rating_x100: u16,
+    pub rating_count: u64,
+}
+
+#[event]
+pub struct BountyMintMigrated {
+    pub bounty_id: u64,
+    pub old_mint: Pubkey,
+    pub new_mint: Pubkey,
+    pub refunded_amount: u64,
+    pub new_amount: u64,
+}
+
+#[event]
+pub struct ContributorApplied {
+    pub bounty_id: u64,
+    pub contributor: Pubkey,
+    pub index: u8,
+}
+
+#[event]
+pub struct MilestoneReleased {
+    pub bounty_id: u64,
+    pub contributor: Pubkey,
+    pub milestone: u8,
+    pub amount: u64,
+    pub fee: u64,
+    pub remaining: u64,
+}
+
+#[event]
+pub struct PartialReleased {
+    pub bounty_id: u64,
+    pub contributor: Pubkey,
+    pub amount: u64,
+    pub fee: u64,
+    pub remaining: u64,
+}
+
+#[event]
+pub struct ConfigChanged {
+    pub admin: Pubkey,
+    // Names of the config fields that were updated
+    pub changed: Vec<String>,
+}
+
+#[event]
+pub struct BountyIncreased {
+    pub bounty_id: u64,
+    pub added: u64,
+    pub new_amount: u64,
+}
+
+#[event]
+pub struct AtaCreationFeeCharged {
+    pub bounty_id: u64,
+    pub contributor: Pubkey,
+    pub amount: u64,
+}
+
+#[event]
+pub struct BountyTipped {
+    pub bounty_id: u64,
+    pub tipper: Pubkey,
+    pub amount: u64,
+    pub new_amount: u64,
+}
+
+#[event]
+pub struct BountyFunded {
+    pub bounty_id: u64,
+    pub funder: Pubkey,
+    pub added: u64,
+    // The funder's total contribution so far
+    pub contribution: u64,
+    pub new_amount: u64,
+}
+
+#[event]
+pub struct FunderRefunded {
+    pub bounty_id: u64,
+    pub funder: Pubkey,
+    pub amount: u64,
+    pub refunded_count: u32,
+    pub funder_count: u32,
+}
+
+#[event]
+pub struct DustSwept {
+    pub bounty_id: u64,
+    pub contributor: Pubkey,
+    pub amount: u64,
+}
+
+// Emitted on every state change of an existing bounty, so indexers can check ordering per bounty
+#[event]
+pub struct StateTransition {
+    pub bounty_id: u64,
+    pub from_state: BountyState,
+    pub to_state: BountyState,
+}
+
+// Every bounty field at the moment it reaches a terminal state, emitted right before the account closes
+#[event]
+pub struct BountyFinalSnapshot {
+    pub bounty: Pubkey,
+    pub maintainer: Pubkey,
+    pub contributor: Option<Pubkey>,
+    pub mint: Pubkey,
+    pub amount: u64,
+    pub state: BountyState,
+    pub bounty_id: u64,
+    pub yield_principal: u64,
+    pub priority: u8,
+    pub merkle_root: [u8; 32],
+    pub created_at: i64,
+    pub deadline: i64,
+    pub keeper: Pubkey,
+    pub contributor_is_pda: bool,
+    pub milestones: Vec<u64>,
+    pub milestones_paid: u8,
+    pub unwrap_on_complete: bool,
+    pub rent_payer: Pubkey,
+    pub allow_custom_fee_recipient: bool,
+    pub locked: bool,
+    pub required_approvals: u8,
+    pub approvals: Vec<Pubkey>,
+    pub donate_bps: u16,
+    pub payout_mint: Option<Pubkey>,
+    pub payout_min_out: u64,
+    pub require_contributor_ack: bool,
+    pub relaunched_from: Option<u64>,
+    pub allowed_keepers: Vec<Pubkey>,
+    pub funder_count: u32,
+    pub refunded_count: u32,
+    pub grace_period: i64,
+    pub submitted_at: i64,
+    pub token_program: Pubkey,
+    pub admin_override_allowed: bool,
+    pub deliverable_commitment: Option<[u8; 32]>,
+    pub tipped_amount: u64,
+    pub state_version: u16,
+    pub reviewers: Vec<Pubkey>,
+    pub custom_fee_recipient: Option<Pubkey>,
+}
+
+impl BountyFinalSnapshot {
+    pub fn new(address: Pubkey, bounty: &Bounty) -> Self {
+        Self {
+            bounty: address,
+            maintainer: bounty.maintainer,
+            contributor: bounty.contributor,
+            mint: bounty.mint,
+            amount: bounty.amount,
+            state: bounty.state.clone(),
+            bounty_id: bounty.bounty_id,
+            yield_principal: bounty.yield_principal,
+            priority: bounty.priority,
+            merkle_root: bounty.merkle_root,
+            created_at: bounty.created_at,
+            deadline: bounty.deadline,
+            keeper: bounty.keeper,
+            contributor_is_pda: bounty.contributor_is_pda,
+            milestones: bounty.milestones.clone(),
+            milestones_paid: bounty.milestones_paid,
+            unwrap_on_complete: bounty.unwrap_on_complete,
+            rent_payer: bounty.rent_payer,
+            allow_custom_fee_recipient: bounty.allow_custom_fee_recipient,
+            locked: bounty.locked,
+            required_approvals: bounty.required_approvals,
+            approvals: bounty.approvals.clone(),
+            donate_bps: bounty.donate_bps,
+            payout_mint: bounty.payout_mint,
+            payout_min_out: bounty.payout_min_out,
+            require_contributor_ack: bounty.require_contributor_ack,
+            relaunched_from: bounty.relaunched_from,
+            allowed_keepers: bounty.allowed_keepers.clone(),
+            funder_count: bounty.funder_count,
+            refunded_count: bounty.refunded_count,
+            grace_period: bounty.grace_period,
+            submitted_at: bounty.submitted_at,
+            token_program: bounty.token_program,
+            admin_override_allowed: bounty.admin_override_allowed,
+            deliverable_commitment: bounty.deliverable_commitment,
+            tipped_amount: bounty.tipped_amount,
+            state_version: bounty.state_version,
+            reviewers: bounty.reviewers.clone(),
+            custom_fee_recipient: bounty.custom_fee_recipient,
+        }
+    }
+}
+
+#[event]
+pub struct CompletedBountiesClosed {
+    pub closed: u32,
+}
+
+#[event]
+pub struct FeeSponsored {
+    pub bounty_id: u64,
+    pub sponsor: Pubkey,
+    pub fee: u64,
+}
+
+// Why the admin intervened on a bounty; `power` is the AdminPower code of the intervention
+#[event]
+pub struct AdminIntervention {
+    pub bounty_id: u64,
+    pub admin: Pubkey,
+    pub power: u8,
+    pub reason: [u8; 64],
+}
+
+#[event]
+pub struct AssignmentCleared {
+    pub bounty_id: u64,
+    pub previous_contributor: Pubkey,
+    pub admin: Pubkey,
+}
+
+#[event]
+pub struct PayoutSwapped {
+    pub bounty_id: u64,
+    pub contributor: Pubkey,
+    pub payout_mint: Pubkey,
+    pub amount_in: u64,
+    pub amount_out: u64,
+}
+
+#[event]
+pub struct LpUnwrapped {
+    pub bounty_id: u64,
+    pub contributor: Pubkey,
+    pub lp_amount: u64,
+    pub underlying_amount: u64,
+}
+
+#[event]
+pub struct DonationMade {
+    pub bounty_id: u64,
+    pub contributor: Pubkey,
+    pub charity: Pubkey,
+    pub amount: u64,
+}
+
+#[event]
+pub struct TokensBurned {
+    pub bounty_id: u64,
+    pub mint: Pubkey,
+    pub amount: u64,
+}
+
+#[event]
+pub struct DeliverableCommitted {
+    pub bounty_id: u64,
+    pub contributor: Pubkey,
+    pub hash: [u8; 32],
+}
+
+#[event]
+pub struct WorkSubmitted {
+    pub bounty_id: u64,
+    pub contributor: Pubkey,
+    pub submitted_at: i64,
+}
+
+#[event]
+pub struct CrankRewardPaid {
+    pub bounty_id: u64,
+    pub cranker: Pubkey,
+    pub amount: u64,
+}
+
+#[event]
+pub struct PowerRenounced {
+    pub admin: Pubkey,
+    pub power_id: u8,
+}
+
+#[event]
+pub struct UpgradeAnnounced {
+    pub new_authority: Pubkey,
+    pub effective_at: i64,
+    pub announced_at: i64,
+}
+
+#[event]
+pub struct WorkApproved {
+    pub bounty_id: u64,
+    pub reviewer: Pubkey,
+    pub approvals: u8,
+}
+
+#[event]
+pub struct FirstCompletionBonusPaid {
+    pub bounty_id: u64,
+    pub contributor: Pubkey,
+    pub lamports: u64,
+}
+
+#[event]
+pub struct RentSwapped {
+    pub maintainer: Pubkey,
+    pub mint: Pubkey,
+    pub lamports: u64,
+    pub amount_out: u64,
+}
+
+#[event]
+pub struct MergeAttested {
+    pub bounty_id: u64,
+    pub pr_id: u64,
+    pub contributor: Pubkey,
+}
+
+#[event]
+pub struct BountyArchived {
+    pub bounty_id: u64,
+    pub merkle_tree: Pubkey,
+    pub leaf: [u8; 32],
+}
@@ -0,0 +1,97 @@
+use anchor_lang::prelude::*;
+use crate::state::MintFeeConfig;
+use crate::util::errors::ContractError;
+
+pub const BPS_DENOMINATOR: u64 = 10_000;
+// Hard ceiling on any fee rate the admin can set (10%)
+pub const MAX_FEE_BPS: u16 = 1_000;
+
+pub fn check_fee_bps(fee_bps: u16) -> Result<()> {
+    require!(fee_bps as u64 <= BPS_DENOMINATOR, ContractError::InvalidFeeBps);
+    require!(fee_bps <= MAX_FEE_BPS, ContractError::FeeTooHigh);
+    Ok(())
+}
+
+// Rounds down, so any remainder stays with the payee
+pub fn apply_bps(amount: u64, bps: u16) -> Result<u64> {
+    let value = (amount as u128) * (bps as u128) / (BPS_DENOMINATOR as u128);
+    u64::try_from(value).map_err(|_| error!(ContractError::InvalidAmount))
+}
+
+// The mint's override if one has been set, otherwise the global fee.
+// `mint_fee_config` is the (possibly uninitialized) [b"mint_fee", mint] PDA.
+pub fn base_fee_bps(global_fee_bps: u16, mint_fee_config: &AccountInfo) -> Result<u16> {
+    if mint_fee_config.owner != &crate::ID || mint_fee_config.data_is_empty() {
+        return Ok(global_fee_bps);
+    }
+    let data = mint_fee_config.try_borrow_data()?;
+    Ok(MintFeeConfig::try_deserialize(&mut &data[..])?.fee_bps)
+}
+
+// Scales `fee_bps` down linearly with the share of the bounty window still
+// remaining, waiving at most `max_discount_bps` of the fee. Completing at or
+// after the deadline pays the full fee.
+pub fn discounted_fee_bps(fee_bps: u16, max_discount_bps: u16, created_at: i64, deadline: i64, now: i64) -> u16 {
+    if max_discount_bps == 0 || now >= deadline || deadline <= created_at {
+        return fee_bps;
+    }
+    let total = (deadline - created_at) as u128;
+    let remaining = (deadline - now.max(created_at)) as u128;
+    let discount = (fee_bps as u128) * (max_discount_bps as u128) * remaining
+        / (total * BPS_DENOMINATOR as u128);
+    fee_bps - discount as u16
+}
+
+// Escrowed tokens beyond what the bounty owes, e.g. sent straight to the escrow ATA.
+// Like the rounding remainders above, they go to the contributor as the last payee,
+// so the escrow is always empty when it closes.
+pub fn escrow_dust(escrow_balance: u64, owed: u64) -> u64 {
+    escrow_balance.saturating_sub(owed)
+}
+
+// Splits what is left for the contributor after the protocol fee into
+// (burned, donation, contributor share). The burn comes off first and the
+// donation is taken from what remains, so both round down in the contributor's favour.
+pub fn split_payout(payout: u64, burn_bps: u16, donate_bps: u16) -> Result<(u64, u64, u64)> {
+    let burned = apply_bps(payout, burn_bps)?;
+    let donation = apply_bps(payout - burned, donate_bps)?;
+    Ok((burned, donation, payout - burned - donation))
+}
+
+// Returns (treasury share, staker share) of a collected fee
+pub fn split_fee(fee: u64, fee_treasury_bps: u16) -> Result<(u64, u64)> {
+    let treasury_share = apply_bps(fee, fee_treasury_bps)?;
+    Ok((treasury_share, fee - treasury_share))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn apply_bps_rounds_the_fee_down() {
+        // 2.5% of 9_999 is 249.975
+        let fee = apply_bps(9_999, 250).unwrap();
+        assert_eq!(fee, 249);
+        assert_eq!(9_999 - fee, 9_750);
+        assert_eq!(apply_bps(1, 9_999).unwrap(), 0);
+        assert_eq!(apply_bps(u64::MAX, BPS_DENOMINATOR as u16).unwrap(), u64::MAX);
+    }
+
+    #[test]
+    fn split_payout_burns_before_donating() {
+        assert_eq!(split_payout(10_000, 500, 1_000).unwrap(), (500, 950, 8_550));
+    }
+
+    #[test]
+    fn split_payout_keeps_everything_without_burn_or_donation() {
+        assert_eq!(split_payout(9_000, 0, 0).unwrap(), (0, 0, 9_000));
+    }
+
+    #[test]
+    fn split_payout_rounds_in_the_contributors_favour() {
+        let (burned, donation, share) = split_payout(999, 100, 100).unwrap();
+        assert_eq!((burned, donation), (9, 9));
+        assert_eq!(burned + donation + share, 999);
+    }
+}
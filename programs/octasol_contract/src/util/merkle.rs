@@ -0,0 +1,19 @@
+use anchor_lang::solana_program::hash::hashv;
+
+// Leaves are sha256(pubkey); parents hash the sorted pair so proofs don't need
+// left/right flags
+pub fn leaf(data: &[u8]) -> [u8; 32] {
+    hashv(&[data]).to_bytes()
+}
+
+pub fn verify_proof(proof: &[[u8; 32]], root: [u8; 32], leaf: [u8; 32]) -> bool {
+    let mut computed = leaf;
+    for node in proof {
+        computed = if computed <= *node {
+            hashv(&[&computed, node]).to_bytes()
+        } else {
+            hashv(&[node, &computed]).to_bytes()
+        };
+    }
+    computed == root
+}
@@ -1,3 +1,16 @@
 
 pub mod errors;
 pub mod events;
+pub mod yield_hook;
+pub mod token_ext;
+pub mod fees;
+pub mod escrow;
+pub mod payout;
+pub mod merkle;
+pub mod notify;
+pub mod unwrap;
+pub mod attestation;
+pub mod compression;
+pub mod reentrancy;
+pub mod swap;
+pub mod validate;
@@ -0,0 +1,38 @@
+use anchor_lang::prelude::*;
+use anchor_lang::solana_program::{instruction::Instruction, program::invoke};
+use super::yield_hook::sighash;
+
+// The notification program is expected to expose an Anchor-style
+// `notify(kind: u8, bounty_id: u64, contributor: Pubkey)` instruction with no accounts.
+pub const KIND_ASSIGNED: u8 = 0;
+pub const KIND_COMPLETED: u8 = 1;
+
+// Best-effort: a failing CPI aborts the whole transaction on Solana, so the only
+// safe way to keep notifications optional is to skip them when the caller does not
+// pass the configured program in `remaining_accounts`.
+pub fn notify<'info>(
+    notification_program: Option<Pubkey>,
+    remaining_accounts: &[AccountInfo<'info>],
+    kind: u8,
+    bounty_id: u64,
+    contributor: Pubkey,
+) -> Result<()> {
+    let Some(program_id) = notification_program else {
+        return Ok(());
+    };
+    let Some(program) = remaining_accounts.iter().find(|acc| acc.key() == program_id && acc.executable) else {
+        return Ok(());
+    };
+
+    let mut data = sighash("notify").to_vec();
+    data.push(kind);
+    data.extend_from_slice(&bounty_id.to_le_bytes());
+    data.extend_from_slice(contributor.as_ref());
+
+    let ix = Instruction {
+        program_id,
+        accounts: vec![],
+        data,
+    };
+    invoke(&ix, std::slice::from_ref(program)).map_err(Into::into)
+}
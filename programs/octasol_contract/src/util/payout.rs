@@ -0,0 +1,104 @@
+use anchor_lang::prelude::*;
+use anchor_spl::token_interface::{burn, Burn, Mint, TokenAccount, TokenInterface};
+use crate::util::errors::ContractError;
+use crate::util::escrow::transfer_from_escrow;
+use crate::util::events::{DonationMade, DustSwept, TokensBurned};
+use crate::util::fees;
+
+// A payout out of one bounty escrow and where each share of it goes. Every path that pays a
+// contributor goes through here so the fee, config.burn_bps and bounty.donate_bps apply the
+// same way everywhere. A destination account is only required once its share is non-zero.
+pub struct EscrowPayout<'a, 'info> {
+    pub bounty_id: u64,
+    pub contributor: Pubkey,
+    pub burn_bps: u16,
+    pub donate_bps: u16,
+    pub token_program: &'a Interface<'info, TokenInterface>,
+    pub mint: &'a InterfaceAccount<'info, Mint>,
+    pub escrow_token_account: &'a InterfaceAccount<'info, TokenAccount>,
+    pub escrow_authority: &'a AccountInfo<'info>,
+    pub signer: &'a [&'a [&'a [u8]]],
+    pub treasury_token_account: Option<&'a InterfaceAccount<'info, TokenAccount>>,
+    pub staker_token_account: Option<&'a InterfaceAccount<'info, TokenAccount>>,
+    pub charity_token_account: Option<&'a InterfaceAccount<'info, TokenAccount>>,
+}
+
+impl<'a, 'info> EscrowPayout<'a, 'info> {
+    fn transfer(&self, to: &InterfaceAccount<'info, TokenAccount>, amount: u64) -> Result<()> {
+        transfer_from_escrow(self.token_program, self.escrow_token_account, self.mint, to, self.escrow_authority, self.signer, amount)
+    }
+
+    pub fn pay_fee(&self, treasury_fee: u64, staker_fee: u64) -> Result<()> {
+        for (destination, share) in [(self.treasury_token_account, treasury_fee), (self.staker_token_account, staker_fee)] {
+            if share == 0 {
+                continue;
+            }
+            self.transfer(destination.ok_or(ContractError::FeeAccountMissing)?, share)?;
+        }
+        Ok(())
+    }
+
+    // Burns and donates out of what the fee left for the contributor, returning their share
+    pub fn burn_and_donate(&self, payout: u64) -> Result<u64> {
+        let (burned, donation, contributor_amount) = fees::split_payout(payout, self.burn_bps, self.donate_bps)?;
+        if burned > 0 {
+            require_keys_eq!(self.escrow_token_account.owner, self.escrow_authority.key(), ContractError::InvalidTokenAccount);
+            burn(
+                CpiContext::new_with_signer(
+                    self.token_program.to_account_info(),
+                    Burn {
+                        mint: self.mint.to_account_info(),
+                        from: self.escrow_token_account.to_account_info(),
+                        authority: self.escrow_authority.clone(),
+                    },
+                    self.signer,
+                ),
+                burned,
+            )?;
+            emit!(TokensBurned {
+                bounty_id: self.bounty_id,
+                mint: self.mint.key(),
+                amount: burned,
+            });
+        }
+        if donation > 0 {
+            let charity_token_account = self.charity_token_account.ok_or(ContractError::CharityAccountMissing)?;
+            self.transfer(charity_token_account, donation)?;
+            emit!(DonationMade {
+                bounty_id: self.bounty_id,
+                contributor: self.contributor,
+                charity: charity_token_account.owner,
+                amount: donation,
+            });
+        }
+        Ok(contributor_amount)
+    }
+
+    // `dust` is whatever the escrow holds beyond what the bounty owes, see `fees::escrow_dust`
+    pub fn pay_contributor(&self, contributor_token_account: &InterfaceAccount<'info, TokenAccount>, amount: u64, dust: u64) -> Result<()> {
+        self.transfer(contributor_token_account, amount + dust)?;
+        if dust > 0 {
+            emit!(DustSwept {
+                bounty_id: self.bounty_id,
+                contributor: self.contributor,
+                amount: dust,
+            });
+        }
+        Ok(())
+    }
+
+    // The whole split for a contributor paid straight into a token account: the fee shares,
+    // then burn and donation off `payout` (what the fee left), then the contributor
+    pub fn settle(
+        &self,
+        contributor_token_account: &InterfaceAccount<'info, TokenAccount>,
+        payout: u64,
+        treasury_fee: u64,
+        staker_fee: u64,
+        dust: u64,
+    ) -> Result<()> {
+        self.pay_fee(treasury_fee, staker_fee)?;
+        let contributor_amount = self.burn_and_donate(payout)?;
+        self.pay_contributor(contributor_token_account, contributor_amount, dust)
+    }
+}
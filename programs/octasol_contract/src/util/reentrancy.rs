@@ -0,0 +1,24 @@
+use anchor_lang::prelude::*;
+use crate::state::Bounty;
+use crate::util::errors::ContractError;
+
+// Marks the bounty busy for the rest of a fund-moving instruction. The flag is written
+// to the account straight away, so any CPI that calls back into the program (a transfer
+// hook, yield or AMM program) sees it set before Anchor's own write at exit.
+// Also refuses a bounty whose crowd-funded cancel has started refunding funders.
+pub fn enter(bounty: &mut Account<Bounty>) -> Result<()> {
+    require!(bounty.refunded_count == 0, ContractError::RefundInProgress);
+    lock(bounty)
+}
+
+// `enter` without the refund check, for `refund_funders_batch` which resumes a started refund
+pub fn lock(bounty: &mut Account<Bounty>) -> Result<()> {
+    require!(!bounty.locked, ContractError::Reentrancy);
+    bounty.locked = true;
+    bounty.exit(&crate::ID)
+}
+
+// Bounties closed by the instruction don't need this, their data is wiped
+pub fn leave(bounty: &mut Account<Bounty>) {
+    bounty.locked = false;
+}
@@ -0,0 +1,110 @@
+use anchor_lang::prelude::*;
+use anchor_lang::solana_program::{
+    instruction::{AccountMeta, Instruction},
+    program::{invoke, invoke_signed},
+};
+use super::yield_hook::sighash;
+
+// The AMM is expected to expose an Anchor-style `swap_sol(lamports, min_out)` instruction that
+// takes `lamports` from the payer and pays the output token into the destination, taking in order:
+//   [payer (signer, mut), destination (mut), token program, system program, ..pool accounts]
+// Pool specific accounts are forwarded untouched from `remaining_accounts`.
+#[allow(clippy::too_many_arguments)]
+pub fn swap_sol<'info>(
+    amm_program: &AccountInfo<'info>,
+    payer: &AccountInfo<'info>,
+    destination: &AccountInfo<'info>,
+    token_program: &AccountInfo<'info>,
+    system_program: &AccountInfo<'info>,
+    remaining_accounts: &[AccountInfo<'info>],
+    lamports: u64,
+    min_out: u64,
+) -> Result<()> {
+    let mut data = sighash("swap_sol").to_vec();
+    data.extend_from_slice(&lamports.to_le_bytes());
+    data.extend_from_slice(&min_out.to_le_bytes());
+
+    let mut accounts = vec![
+        AccountMeta::new(payer.key(), true),
+        AccountMeta::new(destination.key(), false),
+        AccountMeta::new_readonly(token_program.key(), false),
+        AccountMeta::new_readonly(system_program.key(), false),
+    ];
+    // Never forward the payer's signature to a pool account
+    accounts.extend(remaining_accounts.iter().map(|acc| {
+        if acc.is_writable {
+            AccountMeta::new(acc.key(), false)
+        } else {
+            AccountMeta::new_readonly(acc.key(), false)
+        }
+    }));
+
+    let ix = Instruction {
+        program_id: amm_program.key(),
+        accounts,
+        data,
+    };
+
+    let mut infos = vec![
+        payer.clone(),
+        destination.clone(),
+        token_program.clone(),
+        system_program.clone(),
+    ];
+    infos.extend_from_slice(remaining_accounts);
+    infos.push(amm_program.clone());
+
+    invoke(&ix, &infos).map_err(Into::into)
+}
+
+// The AMM is expected to expose an Anchor-style `swap(amount_in, min_out)` instruction that takes
+// `amount_in` of the source token and pays the output token into the destination, taking in order:
+//   [escrow authority (signer), escrow token account (mut), destination (mut), token program, ..pool accounts]
+#[allow(clippy::too_many_arguments)]
+pub fn swap_tokens<'info>(
+    amm_program: &AccountInfo<'info>,
+    escrow_authority: &AccountInfo<'info>,
+    escrow_token_account: &AccountInfo<'info>,
+    destination: &AccountInfo<'info>,
+    token_program: &AccountInfo<'info>,
+    remaining_accounts: &[AccountInfo<'info>],
+    amount_in: u64,
+    min_out: u64,
+    signer: &[&[&[u8]]],
+) -> Result<()> {
+    let mut data = sighash("swap").to_vec();
+    data.extend_from_slice(&amount_in.to_le_bytes());
+    data.extend_from_slice(&min_out.to_le_bytes());
+
+    let mut accounts = vec![
+        AccountMeta::new_readonly(escrow_authority.key(), true),
+        AccountMeta::new(escrow_token_account.key(), false),
+        AccountMeta::new(destination.key(), false),
+        AccountMeta::new_readonly(token_program.key(), false),
+    ];
+    // Never forward the escrow authority's signature to a pool account
+    accounts.extend(remaining_accounts.iter().map(|acc| {
+        if acc.is_writable {
+            AccountMeta::new(acc.key(), false)
+        } else {
+            AccountMeta::new_readonly(acc.key(), false)
+        }
+    }));
+
+    let ix = Instruction {
+        program_id: amm_program.key(),
+        accounts,
+        data,
+    };
+
+    let mut infos = vec![
+        escrow_authority.clone(),
+        escrow_token_account.clone(),
+        destination.clone(),
+        token_program.clone(),
+    ];
+    infos.extend_from_slice(remaining_accounts);
+    infos.push(amm_program.clone());
+
+    invoke_signed(&ix, &infos, signer).map_err(Into::into)
+}
@@ -0,0 +1,15 @@
+use anchor_lang::prelude::*;
+use anchor_spl::token_2022::spl_token_2022::{
+    self,
+    extension::{transfer_hook::TransferHook, BaseStateWithExtensions, StateWithExtensions},
+};
+
+// Classic SPL mints can't carry extensions, so only Token-2022 mints are inspected
+pub fn has_transfer_hook(mint: &AccountInfo) -> Result<bool> {
+    if *mint.owner != spl_token_2022::ID {
+        return Ok(false);
+    }
+    let data = mint.try_borrow_data()?;
+    let mint_state = StateWithExtensions::<spl_token_2022::state::Mint>::unpack(&data)?;
+    Ok(mint_state.get_extension::<TransferHook>().is_ok())
+}
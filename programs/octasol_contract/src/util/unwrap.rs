@@ -0,0 +1,57 @@
+use anchor_lang::prelude::*;
+use anchor_lang::solana_program::{
+    instruction::{AccountMeta, Instruction},
+    program::invoke_signed,
+};
+use super::yield_hook::sighash;
+
+// The AMM is expected to expose an Anchor-style `unwrap(amount)` instruction that burns or
+// takes `amount` LP tokens from the source and pays the underlying into the destination, taking in order:
+//   [escrow authority (signer), escrow token account (mut), destination (mut), token program, ..pool accounts]
+// Pool specific accounts are forwarded untouched from `remaining_accounts`.
+#[allow(clippy::too_many_arguments)]
+pub fn unwrap<'info>(
+    amm_program: &AccountInfo<'info>,
+    escrow_authority: &AccountInfo<'info>,
+    escrow_token_account: &AccountInfo<'info>,
+    destination: &AccountInfo<'info>,
+    token_program: &AccountInfo<'info>,
+    remaining_accounts: &[AccountInfo<'info>],
+    amount: u64,
+    signer: &[&[&[u8]]],
+) -> Result<()> {
+    let mut data = sighash("unwrap").to_vec();
+    data.extend_from_slice(&amount.to_le_bytes());
+
+    let mut accounts = vec![
+        AccountMeta::new_readonly(escrow_authority.key(), true),
+        AccountMeta::new(escrow_token_account.key(), false),
+        AccountMeta::new(destination.key(), false),
+        AccountMeta::new_readonly(token_program.key(), false),
+    ];
+    // Never forward the escrow authority's signature to a pool account
+    accounts.extend(remaining_accounts.iter().map(|acc| {
+        if acc.is_writable {
+            AccountMeta::new(acc.key(), false)
+        } else {
+            AccountMeta::new_readonly(acc.key(), false)
+        }
+    }));
+
+    let ix = Instruction {
+        program_id: amm_program.key(),
+        accounts,
+        data,
+    };
+
+    let mut infos = vec![
+        escrow_authority.clone(),
+        escrow_token_account.clone(),
+        destination.clone(),
+        token_program.clone(),
+    ];
+    infos.extend_from_slice(remaining_accounts);
+    infos.push(amm_program.clone());
+
+    invoke_signed(&ix, &infos, signer).map_err(Into::into)
+}
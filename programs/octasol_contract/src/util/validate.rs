@@ -0,0 +1,21 @@
+use anchor_lang::prelude::*;
+use anchor_spl::token_interface::TokenAccount;
+
+// Account constraint checks that log the offending account before Anchor returns the error,
+// so integrators can tell which of several token accounts was wrong.
+
+pub fn mint_matches(account: &InterfaceAccount<TokenAccount>, expected: &Pubkey) -> bool {
+    if account.mint == *expected {
+        return true;
+    }
+    msg!("Token account {} has mint {}, expected {}", account.key(), account.mint, expected);
+    false
+}
+
+pub fn owner_matches(account: &InterfaceAccount<TokenAccount>, expected: &Pubkey) -> bool {
+    if account.owner == *expected {
+        return true;
+    }
+    msg!("Token account {} is owned by {}, expected {}", account.key(), account.owner, expected);
+    false
+}
@@ -0,0 +1,87 @@
+use anchor_lang::prelude::*;
+use anchor_lang::solana_program::{
+    hash::hash,
+    instruction::{AccountMeta, Instruction},
+    program::invoke_signed,
+};
+
+// The yield program is expected to expose Anchor-style `deposit(amount)` and
+// `withdraw(amount)` instructions taking, in order:
+//   [escrow authority (signer), escrow token account (mut), token program, ..protocol accounts]
+// Protocol specific accounts are forwarded untouched from `remaining_accounts`.
+
+pub(crate) fn sighash(name: &str) -> [u8; 8] {
+    let preimage = format!("global:{}", name);
+    let mut sighash = [0u8; 8];
+    sighash.copy_from_slice(&hash(preimage.as_bytes()).to_bytes()[..8]);
+    sighash
+}
+
+#[allow(clippy::too_many_arguments)]
+fn invoke_yield<'info>(
+    name: &str,
+    yield_program: &AccountInfo<'info>,
+    escrow_authority: &AccountInfo<'info>,
+    escrow_token_account: &AccountInfo<'info>,
+    token_program: &AccountInfo<'info>,
+    remaining_accounts: &[AccountInfo<'info>],
+    amount: u64,
+    signer: &[&[&[u8]]],
+) -> Result<()> {
+    let mut data = sighash(name).to_vec();
+    data.extend_from_slice(&amount.to_le_bytes());
+
+    let mut accounts = vec![
+        AccountMeta::new_readonly(escrow_authority.key(), true),
+        AccountMeta::new(escrow_token_account.key(), false),
+        AccountMeta::new_readonly(token_program.key(), false),
+    ];
+    // Never forward the escrow authority's signature to a protocol account
+    accounts.extend(remaining_accounts.iter().map(|acc| {
+        if acc.is_writable {
+            AccountMeta::new(acc.key(), false)
+        } else {
+            AccountMeta::new_readonly(acc.key(), false)
+        }
+    }));
+
+    let ix = Instruction {
+        program_id: yield_program.key(),
+        accounts,
+        data,
+    };
+
+    let mut infos = vec![
+        escrow_authority.clone(),
+        escrow_token_account.clone(),
+        token_program.clone(),
+    ];
+    infos.extend_from_slice(remaining_accounts);
+    infos.push(yield_program.clone());
+
+    invoke_signed(&ix, &infos, signer).map_err(Into::into)
+}
+
+pub fn deposit<'info>(
+    yield_program: &AccountInfo<'info>,
+    escrow_authority: &AccountInfo<'info>,
+    escrow_token_account: &AccountInfo<'info>,
+    token_program: &AccountInfo<'info>,
+    remaining_accounts: &[AccountInfo<'info>],
+    amount: u64,
+    signer: &[&[&[u8]]],
+) -> Result<()> {
+    invoke_yield("deposit", yield_program, escrow_authority, escrow_token_account, token_program, remaining_accounts, amount, signer)
+}
+
+pub fn withdraw<'info>(
+    yield_program: &AccountInfo<'info>,
+    escrow_authority: &AccountInfo<'info>,
+    escrow_token_account: &AccountInfo<'info>,
+    token_program: &AccountInfo<'info>,
+    remaining_accounts: &[AccountInfo<'info>],
+    amount: u64,
+    signer: &[&[&[u8]]],
+) -> Result<()> {
+    invoke_yield("withdraw", yield_program, escrow_authority, escrow_token_account, token_program, remaining_accounts, amount, signer)
+}
@@ -110,11 +110,11 @@ async function main(){
             
             const newAdminPubKey = new PublicKey(newAdminPublicKey);
             console.log(`Current admin: ${adminWallet.publicKey.toString()}`);
-            console.log(`Updating admin to: ${newAdminPubKey.toString()}`);
+            console.log(`Proposing new admin: ${newAdminPubKey.toString()}`);
             
             // Create the instruction
-            const updateAdminIx = await program.methods
-                .updateAdmin(newAdminPubKey)
+            const proposeAdminIx = await program.methods
+                .proposeAdmin(newAdminPubKey)
                 .accounts({
                     admin: adminWallet.publicKey,
                     config: configPda,
@@ -123,7 +123,7 @@ async function main(){
             
             // Create a new transaction
             const transaction = new Transaction();
-            transaction.add(updateAdminIx);
+            transaction.add(proposeAdminIx);
             
             // Get the latest blockhash
             const { blockhash } = await connection.getLatestBlockhash();
@@ -143,7 +143,7 @@ async function main(){
                 throw new Error(`Transaction failed: ${confirmation.value.err}`);
             }
                 
-            console.log(`Admin updated successfully! Transaction: ${txSignature}`);
+            console.log(`Admin transfer proposed! The new admin must call acceptAdmin to take over. Transaction: ${txSignature}`);
         } catch (error) {
             console.error("Error updating admin:", error);
             process.exit(1);
@@ -151,7 +151,7 @@ async function main(){
     } else if (args.init) {
         // Initialize config when --init flag is provided
         console.log("Initializing config...");
-        const admin = await program.methods.initializeConfig().accounts({
+        const admin = await program.methods.initializeConfig(0, adminWallet.publicKey).accounts({
             admin: adminWallet.publicKey,
             config: configPda
         }).rpc();
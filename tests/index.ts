@@ -1,21 +1,70 @@
 import { assert, expect } from "chai";
 import * as anchor from '@coral-xyz/anchor';
 import { Program, AnchorError } from "@coral-xyz/anchor";
-import { Keypair, PublicKey, SystemProgram, SYSVAR_RENT_PUBKEY } from "@solana/web3.js";
+import { Keypair, PublicKey, SystemProgram, SYSVAR_RENT_PUBKEY, Transaction, sendAndConfirmTransaction } from "@solana/web3.js";
 import { 
   TOKEN_PROGRAM_ID,
   ASSOCIATED_TOKEN_PROGRAM_ID,
   createMint, 
   createAssociatedTokenAccount, 
+  getOrCreateAssociatedTokenAccount,
   mintTo, 
   getAssociatedTokenAddress,
+  getAssociatedTokenAddressSync,
   getAccount,
+  getMint,
+  transfer,
+  closeAccount,
+  TOKEN_2022_PROGRAM_ID,
+  ExtensionType,
+  getMintLen,
+  createInitializeMintInstruction,
+  createInitializeTransferHookInstruction,
 } from '@solana/spl-token';
+import { createHash } from "crypto";
 import { OctasolContract } from '../target/types/octasol_contract';
+import { MockIntegrations } from '../target/types/mock_integrations';
 
 // Helper to generate a random 64-bit number for the bounty ID
 const generateBountyId = () => new anchor.BN(Math.floor(Math.random() * Number.MAX_SAFE_INTEGER));
 
+// Helpers: sorted-pair sha256 Merkle tree matching util/merkle.rs
+const sha256 = (...parts: Buffer[]) => createHash("sha256").update(Buffer.concat(parts)).digest();
+const hashPair = (a: Buffer, b: Buffer) => (Buffer.compare(a, b) <= 0 ? sha256(a, b) : sha256(b, a));
+
+function merkleTree(keys: PublicKey[]) {
+  const layers: Buffer[][] = [keys.map((k) => sha256(k.toBuffer()))];
+  while (layers[layers.length - 1].length > 1) {
+    const prev = layers[layers.length - 1];
+    const next: Buffer[] = [];
+    for (let i = 0; i < prev.length; i += 2) {
+      next.push(i + 1 < prev.length ? hashPair(prev[i], prev[i + 1]) : prev[i]);
+    }
+    layers.push(next);
+  }
+  const proof = (index: number) => {
+    const path: number[][] = [];
+    for (const layer of layers.slice(0, -1)) {
+      const sibling = index ^ 1;
+      if (sibling < layer.length) path.push(Array.from(layer[sibling]));
+      index = Math.floor(index / 2);
+    }
+    return path;
+  };
+  return { root: Array.from(layers[layers.length - 1][0]), proof };
+}
+
+// Helper: unix timestamp `secs` from now, used as a bounty deadline
+const futureDeadline = (secs = 86400) => new anchor.BN(Math.floor(Date.now() / 1000) + secs);
+
+// Mirrors the on-chain CancelReason codes
+const CANCEL_REASON = {
+  maintainerCancel: 0,
+  adminCancel: 1,
+  expiryReclaim: 2,
+  disputeRefund: 3,
+};
+
 // Helper: assert Anchor error code
 function expectAnchorErrorCode(e: unknown, code: string) {
   if (e instanceof AnchorError) {
@@ -32,6 +81,7 @@ describe("Octasol Escrow Contract", () => {
   anchor.setProvider(provider);
 
   const program = anchor.workspace.OctasolContract as Program<OctasolContract>;
+  const mockProgram = anchor.workspace.MockIntegrations as Program<MockIntegrations>;
   const connection = provider.connection;
   const wallet = provider.wallet as anchor.Wallet;
 
@@ -40,9 +90,6 @@ describe("Octasol Escrow Contract", () => {
   const contributor = anchor.web3.Keypair.generate();
   const admin = wallet.payer; // Use the wallet as the admin for simplicity
   
-  // This is the Keypair for the main Bounty account that holds the state
-  const bountyAccountKp = anchor.web3.Keypair.generate();
-
   // Declare variables in the outer scope
   let mint: PublicKey;
   let maintainerTokenAccount: PublicKey;
@@ -51,7 +98,96 @@ describe("Octasol Escrow Contract", () => {
   let escrowTokenAccount: PublicKey;
   let configPda: PublicKey;
   const bountyId = generateBountyId();
+  // The main Bounty account that holds the state
+  const bountyPda = bountyAddress(bountyId);
   const BOUNTY_AMOUNT = new anchor.BN(10000); // Use BN for amounts
+  // Admin interventions take a 64-byte reason; all zeroes counts as none
+  const NO_REASON = Array(64).fill(0);
+
+  // Helper: a bounty's address, derived from its maintainer and id
+  function bountyAddress(id: anchor.BN, owner: PublicKey = maintainer.publicKey) {
+    return PublicKey.findProgramAddressSync(
+      [Buffer.from("bounty"), owner.toBuffer(), id.toArrayLike(Buffer, "le", 8)],
+      program.programId
+    )[0];
+  }
+
+  // Helper: create and fund a fresh bounty from the default maintainer
+  async function createBounty({
+    amount = BOUNTY_AMOUNT,
+    priority = 0,
+    deadline = futureDeadline(),
+    keeper = null,
+    milestones = [],
+    rentPayer = null,
+  }: {
+    amount?: anchor.BN;
+    priority?: number;
+    deadline?: anchor.BN;
+    keeper?: PublicKey | null;
+    milestones?: anchor.BN[];
+    rentPayer?: Keypair | null;
+  } = {}) {
+    const id = generateBountyId();
+    const bounty = bountyAddress(id);
+    const [escrowAuth] = anchor.web3.PublicKey.findProgramAddressSync(
+      [Buffer.from("escrow_auth"), bounty.toBuffer()],
+      program.programId
+    );
+    const escrowAta = await getAssociatedTokenAddress(mint, escrowAuth, true);
+
+    const signature = await program.methods.initializeBounty(id, amount, priority, deadline, keeper, milestones).accountsPartial({
+      maintainer: maintainer.publicKey,
+      bounty,
+      rentPayer: rentPayer ? rentPayer.publicKey : null,
+      maintainerTokenAccount: maintainerTokenAccount,
+      escrowAuthority: escrowAuth,
+      escrowTokenAccount: escrowAta,
+      mint,
+      systemProgram: SystemProgram.programId,
+      tokenProgram: TOKEN_PROGRAM_ID,
+      associatedTokenProgram: ASSOCIATED_TOKEN_PROGRAM_ID,
+      rent: SYSVAR_RENT_PUBKEY,
+    }).signers(rentPayer ? [rentPayer] : []).rpc();
+
+    return { bounty, bountyId: id, escrowAuth, escrowAta, signature };
+  }
+
+  // Helper: admin force-releases a bounty from createBounty to a fresh contributor
+  async function adminRelease(
+    created: { bounty: PublicKey; bountyId: anchor.BN; escrowAuth: PublicKey; escrowAta: PublicKey },
+    extraAccounts: Record<string, PublicKey> = {}
+  ) {
+    const target = anchor.web3.Keypair.generate();
+    const targetAta = await createAssociatedTokenAccount(connection, wallet.payer, mint, target.publicKey);
+    const signature = await program.methods.adminAssignAndRelease(created.bountyId, NO_REASON).accountsPartial({
+      admin: admin.publicKey,
+      config: configPda,
+      bounty: created.bounty,
+      mint,
+      escrowAuthority: created.escrowAuth,
+      maintainer: maintainer.publicKey,
+      contributor: target.publicKey,
+      contributorTokenAccount: targetAta,
+      escrowTokenAccount: created.escrowAta,
+      tokenProgram: TOKEN_PROGRAM_ID,
+      systemProgram: SystemProgram.programId,
+      associatedTokenProgram: ASSOCIATED_TOKEN_PROGRAM_ID,
+      ...extraAccounts,
+    }).rpc();
+    return { signature, target, targetAta };
+  }
+
+  // Helper: decode the program events emitted by a confirmed transaction
+  async function fetchEvents(signature: string) {
+    await connection.confirmTransaction(signature, "confirmed");
+    const tx = await connection.getTransaction(signature, {
+      commitment: "confirmed",
+      maxSupportedTransactionVersion: 0,
+    });
+    const parser = new anchor.EventParser(program.programId, program.coder);
+    return Array.from(parser.parseLogs(tx.meta.logMessages));
+  }
 
   before(async () => {
     // Airdrop SOL to the contributor so they can pay for transactions if needed
@@ -97,7 +233,7 @@ describe("Octasol Escrow Contract", () => {
     // Derive the PDA for the escrow authority
     // This PDA is the sole authority over the escrow_token_account
     [escrowAuthorityPda] = anchor.web3.PublicKey.findProgramAddressSync(
-        [Buffer.from("escrow_auth"), bountyAccountKp.publicKey.toBuffer()],
+        [Buffer.from("escrow_auth"), bountyPda.toBuffer()],
         program.programId
     );
 
@@ -116,7 +252,7 @@ describe("Octasol Escrow Contract", () => {
 
     // Initialize the config
     await program.methods
-      .initializeConfig()
+      .initializeConfig(0, admin.publicKey)
       .accountsPartial({
         admin: admin.publicKey,
         config: configPda,
@@ -125,13 +261,19 @@ describe("Octasol Escrow Contract", () => {
       .rpc();
   });
 
+  it("Stores the fee and treasury passed to initialize_config", async () => {
+    const config = await program.account.configState.fetch(configPda);
+    assert.equal(config.feeBps, 0);
+    assert.ok(config.treasury.equals(admin.publicKey));
+  });
+
   it("Initializes the bounty escrow successfully!", async () => {
     // Now you can write your test with the correctly initialized variables
     await program.methods
-      .initializeBounty(bountyId, BOUNTY_AMOUNT)
+      .initializeBounty(bountyId, BOUNTY_AMOUNT, 0, futureDeadline(), null, [])
       .accountsPartial({
         maintainer: maintainer.publicKey,
-        bounty: bountyAccountKp.publicKey,
+        bounty: bountyPda,
         maintainerTokenAccount: maintainerTokenAccount,
         escrowAuthority: escrowAuthorityPda,
         escrowTokenAccount: escrowTokenAccount,
@@ -141,12 +283,10 @@ describe("Octasol Escrow Contract", () => {
         associatedTokenProgram: ASSOCIATED_TOKEN_PROGRAM_ID,
         rent: SYSVAR_RENT_PUBKEY,
       })
-      // The bounty account is being created, so its keypair must sign the transaction
-      .signers([bountyAccountKp])
       .rpc();
 
     // Fetch the created bounty account and assert its values
-    const bountyAccount = await program.account.bounty.fetch(bountyAccountKp.publicKey);
+    const bountyAccount = await program.account.bounty.fetch(bountyPda);
 
     assert.ok(bountyAccount.maintainer.equals(maintainer.publicKey), "Maintainer public key should match");
     assert.equal(bountyAccount.amount.toString(), BOUNTY_AMOUNT.toString(), "Bounty amount should match");
@@ -162,10 +302,10 @@ describe("Octasol Escrow Contract", () => {
     try {
         // Call the assignContributor instruction
         await program.methods
-            .assignContributor()
+            .assignContributor(false)
             .accountsPartial({
                 maintainer: maintainer.publicKey,
-                bounty: bountyAccountKp.publicKey,
+                bounty: bountyPda,
                 contributor: contributor.publicKey,
                 systemProgram: SystemProgram.programId,
             })
@@ -180,7 +320,7 @@ describe("Octasol Escrow Contract", () => {
     }
 
     // Fetch the updated bounty account state
-    const updatedBountyAccount = await program.account.bounty.fetch(bountyAccountKp.publicKey);
+    const updatedBountyAccount = await program.account.bounty.fetch(bountyPda);
 
     // --- Assertions ---
     assert.ok(updatedBountyAccount.contributor, "Contributor field should be populated but it is null.");
@@ -196,10 +336,10 @@ describe("Octasol Escrow Contract", () => {
     
     try {
         await program.methods
-            .assignContributor()
+            .assignContributor(false)
             .accountsPartial({
                 maintainer: maintainer.publicKey,
-                bounty: bountyAccountKp.publicKey,
+                bounty: bountyPda,
                 contributor: secondContributor.publicKey,
                 systemProgram: SystemProgram.programId,
             })
@@ -217,9 +357,10 @@ describe("Octasol Escrow Contract", () => {
       "confirmed"
     );
 
-    const testBountyKp = anchor.web3.Keypair.generate();
+    const testBountyId = generateBountyId();
+    const testBounty = bountyAddress(testBountyId);
     const testEscrowAuthorityPda = anchor.web3.PublicKey.findProgramAddressSync(
-      [Buffer.from("escrow_auth"), testBountyKp.publicKey.toBuffer()],
+      [Buffer.from("escrow_auth"), testBounty.toBuffer()],
       program.programId
     )[0];
     const testEscrowTokenAccount = await getAssociatedTokenAddress(
@@ -230,10 +371,10 @@ describe("Octasol Escrow Contract", () => {
 
     // Initialize bounty with correct maintainer
     await program.methods
-      .initializeBounty(generateBountyId(), BOUNTY_AMOUNT)
+      .initializeBounty(testBountyId, BOUNTY_AMOUNT, 0, futureDeadline(), null, [])
       .accountsPartial({
         maintainer: maintainer.publicKey,
-        bounty: testBountyKp.publicKey,
+        bounty: testBounty,
         maintainerTokenAccount: maintainerTokenAccount,
         escrowAuthority: testEscrowAuthorityPda,
         escrowTokenAccount: testEscrowTokenAccount,
@@ -243,16 +384,15 @@ describe("Octasol Escrow Contract", () => {
         associatedTokenProgram: ASSOCIATED_TOKEN_PROGRAM_ID,
         rent: SYSVAR_RENT_PUBKEY,
       })
-      .signers([testBountyKp])
       .rpc();
 
     // Try to assign contributor with wrong maintainer
     try {
         await program.methods
-            .assignContributor()
+            .assignContributor(false)
             .accountsPartial({
                 maintainer: wrongMaintainer.publicKey,
-                bounty: testBountyKp.publicKey,
+                bounty: testBounty,
                 contributor: contributor.publicKey,
                 systemProgram: SystemProgram.programId,
             })
@@ -268,9 +408,9 @@ describe("Octasol Escrow Contract", () => {
   it("Completes the bounty and pays the contributor!", async () => {
     try {
         await program.methods
-            .completeBounty(bountyId) 
+            .completeBounty(bountyId, 5, null) 
             .accountsPartial({
-                bounty: bountyAccountKp.publicKey,
+                bounty: bountyPda,
                 escrowAuthority: escrowAuthorityPda,
                 maintainer: maintainer.publicKey,
                 contributor: contributor.publicKey,
@@ -319,10 +459,11 @@ describe("Octasol Escrow Contract", () => {
       "confirmed"
     );
 
-    const testBountyKp = anchor.web3.Keypair.generate();
+    const testBountyId = generateBountyId();
+    const testBounty = bountyAddress(testBountyId);
     const testContributor = anchor.web3.Keypair.generate();
     const testEscrowAuthorityPda = anchor.web3.PublicKey.findProgramAddressSync(
-      [Buffer.from("escrow_auth"), testBountyKp.publicKey.toBuffer()],
+      [Buffer.from("escrow_auth"), testBounty.toBuffer()],
       program.programId
     )[0];
     const testEscrowTokenAccount = await getAssociatedTokenAddress(
@@ -339,10 +480,10 @@ describe("Octasol Escrow Contract", () => {
 
     // Initialize and assign contributor
     await program.methods
-      .initializeBounty(generateBountyId(), BOUNTY_AMOUNT)
+      .initializeBounty(testBountyId, BOUNTY_AMOUNT, 0, futureDeadline(), null, [])
       .accountsPartial({
         maintainer: maintainer.publicKey,
-        bounty: testBountyKp.publicKey,
+        bounty: testBounty,
         maintainerTokenAccount: maintainerTokenAccount,
         escrowAuthority: testEscrowAuthorityPda,
         escrowTokenAccount: testEscrowTokenAccount,
@@ -352,14 +493,13 @@ describe("Octasol Escrow Contract", () => {
         associatedTokenProgram: ASSOCIATED_TOKEN_PROGRAM_ID,
         rent: SYSVAR_RENT_PUBKEY,
       })
-      .signers([testBountyKp])
       .rpc();
 
     await program.methods
-      .assignContributor()
+      .assignContributor(false)
       .accountsPartial({
         maintainer: maintainer.publicKey,
-        bounty: testBountyKp.publicKey,
+        bounty: testBounty,
         contributor: testContributor.publicKey,
         systemProgram: SystemProgram.programId,
       })
@@ -367,9 +507,9 @@ describe("Octasol Escrow Contract", () => {
 
     try {
         await program.methods
-            .completeBounty(generateBountyId())
+            .completeBounty(generateBountyId(), 5, null)
             .accountsPartial({
-                bounty: testBountyKp.publicKey,
+                bounty: testBounty,
                 escrowAuthority: testEscrowAuthorityPda,
                 maintainer: maintainer.publicKey,
                 contributor: testContributor.publicKey,
@@ -391,14 +531,14 @@ describe("Octasol Escrow Contract", () => {
 
   it("Cancels the bounty and returns funds to the maintainer!", async () => {
     // --- Setup a new, independent bounty for this test ---
-    const cancelBountyKp = anchor.web3.Keypair.generate();
     const cancelBountyId = generateBountyId();
+    const cancelBounty = bountyAddress(cancelBountyId);
     const cancelContributor = anchor.web3.Keypair.generate();
     const initialMaintainerBalance = (await getAccount(connection, maintainerTokenAccount)).amount;
 
     // Derive the PDA for the new escrow authority
     const [cancelEscrowAuthorityPda] = anchor.web3.PublicKey.findProgramAddressSync(
-        [Buffer.from("escrow_auth"), cancelBountyKp.publicKey.toBuffer()],
+        [Buffer.from("escrow_auth"), cancelBounty.toBuffer()],
         program.programId
     );
 
@@ -411,10 +551,10 @@ describe("Octasol Escrow Contract", () => {
 
     // 1. Initialize the new bounty
     await program.methods
-        .initializeBounty(cancelBountyId, BOUNTY_AMOUNT)
+        .initializeBounty(cancelBountyId, BOUNTY_AMOUNT, 0, futureDeadline(), null, [])
         .accountsPartial({
             maintainer: maintainer.publicKey,
-            bounty: cancelBountyKp.publicKey,
+            bounty: cancelBounty,
             maintainerTokenAccount: maintainerTokenAccount,
             escrowAuthority: cancelEscrowAuthorityPda,
             escrowTokenAccount: cancelEscrowTokenAccount,
@@ -424,15 +564,14 @@ describe("Octasol Escrow Contract", () => {
             associatedTokenProgram: ASSOCIATED_TOKEN_PROGRAM_ID,
             rent: SYSVAR_RENT_PUBKEY,
         })
-        .signers([cancelBountyKp])
         .rpc();
 
     // 2. Assign a contributor to it
     await program.methods
-        .assignContributor()
+        .assignContributor(false)
         .accountsPartial({
             maintainer: maintainer.publicKey,
-            bounty: cancelBountyKp.publicKey,
+            bounty: cancelBounty,
             contributor: cancelContributor.publicKey,
             systemProgram: SystemProgram.programId,
         })
@@ -441,11 +580,11 @@ describe("Octasol Escrow Contract", () => {
     // --- Execute the cancelBounty instruction ---
     try {
         await program.methods
-            .cancelBounty()
+            .cancelBounty(null, NO_REASON)
             .accountsPartial({
                 admin: admin.publicKey,
                 config: configPda,
-                bounty: cancelBountyKp.publicKey,
+                bounty: cancelBounty,
                 escrowAuthority: cancelEscrowAuthorityPda,
                 maintainer: maintainer.publicKey,
                 maintainerTokenAccount: maintainerTokenAccount,
@@ -484,7 +623,7 @@ describe("Octasol Escrow Contract", () => {
 
     // 3. Check that the bounty account is closed.
     try {
-        await program.account.bounty.fetch(cancelBountyKp.publicKey);
+        await program.account.bounty.fetch(cancelBounty);
         assert.fail("Cancelled bounty account should be closed.");
     } catch (error) {
         assert.isOk(error, "Successfully confirmed bounty account is closed.");
@@ -494,13 +633,13 @@ describe("Octasol Escrow Contract", () => {
 
   it("Only admin can cancel bounty!", async () => {
     // --- Setup a new, independent bounty for this test ---
-    const securityCancelBountyKp = anchor.web3.Keypair.generate();
     const securityCancelBountyId = generateBountyId();
+    const securityCancelBounty = bountyAddress(securityCancelBountyId);
     const securityCancelContributor = anchor.web3.Keypair.generate();
     const wrongAdmin = anchor.web3.Keypair.generate();
 
     const [securityCancelEscrowAuthorityPda] = anchor.web3.PublicKey.findProgramAddressSync(
-        [Buffer.from("escrow_auth"), securityCancelBountyKp.publicKey.toBuffer()],
+        [Buffer.from("escrow_auth"), securityCancelBounty.toBuffer()],
         program.programId
     );
     const securityCancelEscrowTokenAccount = await getAssociatedTokenAddress(
@@ -510,10 +649,10 @@ describe("Octasol Escrow Contract", () => {
     );
 
     await program.methods
-        .initializeBounty(securityCancelBountyId, BOUNTY_AMOUNT)
+        .initializeBounty(securityCancelBountyId, BOUNTY_AMOUNT, 0, futureDeadline(), null, [])
         .accountsPartial({
             maintainer: maintainer.publicKey,
-            bounty: securityCancelBountyKp.publicKey,
+            bounty: securityCancelBounty,
             maintainerTokenAccount: maintainerTokenAccount,
             escrowAuthority: securityCancelEscrowAuthorityPda,
             escrowTokenAccount: securityCancelEscrowTokenAccount,
@@ -523,14 +662,13 @@ describe("Octasol Escrow Contract", () => {
             associatedTokenProgram: ASSOCIATED_TOKEN_PROGRAM_ID,
             rent: SYSVAR_RENT_PUBKEY,
         })
-        .signers([securityCancelBountyKp])
         .rpc();
 
     await program.methods
-        .assignContributor()
+        .assignContributor(false)
         .accountsPartial({
             maintainer: maintainer.publicKey,
-            bounty: securityCancelBountyKp.publicKey,
+            bounty: securityCancelBounty,
             contributor: securityCancelContributor.publicKey,
             systemProgram: SystemProgram.programId,
         })
@@ -538,11 +676,11 @@ describe("Octasol Escrow Contract", () => {
 
     try {
         await program.methods
-            .cancelBounty()
+            .cancelBounty(null, NO_REASON)
             .accountsPartial({
                 admin: wrongAdmin.publicKey,
                 config: configPda,
-                bounty: securityCancelBountyKp.publicKey,
+                bounty: securityCancelBounty,
                 escrowAuthority: securityCancelEscrowAuthorityPda,
                 maintainer: maintainer.publicKey,
                 maintainerTokenAccount: maintainerTokenAccount,
@@ -559,7 +697,7 @@ describe("Octasol Escrow Contract", () => {
         expectAnchorErrorCode(error, "Unauthorized");
     }
 
-    const bountyAccount = await program.account.bounty.fetch(securityCancelBountyKp.publicKey);
+    const bountyAccount = await program.account.bounty.fetch(securityCancelBounty);
     // Check state variant
     assert.isTrue(
       bountyAccount.state && Object.prototype.hasOwnProperty.call(bountyAccount.state, 'inProgress'),
@@ -581,29 +719,50 @@ describe("Octasol Escrow Contract", () => {
     const currentConfig = await program.account.configState.fetch(configPda);
     assert.ok(currentConfig.admin.equals(admin.publicKey), "Current admin should match the original admin");
 
-    // Update the admin to newAdmin (signed by current admin)
+    // Propose newAdmin (signed by current admin), nothing changes until they accept
     await program.methods
-      .updateAdmin(newAdmin.publicKey)
+      .proposeAdmin(newAdmin.publicKey)
       .accounts({
         admin: admin.publicKey,
         config: configPda,
       })
       .rpc();
 
+    const proposedConfig = await program.account.configState.fetch(configPda);
+    assert.ok(proposedConfig.admin.equals(admin.publicKey), "Admin should not change until the proposal is accepted");
+    assert.ok(proposedConfig.pendingAdmin.equals(newAdmin.publicKey), "newAdmin should be pending");
+
+    await program.methods
+      .acceptAdmin()
+      .accounts({
+        newAdmin: newAdmin.publicKey,
+        config: configPda,
+      })
+      .signers([newAdmin])
+      .rpc();
+
     // Verify the admin was updated
     const updatedConfig = await program.account.configState.fetch(configPda);
     assert.ok(updatedConfig.admin.equals(newAdmin.publicKey), "Admin should be updated to the new admin");
     assert.ok(!updatedConfig.admin.equals(admin.publicKey), "Admin should no longer be the old admin");
+    assert.isNull(updatedConfig.pendingAdmin, "Accepting should clear the pending admin");
 
-    // Revert admin back to original admin, signed by newAdmin
+    // Hand admin back to the original admin, proposed by newAdmin
     await program.methods
-      .updateAdmin(admin.publicKey)
+      .proposeAdmin(admin.publicKey)
       .accounts({
         admin: newAdmin.publicKey,
         config: configPda,
       })
       .signers([newAdmin])
       .rpc();
+    await program.methods
+      .acceptAdmin()
+      .accounts({
+        newAdmin: admin.publicKey,
+        config: configPda,
+      })
+      .rpc();
 
     const revertedConfig = await program.account.configState.fetch(configPda);
     assert.ok(revertedConfig.admin.equals(admin.publicKey), "Admin should be reverted to the original admin");
@@ -624,7 +783,7 @@ describe("Octasol Escrow Contract", () => {
     // Try to update admin with non-admin account - this should fail
     try {
       await program.methods
-        .updateAdmin(nonAdmin.publicKey)
+        .proposeAdmin(nonAdmin.publicKey)
         .accounts({
           admin: nonAdmin.publicKey,
           config: configPda,
@@ -653,7 +812,7 @@ describe("Octasol Escrow Contract", () => {
     // Try to update admin to the same admin - this should fail
     try {
       await program.methods
-        .updateAdmin(currentAdmin.publicKey)
+        .proposeAdmin(currentAdmin.publicKey)
         .accounts({
           admin: currentAdmin.publicKey,
           config: configPda,
@@ -680,7 +839,7 @@ describe("Octasol Escrow Contract", () => {
     // Try to update admin to default pubkey - this should fail
     try {
       await program.methods
-        .updateAdmin(anchor.web3.PublicKey.default)
+        .proposeAdmin(anchor.web3.PublicKey.default)
         .accounts({
           admin: currentAdmin.publicKey,
           config: configPda,
@@ -716,8 +875,8 @@ describe("Octasol Escrow Contract", () => {
 
     // If we reach here, the current admin is the original admin
     // Create a new bounty for testing
-    const testBountyKp = anchor.web3.Keypair.generate();
     const testBountyId = generateBountyId();
+    const testBounty = bountyAddress(testBountyId);
     const testContributor = anchor.web3.Keypair.generate();
     
     // Airdrop SOL to the contributor
@@ -736,7 +895,7 @@ describe("Octasol Escrow Contract", () => {
 
     // Derive escrow authority for the test bounty
     const [testEscrowAuthorityPda] = anchor.web3.PublicKey.findProgramAddressSync(
-      [Buffer.from("escrow_auth"), testBountyKp.publicKey.toBuffer()],
+      [Buffer.from("escrow_auth"), testBounty.toBuffer()],
       program.programId
     );
 
@@ -749,10 +908,10 @@ describe("Octasol Escrow Contract", () => {
 
     // Initialize the test bounty
     await program.methods
-      .initializeBounty(testBountyId, BOUNTY_AMOUNT)
+      .initializeBounty(testBountyId, BOUNTY_AMOUNT, 0, futureDeadline(), null, [])
       .accountsPartial({
         maintainer: maintainer.publicKey,
-        bounty: testBountyKp.publicKey,
+        bounty: testBounty,
         maintainerTokenAccount: maintainerTokenAccount,
         escrowAuthority: testEscrowAuthorityPda,
         escrowTokenAccount: testEscrowTokenAccount,
@@ -762,22 +921,21 @@ describe("Octasol Escrow Contract", () => {
         associatedTokenProgram: ASSOCIATED_TOKEN_PROGRAM_ID,
         rent: SYSVAR_RENT_PUBKEY,
       })
-      .signers([testBountyKp])
       .rpc();
 
     // Assign contributor
     await program.methods
-      .assignContributor()
+      .assignContributor(false)
       .accountsPartial({
         maintainer: maintainer.publicKey,
-        bounty: testBountyKp.publicKey,
+        bounty: testBounty,
         contributor: testContributor.publicKey,
         systemProgram: SystemProgram.programId,
       })
       .rpc();
 
     // Verify the bounty state
-    const bountyAccount = await program.account.bounty.fetch(testBountyKp.publicKey);
+    const bountyAccount = await program.account.bounty.fetch(testBounty);
     assert.ok(bountyAccount.contributor.equals(testContributor.publicKey), "Contributor should be assigned");
     assert.ok(bountyAccount.state.hasOwnProperty('inProgress'), "Bounty should be in progress");
 
@@ -826,9 +984,10 @@ describe("Octasol Escrow Contract", () => {
         1000000       // 1,000,000 tokens
       );
       
-      const testBountyKp = anchor.web3.Keypair.generate();
+      const testBountyId = generateBountyId();
+      const testBounty = bountyAddress(testBountyId, nonAdminMaintainer.publicKey);
       const testEscrowAuthorityPda = anchor.web3.PublicKey.findProgramAddressSync(
-        [Buffer.from("escrow_auth"), testBountyKp.publicKey.toBuffer()],
+        [Buffer.from("escrow_auth"), testBounty.toBuffer()],
         program.programId
       )[0];
       const testEscrowTokenAccount = await getAssociatedTokenAddress(
@@ -839,10 +998,10 @@ describe("Octasol Escrow Contract", () => {
 
       try {
         await program.methods
-          .initializeBounty(generateBountyId(), BOUNTY_AMOUNT)
+          .initializeBounty(testBountyId, BOUNTY_AMOUNT, 0, futureDeadline(), null, [])
           .accountsPartial({
             maintainer: nonAdminMaintainer.publicKey,
-            bounty: testBountyKp.publicKey,
+            bounty: testBounty,
             maintainerTokenAccount: nonAdminTokenAccount, // Use the non-admin's token account
             escrowAuthority: testEscrowAuthorityPda,
             escrowTokenAccount: testEscrowTokenAccount,
@@ -852,7 +1011,7 @@ describe("Octasol Escrow Contract", () => {
             associatedTokenProgram: ASSOCIATED_TOKEN_PROGRAM_ID,
             rent: SYSVAR_RENT_PUBKEY,
           })
-          .signers([testBountyKp, nonAdminMaintainer])
+          .signers([nonAdminMaintainer])
           .rpc();
         
         assert.fail("Non-admin should not be able to create bounty");
@@ -870,8 +1029,8 @@ describe("Octasol Escrow Contract", () => {
 
     // If we reach here, the current admin is the original admin
     // Create a new bounty for testing
-    const testBountyKp = anchor.web3.Keypair.generate();
     const testBountyId = generateBountyId();
+    const testBounty = bountyAddress(testBountyId);
     const testContributor = anchor.web3.Keypair.generate();
     
     // Airdrop SOL to the contributor
@@ -890,7 +1049,7 @@ describe("Octasol Escrow Contract", () => {
 
     // Derive escrow authority for the test bounty
     const [testEscrowAuthorityPda] = anchor.web3.PublicKey.findProgramAddressSync(
-      [Buffer.from("escrow_auth"), testBountyKp.publicKey.toBuffer()],
+      [Buffer.from("escrow_auth"), testBounty.toBuffer()],
       program.programId
     );
 
@@ -903,10 +1062,10 @@ describe("Octasol Escrow Contract", () => {
 
     // Initialize the test bounty
     await program.methods
-      .initializeBounty(testBountyId, BOUNTY_AMOUNT)
+      .initializeBounty(testBountyId, BOUNTY_AMOUNT, 0, futureDeadline(), null, [])
       .accountsPartial({
         maintainer: maintainer.publicKey,
-        bounty: testBountyKp.publicKey,
+        bounty: testBounty,
         maintainerTokenAccount: maintainerTokenAccount,
         escrowAuthority: testEscrowAuthorityPda,
         escrowTokenAccount: testEscrowTokenAccount,
@@ -916,15 +1075,14 @@ describe("Octasol Escrow Contract", () => {
         associatedTokenProgram: ASSOCIATED_TOKEN_PROGRAM_ID,
         rent: SYSVAR_RENT_PUBKEY,
       })
-      .signers([testBountyKp])
       .rpc();
 
     // Assign contributor
     await program.methods
-      .assignContributor()
+      .assignContributor(false)
       .accountsPartial({
         maintainer: maintainer.publicKey,
-        bounty: testBountyKp.publicKey,
+        bounty: testBounty,
         contributor: testContributor.publicKey,
         systemProgram: SystemProgram.programId,
       })
@@ -933,9 +1091,9 @@ describe("Octasol Escrow Contract", () => {
     // Try to complete bounty with the wrong admin - this should fail
     try {
       await program.methods
-        .completeBounty(testBountyId)
+        .completeBounty(testBountyId, 5, null)
         .accountsPartial({
-          bounty: testBountyKp.publicKey,
+          bounty: testBounty,
           escrowAuthority: testEscrowAuthorityPda,
           maintainer: maintainer.publicKey,
           contributor: testContributor.publicKey,
@@ -965,20 +1123,20 @@ describe("Octasol Escrow Contract", () => {
   });
 
   it("Admin assigns and releases funds in one call (success)", async () => {
-    const bntyKp = anchor.web3.Keypair.generate();
     const newBountyId = generateBountyId();
+    const bnty = bountyAddress(newBountyId);
     const targetContributor = anchor.web3.Keypair.generate();
 
     const [escrowAuth] = anchor.web3.PublicKey.findProgramAddressSync(
-      [Buffer.from("escrow_auth"), bntyKp.publicKey.toBuffer()],
+      [Buffer.from("escrow_auth"), bnty.toBuffer()],
       program.programId
     );
     const escrowAta = await getAssociatedTokenAddress(mint, escrowAuth, true);
 
     // init bounty
-    await program.methods.initializeBounty(newBountyId, BOUNTY_AMOUNT).accountsPartial({
+    await program.methods.initializeBounty(newBountyId, BOUNTY_AMOUNT, 0, futureDeadline(), null, []).accountsPartial({
       maintainer: maintainer.publicKey,
-      bounty: bntyKp.publicKey,
+      bounty: bnty,
       maintainerTokenAccount: maintainerTokenAccount,
       escrowAuthority: escrowAuth,
       escrowTokenAccount: escrowAta,
@@ -987,7 +1145,7 @@ describe("Octasol Escrow Contract", () => {
       tokenProgram: TOKEN_PROGRAM_ID,
       associatedTokenProgram: ASSOCIATED_TOKEN_PROGRAM_ID,
       rent: SYSVAR_RENT_PUBKEY,
-    }).signers([bntyKp]).rpc();
+    }).rpc();
 
     // Ensure contributor ATA exists
     const contribAta = await createAssociatedTokenAccount(
@@ -997,10 +1155,10 @@ describe("Octasol Escrow Contract", () => {
       targetContributor.publicKey
     );
 
-    await program.methods.adminAssignAndRelease(newBountyId).accountsPartial({
+    await program.methods.adminAssignAndRelease(newBountyId, NO_REASON).accountsPartial({
       admin: admin.publicKey,
       config: configPda,
-      bounty: bntyKp.publicKey,
+      bounty: bnty,
       escrowAuthority: escrowAuth,
       maintainer: maintainer.publicKey,
       contributor: targetContributor.publicKey,
@@ -1019,17 +1177,17 @@ describe("Octasol Escrow Contract", () => {
     const wrong = anchor.web3.Keypair.generate();
     await connection.confirmTransaction(await connection.requestAirdrop(wrong.publicKey, anchor.web3.LAMPORTS_PER_SOL));
 
-    const bntyKp = anchor.web3.Keypair.generate();
     const newBountyId = generateBountyId();
+    const bnty = bountyAddress(newBountyId);
     const targetContributor = anchor.web3.Keypair.generate();
     const [escrowAuth] = anchor.web3.PublicKey.findProgramAddressSync([
-      Buffer.from("escrow_auth"), bntyKp.publicKey.toBuffer()
+      Buffer.from("escrow_auth"), bnty.toBuffer()
     ], program.programId);
     const escrowAta = await getAssociatedTokenAddress(mint, escrowAuth, true);
 
-    await program.methods.initializeBounty(newBountyId, BOUNTY_AMOUNT).accountsPartial({
+    await program.methods.initializeBounty(newBountyId, BOUNTY_AMOUNT, 0, futureDeadline(), null, []).accountsPartial({
       maintainer: maintainer.publicKey,
-      bounty: bntyKp.publicKey,
+      bounty: bnty,
       maintainerTokenAccount: maintainerTokenAccount,
       escrowAuthority: escrowAuth,
       escrowTokenAccount: escrowAta,
@@ -1038,7 +1196,7 @@ describe("Octasol Escrow Contract", () => {
       tokenProgram: TOKEN_PROGRAM_ID,
       associatedTokenProgram: ASSOCIATED_TOKEN_PROGRAM_ID,
       rent: SYSVAR_RENT_PUBKEY,
-    }).signers([bntyKp]).rpc();
+    }).rpc();
 
     // Ensure contributor ATA exists
     const contribAta = await createAssociatedTokenAccount(
@@ -1049,10 +1207,10 @@ describe("Octasol Escrow Contract", () => {
     );
 
     try {
-      await program.methods.adminAssignAndRelease(newBountyId).accountsPartial({
+      await program.methods.adminAssignAndRelease(newBountyId, NO_REASON).accountsPartial({
         admin: wrong.publicKey,
         config: configPda,
-        bounty: bntyKp.publicKey,
+        bounty: bnty,
         escrowAuthority: escrowAuth,
         maintainer: maintainer.publicKey,
         contributor: targetContributor.publicKey,
@@ -1070,17 +1228,17 @@ describe("Octasol Escrow Contract", () => {
 
 
   it("Admin assign+release fails with contributor ATA mint mismatch (InvalidMint)", async () => {
-    const bntyKp = anchor.web3.Keypair.generate();
     const newBountyId = generateBountyId();
+    const bnty = bountyAddress(newBountyId);
     const targetContributor = anchor.web3.Keypair.generate();
     const [escrowAuth] = anchor.web3.PublicKey.findProgramAddressSync([
-      Buffer.from("escrow_auth"), bntyKp.publicKey.toBuffer()
+      Buffer.from("escrow_auth"), bnty.toBuffer()
     ], program.programId);
     const escrowAta = await getAssociatedTokenAddress(mint, escrowAuth, true);
 
-    await program.methods.initializeBounty(newBountyId, BOUNTY_AMOUNT).accountsPartial({
+    await program.methods.initializeBounty(newBountyId, BOUNTY_AMOUNT, 0, futureDeadline(), null, []).accountsPartial({
       maintainer: maintainer.publicKey,
-      bounty: bntyKp.publicKey,
+      bounty: bnty,
       maintainerTokenAccount: maintainerTokenAccount,
       escrowAuthority: escrowAuth,
       escrowTokenAccount: escrowAta,
@@ -1089,7 +1247,7 @@ describe("Octasol Escrow Contract", () => {
       tokenProgram: TOKEN_PROGRAM_ID,
       associatedTokenProgram: ASSOCIATED_TOKEN_PROGRAM_ID,
       rent: SYSVAR_RENT_PUBKEY,
-    }).signers([bntyKp]).rpc();
+    }).rpc();
 
     // Create wrong-mint and correct ATAs to ensure initialized accounts
     const wrongMint = await createMint(connection, wallet.payer, wallet.publicKey, wallet.publicKey, 6);
@@ -1101,10 +1259,10 @@ describe("Octasol Escrow Contract", () => {
     );
 
     try {
-      await program.methods.adminAssignAndRelease(newBountyId).accountsPartial({
+      await program.methods.adminAssignAndRelease(newBountyId, NO_REASON).accountsPartial({
         admin: admin.publicKey,
         config: configPda,
-        bounty: bntyKp.publicKey,
+        bounty: bnty,
         escrowAuthority: escrowAuth,
         maintainer: maintainer.publicKey,
         contributor: targetContributor.publicKey,
@@ -1121,19 +1279,19 @@ describe("Octasol Escrow Contract", () => {
   });
 
   it("Admin assign+release fails when contributor ATA owner mismatch (InvalidTokenAccount)", async () => {
-    const bntyKp = anchor.web3.Keypair.generate();
     const newBountyId = generateBountyId();
+    const bnty = bountyAddress(newBountyId);
     const targetContributor = anchor.web3.Keypair.generate();
     const otherOwner = anchor.web3.Keypair.generate();
 
     const [escrowAuth] = anchor.web3.PublicKey.findProgramAddressSync([
-      Buffer.from("escrow_auth"), bntyKp.publicKey.toBuffer()
+      Buffer.from("escrow_auth"), bnty.toBuffer()
     ], program.programId);
     const escrowAta = await getAssociatedTokenAddress(mint, escrowAuth, true);
 
-    await program.methods.initializeBounty(newBountyId, BOUNTY_AMOUNT).accountsPartial({
+    await program.methods.initializeBounty(newBountyId, BOUNTY_AMOUNT, 0, futureDeadline(), null, []).accountsPartial({
       maintainer: maintainer.publicKey,
-      bounty: bntyKp.publicKey,
+      bounty: bnty,
       maintainerTokenAccount: maintainerTokenAccount,
       escrowAuthority: escrowAuth,
       escrowTokenAccount: escrowAta,
@@ -1142,7 +1300,7 @@ describe("Octasol Escrow Contract", () => {
       tokenProgram: TOKEN_PROGRAM_ID,
       associatedTokenProgram: ASSOCIATED_TOKEN_PROGRAM_ID,
       rent: SYSVAR_RENT_PUBKEY,
-    }).signers([bntyKp]).rpc();
+    }).rpc();
 
     // Initialize ATA owned by a different wallet
     const wrongOwnerAta = await createAssociatedTokenAccount(
@@ -1153,10 +1311,10 @@ describe("Octasol Escrow Contract", () => {
     );
 
     try {
-      await program.methods.adminAssignAndRelease(newBountyId).accountsPartial({
+      await program.methods.adminAssignAndRelease(newBountyId, NO_REASON).accountsPartial({
         admin: admin.publicKey,
         config: configPda,
-        bounty: bntyKp.publicKey,
+        bounty: bnty,
         escrowAuthority: escrowAuth,
         maintainer: maintainer.publicKey,
         contributor: targetContributor.publicKey,
@@ -1173,19 +1331,19 @@ describe("Octasol Escrow Contract", () => {
   });
 
   it("Admin assign+release overrides existing contributor successfully", async () => {
-    const bntyKp = anchor.web3.Keypair.generate();
     const newBountyId = generateBountyId();
+    const bnty = bountyAddress(newBountyId);
     const initialContributor = anchor.web3.Keypair.generate();
     const targetContributor = anchor.web3.Keypair.generate();
 
     const [escrowAuth] = anchor.web3.PublicKey.findProgramAddressSync([
-      Buffer.from("escrow_auth"), bntyKp.publicKey.toBuffer()
+      Buffer.from("escrow_auth"), bnty.toBuffer()
     ], program.programId);
     const escrowAta = await getAssociatedTokenAddress(mint, escrowAuth, true);
 
-    await program.methods.initializeBounty(newBountyId, BOUNTY_AMOUNT).accountsPartial({
+    await program.methods.initializeBounty(newBountyId, BOUNTY_AMOUNT, 0, futureDeadline(), null, []).accountsPartial({
       maintainer: maintainer.publicKey,
-      bounty: bntyKp.publicKey,
+      bounty: bnty,
       maintainerTokenAccount: maintainerTokenAccount,
       escrowAuthority: escrowAuth,
       escrowTokenAccount: escrowAta,
@@ -1194,18 +1352,18 @@ describe("Octasol Escrow Contract", () => {
       tokenProgram: TOKEN_PROGRAM_ID,
       associatedTokenProgram: ASSOCIATED_TOKEN_PROGRAM_ID,
       rent: SYSVAR_RENT_PUBKEY,
-    }).signers([bntyKp]).rpc();
+    }).rpc();
 
     // Assign initial contributor using maintainer flow
-    await program.methods.assignContributor().accountsPartial({
+    await program.methods.assignContributor(false).accountsPartial({
       maintainer: maintainer.publicKey,
-      bounty: bntyKp.publicKey,
+      bounty: bnty,
       contributor: initialContributor.publicKey,
       systemProgram: SystemProgram.programId,
     }).rpc();
 
     // Verify initial assignment
-    const bountyAfterInitial = await program.account.bounty.fetch(bntyKp.publicKey);
+    const bountyAfterInitial = await program.account.bounty.fetch(bnty);
     assert.equal(bountyAfterInitial.contributor.toString(), initialContributor.publicKey.toString());
     assert.ok(bountyAfterInitial.state.hasOwnProperty('inProgress'), "Bounty should be in progress");
 
@@ -1218,10 +1376,10 @@ describe("Octasol Escrow Contract", () => {
     );
 
     // Admin overrides with new contributor and releases funds
-    await program.methods.adminAssignAndRelease(newBountyId).accountsPartial({
+    await program.methods.adminAssignAndRelease(newBountyId, NO_REASON).accountsPartial({
       admin: admin.publicKey,
       config: configPda,
-      bounty: bntyKp.publicKey,
+      bounty: bnty,
       escrowAuthority: escrowAuth,
       maintainer: maintainer.publicKey,
       contributor: targetContributor.publicKey,
@@ -1249,10 +1407,6103 @@ describe("Octasol Escrow Contract", () => {
 
     // Verify the bounty account is closed (should throw an error when trying to fetch)
     try {
-      await program.account.bounty.fetch(bntyKp.publicKey);
+      await program.account.bounty.fetch(bnty);
       assert.fail("Bounty account should be closed after admin assign and release");
     } catch (error) {
       assert.isOk(error, "Successfully confirmed bounty account is closed");
     }
   });
+  describe("Yield integration", () => {
+    // The mock lending protocol keeps one vault per depositor (the escrow authority)
+    async function mockVault(escrowAuth: PublicKey) {
+      const [vaultAuthority] = anchor.web3.PublicKey.findProgramAddressSync(
+        [Buffer.from("vault"), escrowAuth.toBuffer()],
+        mockProgram.programId
+      );
+      const vault = await createAssociatedTokenAccount(connection, wallet.payer, mint, vaultAuthority, undefined, undefined, undefined, true);
+      return { vaultAuthority, vault };
+    }
+
+    before(async () => {
+      await program.methods
+        .setYieldConfig(true, mockProgram.programId)
+        .accountsPartial({ admin: admin.publicKey, config: configPda })
+        .rpc();
+    });
+
+    after(async () => {
+      await program.methods
+        .setYieldConfig(false, mockProgram.programId)
+        .accountsPartial({ admin: admin.publicKey, config: configPda })
+        .rpc();
+    });
+
+    it("Deposits escrow to yield and withdraws principal plus yield to the treasury", async () => {
+      const { bounty, escrowAuth, escrowAta } = await createBounty();
+      const { vaultAuthority, vault } = await mockVault(escrowAuth);
+
+      await program.methods.depositToYield(BOUNTY_AMOUNT).accountsPartial({
+        admin: admin.publicKey,
+        config: configPda,
+        bounty: bounty,
+        escrowAuthority: escrowAuth,
+        escrowTokenAccount: escrowAta,
+        yieldProgram: mockProgram.programId,
+        tokenProgram: TOKEN_PROGRAM_ID,
+      }).remainingAccounts([
+        { pubkey: vault, isSigner: false, isWritable: true },
+      ]).rpc();
+
+      assert.equal((await getAccount(connection, escrowAta)).amount.toString(), "0");
+      assert.equal((await getAccount(connection, vault)).amount.toString(), BOUNTY_AMOUNT.toString());
+      let bountyAccount = await program.account.bounty.fetch(bounty);
+      assert.equal(bountyAccount.yieldPrincipal.toString(), BOUNTY_AMOUNT.toString());
+
+      // Simulate accrued interest
+      await mintTo(connection, wallet.payer, mint, vault, wallet.payer, 500);
+      const treasuryBefore = (await getAccount(connection, maintainerTokenAccount)).amount;
+
+      await program.methods.withdrawFromYield().accountsPartial({
+        admin: admin.publicKey,
+        config: configPda,
+        bounty: bounty,
+        escrowAuthority: escrowAuth,
+        escrowTokenAccount: escrowAta,
+        treasuryTokenAccount: maintainerTokenAccount, // treasury defaults to the admin wallet
+        yieldProgram: mockProgram.programId,
+        tokenProgram: TOKEN_PROGRAM_ID,
+      }).remainingAccounts([
+        { pubkey: vaultAuthority, isSigner: false, isWritable: false },
+        { pubkey: vault, isSigner: false, isWritable: true },
+      ]).rpc();
+
+      assert.equal((await getAccount(connection, escrowAta)).amount.toString(), BOUNTY_AMOUNT.toString());
+      const treasuryAfter = (await getAccount(connection, maintainerTokenAccount)).amount;
+      assert.equal((treasuryAfter - treasuryBefore).toString(), "500");
+      bountyAccount = await program.account.bounty.fetch(bounty);
+      assert.equal(bountyAccount.yieldPrincipal.toString(), "0");
+    });
+
+    it("Blocks settlement while principal is deployed to yield", async () => {
+      const { bounty, bountyId: id, escrowAuth, escrowAta } = await createBounty();
+      const { vault } = await mockVault(escrowAuth);
+      const target = anchor.web3.Keypair.generate();
+      const targetAta = await createAssociatedTokenAccount(connection, wallet.payer, mint, target.publicKey);
+
+      await program.methods.depositToYield(BOUNTY_AMOUNT).accountsPartial({
+        admin: admin.publicKey,
+        config: configPda,
+        bounty: bounty,
+        escrowAuthority: escrowAuth,
+        escrowTokenAccount: escrowAta,
+        yieldProgram: mockProgram.programId,
+        tokenProgram: TOKEN_PROGRAM_ID,
+      }).remainingAccounts([
+        { pubkey: vault, isSigner: false, isWritable: true },
+      ]).rpc();
+
+      try {
+        await program.methods.adminAssignAndRelease(id, NO_REASON).accountsPartial({
+          admin: admin.publicKey,
+          config: configPda,
+          bounty: bounty,
+          escrowAuthority: escrowAuth,
+          maintainer: maintainer.publicKey,
+          contributor: target.publicKey,
+          contributorTokenAccount: targetAta,
+          escrowTokenAccount: escrowAta,
+          tokenProgram: TOKEN_PROGRAM_ID,
+          systemProgram: SystemProgram.programId,
+          associatedTokenProgram: ASSOCIATED_TOKEN_PROGRAM_ID,
+        }).rpc();
+        assert.fail("Expected YieldNotWithdrawn");
+      } catch (e) {
+        expectAnchorErrorCode(e, "YieldNotWithdrawn");
+      }
+    });
+
+    it("Refuses to withdraw when the protocol returns less than principal", async () => {
+      const { bounty, escrowAuth, escrowAta } = await createBounty();
+      const { vaultAuthority, vault } = await mockVault(escrowAuth);
+
+      await program.methods.depositToYield(BOUNTY_AMOUNT).accountsPartial({
+        admin: admin.publicKey,
+        config: configPda,
+        bounty: bounty,
+        escrowAuthority: escrowAuth,
+        escrowTokenAccount: escrowAta,
+        yieldProgram: mockProgram.programId,
+        tokenProgram: TOKEN_PROGRAM_ID,
+      }).remainingAccounts([
+        { pubkey: vault, isSigner: false, isWritable: true },
+      ]).rpc();
+
+      await mockProgram.methods.simulateLoss(new anchor.BN(1)).accountsPartial({
+        depositor: escrowAuth,
+        vaultAuthority,
+        vault,
+        sink: maintainerTokenAccount,
+        tokenProgram: TOKEN_PROGRAM_ID,
+      }).rpc();
+
+      try {
+        await program.methods.withdrawFromYield().accountsPartial({
+          admin: admin.publicKey,
+          config: configPda,
+          bounty: bounty,
+          escrowAuthority: escrowAuth,
+          escrowTokenAccount: escrowAta,
+          treasuryTokenAccount: maintainerTokenAccount,
+          yieldProgram: mockProgram.programId,
+          tokenProgram: TOKEN_PROGRAM_ID,
+        }).remainingAccounts([
+          { pubkey: vaultAuthority, isSigner: false, isWritable: false },
+          { pubkey: vault, isSigner: false, isWritable: true },
+        ]).rpc();
+        assert.fail("Expected YieldPrincipalShortfall");
+      } catch (e) {
+        expectAnchorErrorCode(e, "YieldPrincipalShortfall");
+      }
+
+      // Principal is still tracked so it stays recoverable
+      const bountyAccount = await program.account.bounty.fetch(bounty);
+      assert.equal(bountyAccount.yieldPrincipal.toString(), BOUNTY_AMOUNT.toString());
+    });
+
+    it("Rejects switching yield program while principal is deployed", async () => {
+      try {
+        await program.methods
+          .setYieldConfig(true, anchor.web3.Keypair.generate().publicKey)
+          .accountsPartial({ admin: admin.publicKey, config: configPda })
+          .rpc();
+        assert.fail("Expected YieldPositionsOpen");
+      } catch (e) {
+        expectAnchorErrorCode(e, "YieldPositionsOpen");
+      }
+    });
+  });
+
+  it("Cancel refunds only to the bounty's recorded maintainer", async () => {
+    const { bounty, escrowAuth, escrowAta } = await createBounty();
+    const attacker = anchor.web3.Keypair.generate();
+    const attackerAta = await createAssociatedTokenAccount(connection, wallet.payer, mint, attacker.publicKey);
+
+    try {
+      await program.methods.cancelBounty(null, NO_REASON).accountsPartial({
+        admin: admin.publicKey,
+        config: configPda,
+        bounty: bounty,
+        escrowAuthority: escrowAuth,
+        maintainer: attacker.publicKey,
+        maintainerTokenAccount: attackerAta,
+        escrowTokenAccount: escrowAta,
+        systemProgram: SystemProgram.programId,
+        tokenProgram: TOKEN_PROGRAM_ID,
+        associatedTokenProgram: ASSOCIATED_TOKEN_PROGRAM_ID,
+        rent: SYSVAR_RENT_PUBKEY,
+      }).rpc();
+      assert.fail("Expected InvalidTokenAccount");
+    } catch (e) {
+      expectAnchorErrorCode(e, "InvalidTokenAccount");
+    }
+
+    assert.equal((await getAccount(connection, attackerAta)).amount.toString(), "0");
+    assert.equal((await getAccount(connection, escrowAta)).amount.toString(), BOUNTY_AMOUNT.toString());
+  });
+
+  it("Stores the bounty priority and emits it in BountyCreated", async () => {
+    const { bounty, bountyId: id, signature } = await createBounty({ priority: 200 });
+
+    const bountyAccount = await program.account.bounty.fetch(bounty);
+    assert.equal(bountyAccount.priority, 200);
+
+    const created = (await fetchEvents(signature)).find((e) => e.name === "bountyCreated");
+    assert.ok(created, "BountyCreated should be emitted");
+    assert.equal(created.data.bountyId.toString(), id.toString());
+    assert.equal(created.data.priority, 200);
+  });
+
+  describe("Token-2022 transfer hooks", () => {
+    let hookMint: PublicKey;
+    let hookMaintainerAta: PublicKey;
+
+    before(async () => {
+      // Mint with a transfer-hook extension (no hook program, so transfers don't CPI anywhere)
+      const mintKp = anchor.web3.Keypair.generate();
+      const mintLen = getMintLen([ExtensionType.TransferHook]);
+      const lamports = await connection.getMinimumBalanceForRentExemption(mintLen);
+      const tx = new Transaction().add(
+        SystemProgram.createAccount({
+          fromPubkey: wallet.publicKey,
+          newAccountPubkey: mintKp.publicKey,
+          space: mintLen,
+          lamports,
+          programId: TOKEN_2022_PROGRAM_ID,
+        }),
+        createInitializeTransferHookInstruction(mintKp.publicKey, wallet.publicKey, PublicKey.default, TOKEN_2022_PROGRAM_ID),
+        createInitializeMintInstruction(mintKp.publicKey, 6, wallet.publicKey, null, TOKEN_2022_PROGRAM_ID),
+      );
+      await sendAndConfirmTransaction(connection, tx, [wallet.payer, mintKp]);
+      hookMint = mintKp.publicKey;
+
+      hookMaintainerAta = await createAssociatedTokenAccount(connection, wallet.payer, hookMint, maintainer.publicKey, undefined, TOKEN_2022_PROGRAM_ID);
+      await mintTo(connection, wallet.payer, hookMint, hookMaintainerAta, wallet.payer, 1000000, [], undefined, TOKEN_2022_PROGRAM_ID);
+    });
+
+    async function releaseHookBounty() {
+      const id = generateBountyId();
+      const bounty = bountyAddress(id);
+      const [escrowAuth] = anchor.web3.PublicKey.findProgramAddressSync(
+        [Buffer.from("escrow_auth"), bounty.toBuffer()],
+        program.programId
+      );
+      const escrowAta = await getAssociatedTokenAddress(hookMint, escrowAuth, true, TOKEN_2022_PROGRAM_ID);
+      await program.methods.initializeBounty(id, BOUNTY_AMOUNT, 0, futureDeadline(), null, []).accountsPartial({
+        maintainer: maintainer.publicKey,
+        bounty: bounty,
+        maintainerTokenAccount: hookMaintainerAta,
+        escrowAuthority: escrowAuth,
+        escrowTokenAccount: escrowAta,
+        mint: hookMint,
+        systemProgram: SystemProgram.programId,
+        tokenProgram: TOKEN_2022_PROGRAM_ID,
+        associatedTokenProgram: ASSOCIATED_TOKEN_PROGRAM_ID,
+        rent: SYSVAR_RENT_PUBKEY,
+      }).rpc();
+
+      const target = anchor.web3.Keypair.generate();
+      const targetAta = await createAssociatedTokenAccount(connection, wallet.payer, hookMint, target.publicKey, undefined, TOKEN_2022_PROGRAM_ID);
+      await program.methods.adminAssignAndRelease(id, NO_REASON).accountsPartial({
+        admin: admin.publicKey,
+        config: configPda,
+        bounty: bounty,
+        mint: hookMint,
+        escrowAuthority: escrowAuth,
+        maintainer: maintainer.publicKey,
+        contributor: target.publicKey,
+        contributorTokenAccount: targetAta,
+        escrowTokenAccount: escrowAta,
+        tokenProgram: TOKEN_2022_PROGRAM_ID,
+        systemProgram: SystemProgram.programId,
+        associatedTokenProgram: ASSOCIATED_TOKEN_PROGRAM_ID,
+      }).rpc();
+      return targetAta;
+    }
+
+    it("Rejects completion to a hook-enabled mint while hooks are disallowed", async () => {
+      try {
+        await releaseHookBounty();
+        assert.fail("Expected TransferHookNotAllowed");
+      } catch (e) {
+        expectAnchorErrorCode(e, "TransferHookNotAllowed");
+      }
+    });
+
+    it("Accepts completion to a hook-enabled mint once hooks are allowed", async () => {
+      await program.methods.setAllowTransferHooks(true).accountsPartial({ admin: admin.publicKey, config: configPda }).rpc();
+      try {
+        const targetAta = await releaseHookBounty();
+        const info = await getAccount(connection, targetAta, undefined, TOKEN_2022_PROGRAM_ID);
+        assert.equal(info.amount.toString(), BOUNTY_AMOUNT.toString());
+      } finally {
+        await program.methods.setAllowTransferHooks(false).accountsPartial({ admin: admin.publicKey, config: configPda }).rpc();
+      }
+    });
+  });
+
+  it("Reclaims an orphaned escrow ATA to the treasury", async () => {
+    // An escrow ATA left behind for a bounty account that does not exist
+    const goneBounty = anchor.web3.Keypair.generate();
+    const [escrowAuth] = anchor.web3.PublicKey.findProgramAddressSync(
+      [Buffer.from("escrow_auth"), goneBounty.publicKey.toBuffer()],
+      program.programId
+    );
+    const orphanAta = await createAssociatedTokenAccount(connection, wallet.payer, mint, escrowAuth, undefined, undefined, undefined, true);
+    await mintTo(connection, wallet.payer, mint, orphanAta, wallet.payer, 250);
+
+    const treasuryBefore = (await getAccount(connection, maintainerTokenAccount)).amount;
+
+    await program.methods.reclaimOrphanEscrow().accountsPartial({
+      admin: admin.publicKey,
+      config: configPda,
+      bounty: goneBounty.publicKey,
+      escrowAuthority: escrowAuth,
+      escrowTokenAccount: orphanAta,
+      mint,
+      treasuryTokenAccount: maintainerTokenAccount, // treasury defaults to the admin wallet
+      treasury: admin.publicKey,
+      tokenProgram: TOKEN_PROGRAM_ID,
+    }).rpc();
+
+    const treasuryAfter = (await getAccount(connection, maintainerTokenAccount)).amount;
+    assert.equal((treasuryAfter - treasuryBefore).toString(), "250");
+    assert.isNull(await connection.getAccountInfo(orphanAta), "Orphan escrow should be closed");
+  });
+
+  it("Refuses to reclaim the escrow of a live bounty", async () => {
+    const { bounty, escrowAuth, escrowAta } = await createBounty();
+
+    try {
+      await program.methods.reclaimOrphanEscrow().accountsPartial({
+        admin: admin.publicKey,
+        config: configPda,
+        bounty: bounty,
+        escrowAuthority: escrowAuth,
+        escrowTokenAccount: escrowAta,
+        mint,
+        treasuryTokenAccount: maintainerTokenAccount,
+        treasury: admin.publicKey,
+        tokenProgram: TOKEN_PROGRAM_ID,
+      }).rpc();
+      assert.fail("Expected EscrowNotOrphaned");
+    } catch (e) {
+      expectAnchorErrorCode(e, "EscrowNotOrphaned");
+    }
+  });
+
+  it("Admin cancel emits the AdminCancel reason code", async () => {
+    const { bounty, bountyId: id, escrowAuth, escrowAta } = await createBounty();
+
+    const signature = await program.methods.cancelBounty(null, NO_REASON).accountsPartial({
+      admin: admin.publicKey,
+      config: configPda,
+      bounty: bounty,
+      escrowAuthority: escrowAuth,
+      maintainer: maintainer.publicKey,
+      maintainerTokenAccount: maintainerTokenAccount,
+      escrowTokenAccount: escrowAta,
+      systemProgram: SystemProgram.programId,
+      tokenProgram: TOKEN_PROGRAM_ID,
+      associatedTokenProgram: ASSOCIATED_TOKEN_PROGRAM_ID,
+      rent: SYSVAR_RENT_PUBKEY,
+    }).rpc();
+
+    const cancelled = (await fetchEvents(signature)).find((e) => e.name === "bountyCancelled");
+    assert.ok(cancelled, "BountyCancelled should be emitted");
+    assert.equal(cancelled.data.bountyId.toString(), id.toString());
+    assert.equal(cancelled.data.reason, CANCEL_REASON.adminCancel);
+  });
+
+  it("Splits the completion fee between the treasury and staker vaults", async () => {
+    const treasuryOwner = anchor.web3.Keypair.generate();
+    const stakerOwner = anchor.web3.Keypair.generate();
+    const treasuryAta = await createAssociatedTokenAccount(connection, wallet.payer, mint, treasuryOwner.publicKey);
+    const stakerAta = await createAssociatedTokenAccount(connection, wallet.payer, mint, stakerOwner.publicKey);
+
+    await program.methods.setFee(1000, 7000).accountsPartial({ admin: admin.publicKey, config: configPda }).rpc();
+    await program.methods
+      .setFeeRecipients(treasuryOwner.publicKey, stakerOwner.publicKey)
+      .accountsPartial({ admin: admin.publicKey, config: configPda })
+      .rpc();
+
+    try {
+      const { bounty, bountyId: id, escrowAuth, escrowAta } = await createBounty();
+      const target = anchor.web3.Keypair.generate();
+      const targetAta = await createAssociatedTokenAccount(connection, wallet.payer, mint, target.publicKey);
+
+      await program.methods.adminAssignAndRelease(id, NO_REASON).accountsPartial({
+        admin: admin.publicKey,
+        config: configPda,
+        bounty: bounty,
+        mint,
+        escrowAuthority: escrowAuth,
+        maintainer: maintainer.publicKey,
+        contributor: target.publicKey,
+        contributorTokenAccount: targetAta,
+        escrowTokenAccount: escrowAta,
+        treasuryTokenAccount: treasuryAta,
+        stakerTokenAccount: stakerAta,
+        tokenProgram: TOKEN_PROGRAM_ID,
+        systemProgram: SystemProgram.programId,
+        associatedTokenProgram: ASSOCIATED_TOKEN_PROGRAM_ID,
+      }).rpc();
+
+      // 10% of 10_000 = 1_000 fee, split 70/30
+      assert.equal((await getAccount(connection, targetAta)).amount.toString(), "9000");
+      assert.equal((await getAccount(connection, treasuryAta)).amount.toString(), "700");
+      assert.equal((await getAccount(connection, stakerAta)).amount.toString(), "300");
+    } finally {
+      await program.methods.setFee(0, 10000).accountsPartial({ admin: admin.publicKey, config: configPda }).rpc();
+      await program.methods
+        .setFeeRecipients(admin.publicKey, admin.publicKey)
+        .accountsPartial({ admin: admin.publicKey, config: configPda })
+        .rpc();
+    }
+  });
+
+  it("Rejects fee basis points above 10000", async () => {
+    try {
+      await program.methods.setFee(10001, 10000).accountsPartial({ admin: admin.publicKey, config: configPda }).rpc();
+      assert.fail("Expected InvalidFeeBps");
+    } catch (e) {
+      expectAnchorErrorCode(e, "InvalidFeeBps");
+    }
+  });
+
+  describe("Merkle allowlist assignment", () => {
+    const invited = [0, 1, 2, 3].map(() => anchor.web3.Keypair.generate());
+    const tree = merkleTree(invited.map((k) => k.publicKey));
+
+    async function allowlistedBounty() {
+      const created = await createBounty();
+      await program.methods
+        .setContributorMerkleRoot(tree.root)
+        .accountsPartial({ maintainer: maintainer.publicKey, bounty: created.bounty })
+        .rpc();
+      return created;
+    }
+
+    it("Assigns an invited contributor with a valid proof", async () => {
+      const { bounty, bountyId: id } = await allowlistedBounty();
+      const claimant = invited[2];
+
+      await program.methods
+        .assignWithProof(id, tree.proof(2))
+        .accountsPartial({ contributor: claimant.publicKey, bounty: bounty })
+        .signers([claimant])
+        .rpc();
+
+      const bountyAccount = await program.account.bounty.fetch(bounty);
+      assert.ok(bountyAccount.contributor.equals(claimant.publicKey));
+      assert.ok(bountyAccount.state.hasOwnProperty("inProgress"));
+    });
+
+    it("Rejects a contributor with an invalid proof", async () => {
+      const { bounty, bountyId: id } = await allowlistedBounty();
+      const outsider = anchor.web3.Keypair.generate();
+
+      try {
+        await program.methods
+          .assignWithProof(id, tree.proof(1))
+          .accountsPartial({ contributor: outsider.publicKey, bounty: bounty })
+          .signers([outsider])
+          .rpc();
+        assert.fail("Expected InvalidMerkleProof");
+      } catch (e) {
+        expectAnchorErrorCode(e, "InvalidMerkleProof");
+      }
+    });
+
+    it("Rejects the maintainer even with a valid proof", async () => {
+      const created = await createBounty();
+      const selfTree = merkleTree([maintainer.publicKey, invited[0].publicKey]);
+      await program.methods
+        .setContributorMerkleRoot(selfTree.root)
+        .accountsPartial({ maintainer: maintainer.publicKey, bounty: created.bounty })
+        .rpc();
+
+      try {
+        await program.methods
+          .assignWithProof(created.bountyId, selfTree.proof(0))
+          .accountsPartial({ contributor: maintainer.publicKey, bounty: created.bounty })
+          .rpc();
+        assert.fail("Expected ContributorCannotBeMaintainer");
+      } catch (e) {
+        expectAnchorErrorCode(e, "ContributorCannotBeMaintainer");
+      }
+    });
+  });
+
+  describe("Contributor ratings", () => {
+    const rated = anchor.web3.Keypair.generate();
+    let ratedAta: PublicKey;
+
+    before(async () => {
+      ratedAta = await createAssociatedTokenAccount(connection, wallet.payer, mint, rated.publicKey);
+    });
+
+    async function completeWithRating(rating: number) {
+      const { bounty, bountyId: id, escrowAuth, escrowAta } = await createBounty();
+      await program.methods.assignContributor(false).accountsPartial({
+        maintainer: maintainer.publicKey,
+        bounty: bounty,
+        contributor: rated.publicKey,
+        systemProgram: SystemProgram.programId,
+      }).rpc();
+
+      return program.methods.completeBounty(id, rating, null).accountsPartial({
+        bounty: bounty,
+        mint,
+        escrowAuthority: escrowAuth,
+        maintainer: maintainer.publicKey,
+        contributor: rated.publicKey,
+        config: configPda,
+        admin: admin.publicKey,
+        contributorTokenAccount: ratedAta,
+        escrowTokenAccount: escrowAta,
+        tokenProgram: TOKEN_PROGRAM_ID,
+        systemProgram: SystemProgram.programId,
+        associatedTokenProgram: ASSOCIATED_TOKEN_PROGRAM_ID,
+      }).rpc();
+    }
+
+    it("Accumulates the contributor's average rating across completions", async () => {
+      const first = await completeWithRating(5);
+      const ratedEvent = (await fetchEvents(first)).find((e) => e.name === "contributorRated");
+      assert.ok(ratedEvent, "ContributorRated should be emitted");
+      assert.equal(ratedEvent.data.rating, 5);
+
+      await completeWithRating(4);
+
+      const [statsPda] = anchor.web3.PublicKey.findProgramAddressSync(
+        [Buffer.from("user_stats"), rated.publicKey.toBuffer()],
+        program.programId
+      );
+      const stats = await program.account.userStats.fetch(statsPda);
+      assert.equal(stats.ratingCount.toString(), "2");
+      assert.equal(stats.ratingSum.toString(), "9");
+      assert.equal(stats.averageRatingX100, 450);
+      assert.equal(stats.bountiesCompleted.toString(), "2");
+    });
+
+    it("Rejects ratings outside 1-5", async () => {
+      try {
+        await completeWithRating(6);
+        assert.fail("Expected InvalidRating");
+      } catch (e) {
+        expectAnchorErrorCode(e, "InvalidRating");
+      }
+    });
+  });
+
+  describe("Deadline-based fee discount", () => {
+    before(async () => {
+      await program.methods.setFee(1000, 10000).accountsPartial({ admin: admin.publicKey, config: configPda }).rpc();
+      await program.methods.setFeeDiscount(5000).accountsPartial({ admin: admin.publicKey, config: configPda }).rpc();
+    });
+
+    after(async () => {
+      await program.methods.setFee(0, 10000).accountsPartial({ admin: admin.publicKey, config: configPda }).rpc();
+      await program.methods.setFeeDiscount(0).accountsPartial({ admin: admin.publicKey, config: configPda }).rpc();
+    });
+
+    async function releasedFee(created) {
+      const { signature } = await adminRelease(created, { treasuryTokenAccount: maintainerTokenAccount });
+      const completed = (await fetchEvents(signature)).find((e) => e.name === "bountyCompleted");
+      return completed.data.fee.toNumber();
+    }
+
+    it("Charges a reduced fee when completing well before the deadline", async () => {
+      const fee = await releasedFee(await createBounty({ deadline: futureDeadline(30 * 86400) }));
+      // Full fee is 1_000; nearly the whole window remains so close to half is waived
+      assert.isBelow(fee, 1000);
+      assert.isAtMost(fee, 510);
+    });
+
+    it("Charges the full fee at the deadline", async () => {
+      const created = await createBounty({ deadline: futureDeadline(2) });
+      await new Promise((resolve) => setTimeout(resolve, 4000));
+      assert.equal(await releasedFee(created), 1000);
+    });
+  });
+
+  describe("Bounty mint migration", () => {
+    let newMint: PublicKey;
+    let newMaintainerAta: PublicKey;
+
+    before(async () => {
+      newMint = await createMint(connection, wallet.payer, wallet.publicKey, null, 6);
+      newMaintainerAta = await createAssociatedTokenAccount(connection, wallet.payer, newMint, maintainer.publicKey);
+      await mintTo(connection, wallet.payer, newMint, newMaintainerAta, wallet.payer, 1000000);
+    });
+
+    async function migrate(created, newAmount: anchor.BN) {
+      const newEscrowAta = await getAssociatedTokenAddress(newMint, created.escrowAuth, true);
+      const signature = await program.methods.migrateBountyMint(created.bountyId, newAmount).accountsPartial({
+        maintainer: maintainer.publicKey,
+        bounty: created.bounty,
+        escrowAuthority: created.escrowAuth,
+        mint,
+        escrowTokenAccount: created.escrowAta,
+        maintainerTokenAccount: maintainerTokenAccount,
+        newMint,
+        newEscrowTokenAccount: newEscrowAta,
+        newMaintainerTokenAccount: newMaintainerAta,
+        systemProgram: SystemProgram.programId,
+        tokenProgram: TOKEN_PROGRAM_ID,
+        associatedTokenProgram: ASSOCIATED_TOKEN_PROGRAM_ID,
+      }).rpc();
+      return { signature, newEscrowAta };
+    }
+
+    it("Refunds the old escrow and funds a new one under the new mint", async () => {
+      const created = await createBounty();
+      const before = await getAccount(connection, maintainerTokenAccount);
+
+      const { signature, newEscrowAta } = await migrate(created, new anchor.BN(7000));
+
+      const after = await getAccount(connection, maintainerTokenAccount);
+      assert.equal(Number(after.amount) - Number(before.amount), BOUNTY_AMOUNT.toNumber());
+      assert.isNull(await connection.getAccountInfo(created.escrowAta), "Old escrow should be closed");
+      assert.equal(Number((await getAccount(connection, newEscrowAta)).amount), 7000);
+
+      const bounty = await program.account.bounty.fetch(created.bounty);
+      assert.ok(bounty.mint.equals(newMint));
+      assert.equal(bounty.amount.toNumber(), 7000);
+      assert.equal(bounty.bountyId.toString(), created.bountyId.toString());
+
+      const migrated = (await fetchEvents(signature)).find((e) => e.name === "bountyMintMigrated");
+      assert.ok(migrated, "BountyMintMigrated should be emitted");
+      assert.equal(migrated.data.refundedAmount.toNumber(), BOUNTY_AMOUNT.toNumber());
+    });
+
+    it("Rejects migrating a bounty the caller does not maintain", async () => {
+      const created = await createBounty();
+      const stranger = anchor.web3.Keypair.generate();
+      await connection.confirmTransaction(
+        await connection.requestAirdrop(stranger.publicKey, anchor.web3.LAMPORTS_PER_SOL),
+        "confirmed"
+      );
+      try {
+        await program.methods.migrateBountyMint(created.bountyId, new anchor.BN(1)).accountsPartial({
+          maintainer: stranger.publicKey,
+          bounty: created.bounty,
+          escrowAuthority: created.escrowAuth,
+          mint,
+          escrowTokenAccount: created.escrowAta,
+          maintainerTokenAccount: maintainerTokenAccount,
+          newMint,
+          newEscrowTokenAccount: await getAssociatedTokenAddress(newMint, created.escrowAuth, true),
+          newMaintainerTokenAccount: newMaintainerAta,
+          systemProgram: SystemProgram.programId,
+          tokenProgram: TOKEN_PROGRAM_ID,
+          associatedTokenProgram: ASSOCIATED_TOKEN_PROGRAM_ID,
+        }).signers([stranger]).rpc();
+        assert.fail("Expected has_one violation");
+      } catch (e) {
+        expectAnchorErrorCode(e, "ConstraintHasOne");
+      }
+    });
+  });
+
+  describe("Mint invariant on fund-moving paths", () => {
+    const payee = anchor.web3.Keypair.generate();
+    let otherMint: PublicKey;
+    let payeeOtherAta: PublicKey;
+    let maintainerOtherAta: PublicKey;
+
+    before(async () => {
+      otherMint = await createMint(connection, wallet.payer, wallet.publicKey, null, 6);
+      payeeOtherAta = await createAssociatedTokenAccount(connection, wallet.payer, otherMint, payee.publicKey);
+      maintainerOtherAta = await createAssociatedTokenAccount(connection, wallet.payer, otherMint, maintainer.publicKey);
+    });
+
+    async function assignedBounty() {
+      const created = await createBounty();
+      await program.methods.assignContributor(false).accountsPartial({
+        maintainer: maintainer.publicKey,
+        bounty: created.bounty,
+        contributor: payee.publicKey,
+        systemProgram: SystemProgram.programId,
+      }).rpc();
+      return created;
+    }
+
+    it("Rejects completion paying out to an account of another mint", async () => {
+      const created = await assignedBounty();
+      try {
+        await program.methods.completeBounty(created.bountyId, 5, null).accountsPartial({
+          bounty: created.bounty,
+          mint,
+          escrowAuthority: created.escrowAuth,
+          maintainer: maintainer.publicKey,
+          contributor: payee.publicKey,
+          config: configPda,
+          admin: admin.publicKey,
+          contributorTokenAccount: payeeOtherAta,
+          escrowTokenAccount: created.escrowAta,
+          tokenProgram: TOKEN_PROGRAM_ID,
+          systemProgram: SystemProgram.programId,
+          associatedTokenProgram: ASSOCIATED_TOKEN_PROGRAM_ID,
+        }).rpc();
+        assert.fail("Expected InvalidTokenAccount");
+      } catch (e) {
+        // The payout account must be the contributor's ATA for the bounty mint
+        expectAnchorErrorCode(e, "InvalidTokenAccount");
+      }
+    });
+
+    it("Rejects cancellation refunding to an account of another mint", async () => {
+      const created = await assignedBounty();
+      try {
+        await program.methods.cancelBounty(null, NO_REASON).accountsPartial({
+          admin: admin.publicKey,
+          config: configPda,
+          bounty: created.bounty,
+          mint,
+          escrowAuthority: created.escrowAuth,
+          maintainer: maintainer.publicKey,
+          maintainerTokenAccount: maintainerOtherAta,
+          escrowTokenAccount: created.escrowAta,
+          systemProgram: SystemProgram.programId,
+          tokenProgram: TOKEN_PROGRAM_ID,
+          associatedTokenProgram: ASSOCIATED_TOKEN_PROGRAM_ID,
+          rent: SYSVAR_RENT_PUBKEY,
+        }).rpc();
+        assert.fail("Expected ConstraintTokenMint");
+      } catch (e) {
+        // The refund account must be the maintainer's ATA for the bounty mint
+        expectAnchorErrorCode(e, "ConstraintTokenMint");
+      }
+    });
+
+    it("Rejects an admin release against a mint other than the bounty's", async () => {
+      const created = await createBounty();
+      try {
+        await adminRelease(created, { mint: otherMint });
+        assert.fail("Expected InvalidMint");
+      } catch (e) {
+        expectAnchorErrorCode(e, "InvalidMint");
+      }
+    });
+
+    it("Rejects a mint migration refunding to an account of another mint", async () => {
+      const created = await createBounty();
+      const newMint = await createMint(connection, wallet.payer, wallet.publicKey, null, 6);
+      const newMaintainerAta = await createAssociatedTokenAccount(connection, wallet.payer, newMint, maintainer.publicKey);
+      try {
+        await program.methods.migrateBountyMint(created.bountyId, new anchor.BN(1)).accountsPartial({
+          maintainer: maintainer.publicKey,
+          bounty: created.bounty,
+          escrowAuthority: created.escrowAuth,
+          mint,
+          escrowTokenAccount: created.escrowAta,
+          maintainerTokenAccount: maintainerOtherAta,
+          newMint,
+          newEscrowTokenAccount: await getAssociatedTokenAddress(newMint, created.escrowAuth, true),
+          newMaintainerTokenAccount: newMaintainerAta,
+          systemProgram: SystemProgram.programId,
+          tokenProgram: TOKEN_PROGRAM_ID,
+          associatedTokenProgram: ASSOCIATED_TOKEN_PROGRAM_ID,
+        }).rpc();
+        assert.fail("Expected InvalidMint");
+      } catch (e) {
+        expectAnchorErrorCode(e, "InvalidMint");
+      }
+    });
+  });
+
+  describe("Applicant list", () => {
+    const applicants = [0, 1, 2].map(() => anchor.web3.Keypair.generate());
+
+    before(async () => {
+      for (const kp of applicants) {
+        await connection.confirmTransaction(
+          await connection.requestAirdrop(kp.publicKey, anchor.web3.LAMPORTS_PER_SOL),
+          "confirmed"
+        );
+      }
+    });
+
+    function applicantListPda(bounty: PublicKey) {
+      return anchor.web3.PublicKey.findProgramAddressSync(
+        [Buffer.from("applicants"), bounty.toBuffer()],
+        program.programId
+      )[0];
+    }
+
+    async function apply(bounty: PublicKey, kp: Keypair) {
+      return program.methods.applyToBounty().accountsPartial({
+        contributor: kp.publicKey,
+        bounty,
+        applicantList: applicantListPda(bounty),
+        systemProgram: SystemProgram.programId,
+      }).signers([kp]).rpc();
+    }
+
+    it("Records applicants and assigns the one picked by index", async () => {
+      const created = await createBounty();
+      for (const kp of applicants) {
+        await apply(created.bounty, kp);
+      }
+
+      const list = await program.account.applicantList.fetch(applicantListPda(created.bounty));
+      assert.equal(list.applicants.length, 3);
+      assert.ok(list.applicants[1].equals(applicants[1].publicKey));
+
+      await program.methods.assignFromApplicants(1).accountsPartial({
+        maintainer: maintainer.publicKey,
+        bounty: created.bounty,
+        applicantList: applicantListPda(created.bounty),
+      }).rpc();
+
+      const bounty = await program.account.bounty.fetch(created.bounty);
+      assert.ok(bounty.contributor.equals(applicants[1].publicKey));
+      assert.deepEqual(bounty.state, { inProgress: {} });
+    });
+
+    it("Rejects applying twice", async () => {
+      const { bounty } = await createBounty();
+      await apply(bounty, applicants[0]);
+      try {
+        await apply(bounty, applicants[0]);
+        assert.fail("Expected AlreadyApplied");
+      } catch (e) {
+        expectAnchorErrorCode(e, "AlreadyApplied");
+      }
+    });
+
+    it("Rejects an index past the end of the list", async () => {
+      const { bounty } = await createBounty();
+      await apply(bounty, applicants[0]);
+      try {
+        await program.methods.assignFromApplicants(5).accountsPartial({
+          maintainer: maintainer.publicKey,
+          bounty: bounty,
+          applicantList: applicantListPda(bounty),
+        }).rpc();
+        assert.fail("Expected InvalidApplicantIndex");
+      } catch (e) {
+        expectAnchorErrorCode(e, "InvalidApplicantIndex");
+      }
+    });
+
+    it("Rejects picking the maintainer from the applicants", async () => {
+      const { bounty } = await createBounty();
+      await apply(bounty, maintainer);
+      try {
+        await program.methods.assignFromApplicants(0).accountsPartial({
+          maintainer: maintainer.publicKey,
+          bounty: bounty,
+          applicantList: applicantListPda(bounty),
+        }).rpc();
+        assert.fail("Expected ContributorCannotBeMaintainer");
+      } catch (e) {
+        expectAnchorErrorCode(e, "ContributorCannotBeMaintainer");
+      }
+    });
+  });
+
+  describe("Assignment audit trail", () => {
+    it("Flags maintainer assignments as not via admin", async () => {
+      const { bounty } = await createBounty();
+      const assignee = anchor.web3.Keypair.generate();
+      const signature = await program.methods.assignContributor(false).accountsPartial({
+        maintainer: maintainer.publicKey,
+        bounty: bounty,
+        contributor: assignee.publicKey,
+        systemProgram: SystemProgram.programId,
+      }).rpc();
+
+      const assigned = (await fetchEvents(signature)).find((e) => e.name === "contributorAssigned");
+      assert.ok(assigned, "ContributorAssigned should be emitted");
+      assert.isFalse(assigned.data.viaAdmin);
+      assert.ok(assigned.data.assignedBy.equals(maintainer.publicKey));
+    });
+
+    it("Flags admin_assign_and_release assignments as via admin", async () => {
+      const { signature, target } = await adminRelease(await createBounty());
+
+      const assigned = (await fetchEvents(signature)).find((e) => e.name === "contributorAssigned");
+      assert.ok(assigned, "ContributorAssigned should be emitted");
+      assert.isTrue(assigned.data.viaAdmin);
+      assert.ok(assigned.data.assignedBy.equals(admin.publicKey));
+      assert.ok(assigned.data.contributor.equals(target.publicKey));
+    });
+  });
+
+  describe("Partial release", () => {
+    const worker = anchor.web3.Keypair.generate();
+    let workerAta: PublicKey;
+
+    before(async () => {
+      workerAta = await createAssociatedTokenAccount(connection, wallet.payer, mint, worker.publicKey);
+    });
+
+    async function assignedBounty() {
+      const created = await createBounty();
+      await program.methods.assignContributor(false).accountsPartial({
+        maintainer: maintainer.publicKey,
+        bounty: created.bounty,
+        contributor: worker.publicKey,
+        systemProgram: SystemProgram.programId,
+      }).rpc();
+      return created;
+    }
+
+    function releasePartial(created, amount: anchor.BN) {
+      return program.methods.releasePartial(created.bountyId, amount).accountsPartial({
+        maintainer: maintainer.publicKey,
+        bounty: created.bounty,
+        mint,
+        escrowAuthority: created.escrowAuth,
+        contributor: worker.publicKey,
+        config: configPda,
+        contributorTokenAccount: workerAta,
+        escrowTokenAccount: created.escrowAta,
+        tokenProgram: TOKEN_PROGRAM_ID,
+      }).rpc();
+    }
+
+    it("Releases 30% while in progress, then completes the remaining 70%", async () => {
+      const created = await assignedBounty();
+      const start = Number((await getAccount(connection, workerAta)).amount);
+
+      const signature = await releasePartial(created, new anchor.BN(3000));
+      let bounty = await program.account.bounty.fetch(created.bounty);
+      assert.equal(bounty.amount.toNumber(), 7000);
+      assert.deepEqual(bounty.state, { inProgress: {} });
+      assert.equal(Number((await getAccount(connection, workerAta)).amount) - start, 3000);
+
+      const released = (await fetchEvents(signature)).find((e) => e.name === "partialReleased");
+      assert.ok(released, "PartialReleased should be emitted");
+      assert.equal(released.data.remaining.toNumber(), 7000);
+
+      await program.methods.completeBounty(created.bountyId, 5, null).accountsPartial({
+        bounty: created.bounty,
+        mint,
+        escrowAuthority: created.escrowAuth,
+        maintainer: maintainer.publicKey,
+        contributor: worker.publicKey,
+        config: configPda,
+        admin: admin.publicKey,
+        contributorTokenAccount: workerAta,
+        escrowTokenAccount: created.escrowAta,
+        tokenProgram: TOKEN_PROGRAM_ID,
+        systemProgram: SystemProgram.programId,
+        associatedTokenProgram: ASSOCIATED_TOKEN_PROGRAM_ID,
+      }).rpc();
+
+      assert.equal(Number((await getAccount(connection, workerAta)).amount) - start, BOUNTY_AMOUNT.toNumber());
+    });
+
+    it("Rejects releasing the whole remaining amount", async () => {
+      const created = await assignedBounty();
+      try {
+        await releasePartial(created, BOUNTY_AMOUNT);
+        assert.fail("Expected PartialReleaseTooLarge");
+      } catch (e) {
+        expectAnchorErrorCode(e, "PartialReleaseTooLarge");
+      }
+    });
+  });
+
+  describe("Default keeper", () => {
+    const protocolKeeper = anchor.web3.Keypair.generate().publicKey;
+
+    before(async () => {
+      await program.methods.setDefaultKeeper(protocolKeeper).accountsPartial({ admin: admin.publicKey, config: configPda }).rpc();
+    });
+
+    after(async () => {
+      await program.methods.setDefaultKeeper(admin.publicKey).accountsPartial({ admin: admin.publicKey, config: configPda }).rpc();
+    });
+
+    it("Bounties inherit the configured default keeper", async () => {
+      const created = await createBounty();
+      const bounty = await program.account.bounty.fetch(created.bounty);
+      assert.ok(bounty.keeper.equals(protocolKeeper));
+    });
+
+    it("An explicit keeper overrides the default", async () => {
+      const custom = anchor.web3.Keypair.generate().publicKey;
+      const created = await createBounty({ keeper: custom });
+      const bounty = await program.account.bounty.fetch(created.bounty);
+      assert.ok(bounty.keeper.equals(custom));
+    });
+
+    it("Only the admin can change the default keeper", async () => {
+      const outsider = anchor.web3.Keypair.generate();
+      try {
+        await program.methods.setDefaultKeeper(outsider.publicKey)
+          .accountsPartial({ admin: outsider.publicKey, config: configPda })
+          .signers([outsider])
+          .rpc();
+        assert.fail("Expected Unauthorized");
+      } catch (e) {
+        expectAnchorErrorCode(e, "Unauthorized");
+      }
+    });
+  });
+
+  describe("Pre-existing escrow ATA", () => {
+    async function initWithExistingEscrow(prefund: number) {
+      const id = generateBountyId();
+      const bounty = bountyAddress(id);
+      const [escrowAuth] = anchor.web3.PublicKey.findProgramAddressSync(
+        [Buffer.from("escrow_auth"), bounty.toBuffer()],
+        program.programId
+      );
+      const escrowAta = await createAssociatedTokenAccount(
+        connection, wallet.payer, mint, escrowAuth, undefined, TOKEN_PROGRAM_ID, ASSOCIATED_TOKEN_PROGRAM_ID, true
+      );
+      if (prefund > 0) {
+        await mintTo(connection, wallet.payer, mint, escrowAta, wallet.payer, prefund);
+      }
+
+      await program.methods.initializeBounty(id, BOUNTY_AMOUNT, 0, futureDeadline(), null, []).accountsPartial({
+        maintainer: maintainer.publicKey,
+        bounty: bounty,
+        maintainerTokenAccount: maintainerTokenAccount,
+        escrowAuthority: escrowAuth,
+        escrowTokenAccount: escrowAta,
+        mint,
+        systemProgram: SystemProgram.programId,
+        tokenProgram: TOKEN_PROGRAM_ID,
+        associatedTokenProgram: ASSOCIATED_TOKEN_PROGRAM_ID,
+        rent: SYSVAR_RENT_PUBKEY,
+      }).rpc();
+
+      return escrowAta;
+    }
+
+    it("Reuses an existing empty escrow ATA", async () => {
+      const escrowAta = await initWithExistingEscrow(0);
+      assert.equal(Number((await getAccount(connection, escrowAta)).amount), BOUNTY_AMOUNT.toNumber());
+    });
+
+    it("Rejects an existing escrow ATA that already holds funds", async () => {
+      try {
+        await initWithExistingEscrow(1);
+        assert.fail("Expected EscrowNotEmpty");
+      } catch (e) {
+        expectAnchorErrorCode(e, "EscrowNotEmpty");
+      }
+    });
+  });
+
+  describe("Bounty address derivation", () => {
+    it("Matches the client-side findProgramAddress derivation", async () => {
+      const id = generateBountyId();
+      const derived = await program.methods.deriveBountyAddress(maintainer.publicKey, id).view();
+
+      const [expected, bump] = anchor.web3.PublicKey.findProgramAddressSync(
+        [Buffer.from("bounty"), maintainer.publicKey.toBuffer(), id.toArrayLike(Buffer, "le", 8)],
+        program.programId
+      );
+      assert.ok(derived.address.equals(expected));
+      assert.equal(derived.bump, bump);
+    });
+
+    it("Returns the address the bounty is actually created at", async () => {
+      const created = await createBounty();
+      const derived = await program.methods.deriveBountyAddress(maintainer.publicKey, created.bountyId).view();
+
+      assert.ok(derived.address.equals(created.bounty));
+      const bounty = await program.account.bounty.fetch(derived.address);
+      assert.equal(bounty.bountyId.toString(), created.bountyId.toString());
+    });
+  });
+
+  describe("Batched config update", () => {
+    const emptyParams = {
+      feeBps: null,
+      feeTreasuryBps: null,
+      feeDiscountMaxBps: null,
+      minBountyAmount: null,
+      maxBountyAmount: null,
+      paused: null,
+      allowTransferHooks: null,
+      defaultKeeper: null,
+      allowAdminForceOnCreated: null,
+      adminInterventionBps: null,
+    };
+
+    after(async () => {
+      await program.methods
+        .adminUpdateConfig({ ...emptyParams, feeBps: 0, minBountyAmount: new anchor.BN(0), paused: false })
+        .accountsPartial({ admin: admin.publicKey, config: configPda })
+        .rpc();
+    });
+
+    it("Updates two fields at once and leaves the rest unchanged", async () => {
+      const before = await program.account.configState.fetch(configPda);
+
+      const signature = await program.methods
+        .adminUpdateConfig({ ...emptyParams, feeBps: 250, minBountyAmount: new anchor.BN(500) })
+        .accountsPartial({ admin: admin.publicKey, config: configPda })
+        .rpc();
+
+      const after = await program.account.configState.fetch(configPda);
+      assert.equal(after.feeBps, 250);
+      assert.equal(after.minBountyAmount.toNumber(), 500);
+      assert.equal(after.feeTreasuryBps, before.feeTreasuryBps);
+      assert.equal(after.maxBountyAmount.toNumber(), before.maxBountyAmount.toNumber());
+      assert.equal(after.paused, before.paused);
+      assert.ok(after.defaultKeeper.equals(before.defaultKeeper));
+
+      const changed = (await fetchEvents(signature)).find((e) => e.name === "configChanged");
+      assert.ok(changed, "ConfigChanged should be emitted");
+      assert.deepEqual(changed.data.changed, ["fee_bps", "min_bounty_amount"]);
+    });
+
+    it("Enforces the minimum amount and pause on new bounties", async () => {
+      await program.methods
+        .adminUpdateConfig({ ...emptyParams, minBountyAmount: new anchor.BN(BOUNTY_AMOUNT.toNumber() + 1) })
+        .accountsPartial({ admin: admin.publicKey, config: configPda })
+        .rpc();
+      try {
+        await createBounty();
+        assert.fail("Expected AmountBelowMinimum");
+      } catch (e) {
+        expectAnchorErrorCode(e, "AmountBelowMinimum");
+      }
+
+      await program.methods
+        .adminUpdateConfig({ ...emptyParams, minBountyAmount: new anchor.BN(0), paused: true })
+        .accountsPartial({ admin: admin.publicKey, config: configPda })
+        .rpc();
+      try {
+        await createBounty();
+        assert.fail("Expected ProtocolPaused");
+      } catch (e) {
+        expectAnchorErrorCode(e, "ProtocolPaused");
+      }
+    });
+  });
+
+  describe("Mint supply guard", () => {
+    function increaseBounty(created, amount: anchor.BN) {
+      return program.methods.increaseBounty(created.bountyId, amount).accountsPartial({
+        maintainer: maintainer.publicKey,
+        bounty: created.bounty,
+        mint,
+        escrowAuthority: created.escrowAuth,
+        escrowTokenAccount: created.escrowAta,
+        maintainerTokenAccount: maintainerTokenAccount,
+        tokenProgram: TOKEN_PROGRAM_ID,
+      }).rpc();
+    }
+
+    it("Rejects escrowing more than the mint supply", async () => {
+      const supply = (await getMint(connection, mint)).supply;
+      try {
+        await createBounty({ amount: new anchor.BN((supply + 1n).toString()) });
+        assert.fail("Expected AmountExceedsSupply");
+      } catch (e) {
+        expectAnchorErrorCode(e, "AmountExceedsSupply");
+      }
+    });
+
+    it("Tops up a bounty within supply", async () => {
+      const created = await createBounty();
+      await increaseBounty(created, new anchor.BN(500));
+      const bounty = await program.account.bounty.fetch(created.bounty);
+      assert.equal(bounty.amount.toNumber(), BOUNTY_AMOUNT.toNumber() + 500);
+      assert.equal(Number((await getAccount(connection, created.escrowAta)).amount), BOUNTY_AMOUNT.toNumber() + 500);
+    });
+
+    it("Rejects a top-up that would push the bounty past the mint supply", async () => {
+      const created = await createBounty();
+      const supply = (await getMint(connection, mint)).supply;
+      try {
+        await increaseBounty(created, new anchor.BN(supply.toString()));
+        assert.fail("Expected AmountExceedsSupply");
+      } catch (e) {
+        expectAnchorErrorCode(e, "AmountExceedsSupply");
+      }
+    });
+  });
+
+  describe("PDA contributors", () => {
+    // Stand-in for a DAO treasury: a PDA of another program that can never sign
+    const [daoTreasury] = anchor.web3.PublicKey.findProgramAddressSync(
+      [Buffer.from("dao_treasury")],
+      mockProgram.programId
+    );
+    let daoTreasuryAta: PublicKey;
+
+    before(async () => {
+      daoTreasuryAta = await createAssociatedTokenAccount(
+        connection, wallet.payer, mint, daoTreasury, undefined, TOKEN_PROGRAM_ID, ASSOCIATED_TOKEN_PROGRAM_ID, true
+      );
+    });
+
+    it("Settles a completed bounty to a PDA-owned token account", async () => {
+      const created = await createBounty();
+      await program.methods.assignContributor(true).accountsPartial({
+        maintainer: maintainer.publicKey,
+        bounty: created.bounty,
+        contributor: daoTreasury,
+        systemProgram: SystemProgram.programId,
+      }).rpc();
+      assert.isTrue((await program.account.bounty.fetch(created.bounty)).contributorIsPda);
+
+      await program.methods.completeBounty(created.bountyId, 5, null).accountsPartial({
+        bounty: created.bounty,
+        mint,
+        escrowAuthority: created.escrowAuth,
+        maintainer: maintainer.publicKey,
+        contributor: daoTreasury,
+        config: configPda,
+        admin: admin.publicKey,
+        contributorTokenAccount: daoTreasuryAta,
+        escrowTokenAccount: created.escrowAta,
+        tokenProgram: TOKEN_PROGRAM_ID,
+        systemProgram: SystemProgram.programId,
+        associatedTokenProgram: ASSOCIATED_TOKEN_PROGRAM_ID,
+      }).rpc();
+
+      assert.equal(Number((await getAccount(connection, daoTreasuryAta)).amount), BOUNTY_AMOUNT.toNumber());
+    });
+
+    it("Rejects a program-owned contributor unless flagged as a PDA", async () => {
+      const created = await createBounty();
+      try {
+        // The config account is owned by this program, so it is not a wallet
+        await program.methods.assignContributor(false).accountsPartial({
+          maintainer: maintainer.publicKey,
+          bounty: created.bounty,
+          contributor: configPda,
+          systemProgram: SystemProgram.programId,
+        }).rpc();
+        assert.fail("Expected ContributorNotWallet");
+      } catch (e) {
+        expectAnchorErrorCode(e, "ContributorNotWallet");
+      }
+    });
+  });
+
+  describe("Fee epoch stats", () => {
+    before(async () => {
+      await program.methods.setFee(1000, 10000).accountsPartial({ admin: admin.publicKey, config: configPda }).rpc();
+    });
+
+    after(async () => {
+      await program.methods.setFee(0, 10000).accountsPartial({ admin: admin.publicKey, config: configPda }).rpc();
+    });
+
+    function feeStats() {
+      return program.methods.getFeeEpochStats().accountsPartial({ mint }).view();
+    }
+
+    async function waitForNextEpoch(epoch: number) {
+      while ((await connection.getEpochInfo()).epoch <= epoch) {
+        await new Promise((resolve) => setTimeout(resolve, 2000));
+      }
+    }
+
+    it("Rolls collected fees over when the epoch advances", async () => {
+      await adminRelease(await createBounty(), { treasuryTokenAccount: maintainerTokenAccount });
+      const first = await feeStats();
+      assert.isAtLeast(first.feesCollected.toNumber(), 1000);
+
+      await waitForNextEpoch(first.epoch.toNumber());
+      await adminRelease(await createBounty(), { treasuryTokenAccount: maintainerTokenAccount });
+
+      const second = await feeStats();
+      assert.isAbove(second.epoch.toNumber(), first.epoch.toNumber());
+      assert.equal(second.feesCollected.toNumber(), 1000);
+      assert.equal(second.previousEpoch.toNumber(), first.epoch.toNumber());
+      assert.equal(second.previousFeesCollected.toNumber(), first.feesCollected.toNumber());
+    });
+  });
+
+  describe("Create dry run", () => {
+    const emptyParams = {
+      feeBps: null,
+      feeTreasuryBps: null,
+      feeDiscountMaxBps: null,
+      minBountyAmount: null,
+      maxBountyAmount: null,
+      paused: null,
+      allowTransferHooks: null,
+      defaultKeeper: null,
+      allowAdminForceOnCreated: null,
+      adminInterventionBps: null,
+    };
+
+    function updateConfig(params) {
+      return program.methods
+        .adminUpdateConfig({ ...emptyParams, ...params })
+        .accountsPartial({ admin: admin.publicKey, config: configPda })
+        .rpc();
+    }
+
+    function validateCreate(amount: anchor.BN) {
+      return program.methods.validateCreate(amount).accountsPartial({ config: configPda, mint }).view();
+    }
+
+    afterEach(async () => {
+      await updateConfig({ minBountyAmount: new anchor.BN(0), maxBountyAmount: new anchor.BN(0), paused: false });
+    });
+
+    it("Reports ready when every policy passes", async () => {
+      const readiness = await validateCreate(BOUNTY_AMOUNT);
+      assert.isTrue(readiness.ready);
+    });
+
+    it("Reports amounts outside the configured limits", async () => {
+      await updateConfig({ minBountyAmount: new anchor.BN(100), maxBountyAmount: new anchor.BN(1000) });
+
+      const low = await validateCreate(new anchor.BN(10));
+      assert.isFalse(low.ready);
+      assert.isTrue(low.belowMinimum);
+      assert.isFalse(low.aboveMaximum);
+
+      const high = await validateCreate(new anchor.BN(5000));
+      assert.isFalse(high.ready);
+      assert.isTrue(high.aboveMaximum);
+      assert.isFalse(high.belowMinimum);
+    });
+
+    it("Reports a paused protocol", async () => {
+      await updateConfig({ paused: true });
+      const readiness = await validateCreate(BOUNTY_AMOUNT);
+      assert.isFalse(readiness.ready);
+      assert.isTrue(readiness.paused);
+    });
+
+    it("Reports amounts beyond the mint supply", async () => {
+      const supply = (await getMint(connection, mint)).supply;
+      const readiness = await validateCreate(new anchor.BN((supply + 1n).toString()));
+      assert.isFalse(readiness.ready);
+      assert.isTrue(readiness.exceedsSupply);
+    });
+
+    it("Reports the creation policy as admin updates it", async () => {
+      const creationPolicy = () => program.methods.creationPolicy().accountsPartial({ config: configPda, mint }).view();
+
+      let policy = await creationPolicy();
+      assert.isFalse(policy.paused);
+      assert.equal(policy.minBountyAmount.toNumber(), 0);
+      assert.isTrue(policy.mintAllowed);
+
+      await updateConfig({ minBountyAmount: new anchor.BN(100), maxBountyAmount: new anchor.BN(1000), paused: true });
+      await program.methods.setMaxDeadlineDuration(new anchor.BN(86400))
+        .accountsPartial({ admin: admin.publicKey, config: configPda })
+        .rpc();
+      await program.methods.setMintWhitelistEnabled(true)
+        .accountsPartial({ admin: admin.publicKey, config: configPda })
+        .rpc();
+      try {
+        policy = await creationPolicy();
+        assert.isTrue(policy.paused);
+        assert.equal(policy.minBountyAmount.toNumber(), 100);
+        assert.equal(policy.maxBountyAmount.toNumber(), 1000);
+        assert.equal(policy.maxDeadlineDuration.toNumber(), 86400);
+        assert.isTrue(policy.mintWhitelistEnabled);
+        // The mint was never whitelisted
+        assert.isFalse(policy.mintAllowed);
+      } finally {
+        await program.methods.setMintWhitelistEnabled(false)
+          .accountsPartial({ admin: admin.publicKey, config: configPda })
+          .rpc();
+        await program.methods.setMaxDeadlineDuration(new anchor.BN(0))
+          .accountsPartial({ admin: admin.publicKey, config: configPda })
+          .rpc();
+      }
+    });
+  });
+
+  describe("Front-run escrow ATA creation", () => {
+    const attacker = anchor.web3.Keypair.generate();
+    let attackerAta: PublicKey;
+
+    before(async () => {
+      await connection.confirmTransaction(
+        await connection.requestAirdrop(attacker.publicKey, anchor.web3.LAMPORTS_PER_SOL),
+        "confirmed"
+      );
+      attackerAta = await createAssociatedTokenAccount(connection, attacker, mint, attacker.publicKey);
+      await mintTo(connection, wallet.payer, mint, attackerAta, wallet.payer, 10);
+    });
+
+    async function frontRunAndCreate(pollute: boolean) {
+      const id = generateBountyId();
+      const bounty = bountyAddress(id);
+      const [escrowAuth] = anchor.web3.PublicKey.findProgramAddressSync(
+        [Buffer.from("escrow_auth"), bounty.toBuffer()],
+        program.programId
+      );
+      // Attacker derives the bounty address and creates the escrow ATA first
+      const escrowAta = await createAssociatedTokenAccount(
+        connection, attacker, mint, escrowAuth, undefined, TOKEN_PROGRAM_ID, ASSOCIATED_TOKEN_PROGRAM_ID, true
+      );
+      if (pollute) {
+        await transfer(connection, attacker, attackerAta, escrowAta, attacker, 1);
+      }
+
+      await program.methods.initializeBounty(id, BOUNTY_AMOUNT, 0, futureDeadline(), null, []).accountsPartial({
+        maintainer: maintainer.publicKey,
+        bounty: bounty,
+        maintainerTokenAccount: maintainerTokenAccount,
+        escrowAuthority: escrowAuth,
+        escrowTokenAccount: escrowAta,
+        mint,
+        systemProgram: SystemProgram.programId,
+        tokenProgram: TOKEN_PROGRAM_ID,
+        associatedTokenProgram: ASSOCIATED_TOKEN_PROGRAM_ID,
+        rent: SYSVAR_RENT_PUBKEY,
+      }).rpc();
+
+      return escrowAta;
+    }
+
+    it("Still creates the bounty when an attacker pre-created an empty escrow ATA", async () => {
+      const escrowAta = await frontRunAndCreate(false);
+      assert.equal(Number((await getAccount(connection, escrowAta)).amount), BOUNTY_AMOUNT.toNumber());
+    });
+
+    it("Refuses to fund an escrow ATA the attacker seeded with tokens", async () => {
+      try {
+        await frontRunAndCreate(true);
+        assert.fail("Expected EscrowNotEmpty");
+      } catch (e) {
+        expectAnchorErrorCode(e, "EscrowNotEmpty");
+      }
+    });
+  });
+
+  describe("Contributor escrow verification", () => {
+    function verifyFunded(created) {
+      return program.methods.verifyFunded(created.bountyId).accountsPartial({
+        bounty: created.bounty,
+        escrowAuthority: created.escrowAuth,
+        escrowTokenAccount: created.escrowAta,
+      }).view();
+    }
+
+    it("Returns true for a properly funded open bounty", async () => {
+      assert.isTrue(await verifyFunded(await createBounty()));
+    });
+
+    it("Returns false when part of the escrow is missing", async () => {
+      await program.methods
+        .setYieldConfig(true, mockProgram.programId)
+        .accountsPartial({ admin: admin.publicKey, config: configPda })
+        .rpc();
+      try {
+        const created = await createBounty();
+        const [vaultAuthority] = anchor.web3.PublicKey.findProgramAddressSync(
+          [Buffer.from("vault"), created.escrowAuth.toBuffer()],
+          mockProgram.programId
+        );
+        const vault = await createAssociatedTokenAccount(connection, wallet.payer, mint, vaultAuthority, undefined, undefined, undefined, true);
+
+        // Moving part of the escrow out leaves it below bounty.amount
+        await program.methods.depositToYield(new anchor.BN(100)).accountsPartial({
+          admin: admin.publicKey,
+          config: configPda,
+          bounty: created.bounty,
+          escrowAuthority: created.escrowAuth,
+          escrowTokenAccount: created.escrowAta,
+          yieldProgram: mockProgram.programId,
+          tokenProgram: TOKEN_PROGRAM_ID,
+        }).remainingAccounts([
+          { pubkey: vault, isSigner: false, isWritable: true },
+        ]).rpc();
+
+        assert.isFalse(await verifyFunded(created));
+      } finally {
+        await program.methods
+          .setYieldConfig(false, mockProgram.programId)
+          .accountsPartial({ admin: admin.publicKey, config: configPda })
+          .rpc();
+      }
+    });
+  });
+
+  describe("Cancel with expected amount", () => {
+    function cancelExpecting(created, expectedAmount: anchor.BN) {
+      return program.methods.cancelBounty(expectedAmount, NO_REASON).accountsPartial({
+        admin: admin.publicKey,
+        config: configPda,
+        bounty: created.bounty,
+        mint,
+        escrowAuthority: created.escrowAuth,
+        maintainer: maintainer.publicKey,
+        maintainerTokenAccount: maintainerTokenAccount,
+        escrowTokenAccount: created.escrowAta,
+        systemProgram: SystemProgram.programId,
+        tokenProgram: TOKEN_PROGRAM_ID,
+        associatedTokenProgram: ASSOCIATED_TOKEN_PROGRAM_ID,
+        rent: SYSVAR_RENT_PUBKEY,
+      }).rpc();
+    }
+
+    it("Cancels when the expected amount matches", async () => {
+      const created = await createBounty();
+      await cancelExpecting(created, BOUNTY_AMOUNT);
+      assert.isNull(await connection.getAccountInfo(created.bounty), "Bounty should be closed");
+    });
+
+    it("Fails with AmountMismatch when the bounty changed since it was read", async () => {
+      const created = await createBounty();
+      const staleAmount = BOUNTY_AMOUNT;
+      await program.methods.increaseBounty(created.bountyId, new anchor.BN(1)).accountsPartial({
+        maintainer: maintainer.publicKey,
+        bounty: created.bounty,
+        mint,
+        escrowAuthority: created.escrowAuth,
+        escrowTokenAccount: created.escrowAta,
+        maintainerTokenAccount: maintainerTokenAccount,
+        tokenProgram: TOKEN_PROGRAM_ID,
+      }).rpc();
+
+      try {
+        await cancelExpecting(created, staleAmount);
+        assert.fail("Expected AmountMismatch");
+      } catch (e) {
+        expectAnchorErrorCode(e, "AmountMismatch");
+      }
+    });
+  });
+
+  describe("Final bounty snapshot", () => {
+    it("Emits the full final record on completion", async () => {
+      const created = await createBounty({ priority: 7 });
+      const { signature, target } = await adminRelease(created);
+
+      const snapshot = (await fetchEvents(signature)).find((e) => e.name === "bountyFinalSnapshot");
+      assert.ok(snapshot, "BountyFinalSnapshot should be emitted");
+      assert.ok(snapshot.data.bounty.equals(created.bounty));
+      assert.ok(snapshot.data.maintainer.equals(maintainer.publicKey));
+      assert.ok(snapshot.data.contributor.equals(target.publicKey));
+      assert.ok(snapshot.data.mint.equals(mint));
+      assert.equal(snapshot.data.amount.toNumber(), BOUNTY_AMOUNT.toNumber());
+      assert.equal(snapshot.data.bountyId.toString(), created.bountyId.toString());
+      assert.equal(snapshot.data.priority, 7);
+      assert.deepEqual(snapshot.data.state, { completed: {} });
+    });
+
+    it("Emits the full final record on cancellation", async () => {
+      const created = await createBounty();
+      const signature = await program.methods.cancelBounty(null, NO_REASON).accountsPartial({
+        admin: admin.publicKey,
+        config: configPda,
+        bounty: created.bounty,
+        mint,
+        escrowAuthority: created.escrowAuth,
+        maintainer: maintainer.publicKey,
+        maintainerTokenAccount: maintainerTokenAccount,
+        escrowTokenAccount: created.escrowAta,
+        systemProgram: SystemProgram.programId,
+        tokenProgram: TOKEN_PROGRAM_ID,
+        associatedTokenProgram: ASSOCIATED_TOKEN_PROGRAM_ID,
+        rent: SYSVAR_RENT_PUBKEY,
+      }).rpc();
+
+      const snapshot = (await fetchEvents(signature)).find((e) => e.name === "bountyFinalSnapshot");
+      assert.ok(snapshot, "BountyFinalSnapshot should be emitted");
+      assert.deepEqual(snapshot.data.state, { cancelled: {} });
+      assert.isNull(snapshot.data.contributor);
+      assert.equal(snapshot.data.amount.toNumber(), BOUNTY_AMOUNT.toNumber());
+    });
+  });
+
+  describe("Per-mint fee override", () => {
+    before(async () => {
+      await program.methods.setFee(1000, 10000).accountsPartial({ admin: admin.publicKey, config: configPda }).rpc();
+    });
+
+    after(async () => {
+      await program.methods.setFee(0, 10000).accountsPartial({ admin: admin.publicKey, config: configPda }).rpc();
+    });
+
+    async function releasedFee() {
+      const { signature } = await adminRelease(await createBounty(), { treasuryTokenAccount: maintainerTokenAccount });
+      const completed = (await fetchEvents(signature)).find((e) => e.name === "bountyCompleted");
+      return completed.data.fee.toNumber();
+    }
+
+    it("Uses the mint-specific fee when one is set", async () => {
+      await program.methods.setMintFee(300).accountsPartial({ admin: admin.publicKey, config: configPda, mint }).rpc();
+      try {
+        assert.equal(await releasedFee(), 300);
+      } finally {
+        await program.methods.clearMintFee().accountsPartial({ admin: admin.publicKey, config: configPda, mint }).rpc();
+      }
+    });
+
+    it("Falls back to the global fee when no override exists", async () => {
+      assert.equal(await releasedFee(), 1000);
+    });
+  });
+
+  describe("Batch close of completed bounties", () => {
+    const funder = anchor.web3.Keypair.generate();
+
+    before(async () => {
+      const sig = await connection.requestAirdrop(funder.publicKey, anchor.web3.LAMPORTS_PER_SOL);
+      await connection.confirmTransaction(sig, "confirmed");
+    });
+
+    function batchAccounts(created, rentPayer: PublicKey = maintainer.publicKey) {
+      return [
+        { pubkey: created.bounty, isSigner: false, isWritable: true },
+        { pubkey: created.escrowAuth, isSigner: false, isWritable: false },
+        { pubkey: created.escrowAta, isSigner: false, isWritable: true },
+        { pubkey: rentPayer, isSigner: false, isWritable: true },
+      ];
+    }
+
+    // Settled by the keeper batch, which leaves each bounty Completed with its escrow closed into it
+    async function completedBounties(count: number) {
+      const settled = [];
+      for (let i = 0; i < count; i++) {
+        const worker = anchor.web3.Keypair.generate();
+        const workerAta = await createAssociatedTokenAccount(connection, wallet.payer, mint, worker.publicKey);
+        const created = await createBounty({ rentPayer: funder });
+        await program.methods.assignContributor(false).accountsPartial({
+          maintainer: maintainer.publicKey,
+          bounty: created.bounty,
+          contributor: worker.publicKey,
+          systemProgram: SystemProgram.programId,
+        }).rpc();
+        settled.push({ ...created, workerAta });
+      }
+      await program.methods.completeBatch()
+        .accountsPartial({ keeper: admin.publicKey, config: configPda, mint, tokenProgram: TOKEN_PROGRAM_ID })
+        .remainingAccounts(settled.flatMap((created) => [
+          { pubkey: created.bounty, isSigner: false, isWritable: true },
+          { pubkey: created.escrowAuth, isSigner: false, isWritable: false },
+          { pubkey: created.escrowAta, isSigner: false, isWritable: true },
+          { pubkey: created.workerAta, isSigner: false, isWritable: true },
+        ]))
+        .rpc();
+      for (const created of settled) {
+        const bounty = await program.account.bounty.fetch(created.bounty);
+        assert.deepEqual(bounty.state, { completed: {} });
+      }
+      return settled;
+    }
+
+    it("Closes completed bounties and returns their rent to the rent payer", async () => {
+      const completed = await completedBounties(3);
+      let rent = 0;
+      for (const created of completed) {
+        rent += await connection.getBalance(created.bounty);
+      }
+      const funderBefore = await connection.getBalance(funder.publicKey);
+
+      const signature = await program.methods.closeCompletedBatch()
+        .accountsPartial({ tokenProgram: TOKEN_PROGRAM_ID })
+        .remainingAccounts(completed.flatMap((created) => batchAccounts(created, funder.publicKey)))
+        .rpc();
+
+      const summary = (await fetchEvents(signature)).find((e) => e.name === "completedBountiesClosed");
+      assert.equal(summary.data.closed, 3);
+      assert.equal((await connection.getBalance(funder.publicKey)) - funderBefore, rent);
+      for (const created of completed) {
+        assert.isNull(await connection.getAccountInfo(created.bounty));
+      }
+    });
+
+    it("Closes an empty escrow left open alongside its bounty", async () => {
+      const [created] = await completedBounties(1);
+      // Anyone can recreate the escrow ATA once the batch has closed it
+      await createAssociatedTokenAccount(connection, wallet.payer, mint, created.escrowAuth, undefined, undefined, undefined, true);
+      const rent = (await connection.getBalance(created.bounty)) + (await connection.getBalance(created.escrowAta));
+      const funderBefore = await connection.getBalance(funder.publicKey);
+
+      await program.methods.closeCompletedBatch()
+        .accountsPartial({ tokenProgram: TOKEN_PROGRAM_ID })
+        .remainingAccounts(batchAccounts(created, funder.publicKey))
+        .rpc();
+
+      assert.isNull(await connection.getAccountInfo(created.escrowAta));
+      assert.isNull(await connection.getAccountInfo(created.bounty));
+      assert.equal((await connection.getBalance(funder.publicKey)) - funderBefore, rent);
+    });
+
+    it("Skips bounties that are not completed", async () => {
+      const open = [await createBounty(), await createBounty(), await createBounty()];
+
+      const signature = await program.methods.closeCompletedBatch()
+        .accountsPartial({ tokenProgram: TOKEN_PROGRAM_ID })
+        .remainingAccounts(open.flatMap(batchAccounts))
+        .rpc();
+
+      const summary = (await fetchEvents(signature)).find((e) => e.name === "completedBountiesClosed");
+      assert.equal(summary.data.closed, 0);
+      for (const created of open) {
+        const bounty = await program.account.bounty.fetch(created.bounty);
+        assert.deepEqual(bounty.state, { created: {} });
+      }
+    });
+
+    it("Skips bounties already closed by completion", async () => {
+      const created = await createBounty();
+      await adminRelease(created);
+
+      const signature = await program.methods.closeCompletedBatch()
+        .accountsPartial({ tokenProgram: TOKEN_PROGRAM_ID })
+        .remainingAccounts(batchAccounts(created))
+        .rpc();
+
+      const summary = (await fetchEvents(signature)).find((e) => e.name === "completedBountiesClosed");
+      assert.equal(summary.data.closed, 0);
+    });
+
+    it("Rejects a batch that is not made of quadruples", async () => {
+      const created = await createBounty();
+      try {
+        await program.methods.closeCompletedBatch()
+          .accountsPartial({ tokenProgram: TOKEN_PROGRAM_ID })
+          .remainingAccounts(batchAccounts(created).slice(0, 3))
+          .rpc();
+        assert.fail("Expected InvalidBatchAccounts");
+      } catch (e) {
+        expectAnchorErrorCode(e, "InvalidBatchAccounts");
+      }
+    });
+  });
+
+  describe("Same-decimals mint substitution", () => {
+    const payee = anchor.web3.Keypair.generate();
+    let sneakyMint: PublicKey;
+    let payeeAta: PublicKey;
+
+    before(async () => {
+      // Same 6 decimals as the bounty mint so a decimals check alone would not catch it
+      sneakyMint = await createMint(connection, wallet.payer, wallet.publicKey, null, 6);
+      payeeAta = await createAssociatedTokenAccount(connection, wallet.payer, mint, payee.publicKey);
+    });
+
+    async function assignedBounty() {
+      const created = await createBounty();
+      await program.methods.assignContributor(false).accountsPartial({
+        maintainer: maintainer.publicKey,
+        bounty: created.bounty,
+        contributor: payee.publicKey,
+        systemProgram: SystemProgram.programId,
+      }).rpc();
+      return created;
+    }
+
+    it("Rejects completion with a different mint of the same decimals", async () => {
+      const created = await assignedBounty();
+      try {
+        await program.methods.completeBounty(created.bountyId, 5, null).accountsPartial({
+          bounty: created.bounty,
+          mint: sneakyMint,
+          escrowAuthority: created.escrowAuth,
+          maintainer: maintainer.publicKey,
+          contributor: payee.publicKey,
+          config: configPda,
+          admin: admin.publicKey,
+          contributorTokenAccount: payeeAta,
+          escrowTokenAccount: created.escrowAta,
+          tokenProgram: TOKEN_PROGRAM_ID,
+          systemProgram: SystemProgram.programId,
+          associatedTokenProgram: ASSOCIATED_TOKEN_PROGRAM_ID,
+        }).rpc();
+        assert.fail("Expected InvalidMint");
+      } catch (e) {
+        expectAnchorErrorCode(e, "InvalidMint");
+      }
+    });
+
+    it("Rejects a partial release with a different mint of the same decimals", async () => {
+      const created = await assignedBounty();
+      try {
+        await program.methods.releasePartial(created.bountyId, new anchor.BN(100)).accountsPartial({
+          maintainer: maintainer.publicKey,
+          bounty: created.bounty,
+          mint: sneakyMint,
+          escrowAuthority: created.escrowAuth,
+          contributor: payee.publicKey,
+          config: configPda,
+          contributorTokenAccount: payeeAta,
+          escrowTokenAccount: created.escrowAta,
+          tokenProgram: TOKEN_PROGRAM_ID,
+        }).rpc();
+        assert.fail("Expected InvalidMint");
+      } catch (e) {
+        expectAnchorErrorCode(e, "InvalidMint");
+      }
+    });
+  });
+
+  describe("Sponsored completion", () => {
+    const sponsor = anchor.web3.Keypair.generate();
+    let sponsorAta: PublicKey;
+
+    before(async () => {
+      await program.methods.setFee(1000, 10000).accountsPartial({ admin: admin.publicKey, config: configPda }).rpc();
+      sponsorAta = await createAssociatedTokenAccount(connection, wallet.payer, mint, sponsor.publicKey);
+      await mintTo(connection, wallet.payer, mint, sponsorAta, wallet.payer, 100000);
+    });
+
+    after(async () => {
+      await program.methods.setFee(0, 10000).accountsPartial({ admin: admin.publicKey, config: configPda }).rpc();
+    });
+
+    async function completeFor(sponsored: boolean) {
+      const worker = anchor.web3.Keypair.generate();
+      const workerAta = await createAssociatedTokenAccount(connection, wallet.payer, mint, worker.publicKey);
+      const created = await createBounty();
+      await program.methods.assignContributor(false).accountsPartial({
+        maintainer: maintainer.publicKey,
+        bounty: created.bounty,
+        contributor: worker.publicKey,
+        systemProgram: SystemProgram.programId,
+      }).rpc();
+
+      const builder = program.methods.completeBounty(created.bountyId, 5, null).accountsPartial({
+        bounty: created.bounty,
+        mint,
+        escrowAuthority: created.escrowAuth,
+        maintainer: maintainer.publicKey,
+        contributor: worker.publicKey,
+        config: configPda,
+        admin: admin.publicKey,
+        contributorTokenAccount: workerAta,
+        escrowTokenAccount: created.escrowAta,
+        treasuryTokenAccount: maintainerTokenAccount,
+        sponsor: sponsored ? sponsor.publicKey : null,
+        sponsorTokenAccount: sponsored ? sponsorAta : null,
+        tokenProgram: TOKEN_PROGRAM_ID,
+        systemProgram: SystemProgram.programId,
+        associatedTokenProgram: ASSOCIATED_TOKEN_PROGRAM_ID,
+      });
+      const signature = await (sponsored ? builder.signers([sponsor]) : builder).rpc();
+
+      return { signature, payout: Number((await getAccount(connection, workerAta)).amount) };
+    }
+
+    it("Pays the contributor the full amount when a sponsor covers the fee", async () => {
+      const sponsorBefore = Number((await getAccount(connection, sponsorAta)).amount);
+      const { signature, payout } = await completeFor(true);
+
+      assert.equal(payout, BOUNTY_AMOUNT.toNumber());
+      const sponsorAfter = Number((await getAccount(connection, sponsorAta)).amount);
+      assert.equal(sponsorBefore - sponsorAfter, 1000);
+
+      const sponsored = (await fetchEvents(signature)).find((e) => e.name === "feeSponsored");
+      assert.ok(sponsored, "FeeSponsored should be emitted");
+      assert.equal(sponsored.data.fee.toNumber(), 1000);
+    });
+
+    it("Deducts the fee from the payout without a sponsor", async () => {
+      const { payout } = await completeFor(false);
+      assert.equal(payout, BOUNTY_AMOUNT.toNumber() - 1000);
+    });
+  });
+
+  describe("Admin unassign", () => {
+    function assign(created, contributor: PublicKey) {
+      return program.methods.assignContributor(false).accountsPartial({
+        maintainer: maintainer.publicKey,
+        bounty: created.bounty,
+        contributor,
+        systemProgram: SystemProgram.programId,
+      }).rpc();
+    }
+
+    it("Clears an in-progress assignment so the bounty can be reassigned", async () => {
+      const created = await createBounty();
+      const wrong = anchor.web3.Keypair.generate().publicKey;
+      const right = anchor.web3.Keypair.generate().publicKey;
+      await assign(created, wrong);
+
+      const signature = await program.methods.adminUnassign(created.bountyId, NO_REASON).accountsPartial({
+        admin: admin.publicKey,
+        config: configPda,
+        bounty: created.bounty,
+      }).rpc();
+
+      let bounty = await program.account.bounty.fetch(created.bounty);
+      assert.isNull(bounty.contributor);
+      assert.deepEqual(bounty.state, { created: {} });
+      assert.equal(Number((await getAccount(connection, created.escrowAta)).amount), BOUNTY_AMOUNT.toNumber());
+
+      const cleared = (await fetchEvents(signature)).find((e) => e.name === "assignmentCleared");
+      assert.ok(cleared, "AssignmentCleared should be emitted");
+      assert.ok(cleared.data.previousContributor.equals(wrong));
+
+      await assign(created, right);
+      bounty = await program.account.bounty.fetch(created.bounty);
+      assert.ok(bounty.contributor.equals(right));
+    });
+
+    it("Rejects unassigning from a non-admin", async () => {
+      const created = await createBounty();
+      await assign(created, anchor.web3.Keypair.generate().publicKey);
+      const outsider = anchor.web3.Keypair.generate();
+      try {
+        await program.methods.adminUnassign(created.bountyId, NO_REASON).accountsPartial({
+          admin: outsider.publicKey,
+          config: configPda,
+          bounty: created.bounty,
+        }).signers([outsider]).rpc();
+        assert.fail("Expected Unauthorized");
+      } catch (e) {
+        expectAnchorErrorCode(e, "Unauthorized");
+      }
+    });
+  });
+
+  describe("Milestone cap", () => {
+    const MAX_MILESTONES = 10;
+    const splitInto = (count: number) =>
+      Array.from({ length: count }, () => new anchor.BN(BOUNTY_AMOUNT.toNumber() / 10));
+
+    it("Creates a bounty with the maximum number of milestones", async () => {
+      const created = await createBounty({ milestones: splitInto(MAX_MILESTONES) });
+      const bounty = await program.account.bounty.fetch(created.bounty);
+      assert.equal(bounty.milestones.length, MAX_MILESTONES);
+      assert.equal(bounty.milestonesPaid, 0);
+    });
+
+    it("Rejects a bounty with more than the maximum milestones", async () => {
+      const milestones = splitInto(MAX_MILESTONES + 1);
+      try {
+        await createBounty({ amount: new anchor.BN(1000 * (MAX_MILESTONES + 1)), milestones });
+        assert.fail("Expected TooManyMilestones");
+      } catch (e) {
+        expectAnchorErrorCode(e, "TooManyMilestones");
+      }
+    });
+
+    it("Rejects milestones that do not sum to the bounty amount", async () => {
+      try {
+        await createBounty({ milestones: splitInto(3) });
+        assert.fail("Expected InvalidMilestones");
+      } catch (e) {
+        expectAnchorErrorCode(e, "InvalidMilestones");
+      }
+    });
+  });
+
+  describe("Notification hook", () => {
+    before(async () => {
+      await program.methods.setNotificationProgram(mockProgram.programId)
+        .accountsPartial({ admin: admin.publicKey, config: configPda })
+        .rpc();
+    });
+
+    after(async () => {
+      await program.methods.setNotificationProgram(null)
+        .accountsPartial({ admin: admin.publicKey, config: configPda })
+        .rpc();
+    });
+
+    async function mockNotifications(signature: string) {
+      await connection.confirmTransaction(signature, "confirmed");
+      const tx = await connection.getTransaction(signature, {
+        commitment: "confirmed",
+        maxSupportedTransactionVersion: 0,
+      });
+      const parser = new anchor.EventParser(mockProgram.programId, mockProgram.coder);
+      return Array.from(parser.parseLogs(tx.meta.logMessages)).filter((e) => e.name === "notified");
+    }
+
+    it("CPIs into the notification program on assignment", async () => {
+      const created = await createBounty();
+      const assignee = anchor.web3.Keypair.generate().publicKey;
+      const signature = await program.methods.assignContributor(false).accountsPartial({
+        maintainer: maintainer.publicKey,
+        bounty: created.bounty,
+        contributor: assignee,
+        systemProgram: SystemProgram.programId,
+      }).remainingAccounts([
+        { pubkey: mockProgram.programId, isSigner: false, isWritable: false },
+      ]).rpc();
+
+      const notified = await mockNotifications(signature);
+      assert.equal(notified.length, 1);
+      assert.equal(notified[0].data.kind, 0);
+      assert.ok(notified[0].data.contributor.equals(assignee));
+      assert.equal(notified[0].data.bountyId.toString(), created.bountyId.toString());
+    });
+
+    it("Skips the notification when the program is not passed", async () => {
+      const created = await createBounty();
+      const signature = await program.methods.assignContributor(false).accountsPartial({
+        maintainer: maintainer.publicKey,
+        bounty: created.bounty,
+        contributor: anchor.web3.Keypair.generate().publicKey,
+        systemProgram: SystemProgram.programId,
+      }).rpc();
+
+      assert.equal((await mockNotifications(signature)).length, 0);
+      const bounty = await program.account.bounty.fetch(created.bounty);
+      assert.deepEqual(bounty.state, { inProgress: {} });
+    });
+  });
+
+  describe("Admin cancelling their own bounty", () => {
+    it("Refunds tokens and both rents to the admin-maintainer exactly once", async () => {
+      // The test wallet is both admin and maintainer
+      assert.ok(admin.publicKey.equals(maintainer.publicKey));
+      const created = await createBounty();
+
+      const bountyRent = await connection.getBalance(created.bounty);
+      const escrowRent = await connection.getBalance(created.escrowAta);
+      const tokensBefore = Number((await getAccount(connection, maintainerTokenAccount)).amount);
+      const lamportsBefore = await connection.getBalance(admin.publicKey);
+
+      const signature = await program.methods.cancelBounty(null, NO_REASON).accountsPartial({
+        admin: admin.publicKey,
+        config: configPda,
+        bounty: created.bounty,
+        mint,
+        escrowAuthority: created.escrowAuth,
+        maintainer: maintainer.publicKey,
+        maintainerTokenAccount: maintainerTokenAccount,
+        escrowTokenAccount: created.escrowAta,
+        systemProgram: SystemProgram.programId,
+        tokenProgram: TOKEN_PROGRAM_ID,
+        associatedTokenProgram: ASSOCIATED_TOKEN_PROGRAM_ID,
+        rent: SYSVAR_RENT_PUBKEY,
+      }).rpc();
+      await connection.confirmTransaction(signature, "confirmed");
+      const tx = await connection.getTransaction(signature, { commitment: "confirmed", maxSupportedTransactionVersion: 0 });
+
+      const tokensAfter = Number((await getAccount(connection, maintainerTokenAccount)).amount);
+      const lamportsAfter = await connection.getBalance(admin.publicKey);
+      assert.equal(tokensAfter - tokensBefore, BOUNTY_AMOUNT.toNumber());
+      assert.equal(lamportsAfter - lamportsBefore, bountyRent + escrowRent - tx.meta.fee);
+    });
+  });
+
+  describe("Admin force-release gating", () => {
+    function setAllowForce(allow: boolean) {
+      return program.methods
+        .adminUpdateConfig({
+          feeBps: null,
+          feeTreasuryBps: null,
+          feeDiscountMaxBps: null,
+          minBountyAmount: null,
+          maxBountyAmount: null,
+          paused: null,
+          allowTransferHooks: null,
+          defaultKeeper: null,
+          allowAdminForceOnCreated: allow,
+          adminInterventionBps: null,
+        })
+        .accountsPartial({ admin: admin.publicKey, config: configPda })
+        .rpc();
+    }
+
+    after(async () => {
+      await setAllowForce(true);
+    });
+
+    it("Allows force-releasing a Created bounty by default", async () => {
+      const config = await program.account.configState.fetch(configPda);
+      assert.isTrue(config.allowAdminForceOnCreated);
+      const created = await createBounty();
+      const { targetAta } = await adminRelease(created);
+      assert.equal(Number((await getAccount(connection, targetAta)).amount), BOUNTY_AMOUNT.toNumber());
+    });
+
+    it("Rejects force-releasing a Created bounty when disabled", async () => {
+      await setAllowForce(false);
+      const created = await createBounty();
+      try {
+        await adminRelease(created);
+        assert.fail("Force-release of a Created bounty should have failed");
+      } catch (err) {
+        assert.include(err.toString(), "AdminForceOnCreatedDisabled");
+      }
+    });
+
+    it("Still force-releases an InProgress bounty when disabled", async () => {
+      await setAllowForce(false);
+      const created = await createBounty();
+      await program.methods.assignContributor(false).accountsPartial({
+        maintainer: maintainer.publicKey,
+        bounty: created.bounty,
+        contributor: anchor.web3.Keypair.generate().publicKey,
+        systemProgram: SystemProgram.programId,
+      }).rpc();
+
+      const { targetAta } = await adminRelease(created);
+      assert.equal(Number((await getAccount(connection, targetAta)).amount), BOUNTY_AMOUNT.toNumber());
+    });
+  });
+
+  describe("Structured return values", () => {
+    // Decodes the BountyStateChange returned by a confirmed transaction
+    async function fetchStateChange(signature: string) {
+      await connection.confirmTransaction(signature, "confirmed");
+      const tx = await connection.getTransaction(signature, {
+        commitment: "confirmed",
+        maxSupportedTransactionVersion: 0,
+      });
+      const prefix = `Program return: ${program.programId.toBase58()} `;
+      const log = tx.meta.logMessages.find((line) => line.startsWith(prefix));
+      assert.isDefined(log);
+      return program.coder.types.decode("bountyStateChange", Buffer.from(log.slice(prefix.length), "base64"));
+    }
+
+    it("Returns the new state from assign and admin unassign", async () => {
+      const created = await createBounty();
+      const assignSig = await program.methods.assignContributor(false).accountsPartial({
+        maintainer: maintainer.publicKey,
+        bounty: created.bounty,
+        contributor: anchor.web3.Keypair.generate().publicKey,
+        systemProgram: SystemProgram.programId,
+      }).rpc();
+
+      let change = await fetchStateChange(assignSig);
+      let bounty = await program.account.bounty.fetch(created.bounty);
+      assert.equal(change.bountyId.toString(), created.bountyId.toString());
+      assert.deepEqual(change.newState, bounty.state);
+      assert.deepEqual(change.newState, { inProgress: {} });
+
+      const unassignSig = await program.methods.adminUnassign(created.bountyId, NO_REASON).accountsPartial({
+        admin: admin.publicKey,
+        config: configPda,
+        bounty: created.bounty,
+      }).rpc();
+
+      change = await fetchStateChange(unassignSig);
+      bounty = await program.account.bounty.fetch(created.bounty);
+      assert.deepEqual(change.newState, bounty.state);
+      assert.deepEqual(change.newState, { created: {} });
+    });
+
+    it("Returns the final state of bounties closed by admin release and cancel", async () => {
+      const released = await createBounty();
+      const { signature } = await adminRelease(released);
+      let change = await fetchStateChange(signature);
+      assert.equal(change.bountyId.toString(), released.bountyId.toString());
+      assert.deepEqual(change.newState, { completed: {} });
+
+      const cancelled = await createBounty();
+      const cancelSig = await program.methods.cancelBounty(null, NO_REASON).accountsPartial({
+        admin: admin.publicKey,
+        config: configPda,
+        bounty: cancelled.bounty,
+        mint,
+        escrowAuthority: cancelled.escrowAuth,
+        maintainer: maintainer.publicKey,
+        maintainerTokenAccount: maintainerTokenAccount,
+        escrowTokenAccount: cancelled.escrowAta,
+        systemProgram: SystemProgram.programId,
+        tokenProgram: TOKEN_PROGRAM_ID,
+        associatedTokenProgram: ASSOCIATED_TOKEN_PROGRAM_ID,
+        rent: SYSVAR_RENT_PUBKEY,
+      }).rpc();
+      change = await fetchStateChange(cancelSig);
+      assert.equal(change.bountyId.toString(), cancelled.bountyId.toString());
+      assert.deepEqual(change.newState, { cancelled: {} });
+    });
+  });
+
+  describe("LP unwrap on completion", () => {
+    // The bounty mint plays the LP token, the mock AMM pays a separate underlying mint 1:1
+    let underlyingMint: PublicKey;
+    let reserve: PublicKey;
+    let lpVault: PublicKey;
+    const [reserveAuthority] = PublicKey.findProgramAddressSync([Buffer.from("reserve")], mockProgram.programId);
+
+    before(async () => {
+      underlyingMint = await createMint(connection, wallet.payer, wallet.publicKey, null, 6);
+      reserve = await createAssociatedTokenAccount(connection, wallet.payer, underlyingMint, reserveAuthority, undefined, undefined, undefined, true);
+      await mintTo(connection, wallet.payer, underlyingMint, reserve, wallet.payer, 10 * BOUNTY_AMOUNT.toNumber());
+      lpVault = await createAssociatedTokenAccount(connection, wallet.payer, mint, anchor.web3.Keypair.generate().publicKey);
+      await program.methods.setAmmProgram(mockProgram.programId).accountsPartial({ admin: admin.publicKey, config: configPda }).rpc();
+    });
+
+    after(async () => {
+      await program.methods.setAmmProgram(null).accountsPartial({ admin: admin.publicKey, config: configPda }).rpc();
+    });
+
+    async function completeLpBounty(withAmm: boolean) {
+      const worker = anchor.web3.Keypair.generate();
+      const workerLpAta = await createAssociatedTokenAccount(connection, wallet.payer, mint, worker.publicKey);
+      const workerUnderlyingAta = await createAssociatedTokenAccount(connection, wallet.payer, underlyingMint, worker.publicKey);
+      const created = await createBounty();
+      await program.methods.setUnwrapOnComplete(true).accountsPartial({
+        maintainer: maintainer.publicKey,
+        bounty: created.bounty,
+      }).rpc();
+      await program.methods.assignContributor(false).accountsPartial({
+        maintainer: maintainer.publicKey,
+        bounty: created.bounty,
+        contributor: worker.publicKey,
+        systemProgram: SystemProgram.programId,
+      }).rpc();
+
+      const signature = await program.methods.completeBounty(created.bountyId, 5, null).accountsPartial({
+        bounty: created.bounty,
+        mint,
+        escrowAuthority: created.escrowAuth,
+        maintainer: maintainer.publicKey,
+        contributor: worker.publicKey,
+        config: configPda,
+        admin: admin.publicKey,
+        contributorTokenAccount: workerLpAta,
+        escrowTokenAccount: created.escrowAta,
+        ammProgram: withAmm ? mockProgram.programId : null,
+        contributorUnderlyingTokenAccount: withAmm ? workerUnderlyingAta : null,
+        tokenProgram: TOKEN_PROGRAM_ID,
+        systemProgram: SystemProgram.programId,
+        associatedTokenProgram: ASSOCIATED_TOKEN_PROGRAM_ID,
+      }).remainingAccounts(withAmm ? [
+        { pubkey: lpVault, isWritable: true, isSigner: false },
+        { pubkey: reserveAuthority, isWritable: false, isSigner: false },
+        { pubkey: reserve, isWritable: true, isSigner: false },
+      ] : []).rpc();
+
+      return {
+        signature,
+        lp: Number((await getAccount(connection, workerLpAta)).amount),
+        underlying: Number((await getAccount(connection, workerUnderlyingAta)).amount),
+      };
+    }
+
+    it("Pays the contributor in the underlying token through the AMM", async () => {
+      const vaultBefore = Number((await getAccount(connection, lpVault)).amount);
+      const { signature, lp, underlying } = await completeLpBounty(true);
+
+      assert.equal(lp, 0);
+      assert.equal(underlying, BOUNTY_AMOUNT.toNumber());
+      assert.equal(Number((await getAccount(connection, lpVault)).amount) - vaultBefore, BOUNTY_AMOUNT.toNumber());
+
+      const unwrapped = (await fetchEvents(signature)).find((e) => e.name === "lpUnwrapped");
+      assert.ok(unwrapped, "LpUnwrapped should be emitted");
+      assert.equal(unwrapped.data.underlyingAmount.toNumber(), BOUNTY_AMOUNT.toNumber());
+    });
+
+    it("Falls back to paying LP tokens when the AMM accounts are not passed", async () => {
+      const { lp, underlying } = await completeLpBounty(false);
+      assert.equal(lp, BOUNTY_AMOUNT.toNumber());
+      assert.equal(underlying, 0);
+    });
+  });
+
+  describe("Contributor ATA auto-creation on completion", () => {
+    it("Creates and funds the contributor's ATA when they have none", async () => {
+      const newcomer = anchor.web3.Keypair.generate();
+      const newcomerAta = await getAssociatedTokenAddress(mint, newcomer.publicKey);
+      assert.isNull(await connection.getAccountInfo(newcomerAta));
+
+      const created = await createBounty();
+      await program.methods.assignContributor(false).accountsPartial({
+        maintainer: maintainer.publicKey,
+        bounty: created.bounty,
+        contributor: newcomer.publicKey,
+        systemProgram: SystemProgram.programId,
+      }).rpc();
+
+      await program.methods.completeBounty(created.bountyId, 5, null).accountsPartial({
+        bounty: created.bounty,
+        mint,
+        escrowAuthority: created.escrowAuth,
+        maintainer: maintainer.publicKey,
+        contributor: newcomer.publicKey,
+        config: configPda,
+        admin: admin.publicKey,
+        contributorTokenAccount: newcomerAta,
+        escrowTokenAccount: created.escrowAta,
+        tokenProgram: TOKEN_PROGRAM_ID,
+        systemProgram: SystemProgram.programId,
+        associatedTokenProgram: ASSOCIATED_TOKEN_PROGRAM_ID,
+      }).rpc();
+
+      const ata = await getAccount(connection, newcomerAta);
+      assert.ok(ata.owner.equals(newcomer.publicKey));
+      assert.ok(ata.mint.equals(mint));
+      assert.equal(Number(ata.amount), BOUNTY_AMOUNT.toNumber());
+    });
+
+    it("Rejects a payout account that is not the contributor's ATA", async () => {
+      const worker = anchor.web3.Keypair.generate();
+      const stranger = anchor.web3.Keypair.generate();
+      const strangerAta = await createAssociatedTokenAccount(connection, wallet.payer, mint, stranger.publicKey);
+      const created = await createBounty();
+      await program.methods.assignContributor(false).accountsPartial({
+        maintainer: maintainer.publicKey,
+        bounty: created.bounty,
+        contributor: worker.publicKey,
+        systemProgram: SystemProgram.programId,
+      }).rpc();
+
+      try {
+        await program.methods.completeBounty(created.bountyId, 5, null).accountsPartial({
+          bounty: created.bounty,
+          mint,
+          escrowAuthority: created.escrowAuth,
+          maintainer: maintainer.publicKey,
+          contributor: worker.publicKey,
+          config: configPda,
+          admin: admin.publicKey,
+          contributorTokenAccount: strangerAta,
+          escrowTokenAccount: created.escrowAta,
+          tokenProgram: TOKEN_PROGRAM_ID,
+          systemProgram: SystemProgram.programId,
+          associatedTokenProgram: ASSOCIATED_TOKEN_PROGRAM_ID,
+        }).rpc();
+        assert.fail("Expected InvalidTokenAccount");
+      } catch (e) {
+        expectAnchorErrorCode(e, "InvalidTokenAccount");
+      }
+    });
+  });
+
+  describe("Completion via oracle attestation", () => {
+    const relayer = anchor.web3.Keypair.generate();
+
+    before(async () => {
+      const sig = await connection.requestAirdrop(relayer.publicKey, anchor.web3.LAMPORTS_PER_SOL);
+      await connection.confirmTransaction(sig, "confirmed");
+    });
+
+    // (bounty_id, pr_id, contributor) as the program rebuilds it
+    function mergeMessage(id: anchor.BN, prId: anchor.BN, worker: PublicKey) {
+      return Buffer.concat([id.toArrayLike(Buffer, "le", 8), prId.toArrayLike(Buffer, "le", 8), worker.toBuffer()]);
+    }
+
+    async function assignedBounty() {
+      const worker = anchor.web3.Keypair.generate();
+      const workerAta = await createAssociatedTokenAccount(connection, wallet.payer, mint, worker.publicKey);
+      const created = await createBounty();
+      await program.methods.assignContributor(false).accountsPartial({
+        maintainer: maintainer.publicKey,
+        bounty: created.bounty,
+        contributor: worker.publicKey,
+        systemProgram: SystemProgram.programId,
+      }).rpc();
+      return { created, worker, workerAta };
+    }
+
+    function completeWithAttestation(
+      { created, worker, workerAta },
+      prId: anchor.BN,
+      oracle: Keypair,
+      deliverableHash: number[] | null = null
+    ) {
+      const verifyIx = anchor.web3.Ed25519Program.createInstructionWithPrivateKey({
+        privateKey: oracle.secretKey,
+        message: mergeMessage(created.bountyId, prId, worker.publicKey),
+      });
+      // web3.js layout: 16 byte header, 32 byte public key, then the 64 byte signature
+      const signature = Array.from(verifyIx.data.subarray(48, 112));
+
+      return program.methods.completeViaAttestation(created.bountyId, prId, signature, deliverableHash).accountsPartial({
+        payer: relayer.publicKey,
+        config: configPda,
+        bounty: created.bounty,
+        mint,
+        escrowAuthority: created.escrowAuth,
+        maintainer: maintainer.publicKey,
+        contributor: worker.publicKey,
+        contributorTokenAccount: workerAta,
+        escrowTokenAccount: created.escrowAta,
+        instructions: anchor.web3.SYSVAR_INSTRUCTIONS_PUBKEY,
+        tokenProgram: TOKEN_PROGRAM_ID,
+        systemProgram: SystemProgram.programId,
+      }).preInstructions([verifyIx]).signers([relayer]).rpc();
+    }
+
+    it("Releases funds on a valid oracle attestation", async () => {
+      const assigned = await assignedBounty();
+      const signature = await completeWithAttestation(assigned, new anchor.BN(42), admin);
+
+      assert.equal(Number((await getAccount(connection, assigned.workerAta)).amount), BOUNTY_AMOUNT.toNumber());
+      assert.isNull(await connection.getAccountInfo(assigned.created.bounty));
+      const attested = (await fetchEvents(signature)).find((e) => e.name === "mergeAttested");
+      assert.ok(attested, "MergeAttested should be emitted");
+      assert.equal(attested.data.prId.toNumber(), 42);
+    });
+
+    it("Rejects an attestation signed by someone other than the oracle", async () => {
+      const assigned = await assignedBounty();
+      try {
+        await completeWithAttestation(assigned, new anchor.BN(42), anchor.web3.Keypair.generate());
+        assert.fail("Expected InvalidAttestation");
+      } catch (e) {
+        expectAnchorErrorCode(e, "InvalidAttestation");
+      }
+      assert.equal(Number((await getAccount(connection, assigned.workerAta)).amount), 0);
+    });
+
+    it("Only settles a committed deliverable when the attestation reveals it", async () => {
+      const assigned = await assignedBounty();
+      const spec = createHash("sha256").update("spec: attested").digest();
+      await program.methods.commitDeliverable(assigned.created.bountyId, Array.from(spec)).accountsPartial({
+        contributor: assigned.worker.publicKey,
+        bounty: assigned.created.bounty,
+      }).signers([assigned.worker]).rpc();
+
+      try {
+        await completeWithAttestation(assigned, new anchor.BN(42), admin);
+        assert.fail("Expected DeliverableMismatch");
+      } catch (e) {
+        expectAnchorErrorCode(e, "DeliverableMismatch");
+      }
+
+      await completeWithAttestation(assigned, new anchor.BN(42), admin, Array.from(spec));
+      assert.equal(Number((await getAccount(connection, assigned.workerAta)).amount), BOUNTY_AMOUNT.toNumber());
+    });
+  });
+
+  describe("Bounty rent payer", () => {
+    const funder = anchor.web3.Keypair.generate();
+
+    before(async () => {
+      const sig = await connection.requestAirdrop(funder.publicKey, anchor.web3.LAMPORTS_PER_SOL);
+      await connection.confirmTransaction(sig, "confirmed");
+    });
+
+    it("Charges the rent payer and records it on the bounty", async () => {
+      const funderBefore = await connection.getBalance(funder.publicKey);
+      const created = await createBounty({ rentPayer: funder });
+
+      const bountyRent = await connection.getBalance(created.bounty);
+      assert.equal(funderBefore - (await connection.getBalance(funder.publicKey)), bountyRent);
+      const bounty = await program.account.bounty.fetch(created.bounty);
+      assert.ok(bounty.rentPayer.equals(funder.publicKey));
+    });
+
+    it("Returns the bounty rent to the rent payer on cancel", async () => {
+      const created = await createBounty({ rentPayer: funder });
+      const bountyRent = await connection.getBalance(created.bounty);
+      const funderBefore = await connection.getBalance(funder.publicKey);
+
+      await program.methods.cancelBounty(null, NO_REASON).accountsPartial({
+        admin: admin.publicKey,
+        config: configPda,
+        bounty: created.bounty,
+        mint,
+        escrowAuthority: created.escrowAuth,
+        maintainer: maintainer.publicKey,
+        rentPayer: funder.publicKey,
+        maintainerTokenAccount: maintainerTokenAccount,
+        escrowTokenAccount: created.escrowAta,
+        systemProgram: SystemProgram.programId,
+        tokenProgram: TOKEN_PROGRAM_ID,
+        associatedTokenProgram: ASSOCIATED_TOKEN_PROGRAM_ID,
+        rent: SYSVAR_RENT_PUBKEY,
+      }).rpc();
+
+      assert.equal((await connection.getBalance(funder.publicKey)) - funderBefore, bountyRent);
+    });
+
+    it("Returns the bounty rent to the rent payer on completion", async () => {
+      const worker = anchor.web3.Keypair.generate();
+      const workerAta = await createAssociatedTokenAccount(connection, wallet.payer, mint, worker.publicKey);
+      const created = await createBounty({ rentPayer: funder });
+      await program.methods.assignContributor(false).accountsPartial({
+        maintainer: maintainer.publicKey,
+        bounty: created.bounty,
+        contributor: worker.publicKey,
+        systemProgram: SystemProgram.programId,
+      }).rpc();
+      const bountyRent = await connection.getBalance(created.bounty);
+      const funderBefore = await connection.getBalance(funder.publicKey);
+
+      await program.methods.completeBounty(created.bountyId, 5, null).accountsPartial({
+        bounty: created.bounty,
+        mint,
+        escrowAuthority: created.escrowAuth,
+        maintainer: maintainer.publicKey,
+        rentPayer: funder.publicKey,
+        contributor: worker.publicKey,
+        config: configPda,
+        admin: admin.publicKey,
+        contributorTokenAccount: workerAta,
+        escrowTokenAccount: created.escrowAta,
+        tokenProgram: TOKEN_PROGRAM_ID,
+        systemProgram: SystemProgram.programId,
+        associatedTokenProgram: ASSOCIATED_TOKEN_PROGRAM_ID,
+      }).rpc();
+
+      assert.equal((await connection.getBalance(funder.publicKey)) - funderBefore, bountyRent);
+    });
+
+    it("Rejects sending the bounty rent anywhere but the rent payer", async () => {
+      const created = await createBounty({ rentPayer: funder });
+      try {
+        await program.methods.cancelBounty(null, NO_REASON).accountsPartial({
+          admin: admin.publicKey,
+          config: configPda,
+          bounty: created.bounty,
+          mint,
+          escrowAuthority: created.escrowAuth,
+          maintainer: maintainer.publicKey,
+          rentPayer: maintainer.publicKey,
+          maintainerTokenAccount: maintainerTokenAccount,
+          escrowTokenAccount: created.escrowAta,
+          systemProgram: SystemProgram.programId,
+          tokenProgram: TOKEN_PROGRAM_ID,
+          associatedTokenProgram: ASSOCIATED_TOKEN_PROGRAM_ID,
+          rent: SYSVAR_RENT_PUBKEY,
+        }).rpc();
+        assert.fail("Expected RentPayerMismatch");
+      } catch (e) {
+        expectAnchorErrorCode(e, "RentPayerMismatch");
+      }
+    });
+  });
+
+  describe("Canonical config account", () => {
+    it("Rejects a wallet passed as the config", async () => {
+      try {
+        await program.methods.setFee(0, 10000).accountsPartial({ admin: admin.publicKey, config: admin.publicKey }).rpc();
+        assert.fail("Expected AccountOwnedByWrongProgram");
+      } catch (e) {
+        expectAnchorErrorCode(e, "AccountOwnedByWrongProgram");
+      }
+    });
+
+    it("Rejects another program-owned account passed as the config", async () => {
+      const created = await createBounty();
+      try {
+        await program.methods.setFee(0, 10000).accountsPartial({ admin: admin.publicKey, config: created.bounty }).rpc();
+        assert.fail("Expected AccountDiscriminatorMismatch");
+      } catch (e) {
+        expectAnchorErrorCode(e, "AccountDiscriminatorMismatch");
+      }
+    });
+
+    it("Records the canonical bump on the config", async () => {
+      const [expected, bump] = PublicKey.findProgramAddressSync([Buffer.from("config")], program.programId);
+      assert.ok(expected.equals(configPda));
+      assert.equal((await program.account.configState.fetch(configPda)).bump, bump);
+    });
+  });
+
+  describe("Admin intervention fee", () => {
+    function setFees(feeBps: number, adminInterventionBps: number) {
+      return program.methods
+        .adminUpdateConfig({
+          feeBps,
+          feeTreasuryBps: 10000,
+          feeDiscountMaxBps: null,
+          minBountyAmount: null,
+          maxBountyAmount: null,
+          paused: null,
+          allowTransferHooks: null,
+          defaultKeeper: null,
+          allowAdminForceOnCreated: null,
+          adminInterventionBps,
+        })
+        .accountsPartial({ admin: admin.publicKey, config: configPda })
+        .rpc();
+    }
+
+    before(async () => {
+      await setFees(1000, 500);
+    });
+
+    after(async () => {
+      await setFees(0, 0);
+    });
+
+    it("Charges only the regular fee on normal completion", async () => {
+      const worker = anchor.web3.Keypair.generate();
+      const workerAta = await createAssociatedTokenAccount(connection, wallet.payer, mint, worker.publicKey);
+      const created = await createBounty();
+      await program.methods.assignContributor(false).accountsPartial({
+        maintainer: maintainer.publicKey,
+        bounty: created.bounty,
+        contributor: worker.publicKey,
+        systemProgram: SystemProgram.programId,
+      }).rpc();
+
+      await program.methods.completeBounty(created.bountyId, 5, null).accountsPartial({
+        bounty: created.bounty,
+        mint,
+        escrowAuthority: created.escrowAuth,
+        maintainer: maintainer.publicKey,
+        contributor: worker.publicKey,
+        config: configPda,
+        admin: admin.publicKey,
+        contributorTokenAccount: workerAta,
+        escrowTokenAccount: created.escrowAta,
+        treasuryTokenAccount: maintainerTokenAccount,
+        tokenProgram: TOKEN_PROGRAM_ID,
+        systemProgram: SystemProgram.programId,
+        associatedTokenProgram: ASSOCIATED_TOKEN_PROGRAM_ID,
+      }).rpc();
+
+      assert.equal(Number((await getAccount(connection, workerAta)).amount), BOUNTY_AMOUNT.toNumber() - 1000);
+    });
+
+    it("Adds the intervention fee on admin force-release", async () => {
+      const created = await createBounty();
+      const treasuryBefore = Number((await getAccount(connection, maintainerTokenAccount)).amount);
+      const { signature, targetAta } = await adminRelease(created, { treasuryTokenAccount: maintainerTokenAccount });
+
+      assert.equal(Number((await getAccount(connection, targetAta)).amount), BOUNTY_AMOUNT.toNumber() - 1500);
+      assert.equal(Number((await getAccount(connection, maintainerTokenAccount)).amount) - treasuryBefore, 1500);
+      const completed = (await fetchEvents(signature)).find((e) => e.name === "bountyCompleted");
+      assert.equal(completed.data.fee.toNumber(), 1500);
+    });
+  });
+
+  describe("Bounty counts by state", () => {
+    async function counts() {
+      const c = await program.methods.getCountByState().view();
+      return {
+        created: c.created.toNumber(),
+        inProgress: c.inProgress.toNumber(),
+        completed: c.completed.toNumber(),
+        cancelled: c.cancelled.toNumber(),
+      };
+    }
+
+    async function assign(created: { bounty: PublicKey }, worker: PublicKey) {
+      await program.methods.assignContributor(false).accountsPartial({
+        maintainer: maintainer.publicKey,
+        bounty: created.bounty,
+        contributor: worker,
+        systemProgram: SystemProgram.programId,
+      }).rpc();
+    }
+
+    it("Tracks every transition across several lifecycles", async () => {
+      const before = await counts();
+
+      const toComplete = await createBounty();
+      const toCancel = await createBounty();
+      const toUnassign = await createBounty();
+      const toForceRelease = await createBounty();
+      let now = await counts();
+      assert.equal(now.created - before.created, 4);
+
+      const worker = anchor.web3.Keypair.generate();
+      const workerAta = await createAssociatedTokenAccount(connection, wallet.payer, mint, worker.publicKey);
+      await assign(toComplete, worker.publicKey);
+      await assign(toUnassign, anchor.web3.Keypair.generate().publicKey);
+      now = await counts();
+      assert.equal(now.created - before.created, 2);
+      assert.equal(now.inProgress - before.inProgress, 2);
+
+      await program.methods.completeBounty(toComplete.bountyId, 5, null).accountsPartial({
+        bounty: toComplete.bounty,
+        mint,
+        escrowAuthority: toComplete.escrowAuth,
+        maintainer: maintainer.publicKey,
+        contributor: worker.publicKey,
+        config: configPda,
+        admin: admin.publicKey,
+        contributorTokenAccount: workerAta,
+        escrowTokenAccount: toComplete.escrowAta,
+        tokenProgram: TOKEN_PROGRAM_ID,
+        systemProgram: SystemProgram.programId,
+        associatedTokenProgram: ASSOCIATED_TOKEN_PROGRAM_ID,
+      }).rpc();
+
+      await program.methods.cancelBounty(null, NO_REASON).accountsPartial({
+        admin: admin.publicKey,
+        config: configPda,
+        bounty: toCancel.bounty,
+        mint,
+        escrowAuthority: toCancel.escrowAuth,
+        maintainer: maintainer.publicKey,
+        maintainerTokenAccount: maintainerTokenAccount,
+        escrowTokenAccount: toCancel.escrowAta,
+        systemProgram: SystemProgram.programId,
+        tokenProgram: TOKEN_PROGRAM_ID,
+        associatedTokenProgram: ASSOCIATED_TOKEN_PROGRAM_ID,
+        rent: SYSVAR_RENT_PUBKEY,
+      }).rpc();
+
+      await program.methods.adminUnassign(toUnassign.bountyId, NO_REASON).accountsPartial({
+        admin: admin.publicKey,
+        config: configPda,
+        bounty: toUnassign.bounty,
+      }).rpc();
+
+      // Force-released straight from Created
+      await adminRelease(toForceRelease);
+
+      now = await counts();
+      assert.equal(now.created - before.created, 1);
+      assert.equal(now.inProgress - before.inProgress, 0);
+      assert.equal(now.completed - before.completed, 2);
+      assert.equal(now.cancelled - before.cancelled, 1);
+    });
+  });
+
+  describe("Custom fee recipient", () => {
+    const charity = anchor.web3.Keypair.generate();
+    let charityAta: PublicKey;
+
+    before(async () => {
+      await program.methods.setFee(1000, 10000).accountsPartial({ admin: admin.publicKey, config: configPda }).rpc();
+      charityAta = await createAssociatedTokenAccount(connection, wallet.payer, mint, charity.publicKey);
+    });
+
+    after(async () => {
+      await program.methods.setFee(0, 10000).accountsPartial({ admin: admin.publicKey, config: configPda }).rpc();
+    });
+
+    async function completeWith(allowCustom: boolean, feeRecipient: PublicKey | null) {
+      const worker = anchor.web3.Keypair.generate();
+      const workerAta = await createAssociatedTokenAccount(connection, wallet.payer, mint, worker.publicKey);
+      const created = await createBounty();
+      if (allowCustom) {
+        await program.methods.setAllowCustomFeeRecipient(charity.publicKey).accountsPartial({
+          maintainer: maintainer.publicKey,
+          bounty: created.bounty,
+        }).rpc();
+      }
+      await program.methods.assignContributor(false).accountsPartial({
+        maintainer: maintainer.publicKey,
+        bounty: created.bounty,
+        contributor: worker.publicKey,
+        systemProgram: SystemProgram.programId,
+      }).rpc();
+
+      await program.methods.completeBounty(created.bountyId, 5, null).accountsPartial({
+        bounty: created.bounty,
+        mint,
+        escrowAuthority: created.escrowAuth,
+        maintainer: maintainer.publicKey,
+        contributor: worker.publicKey,
+        config: configPda,
+        admin: admin.publicKey,
+        contributorTokenAccount: workerAta,
+        escrowTokenAccount: created.escrowAta,
+        treasuryTokenAccount: maintainerTokenAccount,
+        feeRecipient,
+        tokenProgram: TOKEN_PROGRAM_ID,
+        systemProgram: SystemProgram.programId,
+        associatedTokenProgram: ASSOCIATED_TOKEN_PROGRAM_ID,
+      }).rpc();
+      return Number((await getAccount(connection, workerAta)).amount);
+    }
+
+    it("Routes the whole fee to the custom recipient when the bounty allows it", async () => {
+      const charityBefore = Number((await getAccount(connection, charityAta)).amount);
+      const treasuryBefore = Number((await getAccount(connection, maintainerTokenAccount)).amount);
+
+      const payout = await completeWith(true, charityAta);
+
+      assert.equal(payout, BOUNTY_AMOUNT.toNumber() - 1000);
+      assert.equal(Number((await getAccount(connection, charityAta)).amount) - charityBefore, 1000);
+      // The treasury is the maintainer wallet here, which also gets nothing back from this completion
+      assert.equal(Number((await getAccount(connection, maintainerTokenAccount)).amount), treasuryBefore - BOUNTY_AMOUNT.toNumber());
+    });
+
+    it("Sends the fee to the protocol treasury by default", async () => {
+      const charityBefore = Number((await getAccount(connection, charityAta)).amount);
+      const payout = await completeWith(true, null);
+
+      assert.equal(payout, BOUNTY_AMOUNT.toNumber() - 1000);
+      assert.equal(Number((await getAccount(connection, charityAta)).amount), charityBefore);
+    });
+
+    it("Rejects a custom recipient on a bounty that does not allow one", async () => {
+      try {
+        await completeWith(false, charityAta);
+        assert.fail("Expected CustomFeeRecipientNotAllowed");
+      } catch (e) {
+        expectAnchorErrorCode(e, "CustomFeeRecipientNotAllowed");
+      }
+    });
+
+    it("Rejects a fee recipient other than the one the maintainer approved", async () => {
+      const stranger = anchor.web3.Keypair.generate();
+      const strangerAta = await createAssociatedTokenAccount(connection, wallet.payer, mint, stranger.publicKey);
+      try {
+        await completeWith(true, strangerAta);
+        assert.fail("Expected InvalidTokenAccount");
+      } catch (e) {
+        expectAnchorErrorCode(e, "InvalidTokenAccount");
+      }
+      assert.equal(Number((await getAccount(connection, strangerAta)).amount), 0);
+    });
+  });
+
+  describe("Payout account check at assignment", () => {
+    const worker = anchor.web3.Keypair.generate();
+
+    it("Rejects assigning with a payout account for another mint", async () => {
+      const otherMint = await createMint(connection, wallet.payer, wallet.publicKey, null, 6);
+      const wrongAta = await createAssociatedTokenAccount(connection, wallet.payer, otherMint, worker.publicKey);
+      const created = await createBounty();
+      try {
+        await program.methods.assignContributor(false).accountsPartial({
+          maintainer: maintainer.publicKey,
+          bounty: created.bounty,
+          contributor: worker.publicKey,
+          contributorTokenAccount: wrongAta,
+          systemProgram: SystemProgram.programId,
+        }).rpc();
+        assert.fail("Expected InvalidMint");
+      } catch (e) {
+        expectAnchorErrorCode(e, "InvalidMint");
+      }
+      const bounty = await program.account.bounty.fetch(created.bounty);
+      assert.deepEqual(bounty.state, { created: {} });
+    });
+
+    it("Assigns with a payout account for the bounty mint", async () => {
+      const rightAta = await createAssociatedTokenAccount(connection, wallet.payer, mint, worker.publicKey);
+      const created = await createBounty();
+      await program.methods.assignContributor(false).accountsPartial({
+        maintainer: maintainer.publicKey,
+        bounty: created.bounty,
+        contributor: worker.publicKey,
+        contributorTokenAccount: rightAta,
+        systemProgram: SystemProgram.programId,
+      }).rpc();
+      const bounty = await program.account.bounty.fetch(created.bounty);
+      assert.deepEqual(bounty.state, { inProgress: {} });
+    });
+  });
+
+  describe("Fee cap", () => {
+    after(async () => {
+      await program.methods.setFee(0, 10000).accountsPartial({ admin: admin.publicKey, config: configPda }).rpc();
+    });
+
+    it("Rejects a fee above MAX_FEE_BPS", async () => {
+      try {
+        await program.methods.setFee(1001, 10000).accountsPartial({ admin: admin.publicKey, config: configPda }).rpc();
+        assert.fail("Expected FeeTooHigh");
+      } catch (e) {
+        expectAnchorErrorCode(e, "FeeTooHigh");
+      }
+    });
+
+    it("Rejects a per-mint fee above MAX_FEE_BPS", async () => {
+      try {
+        await program.methods.setMintFee(1001).accountsPartial({ admin: admin.publicKey, config: configPda, mint }).rpc();
+        assert.fail("Expected FeeTooHigh");
+      } catch (e) {
+        expectAnchorErrorCode(e, "FeeTooHigh");
+      }
+    });
+
+    it("Accepts a fee exactly at the cap", async () => {
+      await program.methods.setFee(1000, 10000).accountsPartial({ admin: admin.publicKey, config: configPda }).rpc();
+      assert.equal((await program.account.configState.fetch(configPda)).feeBps, 1000);
+    });
+  });
+
+  describe("Update fee", () => {
+    let original;
+
+    before(async () => {
+      original = await program.account.configState.fetch(configPda);
+    });
+
+    after(async () => {
+      await program.methods.updateFee(original.feeBps, original.treasury)
+        .accountsPartial({ admin: admin.publicKey, config: configPda })
+        .rpc();
+    });
+
+    it("Sets the fee rate and treasury together", async () => {
+      const treasury = anchor.web3.Keypair.generate().publicKey;
+      await program.methods.updateFee(250, treasury)
+        .accountsPartial({ admin: admin.publicKey, config: configPda })
+        .rpc();
+      const config = await program.account.configState.fetch(configPda);
+      assert.equal(config.feeBps, 250);
+      assert.ok(config.treasury.equals(treasury));
+    });
+
+    it("Rejects a fee above MAX_FEE_BPS", async () => {
+      try {
+        await program.methods.updateFee(1001, admin.publicKey)
+          .accountsPartial({ admin: admin.publicKey, config: configPda })
+          .rpc();
+        assert.fail("Expected FeeTooHigh");
+      } catch (e) {
+        expectAnchorErrorCode(e, "FeeTooHigh");
+      }
+    });
+
+    it("Rejects the default pubkey as a fee recipient from every setter", async () => {
+      const attempts = [
+        program.methods.updateFee(0, PublicKey.default),
+        program.methods.setFeeRecipients(PublicKey.default, admin.publicKey),
+        program.methods.setFeeRecipients(admin.publicKey, PublicKey.default),
+      ];
+      for (const attempt of attempts) {
+        try {
+          await attempt.accountsPartial({ admin: admin.publicKey, config: configPda }).rpc();
+          assert.fail("Expected InvalidFeeRecipient");
+        } catch (e) {
+          expectAnchorErrorCode(e, "InvalidFeeRecipient");
+        }
+      }
+      assert.ok((await program.account.configState.fetch(configPda)).treasury.equals(original.treasury));
+    });
+
+    it("Rejects a caller other than the admin", async () => {
+      const stranger = anchor.web3.Keypair.generate();
+      try {
+        await program.methods.updateFee(0, stranger.publicKey)
+          .accountsPartial({ admin: stranger.publicKey, config: configPda })
+          .signers([stranger])
+          .rpc();
+        assert.fail("Expected Unauthorized");
+      } catch (e) {
+        expectAnchorErrorCode(e, "Unauthorized");
+      }
+    });
+  });
+
+  describe("Compressed archive", () => {
+    const tree = anchor.web3.Keypair.generate();
+    const [archiveAuthority] = PublicKey.findProgramAddressSync([Buffer.from("archive_authority")], program.programId);
+
+    before(async () => {
+      await mockProgram.methods.initTree().accountsPartial({
+        payer: wallet.publicKey,
+        authority: archiveAuthority,
+        merkleTree: tree.publicKey,
+        systemProgram: SystemProgram.programId,
+      }).signers([tree]).rpc();
+      await program.methods.setArchiveTree(tree.publicKey).accountsPartial({ admin: admin.publicKey, config: configPda }).rpc();
+    });
+
+    after(async () => {
+      await program.methods.setArchiveTree(null).accountsPartial({ admin: admin.publicKey, config: configPda }).rpc();
+    });
+
+    it("Records the archive tree in the config", async () => {
+      const config = await program.account.configState.fetch(configPda);
+      assert.ok(config.archiveTree.equals(tree.publicKey));
+    });
+
+    function archive(created, rentPayer: PublicKey = maintainer.publicKey) {
+      return program.methods.archiveCompressed(created.bountyId).accountsPartial({
+        admin: admin.publicKey,
+        config: configPda,
+        bounty: created.bounty,
+        rentPayer,
+        archiveAuthority,
+        merkleTree: tree.publicKey,
+        compressionProgram: mockProgram.programId,
+        noopProgram: mockProgram.programId,
+      }).rpc();
+    }
+
+    it("Refuses to archive a bounty that is not completed and leaves the tree untouched", async () => {
+      const created = await createBounty();
+      const rootBefore = (await mockProgram.account.mockTree.fetch(tree.publicKey)).root;
+      try {
+        await archive(created);
+        assert.fail("Expected InvalidBountyStateForOperation");
+      } catch (e) {
+        expectAnchorErrorCode(e, "InvalidBountyStateForOperation");
+      }
+      const after = await mockProgram.account.mockTree.fetch(tree.publicKey);
+      assert.deepEqual(after.root, rootBefore);
+      assert.equal(after.leafCount.toNumber(), 0);
+    });
+
+    // The keeper batch leaves settled bounties Completed, for archiving or close_completed_batch
+    it("Archives a bounty settled by the keeper batch and returns its rent", async () => {
+      const funder = anchor.web3.Keypair.generate();
+      await connection.confirmTransaction(
+        await connection.requestAirdrop(funder.publicKey, anchor.web3.LAMPORTS_PER_SOL),
+        "confirmed"
+      );
+      const worker = anchor.web3.Keypair.generate();
+      const workerAta = await createAssociatedTokenAccount(connection, wallet.payer, mint, worker.publicKey);
+      const created = await createBounty({ rentPayer: funder });
+      await program.methods.assignContributor(false).accountsPartial({
+        maintainer: maintainer.publicKey,
+        bounty: created.bounty,
+        contributor: worker.publicKey,
+        systemProgram: SystemProgram.programId,
+      }).rpc();
+      await program.methods.completeBatch()
+        .accountsPartial({ keeper: admin.publicKey, config: configPda, mint, tokenProgram: TOKEN_PROGRAM_ID })
+        .remainingAccounts([
+          { pubkey: created.bounty, isSigner: false, isWritable: true },
+          { pubkey: created.escrowAuth, isSigner: false, isWritable: false },
+          { pubkey: created.escrowAta, isSigner: false, isWritable: true },
+          { pubkey: workerAta, isSigner: false, isWritable: true },
+        ])
+        .rpc();
+      assert.deepEqual((await program.account.bounty.fetch(created.bounty)).state, { completed: {} });
+
+      const rootBefore = (await mockProgram.account.mockTree.fetch(tree.publicKey)).root;
+      const rent = await connection.getBalance(created.bounty);
+      const funderBefore = await connection.getBalance(funder.publicKey);
+
+      const signature = await archive(created, funder.publicKey);
+
+      const after = await mockProgram.account.mockTree.fetch(tree.publicKey);
+      assert.equal(after.leafCount.toNumber(), 1);
+      assert.notDeepEqual(after.root, rootBefore);
+      assert.isNull(await connection.getAccountInfo(created.bounty));
+      assert.equal((await connection.getBalance(funder.publicKey)) - funderBefore, rent);
+      const archived = (await fetchEvents(signature)).find((e) => e.name === "bountyArchived");
+      assert.equal(archived.data.bountyId.toString(), created.bountyId.toString());
+    });
+  });
+
+  describe("Cancel refund to a closed maintainer ATA", () => {
+    it("Recreates the maintainer's ATA and refunds into it", async () => {
+      const owner = anchor.web3.Keypair.generate();
+      const airdrop = await connection.requestAirdrop(owner.publicKey, anchor.web3.LAMPORTS_PER_SOL);
+      await connection.confirmTransaction(airdrop, "confirmed");
+      const ownerAta = await createAssociatedTokenAccount(connection, wallet.payer, mint, owner.publicKey);
+      await mintTo(connection, wallet.payer, mint, ownerAta, wallet.payer, BOUNTY_AMOUNT.toNumber());
+
+      const id = generateBountyId();
+      const bounty = bountyAddress(id, owner.publicKey);
+      const [escrowAuth] = PublicKey.findProgramAddressSync([Buffer.from("escrow_auth"), bounty.toBuffer()], program.programId);
+      const escrowAta = await getAssociatedTokenAddress(mint, escrowAuth, true);
+      await program.methods.initializeBounty(id, BOUNTY_AMOUNT, 0, futureDeadline(), null, []).accountsPartial({
+        maintainer: owner.publicKey,
+        bounty: bounty,
+        rentPayer: null,
+        maintainerTokenAccount: ownerAta,
+        escrowAuthority: escrowAuth,
+        escrowTokenAccount: escrowAta,
+        mint,
+        systemProgram: SystemProgram.programId,
+        tokenProgram: TOKEN_PROGRAM_ID,
+        associatedTokenProgram: ASSOCIATED_TOKEN_PROGRAM_ID,
+        rent: SYSVAR_RENT_PUBKEY,
+      }).signers([owner]).rpc();
+
+      // The whole balance went into escrow, so the maintainer can close the empty ATA
+      await closeAccount(connection, wallet.payer, ownerAta, owner.publicKey, owner);
+      assert.isNull(await connection.getAccountInfo(ownerAta));
+
+      await program.methods.cancelBounty(null, NO_REASON).accountsPartial({
+        admin: admin.publicKey,
+        config: configPda,
+        bounty: bounty,
+        mint,
+        escrowAuthority: escrowAuth,
+        maintainer: owner.publicKey,
+        maintainerTokenAccount: ownerAta,
+        escrowTokenAccount: escrowAta,
+        systemProgram: SystemProgram.programId,
+        tokenProgram: TOKEN_PROGRAM_ID,
+        associatedTokenProgram: ASSOCIATED_TOKEN_PROGRAM_ID,
+        rent: SYSVAR_RENT_PUBKEY,
+      }).rpc();
+
+      const recreated = await getAccount(connection, ownerAta);
+      assert.ok(recreated.owner.equals(owner.publicKey));
+      assert.equal(Number(recreated.amount), BOUNTY_AMOUNT.toNumber());
+    });
+  });
+
+  describe("Reentrancy lock", () => {
+    before(async () => {
+      await program.methods
+        .setYieldConfig(true, mockProgram.programId)
+        .accountsPartial({ admin: admin.publicKey, config: configPda })
+        .rpc();
+    });
+
+    after(async () => {
+      await program.methods
+        .setYieldConfig(false, mockProgram.programId)
+        .accountsPartial({ admin: admin.publicKey, config: configPda })
+        .rpc();
+    });
+
+    it("Rejects a yield protocol calling back into the bounty mid-deposit", async () => {
+      const { bounty, escrowAuth, escrowAta } = await createBounty();
+      const [vaultAuthority] = PublicKey.findProgramAddressSync(
+        [Buffer.from("vault"), escrowAuth.toBuffer()],
+        mockProgram.programId
+      );
+      const vault = await createAssociatedTokenAccount(connection, wallet.payer, mint, vaultAuthority, undefined, undefined, undefined, true);
+
+      // The hostile mock forwards these to withdraw_from_yield on the same bounty
+      const callback = [
+        { pubkey: program.programId, isSigner: false, isWritable: false },
+        { pubkey: admin.publicKey, isSigner: false, isWritable: false },
+        { pubkey: configPda, isSigner: false, isWritable: true },
+        { pubkey: bounty, isSigner: false, isWritable: true },
+        { pubkey: mint, isSigner: false, isWritable: false },
+        { pubkey: escrowAuth, isSigner: false, isWritable: false },
+        { pubkey: escrowAta, isSigner: false, isWritable: true },
+        { pubkey: maintainerTokenAccount, isSigner: false, isWritable: true },
+        { pubkey: mockProgram.programId, isSigner: false, isWritable: false },
+        { pubkey: TOKEN_PROGRAM_ID, isSigner: false, isWritable: false },
+        { pubkey: vaultAuthority, isSigner: false, isWritable: false },
+        { pubkey: vault, isSigner: false, isWritable: true },
+      ];
+
+      try {
+        await program.methods.depositToYield(BOUNTY_AMOUNT).accountsPartial({
+          admin: admin.publicKey,
+          config: configPda,
+          bounty: bounty,
+          escrowAuthority: escrowAuth,
+          escrowTokenAccount: escrowAta,
+          yieldProgram: mockProgram.programId,
+          tokenProgram: TOKEN_PROGRAM_ID,
+        }).remainingAccounts([
+          { pubkey: vault, isSigner: false, isWritable: true },
+          ...callback,
+        ]).rpc();
+        assert.fail("Reentrant call should have failed");
+      } catch (err) {
+        assert.notInclude(err.toString(), "Reentrant call should have failed");
+      }
+
+      const bountyAccount = await program.account.bounty.fetch(bounty);
+      assert.isFalse(bountyAccount.locked);
+      assert.equal(bountyAccount.yieldPrincipal.toString(), "0");
+      assert.equal((await getAccount(connection, escrowAta)).amount.toString(), BOUNTY_AMOUNT.toString());
+    });
+
+    it("Leaves the bounty unlocked after a fund-moving instruction", async () => {
+      const { bounty, bountyId: id, escrowAuth, escrowAta } = await createBounty();
+
+      await program.methods.increaseBounty(id, new anchor.BN(10)).accountsPartial({
+        maintainer: maintainer.publicKey,
+        bounty: bounty,
+        maintainerTokenAccount,
+        escrowAuthority: escrowAuth,
+        escrowTokenAccount: escrowAta,
+        mint,
+        tokenProgram: TOKEN_PROGRAM_ID,
+      }).rpc();
+
+      const bountyAccount = await program.account.bounty.fetch(bounty);
+      assert.isFalse(bountyAccount.locked);
+    });
+  });
+
+  describe("First completion bonus", () => {
+    const BONUS = 5_000_000;
+    const [bonusReserve] = PublicKey.findProgramAddressSync([Buffer.from("bonus_reserve")], program.programId);
+
+    before(async () => {
+      const tx = new anchor.web3.Transaction().add(
+        SystemProgram.transfer({ fromPubkey: wallet.publicKey, toPubkey: bonusReserve, lamports: anchor.web3.LAMPORTS_PER_SOL })
+      );
+      await provider.sendAndConfirm(tx);
+      await program.methods.setFirstCompletionBonus(new anchor.BN(BONUS))
+        .accountsPartial({ admin: admin.publicKey, config: configPda })
+        .rpc();
+    });
+
+    after(async () => {
+      await program.methods.setFirstCompletionBonus(new anchor.BN(0))
+        .accountsPartial({ admin: admin.publicKey, config: configPda })
+        .rpc();
+    });
+
+    async function assignAndComplete(worker: PublicKey) {
+      const created = await createBounty();
+      await program.methods.assignContributor(false).accountsPartial({
+        maintainer: maintainer.publicKey,
+        bounty: created.bounty,
+        contributor: worker,
+        systemProgram: SystemProgram.programId,
+      }).rpc();
+      await program.methods.completeBounty(created.bountyId, 5, null).accountsPartial({
+        bounty: created.bounty,
+        mint,
+        escrowAuthority: created.escrowAuth,
+        maintainer: maintainer.publicKey,
+        contributor: worker,
+        config: configPda,
+        admin: admin.publicKey,
+        bonusReserve,
+        contributorTokenAccount: await getAssociatedTokenAddress(mint, worker),
+        escrowTokenAccount: created.escrowAta,
+        tokenProgram: TOKEN_PROGRAM_ID,
+        systemProgram: SystemProgram.programId,
+        associatedTokenProgram: ASSOCIATED_TOKEN_PROGRAM_ID,
+      }).rpc();
+    }
+
+    it("Pays the bonus on the first completion only", async () => {
+      const newcomer = anchor.web3.Keypair.generate();
+
+      await assignAndComplete(newcomer.publicKey);
+      assert.equal(await connection.getBalance(newcomer.publicKey), BONUS);
+
+      const reserveBefore = await connection.getBalance(bonusReserve);
+      await assignAndComplete(newcomer.publicKey);
+      assert.equal(await connection.getBalance(newcomer.publicKey), BONUS);
+      assert.equal(await connection.getBalance(bonusReserve), reserveBefore);
+    });
+  });
+
+  describe("Reviewer approvals", () => {
+    const reviewers = [anchor.web3.Keypair.generate(), anchor.web3.Keypair.generate()];
+
+    function approve(created, reviewer: anchor.web3.Keypair) {
+      return program.methods.approveWork(created.bountyId).accountsPartial({
+        reviewer: reviewer.publicKey,
+        bounty: created.bounty,
+      }).signers([reviewer]).rpc();
+    }
+
+    function complete(created, worker: PublicKey) {
+      return program.methods.completeBounty(created.bountyId, 5, null).accountsPartial({
+        bounty: created.bounty,
+        mint,
+        escrowAuthority: created.escrowAuth,
+        maintainer: maintainer.publicKey,
+        contributor: worker,
+        config: configPda,
+        admin: admin.publicKey,
+        escrowTokenAccount: created.escrowAta,
+        tokenProgram: TOKEN_PROGRAM_ID,
+        systemProgram: SystemProgram.programId,
+        associatedTokenProgram: ASSOCIATED_TOKEN_PROGRAM_ID,
+      }).rpc();
+    }
+
+    function setReviewers(created, keys: PublicKey[]) {
+      return program.methods.setReviewers(keys).accountsPartial({
+        maintainer: maintainer.publicKey,
+        bounty: created.bounty,
+      }).rpc();
+    }
+
+    it("Blocks completion until two distinct reviewers approve", async () => {
+      const worker = anchor.web3.Keypair.generate();
+      const created = await createBounty();
+      await setReviewers(created, reviewers.map((r) => r.publicKey));
+      await program.methods.setRequiredApprovals(2).accountsPartial({
+        maintainer: maintainer.publicKey,
+        bounty: created.bounty,
+      }).rpc();
+      await program.methods.assignContributor(false).accountsPartial({
+        maintainer: maintainer.publicKey,
+        bounty: created.bounty,
+        contributor: worker.publicKey,
+        systemProgram: SystemProgram.programId,
+      }).rpc();
+
+      await approve(created, reviewers[0]);
+      try {
+        await complete(created, worker.publicKey);
+        assert.fail("Expected InsufficientApprovals");
+      } catch (e) {
+        expectAnchorErrorCode(e, "InsufficientApprovals");
+      }
+
+      try {
+        await approve(created, reviewers[0]);
+        assert.fail("Expected DuplicateApproval");
+      } catch (e) {
+        expectAnchorErrorCode(e, "DuplicateApproval");
+      }
+
+      await approve(created, reviewers[1]);
+      await complete(created, worker.publicKey);
+      assert.isNull(await connection.getAccountInfo(created.bounty));
+    });
+
+    it("Rejects the contributor approving their own work", async () => {
+      const worker = anchor.web3.Keypair.generate();
+      const airdrop = await connection.requestAirdrop(worker.publicKey, anchor.web3.LAMPORTS_PER_SOL);
+      await connection.confirmTransaction(airdrop, "confirmed");
+      const created = await createBounty();
+      await program.methods.assignContributor(false).accountsPartial({
+        maintainer: maintainer.publicKey,
+        bounty: created.bounty,
+        contributor: worker.publicKey,
+        systemProgram: SystemProgram.programId,
+      }).rpc();
+
+      try {
+        await approve(created, worker);
+        assert.fail("Expected ContributorCannotApprove");
+      } catch (e) {
+        expectAnchorErrorCode(e, "ContributorCannotApprove");
+      }
+    });
+
+    it("Rejects approvals from keys the maintainer didn't list", async () => {
+      const outsider = anchor.web3.Keypair.generate();
+      const created = await createBounty();
+      await setReviewers(created, [reviewers[0].publicKey]);
+      await program.methods.assignContributor(false).accountsPartial({
+        maintainer: maintainer.publicKey,
+        bounty: created.bounty,
+        contributor: contributor.publicKey,
+        systemProgram: SystemProgram.programId,
+      }).rpc();
+
+      try {
+        await approve(created, outsider);
+        assert.fail("Expected ReviewerNotAllowed");
+      } catch (e) {
+        expectAnchorErrorCode(e, "ReviewerNotAllowed");
+      }
+      await approve(created, reviewers[0]);
+    });
+
+    it("Rejects requiring more approvals than there are reviewers", async () => {
+      const created = await createBounty();
+      await setReviewers(created, [reviewers[0].publicKey]);
+      try {
+        await program.methods.setRequiredApprovals(2).accountsPartial({
+          maintainer: maintainer.publicKey,
+          bounty: created.bounty,
+        }).rpc();
+        assert.fail("Expected NotEnoughReviewers");
+      } catch (e) {
+        expectAnchorErrorCode(e, "NotEnoughReviewers");
+      }
+    });
+
+    it("Fixes the approval rules once a contributor is assigned", async () => {
+      const created = await createBounty();
+      await setReviewers(created, reviewers.map((r) => r.publicKey));
+      await program.methods.assignContributor(false).accountsPartial({
+        maintainer: maintainer.publicKey,
+        bounty: created.bounty,
+        contributor: contributor.publicKey,
+        systemProgram: SystemProgram.programId,
+      }).rpc();
+
+      try {
+        await program.methods.setRequiredApprovals(2).accountsPartial({
+          maintainer: maintainer.publicKey,
+          bounty: created.bounty,
+        }).rpc();
+        assert.fail("Expected InvalidBountyStateForOperation");
+      } catch (e) {
+        expectAnchorErrorCode(e, "InvalidBountyStateForOperation");
+      }
+      try {
+        await setReviewers(created, []);
+        assert.fail("Expected InvalidBountyStateForOperation");
+      } catch (e) {
+        expectAnchorErrorCode(e, "InvalidBountyStateForOperation");
+      }
+    });
+  });
+
+  describe("Admin force-release self-payout", () => {
+    it("Rejects the maintainer as the force-release contributor", async () => {
+      const created = await createBounty();
+      try {
+        await program.methods.adminAssignAndRelease(created.bountyId, NO_REASON).accountsPartial({
+          admin: admin.publicKey,
+          config: configPda,
+          bounty: created.bounty,
+          mint,
+          escrowAuthority: created.escrowAuth,
+          maintainer: maintainer.publicKey,
+          contributor: maintainer.publicKey,
+          contributorTokenAccount: maintainerTokenAccount,
+          escrowTokenAccount: created.escrowAta,
+          tokenProgram: TOKEN_PROGRAM_ID,
+          systemProgram: SystemProgram.programId,
+          associatedTokenProgram: ASSOCIATED_TOKEN_PROGRAM_ID,
+        }).rpc();
+        assert.fail("Expected ContributorCannotBeMaintainer");
+      } catch (e) {
+        expectAnchorErrorCode(e, "ContributorCannotBeMaintainer");
+      }
+
+      assert.equal((await getAccount(connection, created.escrowAta)).amount.toString(), BOUNTY_AMOUNT.toString());
+    });
+
+    it("Rejects the maintainer assigning themselves", async () => {
+      const created = await createBounty();
+      try {
+        await program.methods.assignContributor(false).accountsPartial({
+          maintainer: maintainer.publicKey,
+          bounty: created.bounty,
+          contributor: maintainer.publicKey,
+          systemProgram: SystemProgram.programId,
+        }).rpc();
+        assert.fail("Expected ContributorCannotBeMaintainer");
+      } catch (e) {
+        expectAnchorErrorCode(e, "ContributorCannotBeMaintainer");
+      }
+    });
+  });
+
+  describe("Upgrade announcement", () => {
+    it("Records the announced upgrade authority and effective time", async () => {
+      const newAuthority = anchor.web3.Keypair.generate().publicKey;
+      const effectiveAt = new anchor.BN(Math.floor(Date.now() / 1000) + 7 * 24 * 3600);
+
+      await program.methods.announceUpgrade(newAuthority, effectiveAt)
+        .accountsPartial({ admin: admin.publicKey, config: configPda })
+        .rpc();
+
+      const config = await program.account.configState.fetch(configPda);
+      assert.ok(config.announcedUpgradeAuthority.equals(newAuthority));
+      assert.equal(config.upgradeEffectiveAt.toString(), effectiveAt.toString());
+    });
+
+    it("Rejects an effective time in the past", async () => {
+      try {
+        await program.methods.announceUpgrade(admin.publicKey, new anchor.BN(1))
+          .accountsPartial({ admin: admin.publicKey, config: configPda })
+          .rpc();
+        assert.fail("Expected InvalidEffectiveTime");
+      } catch (e) {
+        expectAnchorErrorCode(e, "InvalidEffectiveTime");
+      }
+    });
+  });
+
+  describe("Contributor donation split", () => {
+    const charity = anchor.web3.Keypair.generate();
+    let charityAta: PublicKey;
+
+    before(async () => {
+      charityAta = await createAssociatedTokenAccount(connection, wallet.payer, mint, charity.publicKey);
+      await program.methods.setCharity(charity.publicKey)
+        .accountsPartial({ admin: admin.publicKey, config: configPda })
+        .rpc();
+    });
+
+    after(async () => {
+      await program.methods.setCharity(null)
+        .accountsPartial({ admin: admin.publicKey, config: configPda })
+        .rpc();
+    });
+
+    async function completeWithDonation(donateBps: number) {
+      const worker = anchor.web3.Keypair.generate();
+      const workerAta = await getAssociatedTokenAddress(mint, worker.publicKey);
+      const created = await createBounty();
+      await program.methods.assignContributor(false).accountsPartial({
+        maintainer: maintainer.publicKey,
+        bounty: created.bounty,
+        contributor: worker.publicKey,
+        systemProgram: SystemProgram.programId,
+      }).rpc();
+      await program.methods.setDonateBps(donateBps).accountsPartial({
+        contributor: worker.publicKey,
+        bounty: created.bounty,
+      }).signers([worker]).rpc();
+
+      const charityBefore = (await getAccount(connection, charityAta)).amount;
+      await program.methods.completeBounty(created.bountyId, 5, null).accountsPartial({
+        bounty: created.bounty,
+        mint,
+        escrowAuthority: created.escrowAuth,
+        maintainer: maintainer.publicKey,
+        contributor: worker.publicKey,
+        config: configPda,
+        admin: admin.publicKey,
+        contributorTokenAccount: workerAta,
+        charityTokenAccount: charityAta,
+        escrowTokenAccount: created.escrowAta,
+        tokenProgram: TOKEN_PROGRAM_ID,
+        systemProgram: SystemProgram.programId,
+        associatedTokenProgram: ASSOCIATED_TOKEN_PROGRAM_ID,
+      }).rpc();
+
+      const donated = (await getAccount(connection, charityAta)).amount - charityBefore;
+      const paid = (await getAccount(connection, workerAta)).amount;
+      return { donated, paid };
+    }
+
+    it("Sends 10% of the contributor's payout to the charity", async () => {
+      const { donated, paid } = await completeWithDonation(1_000);
+      assert.equal(donated.toString(), ((donated + paid) * 1_000n / 10_000n).toString());
+      assert.isTrue(donated > 0n);
+    });
+
+    it("Pays the contributor in full with a 0% donation", async () => {
+      const { donated, paid } = await completeWithDonation(0);
+      assert.equal(donated.toString(), "0");
+      assert.isTrue(paid > 0n);
+    });
+
+    async function donatingBounty(worker: Keypair, donateBps: number) {
+      const created = await createBounty();
+      await program.methods.assignContributor(false).accountsPartial({
+        maintainer: maintainer.publicKey,
+        bounty: created.bounty,
+        contributor: worker.publicKey,
+        systemProgram: SystemProgram.programId,
+      }).rpc();
+      await program.methods.setDonateBps(donateBps).accountsPartial({
+        contributor: worker.publicKey,
+        bounty: created.bounty,
+      }).signers([worker]).rpc();
+      return created;
+    }
+
+    function releasePartial(created, worker: Keypair, workerAta: PublicKey, charityTokenAccount: PublicKey | null) {
+      return program.methods.releasePartial(created.bountyId, new anchor.BN(4000)).accountsPartial({
+        maintainer: maintainer.publicKey,
+        bounty: created.bounty,
+        mint,
+        escrowAuthority: created.escrowAuth,
+        contributor: worker.publicKey,
+        config: configPda,
+        contributorTokenAccount: workerAta,
+        escrowTokenAccount: created.escrowAta,
+        charityTokenAccount,
+        tokenProgram: TOKEN_PROGRAM_ID,
+      }).rpc();
+    }
+
+    it("Donates from a partial release too", async () => {
+      const worker = anchor.web3.Keypair.generate();
+      const workerAta = await createAssociatedTokenAccount(connection, wallet.payer, mint, worker.publicKey);
+      const created = await donatingBounty(worker, 1_000);
+
+      const charityBefore = (await getAccount(connection, charityAta)).amount;
+      await releasePartial(created, worker, workerAta, charityAta);
+
+      const donated = (await getAccount(connection, charityAta)).amount - charityBefore;
+      const paid = (await getAccount(connection, workerAta)).amount;
+      assert.isTrue(donated > 0n);
+      assert.equal(donated.toString(), ((donated + paid) * 1_000n / 10_000n).toString());
+    });
+
+    it("Rejects a partial release of a donating bounty without the charity account", async () => {
+      const worker = anchor.web3.Keypair.generate();
+      const workerAta = await createAssociatedTokenAccount(connection, wallet.payer, mint, worker.publicKey);
+      const created = await donatingBounty(worker, 1_000);
+      try {
+        await releasePartial(created, worker, workerAta, null);
+        assert.fail("Expected CharityAccountMissing");
+      } catch (e) {
+        expectAnchorErrorCode(e, "CharityAccountMissing");
+      }
+    });
+
+    it("Donates when an admin force-release settles the bounty", async () => {
+      const worker = anchor.web3.Keypair.generate();
+      const workerAta = await createAssociatedTokenAccount(connection, wallet.payer, mint, worker.publicKey);
+      const created = await donatingBounty(worker, 1_000);
+
+      const charityBefore = (await getAccount(connection, charityAta)).amount;
+      await program.methods.adminAssignAndRelease(created.bountyId, NO_REASON).accountsPartial({
+        admin: admin.publicKey,
+        config: configPda,
+        bounty: created.bounty,
+        mint,
+        escrowAuthority: created.escrowAuth,
+        maintainer: maintainer.publicKey,
+        contributor: worker.publicKey,
+        contributorTokenAccount: workerAta,
+        escrowTokenAccount: created.escrowAta,
+        charityTokenAccount: charityAta,
+        tokenProgram: TOKEN_PROGRAM_ID,
+        systemProgram: SystemProgram.programId,
+        associatedTokenProgram: ASSOCIATED_TOKEN_PROGRAM_ID,
+      }).rpc();
+
+      const donated = (await getAccount(connection, charityAta)).amount - charityBefore;
+      const paid = (await getAccount(connection, workerAta)).amount;
+      assert.isTrue(donated > 0n);
+      assert.equal(donated.toString(), ((donated + paid) * 1_000n / 10_000n).toString());
+    });
+
+    it("Settles a donating bounty in a keeper batch given the charity account", async () => {
+      const worker = anchor.web3.Keypair.generate();
+      const workerAta = await createAssociatedTokenAccount(connection, wallet.payer, mint, worker.publicKey);
+      const created = await donatingBounty(worker, 1_000);
+      const remaining = [
+        { pubkey: created.bounty, isSigner: false, isWritable: true },
+        { pubkey: created.escrowAuth, isSigner: false, isWritable: false },
+        { pubkey: created.escrowAta, isSigner: false, isWritable: true },
+        { pubkey: workerAta, isSigner: false, isWritable: true },
+      ];
+
+      // Without it the bounty is skipped rather than paid in full
+      let signature = await program.methods.completeBatch()
+        .accountsPartial({ keeper: admin.publicKey, config: configPda, mint, charityTokenAccount: null, tokenProgram: TOKEN_PROGRAM_ID })
+        .remainingAccounts(remaining)
+        .rpc();
+      assert.isEmpty((await fetchEvents(signature)).filter((e) => e.name === "bountyCompleted"));
+
+      const charityBefore = (await getAccount(connection, charityAta)).amount;
+      signature = await program.methods.completeBatch()
+        .accountsPartial({ keeper: admin.publicKey, config: configPda, mint, charityTokenAccount: charityAta, tokenProgram: TOKEN_PROGRAM_ID })
+        .remainingAccounts(remaining)
+        .rpc();
+      assert.lengthOf((await fetchEvents(signature)).filter((e) => e.name === "donationMade"), 1);
+      const donated = (await getAccount(connection, charityAta)).amount - charityBefore;
+      const paid = (await getAccount(connection, workerAta)).amount;
+      assert.equal(donated.toString(), ((donated + paid) * 1_000n / 10_000n).toString());
+    });
+
+    it("Rejects a charity account of another mint", async () => {
+      const otherMint = await createMint(connection, wallet.payer, wallet.publicKey, null, 6);
+      const otherAta = await createAssociatedTokenAccount(connection, wallet.payer, otherMint, charity.publicKey);
+      const worker = anchor.web3.Keypair.generate();
+      const created = await createBounty();
+      await program.methods.assignContributor(false).accountsPartial({
+        maintainer: maintainer.publicKey,
+        bounty: created.bounty,
+        contributor: worker.publicKey,
+        systemProgram: SystemProgram.programId,
+      }).rpc();
+
+      try {
+        await program.methods.completeBounty(created.bountyId, 5, null).accountsPartial({
+          bounty: created.bounty,
+          mint,
+          escrowAuthority: created.escrowAuth,
+          maintainer: maintainer.publicKey,
+          contributor: worker.publicKey,
+          config: configPda,
+          admin: admin.publicKey,
+          charityTokenAccount: otherAta,
+          escrowTokenAccount: created.escrowAta,
+          tokenProgram: TOKEN_PROGRAM_ID,
+          systemProgram: SystemProgram.programId,
+          associatedTokenProgram: ASSOCIATED_TOKEN_PROGRAM_ID,
+        }).rpc();
+        assert.fail("Expected InvalidMint");
+      } catch (e) {
+        expectAnchorErrorCode(e, "InvalidMint");
+      }
+    });
+  });
+
+  describe("Specific error codes", () => {
+    it("Rejects handing admin to the current admin with AdminUnchanged", async () => {
+      try {
+        await program.methods.proposeAdmin(admin.publicKey)
+          .accounts({ admin: admin.publicKey, config: configPda })
+          .rpc();
+        assert.fail("Expected AdminUnchanged");
+      } catch (e) {
+        expectAnchorErrorCode(e, "AdminUnchanged");
+      }
+    });
+
+    it("Rejects the default pubkey as admin with InvalidNewAdmin", async () => {
+      try {
+        await program.methods.proposeAdmin(PublicKey.default)
+          .accounts({ admin: admin.publicKey, config: configPda })
+          .rpc();
+        assert.fail("Expected InvalidNewAdmin");
+      } catch (e) {
+        expectAnchorErrorCode(e, "InvalidNewAdmin");
+      }
+    });
+
+    it("Rejects a bounty id that doesn't match the account with BountyIdMismatch", async () => {
+      const created = await createBounty();
+      try {
+        await program.methods.increaseBounty(created.bountyId.addn(1), new anchor.BN(10)).accountsPartial({
+          maintainer: maintainer.publicKey,
+          bounty: created.bounty,
+          mint,
+          escrowAuthority: created.escrowAuth,
+          escrowTokenAccount: created.escrowAta,
+          maintainerTokenAccount,
+          tokenProgram: TOKEN_PROGRAM_ID,
+        }).rpc();
+        assert.fail("Expected BountyIdMismatch");
+      } catch (e) {
+        expectAnchorErrorCode(e, "BountyIdMismatch");
+      }
+    });
+  });
+
+  describe("Mint whitelist query", () => {
+    let otherMint: PublicKey;
+
+    before(async () => {
+      otherMint = await createMint(connection, wallet.payer, wallet.publicKey, null, 6);
+      await program.methods.whitelistMint()
+        .accountsPartial({ admin: admin.publicKey, config: configPda, mint })
+        .rpc();
+      await program.methods.setMintWhitelistEnabled(true)
+        .accountsPartial({ admin: admin.publicKey, config: configPda })
+        .rpc();
+    });
+
+    after(async () => {
+      await program.methods.setMintWhitelistEnabled(false)
+        .accountsPartial({ admin: admin.publicKey, config: configPda })
+        .rpc();
+      await program.methods.unwhitelistMint()
+        .accountsPartial({ admin: admin.publicKey, config: configPda, mint })
+        .rpc();
+    });
+
+    function isMintAllowed(m: PublicKey): Promise<boolean> {
+      return program.methods.isMintAllowed().accountsPartial({ config: configPda, mint: m }).view();
+    }
+
+    it("Reports a whitelisted mint as allowed", async () => {
+      assert.isTrue(await isMintAllowed(mint));
+    });
+
+    it("Reports a mint without a whitelist entry as not allowed", async () => {
+      assert.isFalse(await isMintAllowed(otherMint));
+    });
+
+    it("Rejects creating a bounty in a mint that is not whitelisted", async () => {
+      const ata = await createAssociatedTokenAccount(connection, wallet.payer, otherMint, maintainer.publicKey);
+      await mintTo(connection, wallet.payer, otherMint, ata, wallet.payer, BOUNTY_AMOUNT.toNumber());
+      const id = generateBountyId();
+      const bounty = bountyAddress(id);
+      const [escrowAuth] = PublicKey.findProgramAddressSync([Buffer.from("escrow_auth"), bounty.toBuffer()], program.programId);
+      try {
+        await program.methods.initializeBounty(id, BOUNTY_AMOUNT, 0, futureDeadline(), null, []).accountsPartial({
+          maintainer: maintainer.publicKey,
+          bounty: bounty,
+          rentPayer: null,
+          maintainerTokenAccount: ata,
+          escrowAuthority: escrowAuth,
+          escrowTokenAccount: await getAssociatedTokenAddress(otherMint, escrowAuth, true),
+          mint: otherMint,
+          systemProgram: SystemProgram.programId,
+          tokenProgram: TOKEN_PROGRAM_ID,
+          associatedTokenProgram: ASSOCIATED_TOKEN_PROGRAM_ID,
+          rent: SYSVAR_RENT_PUBKEY,
+        }).rpc();
+        assert.fail("Expected MintNotWhitelisted");
+      } catch (e) {
+        expectAnchorErrorCode(e, "MintNotWhitelisted");
+      }
+    });
+  });
+
+  describe("Cancel after milestone payouts", () => {
+    it("Refunds only the unpaid milestones and reports the paid count", async () => {
+      const worker = anchor.web3.Keypair.generate();
+      const workerAta = await createAssociatedTokenAccount(connection, wallet.payer, mint, worker.publicKey);
+      const created = await createBounty({
+        milestones: [new anchor.BN(2000), new anchor.BN(3000), new anchor.BN(5000)],
+      });
+      await program.methods.assignContributor(false).accountsPartial({
+        maintainer: maintainer.publicKey,
+        bounty: created.bounty,
+        contributor: worker.publicKey,
+        systemProgram: SystemProgram.programId,
+      }).rpc();
+
+      await program.methods.releaseMilestone(created.bountyId).accountsPartial({
+        maintainer: maintainer.publicKey,
+        bounty: created.bounty,
+        mint,
+        escrowAuthority: created.escrowAuth,
+        contributor: worker.publicKey,
+        config: configPda,
+        contributorTokenAccount: workerAta,
+        escrowTokenAccount: created.escrowAta,
+        tokenProgram: TOKEN_PROGRAM_ID,
+      }).rpc();
+      assert.equal(Number((await getAccount(connection, workerAta)).amount), 2000);
+
+      const maintainerBefore = (await getAccount(connection, maintainerTokenAccount)).amount;
+      const signature = await program.methods.cancelBounty(null, NO_REASON).accountsPartial({
+        admin: admin.publicKey,
+        config: configPda,
+        bounty: created.bounty,
+        mint,
+        escrowAuthority: created.escrowAuth,
+        maintainer: maintainer.publicKey,
+        maintainerTokenAccount,
+        escrowTokenAccount: created.escrowAta,
+        systemProgram: SystemProgram.programId,
+        tokenProgram: TOKEN_PROGRAM_ID,
+        associatedTokenProgram: ASSOCIATED_TOKEN_PROGRAM_ID,
+        rent: SYSVAR_RENT_PUBKEY,
+      }).rpc();
+
+      const refunded = (await getAccount(connection, maintainerTokenAccount)).amount - maintainerBefore;
+      assert.equal(refunded.toString(), "8000");
+
+      const cancelled = (await fetchEvents(signature)).find((e) => e.name === "bountyCancelled");
+      assert.ok(cancelled, "BountyCancelled should be emitted");
+      assert.equal(cancelled.data.amount.toNumber(), 8000);
+      assert.equal(cancelled.data.milestonesPaid, 1);
+    });
+
+    it("Leaves the last milestone to completion", async () => {
+      const worker = anchor.web3.Keypair.generate();
+      const workerAta = await createAssociatedTokenAccount(connection, wallet.payer, mint, worker.publicKey);
+      const created = await createBounty({ milestones: [new anchor.BN(4000), new anchor.BN(6000)] });
+      await program.methods.assignContributor(false).accountsPartial({
+        maintainer: maintainer.publicKey,
+        bounty: created.bounty,
+        contributor: worker.publicKey,
+        systemProgram: SystemProgram.programId,
+      }).rpc();
+
+      const release = () => program.methods.releaseMilestone(created.bountyId).accountsPartial({
+        maintainer: maintainer.publicKey,
+        bounty: created.bounty,
+        mint,
+        escrowAuthority: created.escrowAuth,
+        contributor: worker.publicKey,
+        config: configPda,
+        contributorTokenAccount: workerAta,
+        escrowTokenAccount: created.escrowAta,
+        tokenProgram: TOKEN_PROGRAM_ID,
+      }).rpc();
+
+      await release();
+      try {
+        await release();
+        assert.fail("Expected InvalidMilestones");
+      } catch (e) {
+        expectAnchorErrorCode(e, "InvalidMilestones");
+      }
+    });
+  });
+
+  describe("Reclaimed rent swap", () => {
+    const [reserveAuthority] = PublicKey.findProgramAddressSync([Buffer.from("reserve")], mockProgram.programId);
+
+    before(async () => {
+      const reserve = await getOrCreateAssociatedTokenAccount(connection, wallet.payer, mint, reserveAuthority, true);
+      await mintTo(connection, wallet.payer, mint, reserve.address, wallet.payer, 100_000_000);
+      await program.methods.setAmmProgram(mockProgram.programId).accountsPartial({ admin: admin.publicKey, config: configPda }).rpc();
+    });
+
+    after(async () => {
+      await program.methods.setAmmProgram(null).accountsPartial({ admin: admin.publicKey, config: configPda }).rpc();
+    });
+
+    function swapRent(lamports: number, minOut: number) {
+      return program.methods.swapReclaimedRent(new anchor.BN(lamports), new anchor.BN(minOut)).accountsPartial({
+        maintainer: maintainer.publicKey,
+        config: configPda,
+        mint,
+        maintainerTokenAccount,
+        ammProgram: mockProgram.programId,
+        tokenProgram: TOKEN_PROGRAM_ID,
+        systemProgram: SystemProgram.programId,
+      }).remainingAccounts([
+        { pubkey: reserveAuthority, isWritable: true, isSigner: false },
+        { pubkey: getAssociatedTokenAddressSync(mint, reserveAuthority, true), isWritable: true, isSigner: false },
+      ]).rpc();
+    }
+
+    it("Swaps the rent reclaimed by a cancel into the bounty token", async () => {
+      const created = await createBounty();
+      const lamportsBefore = await connection.getBalance(maintainer.publicKey);
+      await program.methods.cancelBounty(null, NO_REASON).accountsPartial({
+        admin: admin.publicKey,
+        config: configPda,
+        bounty: created.bounty,
+        mint,
+        escrowAuthority: created.escrowAuth,
+        maintainer: maintainer.publicKey,
+        maintainerTokenAccount,
+        escrowTokenAccount: created.escrowAta,
+        systemProgram: SystemProgram.programId,
+        tokenProgram: TOKEN_PROGRAM_ID,
+        associatedTokenProgram: ASSOCIATED_TOKEN_PROGRAM_ID,
+        rent: SYSVAR_RENT_PUBKEY,
+      }).rpc();
+      const reclaimed = (await connection.getBalance(maintainer.publicKey)) - lamportsBefore;
+      assert.isTrue(reclaimed > 0, "cancel should return rent to the maintainer");
+
+      const tokensBefore = (await getAccount(connection, maintainerTokenAccount)).amount;
+      await swapRent(reclaimed, reclaimed);
+      const received = (await getAccount(connection, maintainerTokenAccount)).amount - tokensBefore;
+      assert.equal(received.toString(), reclaimed.toString());
+    });
+
+    it("Rejects a swap that returns less than min_out", async () => {
+      try {
+        await swapRent(1_000, 1_001);
+        assert.fail("Expected SlippageExceeded");
+      } catch (e) {
+        expectAnchorErrorCode(e, "SlippageExceeded");
+      }
+    });
+  });
+
+  describe("Escrow authority validation", () => {
+    it("Rejects a keypair posing as the escrow authority on cancel", async () => {
+      const created = await createBounty();
+      const impostor = anchor.web3.Keypair.generate();
+      try {
+        await program.methods.cancelBounty(null, NO_REASON).accountsPartial({
+          admin: admin.publicKey,
+          config: configPda,
+          bounty: created.bounty,
+          mint,
+          escrowAuthority: impostor.publicKey,
+          maintainer: maintainer.publicKey,
+          maintainerTokenAccount,
+          escrowTokenAccount: created.escrowAta,
+          systemProgram: SystemProgram.programId,
+          tokenProgram: TOKEN_PROGRAM_ID,
+          associatedTokenProgram: ASSOCIATED_TOKEN_PROGRAM_ID,
+          rent: SYSVAR_RENT_PUBKEY,
+        }).signers([impostor]).rpc();
+        assert.fail("Expected the impostor escrow authority to be rejected");
+      } catch (e) {
+        assert.instanceOf(e, AnchorError);
+        assert.include(["ConstraintSeeds", "InvalidEscrowAuthority"], e.error.errorCode.code);
+      }
+      assert.equal((await getAccount(connection, created.escrowAta)).amount.toString(), BOUNTY_AMOUNT.toString());
+    });
+
+    it("Rejects a keypair posing as the escrow authority on admin force-release", async () => {
+      const created = await createBounty();
+      const impostor = anchor.web3.Keypair.generate();
+      const target = anchor.web3.Keypair.generate();
+      const targetAta = await createAssociatedTokenAccount(connection, wallet.payer, mint, target.publicKey);
+      try {
+        await program.methods.adminAssignAndRelease(created.bountyId, NO_REASON).accountsPartial({
+          admin: admin.publicKey,
+          config: configPda,
+          bounty: created.bounty,
+          mint,
+          escrowAuthority: impostor.publicKey,
+          maintainer: maintainer.publicKey,
+          contributor: target.publicKey,
+          contributorTokenAccount: targetAta,
+          escrowTokenAccount: created.escrowAta,
+          tokenProgram: TOKEN_PROGRAM_ID,
+          systemProgram: SystemProgram.programId,
+          associatedTokenProgram: ASSOCIATED_TOKEN_PROGRAM_ID,
+        }).signers([impostor]).rpc();
+        assert.fail("Expected the impostor escrow authority to be rejected");
+      } catch (e) {
+        assert.instanceOf(e, AnchorError);
+        assert.include(["ConstraintSeeds", "InvalidEscrowAuthority"], e.error.errorCode.code);
+      }
+      assert.equal(Number((await getAccount(connection, targetAta)).amount), 0);
+    });
+  });
+
+  describe("Payout in another token", () => {
+    let payoutMint: PublicKey;
+    let reserve: PublicKey;
+    let inVault: PublicKey;
+    const [reserveAuthority] = PublicKey.findProgramAddressSync([Buffer.from("reserve")], mockProgram.programId);
+
+    before(async () => {
+      payoutMint = await createMint(connection, wallet.payer, wallet.publicKey, null, 6);
+      reserve = await createAssociatedTokenAccount(connection, wallet.payer, payoutMint, reserveAuthority, undefined, undefined, undefined, true);
+      await mintTo(connection, wallet.payer, payoutMint, reserve, wallet.payer, 10 * BOUNTY_AMOUNT.toNumber());
+      inVault = await createAssociatedTokenAccount(connection, wallet.payer, mint, anchor.web3.Keypair.generate().publicKey);
+      await program.methods.setAmmProgram(mockProgram.programId).accountsPartial({ admin: admin.publicKey, config: configPda }).rpc();
+    });
+
+    after(async () => {
+      await program.methods.setAmmProgram(null).accountsPartial({ admin: admin.publicKey, config: configPda }).rpc();
+    });
+
+    async function completeInPayoutMint(minOut: anchor.BN) {
+      const worker = anchor.web3.Keypair.generate();
+      const workerAta = await getAssociatedTokenAddress(mint, worker.publicKey);
+      const workerPayoutAta = await createAssociatedTokenAccount(connection, wallet.payer, payoutMint, worker.publicKey);
+      const created = await createBounty();
+      await program.methods.assignContributor(false).accountsPartial({
+        maintainer: maintainer.publicKey,
+        bounty: created.bounty,
+        contributor: worker.publicKey,
+        systemProgram: SystemProgram.programId,
+      }).rpc();
+      await program.methods.setPayoutMint(payoutMint, minOut).accountsPartial({
+        contributor: worker.publicKey,
+        bounty: created.bounty,
+      }).signers([worker]).rpc();
+
+      await program.methods.completeBounty(created.bountyId, 5, null).accountsPartial({
+        bounty: created.bounty,
+        mint,
+        escrowAuthority: created.escrowAuth,
+        maintainer: maintainer.publicKey,
+        contributor: worker.publicKey,
+        config: configPda,
+        admin: admin.publicKey,
+        contributorTokenAccount: workerAta,
+        escrowTokenAccount: created.escrowAta,
+        ammProgram: mockProgram.programId,
+        contributorUnderlyingTokenAccount: workerPayoutAta,
+        tokenProgram: TOKEN_PROGRAM_ID,
+        systemProgram: SystemProgram.programId,
+        associatedTokenProgram: ASSOCIATED_TOKEN_PROGRAM_ID,
+      }).remainingAccounts([
+        { pubkey: inVault, isWritable: true, isSigner: false },
+        { pubkey: reserveAuthority, isWritable: false, isSigner: false },
+        { pubkey: reserve, isWritable: true, isSigner: false },
+      ]).rpc();
+
+      return Number((await getAccount(connection, workerPayoutAta)).amount);
+    }
+
+    it("Swaps the escrow into the contributor's payout token", async () => {
+      const paid = await completeInPayoutMint(BOUNTY_AMOUNT);
+      assert.equal(paid, BOUNTY_AMOUNT.toNumber());
+    });
+
+    it("Reverts the completion when the swap returns less than min_out", async () => {
+      try {
+        await completeInPayoutMint(BOUNTY_AMOUNT.addn(1));
+        assert.fail("Expected SlippageExceeded");
+      } catch (e) {
+        expectAnchorErrorCode(e, "SlippageExceeded");
+      }
+    });
+  });
+
+  describe("Offending account in validation logs", () => {
+    it("Logs the token account whose mint is wrong", async () => {
+      const worker = anchor.web3.Keypair.generate();
+      const otherMint = await createMint(connection, wallet.payer, wallet.publicKey, null, 6);
+      const wrongAta = await createAssociatedTokenAccount(connection, wallet.payer, otherMint, worker.publicKey);
+      const created = await createBounty();
+      try {
+        await program.methods.assignContributor(false).accountsPartial({
+          maintainer: maintainer.publicKey,
+          bounty: created.bounty,
+          contributor: worker.publicKey,
+          contributorTokenAccount: wrongAta,
+          systemProgram: SystemProgram.programId,
+        }).rpc();
+        assert.fail("Expected InvalidMint");
+      } catch (e) {
+        expectAnchorErrorCode(e, "InvalidMint");
+        const logs: string[] = e.logs;
+        assert.isTrue(
+          logs.some((line) => line.includes(wrongAta.toBase58()) && line.includes(otherMint.toBase58())),
+          "the offending token account and its mint should be logged"
+        );
+      }
+    });
+
+    it("Logs the token account whose owner is wrong", async () => {
+      const worker = anchor.web3.Keypair.generate();
+      const stranger = anchor.web3.Keypair.generate();
+      const strangerAta = await createAssociatedTokenAccount(connection, wallet.payer, mint, stranger.publicKey);
+      const created = await createBounty();
+      try {
+        await program.methods.assignContributor(false).accountsPartial({
+          maintainer: maintainer.publicKey,
+          bounty: created.bounty,
+          contributor: worker.publicKey,
+          contributorTokenAccount: strangerAta,
+          systemProgram: SystemProgram.programId,
+        }).rpc();
+        assert.fail("Expected InvalidTokenAccount");
+      } catch (e) {
+        expectAnchorErrorCode(e, "InvalidTokenAccount");
+        const logs: string[] = e.logs;
+        assert.isTrue(logs.some((line) => line.includes(strangerAta.toBase58())), "the offending token account should be logged");
+      }
+    });
+  });
+
+  describe("Contributor acknowledgement", () => {
+    async function assignedBounty(requireAck: boolean) {
+      const worker = anchor.web3.Keypair.generate();
+      const created = await createBounty();
+      await program.methods.setRequireContributorAck(requireAck).accountsPartial({
+        maintainer: maintainer.publicKey,
+        bounty: created.bounty,
+      }).rpc();
+      await program.methods.assignContributor(false).accountsPartial({
+        maintainer: maintainer.publicKey,
+        bounty: created.bounty,
+        contributor: worker.publicKey,
+        systemProgram: SystemProgram.programId,
+      }).rpc();
+      return { created, worker };
+    }
+
+    function completeIx(created, worker: PublicKey) {
+      return program.methods.completeBounty(created.bountyId, 5, null).accountsPartial({
+        bounty: created.bounty,
+        mint,
+        escrowAuthority: created.escrowAuth,
+        maintainer: maintainer.publicKey,
+        contributor: worker,
+        config: configPda,
+        admin: admin.publicKey,
+        escrowTokenAccount: created.escrowAta,
+        tokenProgram: TOKEN_PROGRAM_ID,
+        systemProgram: SystemProgram.programId,
+        associatedTokenProgram: ASSOCIATED_TOKEN_PROGRAM_ID,
+      }).instruction();
+    }
+
+    it("Requires the contributor to co-sign when the flag is set", async () => {
+      const { created, worker } = await assignedBounty(true);
+
+      try {
+        await provider.sendAndConfirm(new Transaction().add(await completeIx(created, worker.publicKey)));
+        assert.fail("Expected ContributorAckRequired");
+      } catch (e) {
+        expect(e.logs.join("\n")).to.include("ContributorAckRequired");
+      }
+
+      // The contributor is an unchecked account in the IDL, so mark it as a signer by hand
+      const ix = await completeIx(created, worker.publicKey);
+      ix.keys.find((key) => key.pubkey.equals(worker.publicKey)).isSigner = true;
+      await provider.sendAndConfirm(new Transaction().add(ix), [worker]);
+      assert.isNull(await connection.getAccountInfo(created.bounty));
+    });
+
+    it("Completes without the contributor's signature when the flag is off", async () => {
+      const { created, worker } = await assignedBounty(false);
+      await provider.sendAndConfirm(new Transaction().add(await completeIx(created, worker.publicKey)));
+      assert.isNull(await connection.getAccountInfo(created.bounty));
+    });
+  });
+
+  describe("Maximum deadline duration", () => {
+    const cap = 7 * 86400;
+
+    function setMaxDeadlineDuration(seconds: number) {
+      return program.methods.setMaxDeadlineDuration(new anchor.BN(seconds)).accountsPartial({
+        admin: admin.publicKey,
+        config: configPda,
+      }).rpc();
+    }
+
+    async function chainNow() {
+      return connection.getBlockTime(await connection.getSlot("confirmed"));
+    }
+
+    before(async () => {
+      await setMaxDeadlineDuration(cap);
+    });
+
+    after(async () => {
+      await setMaxDeadlineDuration(0);
+    });
+
+    it("Rejects a deadline past the cap", async () => {
+      const now = await chainNow();
+      try {
+        await createBounty({ deadline: new anchor.BN(now + cap + 3600) });
+        assert.fail("Expected DeadlineTooFar");
+      } catch (e) {
+        expectAnchorErrorCode(e, "DeadlineTooFar");
+      }
+    });
+
+    it("Accepts a deadline exactly at the cap", async () => {
+      // The clock only moves forward before the instruction runs, so this stays within the cap
+      const deadline = new anchor.BN((await chainNow()) + cap);
+      const created = await createBounty({ deadline });
+      const bounty = await program.account.bounty.fetch(created.bounty);
+      assert.isTrue(bounty.deadline.eq(deadline));
+    });
+  });
+
+  describe("State transition events", () => {
+    async function transition(signature: string) {
+      const events = (await fetchEvents(signature)).filter((e) => e.name === "stateTransition");
+      assert.lengthOf(events, 1, "exactly one StateTransition per instruction");
+      return events[0].data;
+    }
+
+    function assign(created, worker: PublicKey) {
+      return program.methods.assignContributor(false).accountsPartial({
+        maintainer: maintainer.publicKey,
+        bounty: created.bounty,
+        contributor: worker,
+        systemProgram: SystemProgram.programId,
+      }).rpc();
+    }
+
+    it("Emits Created -> InProgress on assign and InProgress -> Completed on complete", async () => {
+      const worker = anchor.web3.Keypair.generate();
+      const created = await createBounty();
+
+      const assigned = await transition(await assign(created, worker.publicKey));
+      assert.equal(assigned.bountyId.toString(), created.bountyId.toString());
+      assert.deepEqual(assigned.fromState, { created: {} });
+      assert.deepEqual(assigned.toState, { inProgress: {} });
+
+      const signature = await program.methods.completeBounty(created.bountyId, 5, null).accountsPartial({
+        bounty: created.bounty,
+        mint,
+        escrowAuthority: created.escrowAuth,
+        maintainer: maintainer.publicKey,
+        contributor: worker.publicKey,
+        config: configPda,
+        admin: admin.publicKey,
+        escrowTokenAccount: created.escrowAta,
+        tokenProgram: TOKEN_PROGRAM_ID,
+        systemProgram: SystemProgram.programId,
+        associatedTokenProgram: ASSOCIATED_TOKEN_PROGRAM_ID,
+      }).rpc();
+      const completed = await transition(signature);
+      assert.deepEqual(completed.fromState, { inProgress: {} });
+      assert.deepEqual(completed.toState, { completed: {} });
+    });
+
+    it("Emits the bounty's prior state on cancel", async () => {
+      const worker = anchor.web3.Keypair.generate();
+      const open = await createBounty();
+      const assigned = await createBounty();
+      await assign(assigned, worker.publicKey);
+
+      for (const [created, from] of [[open, { created: {} }], [assigned, { inProgress: {} }]] as const) {
+        const signature = await program.methods.cancelBounty(null, NO_REASON).accountsPartial({
+          admin: admin.publicKey,
+          config: configPda,
+          bounty: created.bounty,
+          escrowAuthority: created.escrowAuth,
+          maintainer: maintainer.publicKey,
+          maintainerTokenAccount: maintainerTokenAccount,
+          escrowTokenAccount: created.escrowAta,
+          systemProgram: SystemProgram.programId,
+          tokenProgram: TOKEN_PROGRAM_ID,
+          associatedTokenProgram: ASSOCIATED_TOKEN_PROGRAM_ID,
+          rent: SYSVAR_RENT_PUBKEY,
+        }).rpc();
+        const cancelled = await transition(signature);
+        assert.equal(cancelled.bountyId.toString(), created.bountyId.toString());
+        assert.deepEqual(cancelled.fromState, from);
+        assert.deepEqual(cancelled.toState, { cancelled: {} });
+      }
+    });
+  });
+
+  describe("Escrow dust on split payouts", () => {
+    it("Pays rounding remainders and stray escrow tokens to the contributor on an indivisible split", async () => {
+      const treasuryOwner = anchor.web3.Keypair.generate();
+      const stakerOwner = anchor.web3.Keypair.generate();
+      const treasuryAta = await createAssociatedTokenAccount(connection, wallet.payer, mint, treasuryOwner.publicKey);
+      const stakerAta = await createAssociatedTokenAccount(connection, wallet.payer, mint, stakerOwner.publicKey);
+
+      await program.methods.setFee(333, 7000).accountsPartial({ admin: admin.publicKey, config: configPda }).rpc();
+      await program.methods
+        .setFeeRecipients(treasuryOwner.publicKey, stakerOwner.publicKey)
+        .accountsPartial({ admin: admin.publicKey, config: configPda })
+        .rpc();
+
+      try {
+        const created = await createBounty({ amount: new anchor.BN(10_001) });
+        // Stray tokens sent straight to the escrow on top of the bounty amount
+        await transfer(connection, wallet.payer, maintainerTokenAccount, created.escrowAta, wallet.payer, 7);
+        const target = anchor.web3.Keypair.generate();
+        const targetAta = await createAssociatedTokenAccount(connection, wallet.payer, mint, target.publicKey);
+
+        const signature = await program.methods.adminAssignAndRelease(created.bountyId, NO_REASON).accountsPartial({
+          admin: admin.publicKey,
+          config: configPda,
+          bounty: created.bounty,
+          mint,
+          escrowAuthority: created.escrowAuth,
+          maintainer: maintainer.publicKey,
+          contributor: target.publicKey,
+          contributorTokenAccount: targetAta,
+          escrowTokenAccount: created.escrowAta,
+          treasuryTokenAccount: treasuryAta,
+          stakerTokenAccount: stakerAta,
+          tokenProgram: TOKEN_PROGRAM_ID,
+          systemProgram: SystemProgram.programId,
+          associatedTokenProgram: ASSOCIATED_TOKEN_PROGRAM_ID,
+        }).rpc();
+
+        // 3.33% of 10_001 rounds down to 333, split 70/30 as 233/100; the contributor gets the rest plus the 7 stray tokens
+        assert.equal((await getAccount(connection, treasuryAta)).amount.toString(), "233");
+        assert.equal((await getAccount(connection, stakerAta)).amount.toString(), "100");
+        assert.equal((await getAccount(connection, targetAta)).amount.toString(), (10_001 - 333 + 7).toString());
+        assert.isNull(await connection.getAccountInfo(created.escrowAta), "escrow should be emptied and closed");
+
+        const swept = (await fetchEvents(signature)).find((e) => e.name === "dustSwept");
+        assert.ok(swept, "DustSwept should be emitted");
+        assert.equal(swept.data.amount.toString(), "7");
+      } finally {
+        await program.methods.setFee(0, 10000).accountsPartial({ admin: admin.publicKey, config: configPda }).rpc();
+        await program.methods
+          .setFeeRecipients(admin.publicKey, admin.publicKey)
+          .accountsPartial({ admin: admin.publicKey, config: configPda })
+          .rpc();
+      }
+    });
+
+    it("Completes a bounty whose escrow holds more than the bounty amount", async () => {
+      const worker = anchor.web3.Keypair.generate();
+      const created = await createBounty();
+      await transfer(connection, wallet.payer, maintainerTokenAccount, created.escrowAta, wallet.payer, 3);
+      await program.methods.assignContributor(false).accountsPartial({
+        maintainer: maintainer.publicKey,
+        bounty: created.bounty,
+        contributor: worker.publicKey,
+        systemProgram: SystemProgram.programId,
+      }).rpc();
+
+      await program.methods.completeBounty(created.bountyId, 5, null).accountsPartial({
+        bounty: created.bounty,
+        mint,
+        escrowAuthority: created.escrowAuth,
+        maintainer: maintainer.publicKey,
+        contributor: worker.publicKey,
+        config: configPda,
+        admin: admin.publicKey,
+        escrowTokenAccount: created.escrowAta,
+        tokenProgram: TOKEN_PROGRAM_ID,
+        systemProgram: SystemProgram.programId,
+        associatedTokenProgram: ASSOCIATED_TOKEN_PROGRAM_ID,
+      }).rpc();
+
+      const workerAta = getAssociatedTokenAddressSync(mint, worker.publicKey);
+      assert.equal((await getAccount(connection, workerAta)).amount.toString(), BOUNTY_AMOUNT.addn(3).toString());
+      assert.isNull(await connection.getAccountInfo(created.escrowAta), "no tokens should be stranded in escrow");
+    });
+  });
+
+  describe("Relaunching a closed bounty", () => {
+    it("Funds a fresh bounty that references the old bounty id", async () => {
+      const old = await createBounty();
+      await program.methods.cancelBounty(null, NO_REASON).accountsPartial({
+        admin: admin.publicKey,
+        config: configPda,
+        bounty: old.bounty,
+        escrowAuthority: old.escrowAuth,
+        maintainer: maintainer.publicKey,
+        maintainerTokenAccount: maintainerTokenAccount,
+        escrowTokenAccount: old.escrowAta,
+        systemProgram: SystemProgram.programId,
+        tokenProgram: TOKEN_PROGRAM_ID,
+        associatedTokenProgram: ASSOCIATED_TOKEN_PROGRAM_ID,
+        rent: SYSVAR_RENT_PUBKEY,
+      }).rpc();
+      assert.isNull(await connection.getAccountInfo(old.bounty));
+
+      // The relaunch gets its own id, and with it a fresh address
+      const newBountyId = generateBountyId();
+      const bounty = bountyAddress(newBountyId);
+      const [escrowAuth] = anchor.web3.PublicKey.findProgramAddressSync(
+        [Buffer.from("escrow_auth"), bounty.toBuffer()],
+        program.programId
+      );
+      const escrowAta = await getAssociatedTokenAddress(mint, escrowAuth, true);
+      const deadline = futureDeadline(2 * 86400);
+      const amount = new anchor.BN(2500);
+
+      const signature = await program.methods.relaunchBounty(newBountyId, old.bountyId, deadline, amount).accountsPartial({
+        maintainer: maintainer.publicKey,
+        bounty: bounty,
+        rentPayer: null,
+        maintainerTokenAccount: maintainerTokenAccount,
+        escrowAuthority: escrowAuth,
+        escrowTokenAccount: escrowAta,
+        mint,
+        systemProgram: SystemProgram.programId,
+        tokenProgram: TOKEN_PROGRAM_ID,
+        associatedTokenProgram: ASSOCIATED_TOKEN_PROGRAM_ID,
+        rent: SYSVAR_RENT_PUBKEY,
+      }).rpc();
+
+      const relaunched = await program.account.bounty.fetch(bounty);
+      assert.equal(relaunched.relaunchedFrom.toString(), old.bountyId.toString());
+      assert.equal(relaunched.bountyId.toString(), newBountyId.toString());
+      assert.notEqual(relaunched.bountyId.toString(), old.bountyId.toString());
+      assert.isFalse(bounty.equals(old.bounty));
+      assert.deepEqual(relaunched.state, { created: {} });
+      assert.isTrue(relaunched.deadline.eq(deadline));
+      assert.equal((await getAccount(connection, escrowAta)).amount.toString(), amount.toString());
+
+      const event = (await fetchEvents(signature)).find((e) => e.name === "bountyRelaunched");
+      assert.ok(event, "BountyRelaunched should be emitted");
+      assert.equal(event.data.oldBountyId.toString(), old.bountyId.toString());
+      assert.equal(event.data.bountyId.toString(), newBountyId.toString());
+      assert.isTrue(event.data.bounty.equals(bounty));
+    });
+
+    it("Leaves relaunched_from unset on a bounty created directly", async () => {
+      const created = await createBounty();
+      const bounty = await program.account.bounty.fetch(created.bounty);
+      assert.isNull(bounty.relaunchedFrom);
+    });
+  });
+
+  describe("Allowed keeper set", () => {
+    const keepers = [anchor.web3.Keypair.generate(), anchor.web3.Keypair.generate()];
+
+    async function restrictedBounty(worker: PublicKey) {
+      const created = await createBounty();
+      await program.methods.setAllowedKeepers(keepers.map((k) => k.publicKey)).accountsPartial({
+        maintainer: maintainer.publicKey,
+        bounty: created.bounty,
+      }).rpc();
+      await program.methods.assignContributor(false).accountsPartial({
+        maintainer: maintainer.publicKey,
+        bounty: created.bounty,
+        contributor: worker,
+        systemProgram: SystemProgram.programId,
+      }).rpc();
+      return created;
+    }
+
+    function complete(created, worker: PublicKey, keeper: Keypair | null) {
+      const builder = program.methods.completeBounty(created.bountyId, 5, null).accountsPartial({
+        bounty: created.bounty,
+        mint,
+        escrowAuthority: created.escrowAuth,
+        maintainer: maintainer.publicKey,
+        contributor: worker,
+        config: configPda,
+        admin: admin.publicKey,
+        keeper: keeper ? keeper.publicKey : null,
+        escrowTokenAccount: created.escrowAta,
+        tokenProgram: TOKEN_PROGRAM_ID,
+        systemProgram: SystemProgram.programId,
+        associatedTokenProgram: ASSOCIATED_TOKEN_PROGRAM_ID,
+      });
+      return (keeper ? builder.signers([keeper]) : builder).rpc();
+    }
+
+    it("Lets each keeper in the set settle", async () => {
+      for (const keeper of keepers) {
+        const worker = anchor.web3.Keypair.generate();
+        const created = await restrictedBounty(worker.publicKey);
+        await complete(created, worker.publicKey, keeper);
+        assert.isNull(await connection.getAccountInfo(created.bounty));
+      }
+    });
+
+    it("Rejects a keeper outside the set, or no keeper at all", async () => {
+      const worker = anchor.web3.Keypair.generate();
+      const created = await restrictedBounty(worker.publicKey);
+
+      for (const keeper of [anchor.web3.Keypair.generate(), null]) {
+        try {
+          await complete(created, worker.publicKey, keeper);
+          assert.fail("Expected KeeperNotAllowed");
+        } catch (e) {
+          expectAnchorErrorCode(e, "KeeperNotAllowed");
+        }
+      }
+    });
+
+    it("Bounds the size of the set", async () => {
+      const created = await createBounty();
+      const tooMany = Array.from({ length: 6 }, () => anchor.web3.Keypair.generate().publicKey);
+      try {
+        await program.methods.setAllowedKeepers(tooMany).accountsPartial({
+          maintainer: maintainer.publicKey,
+          bounty: created.bounty,
+        }).rpc();
+        assert.fail("Expected TooManyKeepers");
+      } catch (e) {
+        expectAnchorErrorCode(e, "TooManyKeepers");
+      }
+    });
+  });
+
+  describe("Crowd-funded cancel in batches", () => {
+    async function newFunder(amount: number) {
+      const funder = anchor.web3.Keypair.generate();
+      await connection.confirmTransaction(
+        await connection.requestAirdrop(funder.publicKey, anchor.web3.LAMPORTS_PER_SOL),
+        "confirmed"
+      );
+      const funderAta = await createAssociatedTokenAccount(connection, wallet.payer, mint, funder.publicKey);
+      await mintTo(connection, wallet.payer, mint, funderAta, wallet.payer, amount);
+      return { funder, funderAta };
+    }
+
+    function contributionPda(bounty: PublicKey, funder: PublicKey) {
+      return anchor.web3.PublicKey.findProgramAddressSync(
+        [Buffer.from("contribution"), bounty.toBuffer(), funder.toBuffer()],
+        program.programId
+      )[0];
+    }
+
+    function refundBatch(created, funders: { funder: Keypair; funderAta: PublicKey }[]) {
+      return program.methods.refundFundersBatch(created.bountyId).accountsPartial({
+        admin: admin.publicKey,
+        config: configPda,
+        bounty: created.bounty,
+        mint,
+        escrowAuthority: created.escrowAuth,
+        maintainer: maintainer.publicKey,
+        rentPayer: maintainer.publicKey,
+        maintainerTokenAccount: maintainerTokenAccount,
+        escrowTokenAccount: created.escrowAta,
+        tokenProgram: TOKEN_PROGRAM_ID,
+      }).remainingAccounts(funders.flatMap(({ funder, funderAta }) => [
+        { pubkey: contributionPda(created.bounty, funder.publicKey), isWritable: true, isSigner: false },
+        { pubkey: funderAta, isWritable: true, isSigner: false },
+        { pubkey: funder.publicKey, isWritable: true, isSigner: false },
+      ])).rpc();
+    }
+
+    it("Refunds five funders across two calls and finalizes the cancel on the last", async () => {
+      const created = await createBounty();
+      const funders = [];
+      for (let i = 0; i < 5; i++) {
+        const amount = 100 * (i + 1);
+        const f = await newFunder(amount);
+        await program.methods.fundBounty(created.bountyId, new anchor.BN(amount)).accountsPartial({
+          funder: f.funder.publicKey,
+          bounty: created.bounty,
+          mint,
+          escrowAuthority: created.escrowAuth,
+          escrowTokenAccount: created.escrowAta,
+          funderTokenAccount: f.funderAta,
+          tokenProgram: TOKEN_PROGRAM_ID,
+          systemProgram: SystemProgram.programId,
+        }).signers([f.funder]).rpc();
+        funders.push({ ...f, amount });
+      }
+
+      let bounty = await program.account.bounty.fetch(created.bounty);
+      assert.equal(bounty.funderCount, 5);
+      assert.equal(bounty.amount.toString(), BOUNTY_AMOUNT.addn(1500).toString());
+
+      // A plain cancel would send every funder's tokens to the maintainer
+      try {
+        await program.methods.cancelBounty(null, NO_REASON).accountsPartial({
+          admin: admin.publicKey,
+          config: configPda,
+          bounty: created.bounty,
+          escrowAuthority: created.escrowAuth,
+          maintainer: maintainer.publicKey,
+          maintainerTokenAccount: maintainerTokenAccount,
+          escrowTokenAccount: created.escrowAta,
+          systemProgram: SystemProgram.programId,
+          tokenProgram: TOKEN_PROGRAM_ID,
+          associatedTokenProgram: ASSOCIATED_TOKEN_PROGRAM_ID,
+          rent: SYSVAR_RENT_PUBKEY,
+        }).rpc();
+        assert.fail("Expected FundersNotRefunded");
+      } catch (e) {
+        expectAnchorErrorCode(e, "FundersNotRefunded");
+      }
+
+      const maintainerBefore = (await getAccount(connection, maintainerTokenAccount)).amount;
+
+      await refundBatch(created, funders.slice(0, 3));
+      bounty = await program.account.bounty.fetch(created.bounty);
+      assert.equal(bounty.refundedCount, 3);
+      assert.deepEqual(bounty.state, { created: {} }, "cancel is not final until every funder is refunded");
+      for (const { funderAta, amount } of funders.slice(0, 3)) {
+        assert.equal((await getAccount(connection, funderAta)).amount.toString(), amount.toString());
+      }
+
+      const signature = await refundBatch(created, funders.slice(3));
+      for (const { funder, funderAta, amount } of funders) {
+        assert.equal((await getAccount(connection, funderAta)).amount.toString(), amount.toString());
+        assert.isNull(await connection.getAccountInfo(contributionPda(created.bounty, funder.publicKey)));
+      }
+      assert.isNull(await connection.getAccountInfo(created.bounty), "bounty closed on the final batch");
+      assert.isNull(await connection.getAccountInfo(created.escrowAta), "escrow closed on the final batch");
+      const maintainerAfter = (await getAccount(connection, maintainerTokenAccount)).amount;
+      assert.equal((maintainerAfter - maintainerBefore).toString(), BOUNTY_AMOUNT.toString());
+
+      const events = await fetchEvents(signature);
+      assert.lengthOf(events.filter((e) => e.name === "funderRefunded"), 2);
+      assert.ok(events.find((e) => e.name === "bountyCancelled"), "BountyCancelled should be emitted on the final batch");
+    });
+  });
+
+  describe("Draft bounties", () => {
+    async function createDraft(amount = BOUNTY_AMOUNT) {
+      const bountyId = generateBountyId();
+      const bounty = bountyAddress(bountyId);
+      const [escrowAuth] = anchor.web3.PublicKey.findProgramAddressSync(
+        [Buffer.from("escrow_auth"), bounty.toBuffer()],
+        program.programId
+      );
+      const escrowAta = await getAssociatedTokenAddress(mint, escrowAuth, true);
+      await program.methods.initializeDraft(bountyId, amount, 0, futureDeadline(), null, []).accountsPartial({
+        maintainer: maintainer.publicKey,
+        bounty: bounty,
+        rentPayer: null,
+        maintainerTokenAccount: maintainerTokenAccount,
+        escrowAuthority: escrowAuth,
+        escrowTokenAccount: escrowAta,
+        mint,
+        systemProgram: SystemProgram.programId,
+        tokenProgram: TOKEN_PROGRAM_ID,
+        associatedTokenProgram: ASSOCIATED_TOKEN_PROGRAM_ID,
+        rent: SYSVAR_RENT_PUBKEY,
+      }).rpc();
+      return { bounty, bountyId, escrowAuth, escrowAta };
+    }
+
+    function fundDraft(draft) {
+      return program.methods.fundDraft(draft.bountyId).accountsPartial({
+        maintainer: maintainer.publicKey,
+        bounty: draft.bounty,
+        config: configPda,
+        mint,
+        escrowAuthority: draft.escrowAuth,
+        escrowTokenAccount: draft.escrowAta,
+        maintainerTokenAccount: maintainerTokenAccount,
+        tokenProgram: TOKEN_PROGRAM_ID,
+      }).rpc();
+    }
+
+    it("Creates a draft without moving tokens, then funds it into a Created bounty", async () => {
+      const before = (await getAccount(connection, maintainerTokenAccount)).amount;
+      const draft = await createDraft();
+
+      let bounty = await program.account.bounty.fetch(draft.bounty);
+      assert.deepEqual(bounty.state, { draft: {} });
+      assert.equal((await getAccount(connection, draft.escrowAta)).amount.toString(), "0");
+      assert.equal((await getAccount(connection, maintainerTokenAccount)).amount, before);
+
+      const signature = await fundDraft(draft);
+      bounty = await program.account.bounty.fetch(draft.bounty);
+      assert.deepEqual(bounty.state, { created: {} });
+      assert.equal((await getAccount(connection, draft.escrowAta)).amount.toString(), BOUNTY_AMOUNT.toString());
+
+      const transition = (await fetchEvents(signature)).find((e) => e.name === "stateTransition");
+      assert.deepEqual(transition.data.fromState, { draft: {} });
+      assert.deepEqual(transition.data.toState, { created: {} });
+
+      try {
+        await fundDraft(draft);
+        assert.fail("Expected InvalidBountyStateForOperation");
+      } catch (e) {
+        expectAnchorErrorCode(e, "InvalidBountyStateForOperation");
+      }
+    });
+
+    it("Rejects assignment and admin release while still a draft", async () => {
+      const draft = await createDraft();
+      const worker = anchor.web3.Keypair.generate();
+
+      try {
+        await program.methods.assignContributor(false).accountsPartial({
+          maintainer: maintainer.publicKey,
+          bounty: draft.bounty,
+          contributor: worker.publicKey,
+          systemProgram: SystemProgram.programId,
+        }).rpc();
+        assert.fail("Expected InvalidBountyStateForOperation");
+      } catch (e) {
+        expectAnchorErrorCode(e, "InvalidBountyStateForOperation");
+      }
+
+      try {
+        await adminRelease(draft);
+        assert.fail("Expected BountyIsDraft");
+      } catch (e) {
+        expectAnchorErrorCode(e, "BountyIsDraft");
+      }
+    });
+
+    function discardDraft(draft) {
+      return program.methods.discardDraft(draft.bountyId).accountsPartial({
+        maintainer: maintainer.publicKey,
+        bounty: draft.bounty,
+        mint,
+        escrowAuthority: draft.escrowAuth,
+        rentPayer: maintainer.publicKey,
+        escrowTokenAccount: draft.escrowAta,
+        tokenProgram: TOKEN_PROGRAM_ID,
+      }).rpc();
+    }
+
+    it("Discards a draft, closing its accounts without moving tokens", async () => {
+      const draft = await createDraft();
+      const tokensBefore = (await getAccount(connection, maintainerTokenAccount)).amount;
+
+      const signature = await discardDraft(draft);
+
+      assert.isNull(await connection.getAccountInfo(draft.bounty));
+      assert.isNull(await connection.getAccountInfo(draft.escrowAta));
+      assert.equal((await getAccount(connection, maintainerTokenAccount)).amount, tokensBefore);
+
+      const events = await fetchEvents(signature);
+      const cancelled = events.find((e) => e.name === "bountyCancelled");
+      assert.equal(cancelled.data.reason, 4); // CancelReason::DraftDiscarded
+      assert.equal(cancelled.data.amount.toNumber(), 0);
+      assert.isUndefined(events.find((e) => e.name === "tokensBurned"));
+    });
+
+    it("Rejects discarding a funded bounty", async () => {
+      const draft = await createDraft();
+      await fundDraft(draft);
+      try {
+        await discardDraft(draft);
+        assert.fail("Expected InvalidBountyStateForOperation");
+      } catch (e) {
+        expectAnchorErrorCode(e, "InvalidBountyStateForOperation");
+      }
+    });
+
+    it("Re-checks the mint whitelist and deadline cap when funding", async () => {
+      const draft = await createDraft();
+
+      await program.methods.setMintWhitelistEnabled(true)
+        .accountsPartial({ admin: admin.publicKey, config: configPda })
+        .rpc();
+      try {
+        await fundDraft(draft);
+        assert.fail("Expected MintNotWhitelisted");
+      } catch (e) {
+        expectAnchorErrorCode(e, "MintNotWhitelisted");
+      } finally {
+        await program.methods.setMintWhitelistEnabled(false)
+          .accountsPartial({ admin: admin.publicKey, config: configPda })
+          .rpc();
+      }
+
+      await program.methods.setMaxDeadlineDuration(new anchor.BN(60))
+        .accountsPartial({ admin: admin.publicKey, config: configPda })
+        .rpc();
+      try {
+        await fundDraft(draft);
+        assert.fail("Expected DeadlineTooFar");
+      } catch (e) {
+        expectAnchorErrorCode(e, "DeadlineTooFar");
+      } finally {
+        await program.methods.setMaxDeadlineDuration(new anchor.BN(0))
+          .accountsPartial({ admin: admin.publicKey, config: configPda })
+          .rpc();
+      }
+
+      await fundDraft(draft);
+      assert.deepEqual((await program.account.bounty.fetch(draft.bounty)).state, { created: {} });
+    });
+  });
+
+  describe("Program info", () => {
+    it("Reports the admin, config bump and the declared version", async () => {
+      const info = await program.methods.programInfo().accountsPartial({ config: configPda }).view();
+      const version = program.idl.constants.find((c) => c.name === "PROGRAM_VERSION");
+      const [, configBump] = PublicKey.findProgramAddressSync([Buffer.from("config")], program.programId);
+
+      assert.equal(info.version, Number(version.value));
+      assert.isTrue(info.admin.equals(admin.publicKey));
+      assert.equal(info.configBump, configBump);
+    });
+  });
+
+  describe("Expiry grace period", () => {
+    const grace = 4;
+
+    async function chainNow() {
+      return connection.getBlockTime(await connection.getSlot("confirmed"));
+    }
+
+    async function waitUntil(timestamp: number) {
+      while ((await chainNow()) <= timestamp) {
+        await new Promise((resolve) => setTimeout(resolve, 1000));
+      }
+    }
+
+    async function shortLivedBounty() {
+      const deadline = (await chainNow()) + 3;
+      const created = await createBounty({ deadline: new anchor.BN(deadline) });
+      await program.methods.setGracePeriod(new anchor.BN(grace)).accountsPartial({
+        maintainer: maintainer.publicKey,
+        bounty: created.bounty,
+      }).rpc();
+      return { ...created, deadline };
+    }
+
+    function reclaim(created, caller: Keypair = maintainer) {
+      return program.methods.reclaimExpired(created.bountyId).accountsPartial({
+        caller: caller.publicKey,
+        maintainer: maintainer.publicKey,
+        bounty: created.bounty,
+        mint,
+        escrowAuthority: created.escrowAuth,
+        rentPayer: maintainer.publicKey,
+        maintainerTokenAccount: maintainerTokenAccount,
+        escrowTokenAccount: created.escrowAta,
+        tokenProgram: TOKEN_PROGRAM_ID,
+      }).signers([caller]).rpc();
+    }
+
+    it("Lets the maintainer reclaim only once the grace period has passed", async () => {
+      const created = await shortLivedBounty();
+
+      await waitUntil(created.deadline);
+      try {
+        await reclaim(created);
+        assert.fail("Expected BountyNotExpired");
+      } catch (e) {
+        expectAnchorErrorCode(e, "BountyNotExpired");
+      }
+
+      await waitUntil(created.deadline + grace);
+      const before = (await getAccount(connection, maintainerTokenAccount)).amount;
+      const signature = await reclaim(created);
+
+      const after = (await getAccount(connection, maintainerTokenAccount)).amount;
+      assert.equal((after - before).toString(), BOUNTY_AMOUNT.toString());
+      assert.isNull(await connection.getAccountInfo(created.bounty));
+      const cancelled = (await fetchEvents(signature)).find((e) => e.name === "bountyCancelled");
+      assert.equal(cancelled.data.reason, 2);
+    });
+
+    it("Lets anyone reclaim an expired bounty, refunding the maintainer", async () => {
+      const created = await shortLivedBounty();
+      const stranger = anchor.web3.Keypair.generate();
+
+      await waitUntil(created.deadline + grace);
+      const before = (await getAccount(connection, maintainerTokenAccount)).amount;
+      const signature = await reclaim(created, stranger);
+
+      const after = (await getAccount(connection, maintainerTokenAccount)).amount;
+      assert.equal((after - before).toString(), BOUNTY_AMOUNT.toString());
+      const expired = (await fetchEvents(signature)).find((e) => e.name === "bountyExpired");
+      assert.equal(expired.data.bountyId.toString(), created.bountyId.toString());
+      assert.equal(expired.data.refunded.toNumber(), BOUNTY_AMOUNT.toNumber());
+      assert.ok(expired.data.caller.equals(stranger.publicKey));
+    });
+
+    it("Cannot reclaim a bounty that was already cancelled", async () => {
+      const created = await shortLivedBounty();
+      await waitUntil(created.deadline + grace);
+      await reclaim(created);
+      try {
+        await reclaim(created);
+        assert.fail("Expected the closed bounty to be rejected");
+      } catch (e) {
+        expectAnchorErrorCode(e, "AccountNotInitialized");
+      }
+    });
+
+    it("Lets the assigned contributor still complete within the grace period", async () => {
+      const created = await shortLivedBounty();
+      const worker = anchor.web3.Keypair.generate();
+      await program.methods.assignContributor(false).accountsPartial({
+        maintainer: maintainer.publicKey,
+        bounty: created.bounty,
+        contributor: worker.publicKey,
+        systemProgram: SystemProgram.programId,
+      }).rpc();
+
+      await waitUntil(created.deadline);
+      await program.methods.completeBounty(created.bountyId, 5, null).accountsPartial({
+        bounty: created.bounty,
+        mint,
+        escrowAuthority: created.escrowAuth,
+        maintainer: maintainer.publicKey,
+        contributor: worker.publicKey,
+        config: configPda,
+        admin: admin.publicKey,
+        keeper: null,
+        escrowTokenAccount: created.escrowAta,
+        tokenProgram: TOKEN_PROGRAM_ID,
+        systemProgram: SystemProgram.programId,
+        associatedTokenProgram: ASSOCIATED_TOKEN_PROGRAM_ID,
+      }).rpc();
+
+      const workerAta = await getAssociatedTokenAddress(mint, worker.publicKey);
+      assert.isAbove(Number((await getAccount(connection, workerAta)).amount), 0);
+    });
+
+    it("Rejects a negative grace period", async () => {
+      const created = await createBounty();
+      try {
+        await program.methods.setGracePeriod(new anchor.BN(-1)).accountsPartial({
+          maintainer: maintainer.publicKey,
+          bounty: created.bounty,
+        }).rpc();
+        assert.fail("Expected InvalidGracePeriod");
+      } catch (e) {
+        expectAnchorErrorCode(e, "InvalidGracePeriod");
+      }
+    });
+  });
+
+  describe("Deflationary burn on completion", () => {
+    function setBurnBps(burnBps: number) {
+      return program.methods.setBurnBps(burnBps).accountsPartial({
+        admin: admin.publicKey,
+        config: configPda,
+      }).rpc();
+    }
+
+    after(async () => {
+      await setBurnBps(0);
+    });
+
+    async function completeWithBurn(burnBps: number) {
+      await setBurnBps(burnBps);
+      const worker = anchor.web3.Keypair.generate();
+      const workerAta = await getAssociatedTokenAddress(mint, worker.publicKey);
+      const created = await createBounty();
+      await program.methods.assignContributor(false).accountsPartial({
+        maintainer: maintainer.publicKey,
+        bounty: created.bounty,
+        contributor: worker.publicKey,
+        systemProgram: SystemProgram.programId,
+      }).rpc();
+
+      const supplyBefore = (await getMint(connection, mint)).supply;
+      const signature = await program.methods.completeBounty(created.bountyId, 5, null).accountsPartial({
+        bounty: created.bounty,
+        mint,
+        escrowAuthority: created.escrowAuth,
+        maintainer: maintainer.publicKey,
+        contributor: worker.publicKey,
+        config: configPda,
+        admin: admin.publicKey,
+        contributorTokenAccount: workerAta,
+        escrowTokenAccount: created.escrowAta,
+        tokenProgram: TOKEN_PROGRAM_ID,
+        systemProgram: SystemProgram.programId,
+        associatedTokenProgram: ASSOCIATED_TOKEN_PROGRAM_ID,
+      }).rpc();
+
+      const burned = supplyBefore - (await getMint(connection, mint)).supply;
+      const paid = (await getAccount(connection, workerAta)).amount;
+      const event = (await fetchEvents(signature)).find((e) => e.name === "tokensBurned");
+      return { burned, paid, event };
+    }
+
+    it("Burns 5% of the payout and pays the contributor the rest", async () => {
+      const { burned, paid, event } = await completeWithBurn(500);
+      assert.isTrue(burned > 0n);
+      assert.equal(burned.toString(), ((burned + paid) * 500n / 10_000n).toString());
+      assert.equal(event.data.amount.toString(), burned.toString());
+      assert.isTrue(event.data.mint.equals(mint));
+    });
+
+    it("Leaves the supply unchanged with a 0% burn", async () => {
+      const { burned, paid, event } = await completeWithBurn(0);
+      assert.equal(burned.toString(), "0");
+      assert.isTrue(paid > 0n);
+      assert.isUndefined(event);
+    });
+
+    it("Burns the same share when an admin force-release settles the bounty", async () => {
+      await setBurnBps(500);
+      const created = await createBounty();
+      const supplyBefore = (await getMint(connection, mint)).supply;
+
+      const { signature, targetAta } = await adminRelease(created);
+
+      const burned = supplyBefore - (await getMint(connection, mint)).supply;
+      const paid = (await getAccount(connection, targetAta)).amount;
+      assert.isTrue(burned > 0n);
+      assert.equal(burned.toString(), ((burned + paid) * 500n / 10_000n).toString());
+      const event = (await fetchEvents(signature)).find((e) => e.name === "tokensBurned");
+      assert.equal(event.data.amount.toString(), burned.toString());
+    });
+
+    it("Rejects a burn share above 100%", async () => {
+      try {
+        await setBurnBps(10_001);
+        assert.fail("Expected InvalidBurnBps");
+      } catch (e) {
+        expectAnchorErrorCode(e, "InvalidBurnBps");
+      }
+    });
+  });
+
+  describe("Bounty account size", () => {
+    // Bounty::LEN in programs/octasol_contract/src/state/bounty.rs
+    const BOUNTY_LEN = 1012;
+
+    it("Creates the bounty sized exactly to Bounty::LEN and rent-exempt", async () => {
+      const created = await createBounty();
+      const info = await connection.getAccountInfo(created.bounty);
+      assert.equal(info.data.length, BOUNTY_LEN);
+      const minimum = await connection.getMinimumBalanceForRentExemption(info.data.length);
+      assert.isAtLeast(info.lamports, minimum);
+    });
+  });
+
+  describe("Auto-release crank", () => {
+    const window = 3;
+
+    function setFeeBps(feeBps: number) {
+      return program.methods
+        .adminUpdateConfig({
+          feeBps,
+          feeTreasuryBps: 10000,
+          feeDiscountMaxBps: null,
+          minBountyAmount: null,
+          maxBountyAmount: null,
+          paused: null,
+          allowTransferHooks: null,
+          defaultKeeper: null,
+          allowAdminForceOnCreated: null,
+          adminInterventionBps: null,
+        })
+        .accountsPartial({ admin: admin.publicKey, config: configPda })
+        .rpc();
+    }
+
+    function setAutoRelease(seconds: number, crankRewardBps: number) {
+      return program.methods.setAutoRelease(new anchor.BN(seconds), crankRewardBps).accountsPartial({
+        admin: admin.publicKey,
+        config: configPda,
+      }).rpc();
+    }
+
+    before(async () => {
+      await setFeeBps(1000);
+      await setAutoRelease(window, 5000);
+    });
+
+    after(async () => {
+      await setAutoRelease(0, 0);
+      await setFeeBps(0);
+    });
+
+    // `beforeAssign` runs while the bounty is still Created, `commitment` is committed by the worker
+    async function submittedBounty(beforeAssign?: (created) => Promise<unknown>, commitment?: number[]) {
+      const worker = anchor.web3.Keypair.generate();
+      const workerAta = await createAssociatedTokenAccount(connection, wallet.payer, mint, worker.publicKey);
+      const created = await createBounty();
+      if (beforeAssign) {
+        await beforeAssign(created);
+      }
+      await program.methods.assignContributor(false).accountsPartial({
+        maintainer: maintainer.publicKey,
+        bounty: created.bounty,
+        contributor: worker.publicKey,
+        systemProgram: SystemProgram.programId,
+      }).rpc();
+      if (commitment) {
+        await program.methods.commitDeliverable(created.bountyId, commitment).accountsPartial({
+          contributor: worker.publicKey,
+          bounty: created.bounty,
+        }).signers([worker]).rpc();
+      }
+      const signature = await program.methods.submitWork(created.bountyId).accountsPartial({
+        contributor: worker.publicKey,
+        bounty: created.bounty,
+      }).signers([worker]).rpc();
+      const submitted = (await fetchEvents(signature)).find((e) => e.name === "workSubmitted");
+      return { ...created, worker, workerAta, submittedAt: submitted.data.submittedAt.toNumber() };
+    }
+
+    async function waitOutWindow(created) {
+      while ((await connection.getBlockTime(await connection.getSlot("confirmed"))) < created.submittedAt + window) {
+        await new Promise((resolve) => setTimeout(resolve, 1000));
+      }
+    }
+
+    function crank(created, cranker: Keypair, crankerAta: PublicKey, deliverableHash: number[] | null = null) {
+      return program.methods.crankAutoRelease(created.bountyId, deliverableHash).accountsPartial({
+        cranker: cranker.publicKey,
+        config: configPda,
+        bounty: created.bounty,
+        mint,
+        escrowAuthority: created.escrowAuth,
+        maintainer: maintainer.publicKey,
+        rentPayer: maintainer.publicKey,
+        contributor: created.worker.publicKey,
+        contributorTokenAccount: created.workerAta,
+        crankerTokenAccount: crankerAta,
+        escrowTokenAccount: created.escrowAta,
+        treasuryTokenAccount: maintainerTokenAccount,
+        stakerTokenAccount: null,
+        charityTokenAccount: null,
+        tokenProgram: TOKEN_PROGRAM_ID,
+        systemProgram: SystemProgram.programId,
+      }).signers([cranker]).rpc();
+    }
+
+    async function newCranker() {
+      const cranker = anchor.web3.Keypair.generate();
+      await connection.confirmTransaction(
+        await connection.requestAirdrop(cranker.publicKey, anchor.web3.LAMPORTS_PER_SOL),
+        "confirmed"
+      );
+      const crankerAta = await createAssociatedTokenAccount(connection, wallet.payer, mint, cranker.publicKey);
+      return { cranker, crankerAta };
+    }
+
+    it("Rejects a crank before the auto-release window has passed", async () => {
+      const created = await submittedBounty();
+      const { cranker, crankerAta } = await newCranker();
+      try {
+        await crank(created, cranker, crankerAta);
+        assert.fail("Expected AutoReleaseNotReady");
+      } catch (e) {
+        expectAnchorErrorCode(e, "AutoReleaseNotReady");
+      }
+    });
+
+    it("Settles to the contributor after the window and pays the cranker from the fee", async () => {
+      const created = await submittedBounty();
+      const { cranker, crankerAta } = await newCranker();
+      await waitOutWindow(created);
+
+      const signature = await crank(created, cranker, crankerAta);
+
+      assert.equal(Number((await getAccount(connection, created.workerAta)).amount), BOUNTY_AMOUNT.toNumber() - 1000);
+      assert.equal(Number((await getAccount(connection, crankerAta)).amount), 500);
+      assert.isNull(await connection.getAccountInfo(created.bounty));
+      const events = await fetchEvents(signature);
+      assert.equal(events.find((e) => e.name === "crankRewardPaid").data.amount.toNumber(), 500);
+      assert.equal(events.find((e) => e.name === "bountyCompleted").data.fee.toNumber(), 1000);
+    });
+
+    it("Rejects a crank on a bounty whose work was never submitted", async () => {
+      const worker = anchor.web3.Keypair.generate();
+      const workerAta = await createAssociatedTokenAccount(connection, wallet.payer, mint, worker.publicKey);
+      const created = await createBounty();
+      await program.methods.assignContributor(false).accountsPartial({
+        maintainer: maintainer.publicKey,
+        bounty: created.bounty,
+        contributor: worker.publicKey,
+        systemProgram: SystemProgram.programId,
+      }).rpc();
+      const { cranker, crankerAta } = await newCranker();
+      try {
+        await crank({ ...created, worker, workerAta }, cranker, crankerAta);
+        assert.fail("Expected WorkNotSubmitted");
+      } catch (e) {
+        expectAnchorErrorCode(e, "WorkNotSubmitted");
+      }
+    });
+
+    it("Rejects a crank until the bounty's required approvals are in", async () => {
+      const reviewer = anchor.web3.Keypair.generate();
+      const created = await submittedBounty(async (c) => {
+        await program.methods.setReviewers([reviewer.publicKey]).accountsPartial({
+          maintainer: maintainer.publicKey,
+          bounty: c.bounty,
+        }).rpc();
+        await program.methods.setRequiredApprovals(1).accountsPartial({
+          maintainer: maintainer.publicKey,
+          bounty: c.bounty,
+        }).rpc();
+      });
+      const { cranker, crankerAta } = await newCranker();
+      await waitOutWindow(created);
+
+      try {
+        await crank(created, cranker, crankerAta);
+        assert.fail("Expected InsufficientApprovals");
+      } catch (e) {
+        expectAnchorErrorCode(e, "InsufficientApprovals");
+      }
+      assert.equal((await getAccount(connection, created.escrowAta)).amount.toString(), BOUNTY_AMOUNT.toString());
+    });
+
+    it("Only settles a committed deliverable when the crank reveals it", async () => {
+      const hash = Array.from(Buffer.alloc(32, 7));
+      const created = await submittedBounty(undefined, hash);
+      const { cranker, crankerAta } = await newCranker();
+      await waitOutWindow(created);
+
+      try {
+        await crank(created, cranker, crankerAta);
+        assert.fail("Expected DeliverableMismatch");
+      } catch (e) {
+        expectAnchorErrorCode(e, "DeliverableMismatch");
+      }
+
+      await crank(created, cranker, crankerAta, hash);
+      assert.isNull(await connection.getAccountInfo(created.bounty));
+    });
+  });
+
+  describe("Per-bounty token program", () => {
+    let mint22: PublicKey;
+    let maintainerAta22: PublicKey;
+
+    before(async () => {
+      mint22 = await createMint(connection, wallet.payer, wallet.publicKey, null, 6, undefined, undefined, TOKEN_2022_PROGRAM_ID);
+      maintainerAta22 = await createAssociatedTokenAccount(connection, wallet.payer, mint22, maintainer.publicKey, undefined, TOKEN_2022_PROGRAM_ID);
+      await mintTo(connection, wallet.payer, mint22, maintainerAta22, wallet.payer, 1000000, [], undefined, TOKEN_2022_PROGRAM_ID);
+    });
+
+    async function assignedToken2022Bounty() {
+      const id = generateBountyId();
+      const bounty = bountyAddress(id);
+      const [escrowAuth] = PublicKey.findProgramAddressSync(
+        [Buffer.from("escrow_auth"), bounty.toBuffer()],
+        program.programId
+      );
+      const escrowAta = await getAssociatedTokenAddress(mint22, escrowAuth, true, TOKEN_2022_PROGRAM_ID);
+      await program.methods.initializeBounty(id, BOUNTY_AMOUNT, 0, futureDeadline(), null, []).accountsPartial({
+        maintainer: maintainer.publicKey,
+        bounty: bounty,
+        rentPayer: null,
+        maintainerTokenAccount: maintainerAta22,
+        escrowAuthority: escrowAuth,
+        escrowTokenAccount: escrowAta,
+        mint: mint22,
+        systemProgram: SystemProgram.programId,
+        tokenProgram: TOKEN_2022_PROGRAM_ID,
+        associatedTokenProgram: ASSOCIATED_TOKEN_PROGRAM_ID,
+        rent: SYSVAR_RENT_PUBKEY,
+      }).rpc();
+
+      const worker = anchor.web3.Keypair.generate();
+      await program.methods.assignContributor(false).accountsPartial({
+        maintainer: maintainer.publicKey,
+        bounty: bounty,
+        contributor: worker.publicKey,
+        systemProgram: SystemProgram.programId,
+      }).rpc();
+      return { bounty, bountyId: id, escrowAuth, escrowAta, worker };
+    }
+
+    function complete(created, tokenProgram: PublicKey) {
+      return program.methods.completeBounty(created.bountyId, 5, null).accountsPartial({
+        bounty: created.bounty,
+        mint: mint22,
+        escrowAuthority: created.escrowAuth,
+        maintainer: maintainer.publicKey,
+        contributor: created.worker.publicKey,
+        config: configPda,
+        admin: admin.publicKey,
+        contributorTokenAccount: getAssociatedTokenAddressSync(mint22, created.worker.publicKey, false, tokenProgram),
+        escrowTokenAccount: created.escrowAta,
+        tokenProgram,
+        systemProgram: SystemProgram.programId,
+        associatedTokenProgram: ASSOCIATED_TOKEN_PROGRAM_ID,
+      }).rpc();
+    }
+
+    it("Records the token program the escrow was created with", async () => {
+      const created = await assignedToken2022Bounty();
+      const bounty = await program.account.bounty.fetch(created.bounty);
+      assert.isTrue(bounty.tokenProgram.equals(TOKEN_2022_PROGRAM_ID));
+    });
+
+    it("Rejects a completion that passes classic Token for a Token-2022 bounty", async () => {
+      const created = await assignedToken2022Bounty();
+      try {
+        await complete(created, TOKEN_PROGRAM_ID);
+        assert.fail("Expected TokenProgramMismatch");
+      } catch (e) {
+        expectAnchorErrorCode(e, "TokenProgramMismatch");
+      }
+
+      await complete(created, TOKEN_2022_PROGRAM_ID);
+      const workerAta = getAssociatedTokenAddressSync(mint22, created.worker.publicKey, false, TOKEN_2022_PROGRAM_ID);
+      const paid = await getAccount(connection, workerAta, undefined, TOKEN_2022_PROGRAM_ID);
+      assert.equal(paid.amount.toString(), BOUNTY_AMOUNT.toString());
+    });
+  });
+
+  describe("Maintainer opt-out of admin override", () => {
+    it("Blocks admin force-release on a bounty with the override disabled", async () => {
+      const created = await createBounty();
+      await program.methods.setAdminOverrideAllowed(false).accountsPartial({
+        maintainer: maintainer.publicKey,
+        bounty: created.bounty,
+      }).rpc();
+
+      try {
+        await adminRelease(created);
+        assert.fail("Expected AdminOverrideDisabled");
+      } catch (e) {
+        expectAnchorErrorCode(e, "AdminOverrideDisabled");
+      }
+      assert.equal((await getAccount(connection, created.escrowAta)).amount.toString(), BOUNTY_AMOUNT.toString());
+    });
+
+    it("Still lets admin force-release a bounty that keeps the default", async () => {
+      const created = await createBounty();
+      assert.isTrue((await program.account.bounty.fetch(created.bounty)).adminOverrideAllowed);
+      const { targetAta } = await adminRelease(created);
+      assert.isAbove(Number((await getAccount(connection, targetAta)).amount), 0);
+    });
+  });
+
+  describe("Settlement receipt chain", () => {
+    const [globalStatsPda] = PublicKey.findProgramAddressSync([Buffer.from("global_stats")], program.programId);
+
+    async function assignAndComplete() {
+      const worker = anchor.web3.Keypair.generate();
+      const created = await createBounty();
+      await program.methods.assignContributor(false).accountsPartial({
+        maintainer: maintainer.publicKey,
+        bounty: created.bounty,
+        contributor: worker.publicKey,
+        systemProgram: SystemProgram.programId,
+      }).rpc();
+      await program.methods.completeBounty(created.bountyId, 5, null).accountsPartial({
+        bounty: created.bounty,
+        mint,
+        escrowAuthority: created.escrowAuth,
+        maintainer: maintainer.publicKey,
+        contributor: worker.publicKey,
+        config: configPda,
+        admin: admin.publicKey,
+        escrowTokenAccount: created.escrowAta,
+        tokenProgram: TOKEN_PROGRAM_ID,
+        systemProgram: SystemProgram.programId,
+        associatedTokenProgram: ASSOCIATED_TOKEN_PROGRAM_ID,
+      }).rpc();
+      return { bountyId: created.bountyId, contributor: worker.publicKey };
+    }
+
+    function nextChain(prev: Buffer, bountyId: anchor.BN, contributor: PublicKey, amount: anchor.BN) {
+      return createHash("sha256")
+        .update(prev)
+        .update(bountyId.toArrayLike(Buffer, "le", 8))
+        .update(contributor.toBuffer())
+        .update(amount.toArrayLike(Buffer, "le", 8))
+        .digest();
+    }
+
+    it("Advances the chain by hash(prev, bounty_id, contributor, amount) on each completion", async () => {
+      // Makes sure the global stats account exists before reading it
+      await assignAndComplete();
+      const start = await program.account.globalStats.fetch(globalStatsPda);
+
+      const second = await assignAndComplete();
+      const third = await assignAndComplete();
+      const end = await program.account.globalStats.fetch(globalStatsPda);
+
+      let expected = Buffer.from(start.receiptChain);
+      expected = nextChain(expected, second.bountyId, second.contributor, BOUNTY_AMOUNT);
+      expected = nextChain(expected, third.bountyId, third.contributor, BOUNTY_AMOUNT);
+      assert.deepEqual(Buffer.from(end.receiptChain), expected);
+      assert.equal(end.receipts.toNumber(), start.receipts.toNumber() + 2);
+    });
+  });
+
+  describe("Payout preview", () => {
+    function setFeeBps(feeBps: number) {
+      return program.methods
+        .adminUpdateConfig({
+          feeBps,
+          feeTreasuryBps: 10000,
+          feeDiscountMaxBps: null,
+          minBountyAmount: null,
+          maxBountyAmount: null,
+          paused: null,
+          allowTransferHooks: null,
+          defaultKeeper: null,
+          allowAdminForceOnCreated: null,
+          adminInterventionBps: null,
+        })
+        .accountsPartial({ admin: admin.publicKey, config: configPda })
+        .rpc();
+    }
+
+    function setBurnBps(burnBps: number) {
+      return program.methods.setBurnBps(burnBps).accountsPartial({ admin: admin.publicKey, config: configPda }).rpc();
+    }
+
+    after(async () => {
+      await setFeeBps(0);
+      await setBurnBps(0);
+    });
+
+    async function previewThenComplete() {
+      const worker = anchor.web3.Keypair.generate();
+      const workerAta = await getAssociatedTokenAddress(mint, worker.publicKey);
+      const created = await createBounty();
+      await program.methods.assignContributor(false).accountsPartial({
+        maintainer: maintainer.publicKey,
+        bounty: created.bounty,
+        contributor: worker.publicKey,
+        systemProgram: SystemProgram.programId,
+      }).rpc();
+
+      const preview = await program.methods.previewPayout(created.bountyId).accountsPartial({
+        config: configPda,
+        bounty: created.bounty,
+        mint,
+        escrowAuthority: created.escrowAuth,
+        escrowTokenAccount: created.escrowAta,
+      }).view();
+
+      const signature = await program.methods.completeBounty(created.bountyId, 5, null).accountsPartial({
+        bounty: created.bounty,
+        mint,
+        escrowAuthority: created.escrowAuth,
+        maintainer: maintainer.publicKey,
+        contributor: worker.publicKey,
+        config: configPda,
+        admin: admin.publicKey,
+        contributorTokenAccount: workerAta,
+        escrowTokenAccount: created.escrowAta,
+        treasuryTokenAccount: maintainerTokenAccount,
+        tokenProgram: TOKEN_PROGRAM_ID,
+        systemProgram: SystemProgram.programId,
+        associatedTokenProgram: ASSOCIATED_TOKEN_PROGRAM_ID,
+      }).rpc();
+
+      const events = await fetchEvents(signature);
+      const paid = (await getAccount(connection, workerAta)).amount;
+      return { preview, events, paid };
+    }
+
+    it("Matches the amounts a plain completion transfers", async () => {
+      const { preview, events, paid } = await previewThenComplete();
+      assert.equal(preview.gross.toString(), BOUNTY_AMOUNT.toString());
+      assert.equal(preview.fee.toNumber(), 0);
+      assert.equal(preview.net.toString(), paid.toString());
+      assert.equal(events.find((e) => e.name === "bountyCompleted").data.fee.toNumber(), 0);
+    });
+
+    it("Accounts for the protocol fee and the burn", async () => {
+      await setFeeBps(1000);
+      await setBurnBps(500);
+      const { preview, events, paid } = await previewThenComplete();
+
+      assert.equal(preview.fee.toNumber(), events.find((e) => e.name === "bountyCompleted").data.fee.toNumber());
+      assert.equal(preview.burned.toNumber(), events.find((e) => e.name === "tokensBurned").data.amount.toNumber());
+      assert.equal(preview.net.toString(), paid.toString());
+      assert.equal(preview.fee.add(preview.burned).add(preview.donation).add(preview.net).toString(), BOUNTY_AMOUNT.toString());
+    });
+  });
+
+  describe("Escrow rent maintainer check", () => {
+    it("Rejects a cancel that names someone other than the bounty's maintainer", async () => {
+      const created = await createBounty();
+      const impostor = anchor.web3.Keypair.generate();
+      try {
+        await program.methods.cancelBounty(null, NO_REASON).accountsPartial({
+          admin: admin.publicKey,
+          config: configPda,
+          bounty: created.bounty,
+          mint,
+          escrowAuthority: created.escrowAuth,
+          maintainer: impostor.publicKey,
+          maintainerTokenAccount: maintainerTokenAccount,
+          escrowTokenAccount: created.escrowAta,
+          systemProgram: SystemProgram.programId,
+          tokenProgram: TOKEN_PROGRAM_ID,
+          associatedTokenProgram: ASSOCIATED_TOKEN_PROGRAM_ID,
+          rent: SYSVAR_RENT_PUBKEY,
+        }).rpc();
+        assert.fail("Expected MaintainerMismatch");
+      } catch (e) {
+        expectAnchorErrorCode(e, "MaintainerMismatch");
+      }
+      assert.equal((await getAccount(connection, created.escrowAta)).amount.toString(), BOUNTY_AMOUNT.toString());
+    });
+
+    it("Rejects an admin force-release that sends the escrow rent elsewhere", async () => {
+      const created = await createBounty();
+      const impostor = anchor.web3.Keypair.generate();
+      try {
+        await adminRelease(created, { maintainer: impostor.publicKey });
+        assert.fail("Expected MaintainerMismatch");
+      } catch (e) {
+        expectAnchorErrorCode(e, "MaintainerMismatch");
+      }
+      assert.equal((await getAccount(connection, created.escrowAta)).amount.toString(), BOUNTY_AMOUNT.toString());
+    });
+  });
+
+  describe("Daily volume circuit breaker", () => {
+    function setMaxDailyVolume(max: anchor.BN) {
+      return program.methods.setMaxDailyVolume(max).accountsPartial({
+        admin: admin.publicKey,
+        config: configPda,
+      }).rpc();
+    }
+
+    // Today's bucket, which every earlier test creating a bounty in `mint` has added to
+    async function dailyVolume() {
+      const [volumeWindowPda] = PublicKey.findProgramAddressSync(
+        [Buffer.from("volume_window"), mint.toBuffer()],
+        program.programId
+      );
+      const window = await program.account.volumeWindow.fetch(volumeWindowPda);
+      const now = await connection.getBlockTime(await connection.getSlot("confirmed"));
+      const day = Math.floor(now / 86400);
+      const i = day % window.bucketDays.length;
+      return window.bucketDays[i].toNumber() === day ? window.bucketVolumes[i] : new anchor.BN(0);
+    }
+
+    after(async () => {
+      await setMaxDailyVolume(new anchor.BN(0));
+    });
+
+    it("Trips once a new bounty would take the day's volume past the limit", async () => {
+      await setMaxDailyVolume((await dailyVolume()).add(BOUNTY_AMOUNT.muln(2)));
+
+      await createBounty();
+      await createBounty();
+      try {
+        await createBounty();
+        assert.fail("Expected DailyVolumeExceeded");
+      } catch (e) {
+        expectAnchorErrorCode(e, "DailyVolumeExceeded");
+      }
+
+      // Lifting the limit lets creation through again
+      await setMaxDailyVolume(new anchor.BN(0));
+      await createBounty();
+    });
+  });
+
+  describe("Deliverable commitment", () => {
+    const spec = createHash("sha256").update("spec: add dark mode").digest();
+
+    async function committedBounty() {
+      const worker = anchor.web3.Keypair.generate();
+      const created = await createBounty();
+      await program.methods.assignContributor(false).accountsPartial({
+        maintainer: maintainer.publicKey,
+        bounty: created.bounty,
+        contributor: worker.publicKey,
+        systemProgram: SystemProgram.programId,
+      }).rpc();
+      await program.methods.commitDeliverable(created.bountyId, Array.from(spec)).accountsPartial({
+        contributor: worker.publicKey,
+        bounty: created.bounty,
+      }).signers([worker]).rpc();
+      return { ...created, worker };
+    }
+
+    function complete(created, deliverableHash: number[] | null) {
+      return program.methods.completeBounty(created.bountyId, 5, deliverableHash).accountsPartial({
+        bounty: created.bounty,
+        mint,
+        escrowAuthority: created.escrowAuth,
+        maintainer: maintainer.publicKey,
+        contributor: created.worker.publicKey,
+        config: configPda,
+        admin: admin.publicKey,
+        escrowTokenAccount: created.escrowAta,
+        tokenProgram: TOKEN_PROGRAM_ID,
+        systemProgram: SystemProgram.programId,
+        associatedTokenProgram: ASSOCIATED_TOKEN_PROGRAM_ID,
+      }).rpc();
+    }
+
+    it("Completes when the final deliverable matches the commitment", async () => {
+      const created = await committedBounty();
+      const bounty = await program.account.bounty.fetch(created.bounty);
+      assert.deepEqual(Buffer.from(bounty.deliverableCommitment), spec);
+
+      await complete(created, Array.from(spec));
+      assert.isNull(await connection.getAccountInfo(created.bounty));
+    });
+
+    it("Rejects a mismatching or missing deliverable hash", async () => {
+      const created = await committedBounty();
+      const other = createHash("sha256").update("spec: something else").digest();
+      for (const hash of [Array.from(other), null]) {
+        try {
+          await complete(created, hash);
+          assert.fail("Expected DeliverableMismatch");
+        } catch (e) {
+          expectAnchorErrorCode(e, "DeliverableMismatch");
+        }
+      }
+    });
+
+    it("Refuses to replace a commitment once made", async () => {
+      const created = await committedBounty();
+      try {
+        await program.methods.commitDeliverable(created.bountyId, Array(32).fill(1)).accountsPartial({
+          contributor: created.worker.publicKey,
+          bounty: created.bounty,
+        }).signers([created.worker]).rpc();
+        assert.fail("Expected DeliverableAlreadyCommitted");
+      } catch (e) {
+        expectAnchorErrorCode(e, "DeliverableAlreadyCommitted");
+      }
+    });
+  });
+
+  describe("Keeper batch completion", () => {
+    async function assignedBounty() {
+      const worker = anchor.web3.Keypair.generate();
+      const workerAta = await createAssociatedTokenAccount(connection, wallet.payer, mint, worker.publicKey);
+      const created = await createBounty();
+      await program.methods.assignContributor(false).accountsPartial({
+        maintainer: maintainer.publicKey,
+        bounty: created.bounty,
+        contributor: worker.publicKey,
+        systemProgram: SystemProgram.programId,
+      }).rpc();
+      return { ...created, worker, workerAta };
+    }
+
+    function batchAccounts(created, contributorTokenAccount: PublicKey) {
+      return [
+        { pubkey: created.bounty, isSigner: false, isWritable: true },
+        { pubkey: created.escrowAuth, isSigner: false, isWritable: false },
+        { pubkey: created.escrowAta, isSigner: false, isWritable: true },
+        { pubkey: contributorTokenAccount, isSigner: false, isWritable: true },
+      ];
+    }
+
+    it("Settles every eligible bounty and skips the rest", async () => {
+      const assigned = [await assignedBounty(), await assignedBounty(), await assignedBounty()];
+      // Never assigned, so there is nobody to pay
+      const open = await createBounty();
+
+      const remaining = [
+        ...assigned.flatMap((created) => batchAccounts(created, created.workerAta)),
+        ...batchAccounts(open, maintainerTokenAccount),
+      ];
+      const signature = await program.methods.completeBatch()
+        .accountsPartial({ keeper: admin.publicKey, config: configPda, mint, tokenProgram: TOKEN_PROGRAM_ID })
+        .remainingAccounts(remaining)
+        .rpc();
+
+      const completed = (await fetchEvents(signature)).filter((e) => e.name === "bountyCompleted");
+      assert.equal(completed.length, 3);
+      for (const created of assigned) {
+        const bounty = await program.account.bounty.fetch(created.bounty);
+        assert.deepEqual(bounty.state, { completed: {} });
+        assert.equal((await getAccount(connection, created.workerAta)).amount, BigInt(BOUNTY_AMOUNT.toNumber()));
+        assert.isNull(await connection.getAccountInfo(created.escrowAta));
+      }
+      const untouched = await program.account.bounty.fetch(open.bounty);
+      assert.deepEqual(untouched.state, { created: {} });
+    });
+
+    it("Skips a bounty whose contributor account belongs to someone else", async () => {
+      const created = await assignedBounty();
+      const signature = await program.methods.completeBatch()
+        .accountsPartial({ keeper: admin.publicKey, config: configPda, mint, tokenProgram: TOKEN_PROGRAM_ID })
+        .remainingAccounts(batchAccounts(created, maintainerTokenAccount))
+        .rpc();
+
+      assert.isEmpty((await fetchEvents(signature)).filter((e) => e.name === "bountyCompleted"));
+      const bounty = await program.account.bounty.fetch(created.bounty);
+      assert.deepEqual(bounty.state, { inProgress: {} });
+    });
+
+    it("Lets the keeper chosen at creation settle the bounty", async () => {
+      const keeper = anchor.web3.Keypair.generate();
+      await connection.confirmTransaction(
+        await connection.requestAirdrop(keeper.publicKey, anchor.web3.LAMPORTS_PER_SOL),
+        "confirmed"
+      );
+      const worker = anchor.web3.Keypair.generate();
+      const workerAta = await createAssociatedTokenAccount(connection, wallet.payer, mint, worker.publicKey);
+      const created = await createBounty({ keeper: keeper.publicKey });
+
+      const createdEvent = (await fetchEvents(created.signature)).find((e) => e.name === "bountyCreated");
+      assert.ok(createdEvent.data.keeper.equals(keeper.publicKey));
+      const bounty = await program.account.bounty.fetch(created.bounty);
+      assert.ok(bounty.keeper.equals(keeper.publicKey));
+
+      await program.methods.assignContributor(false).accountsPartial({
+        maintainer: maintainer.publicKey,
+        bounty: created.bounty,
+        contributor: worker.publicKey,
+        systemProgram: SystemProgram.programId,
+      }).rpc();
+
+      // The default keeper may not settle it
+      let signature = await program.methods.completeBatch()
+        .accountsPartial({ keeper: admin.publicKey, config: configPda, mint, tokenProgram: TOKEN_PROGRAM_ID })
+        .remainingAccounts(batchAccounts(created, workerAta))
+        .rpc();
+      assert.isEmpty((await fetchEvents(signature)).filter((e) => e.name === "bountyCompleted"));
+
+      signature = await program.methods.completeBatch()
+        .accountsPartial({ keeper: keeper.publicKey, config: configPda, mint, tokenProgram: TOKEN_PROGRAM_ID })
+        .remainingAccounts(batchAccounts(created, workerAta))
+        .signers([keeper])
+        .rpc();
+      assert.lengthOf((await fetchEvents(signature)).filter((e) => e.name === "bountyCompleted"), 1);
+      assert.equal(Number((await getAccount(connection, workerAta)).amount), BOUNTY_AMOUNT.toNumber());
+    });
+
+    it("Only lets the bounty's keeper sign complete_bounty", async () => {
+      const keeper = anchor.web3.Keypair.generate();
+      const worker = anchor.web3.Keypair.generate();
+      const workerAta = await getAssociatedTokenAddress(mint, worker.publicKey);
+      const created = await createBounty({ keeper: keeper.publicKey });
+      await program.methods.assignContributor(false).accountsPartial({
+        maintainer: maintainer.publicKey,
+        bounty: created.bounty,
+        contributor: worker.publicKey,
+        systemProgram: SystemProgram.programId,
+      }).rpc();
+
+      const complete = (signer: Keypair | null) => {
+        const builder = program.methods.completeBounty(created.bountyId, 5, null).accountsPartial({
+          bounty: created.bounty,
+          mint,
+          escrowAuthority: created.escrowAuth,
+          maintainer: maintainer.publicKey,
+          contributor: worker.publicKey,
+          config: configPda,
+          admin: admin.publicKey,
+          keeper: signer ? signer.publicKey : null,
+          contributorTokenAccount: workerAta,
+          escrowTokenAccount: created.escrowAta,
+          tokenProgram: TOKEN_PROGRAM_ID,
+          systemProgram: SystemProgram.programId,
+          associatedTokenProgram: ASSOCIATED_TOKEN_PROGRAM_ID,
+        });
+        return (signer ? builder.signers([signer]) : builder).rpc();
+      };
+
+      // Neither another keeper nor the admin on its own may settle it
+      for (const wrong of [anchor.web3.Keypair.generate(), null]) {
+        try {
+          await complete(wrong);
+          assert.fail("Expected KeeperNotAllowed");
+        } catch (e) {
+          expectAnchorErrorCode(e, "KeeperNotAllowed");
+        }
+      }
+
+      await complete(keeper);
+      assert.isNull(await connection.getAccountInfo(created.bounty));
+      assert.equal(Number((await getAccount(connection, workerAta)).amount), BOUNTY_AMOUNT.toNumber());
+    });
+
+    it("Rejects a batch that is not made of quadruples", async () => {
+      const created = await assignedBounty();
+      try {
+        await program.methods.completeBatch()
+          .accountsPartial({ keeper: admin.publicKey, config: configPda, mint, tokenProgram: TOKEN_PROGRAM_ID })
+          .remainingAccounts(batchAccounts(created, created.workerAta).slice(0, 3))
+          .rpc();
+        assert.fail("Expected InvalidBatchAccounts");
+      } catch (e) {
+        expectAnchorErrorCode(e, "InvalidBatchAccounts");
+      }
+    });
+  });
+
+  describe("Create retry", () => {
+    it("Rejects a retried create without charging the maintainer again", async () => {
+      const created = await createBounty();
+      // The address is derived from the maintainer and id, so a retry can only target the same account
+      assert.ok(created.bounty.equals(bountyAddress(created.bountyId)));
+      const balanceBefore = (await getAccount(connection, maintainerTokenAccount)).amount;
+
+      try {
+        await program.methods.initializeBounty(created.bountyId, BOUNTY_AMOUNT, 0, futureDeadline(), null, []).accountsPartial({
+          maintainer: maintainer.publicKey,
+          bounty: created.bounty,
+          rentPayer: null,
+          maintainerTokenAccount: maintainerTokenAccount,
+          escrowAuthority: created.escrowAuth,
+          escrowTokenAccount: created.escrowAta,
+          mint,
+          systemProgram: SystemProgram.programId,
+          tokenProgram: TOKEN_PROGRAM_ID,
+          associatedTokenProgram: ASSOCIATED_TOKEN_PROGRAM_ID,
+          rent: SYSVAR_RENT_PUBKEY,
+        }).rpc();
+        assert.fail("Expected BountyAlreadyExists");
+      } catch (e) {
+        expectAnchorErrorCode(e, "BountyAlreadyExists");
+      }
+
+      assert.equal((await getAccount(connection, maintainerTokenAccount)).amount, balanceBefore);
+      assert.equal((await getAccount(connection, created.escrowAta)).amount, BigInt(BOUNTY_AMOUNT.toNumber()));
+    });
+  });
+
+  describe("Admin intervention reasons", () => {
+    const reason = Array.from(Buffer.from("contributor unresponsive for 30 days".padEnd(64, "\0")));
+
+    function setRequired(required: boolean) {
+      return program.methods.setRequireAdminReason(required).accountsPartial({ admin: admin.publicKey, config: configPda }).rpc();
+    }
+
+    async function assignedBounty() {
+      const created = await createBounty();
+      await program.methods.assignContributor(false).accountsPartial({
+        maintainer: maintainer.publicKey,
+        bounty: created.bounty,
+        contributor: anchor.web3.Keypair.generate().publicKey,
+        systemProgram: SystemProgram.programId,
+      }).rpc();
+      return created;
+    }
+
+    function unassign(created, withReason: number[]) {
+      return program.methods.adminUnassign(created.bountyId, withReason).accountsPartial({
+        admin: admin.publicKey,
+        config: configPda,
+        bounty: created.bounty,
+      }).rpc();
+    }
+
+    before(async () => {
+      await setRequired(true);
+    });
+
+    after(async () => {
+      await setRequired(false);
+    });
+
+    it("Emits the reason with the intervention", async () => {
+      const created = await assignedBounty();
+      const signature = await unassign(created, reason);
+
+      const intervention = (await fetchEvents(signature)).find((e) => e.name === "adminIntervention");
+      assert.ok(intervention, "AdminIntervention should be emitted");
+      assert.equal(intervention.data.power, 1); // AdminPower::Unassign
+      assert.deepEqual(Array.from(intervention.data.reason), reason);
+    });
+
+    it("Rejects an empty reason in strict mode", async () => {
+      const created = await assignedBounty();
+      try {
+        await unassign(created, NO_REASON);
+        assert.fail("Expected ReasonRequired");
+      } catch (e) {
+        expectAnchorErrorCode(e, "ReasonRequired");
+      }
+    });
+
+    it("Accepts an empty reason once strict mode is off", async () => {
+      await setRequired(false);
+      try {
+        await unassign(await assignedBounty(), NO_REASON);
+      } finally {
+        await setRequired(true);
+      }
+    });
+  });
+
+  describe("Tips", () => {
+    const tipper = anchor.web3.Keypair.generate();
+    const TIP = 2500;
+    let tipperAta: PublicKey;
+
+    before(async () => {
+      tipperAta = await createAssociatedTokenAccount(connection, wallet.payer, mint, tipper.publicKey);
+      await mintTo(connection, wallet.payer, mint, tipperAta, wallet.payer, 10 * TIP);
+    });
+
+    function tip(created, amount: number) {
+      return program.methods.tipBounty(created.bountyId, new anchor.BN(amount)).accountsPartial({
+        tipper: tipper.publicKey,
+        bounty: created.bounty,
+        mint,
+        escrowAuthority: created.escrowAuth,
+        escrowTokenAccount: created.escrowAta,
+        tipperTokenAccount: tipperAta,
+        tokenProgram: TOKEN_PROGRAM_ID,
+      }).signers([tipper]).rpc();
+    }
+
+    it("Adds the tip to the payout without making the tipper a funder", async () => {
+      const created = await createBounty();
+      const signature = await tip(created, TIP);
+
+      const tipped = (await fetchEvents(signature)).find((e) => e.name === "bountyTipped");
+      assert.ok(tipped.data.tipper.equals(tipper.publicKey));
+      assert.equal(tipped.data.amount.toNumber(), TIP);
+
+      const bounty = await program.account.bounty.fetch(created.bounty);
+      assert.equal(bounty.amount.toNumber(), BOUNTY_AMOUNT.toNumber() + TIP);
+      assert.equal(bounty.tippedAmount.toNumber(), TIP);
+      assert.equal(bounty.funderCount, 0);
+
+      const { targetAta } = await adminRelease(created);
+      assert.equal(Number((await getAccount(connection, targetAta)).amount), BOUNTY_AMOUNT.toNumber() + TIP);
+    });
+
+    it("Rejects a zero tip", async () => {
+      const created = await createBounty();
+      try {
+        await tip(created, 0);
+        assert.fail("Expected InvalidAmount");
+      } catch (e) {
+        expectAnchorErrorCode(e, "InvalidAmount");
+      }
+    });
+  });
+
+  describe("ATA creation fee", () => {
+    const ATA_FEE = 300;
+
+    function setAtaFee(fee: number) {
+      return program.methods.setAtaCreationFee(new anchor.BN(fee)).accountsPartial({ admin: admin.publicKey, config: configPda }).rpc();
+    }
+
+    before(async () => {
+      await setAtaFee(ATA_FEE);
+    });
+
+    after(async () => {
+      await setAtaFee(0);
+    });
+
+    async function completeFor(worker: PublicKey) {
+      const created = await createBounty();
+      await program.methods.assignContributor(false).accountsPartial({
+        maintainer: maintainer.publicKey,
+        bounty: created.bounty,
+        contributor: worker,
+        systemProgram: SystemProgram.programId,
+      }).rpc();
+      const workerAta = getAssociatedTokenAddressSync(mint, worker);
+      const signature = await program.methods.completeBounty(created.bountyId, 5, null).accountsPartial({
+        bounty: created.bounty,
+        mint,
+        escrowAuthority: created.escrowAuth,
+        maintainer: maintainer.publicKey,
+        contributor: worker,
+        config: configPda,
+        admin: admin.publicKey,
+        contributorTokenAccount: workerAta,
+        escrowTokenAccount: created.escrowAta,
+        treasuryTokenAccount: maintainerTokenAccount,
+        tokenProgram: TOKEN_PROGRAM_ID,
+        systemProgram: SystemProgram.programId,
+        associatedTokenProgram: ASSOCIATED_TOKEN_PROGRAM_ID,
+      }).rpc();
+      return { signature, received: Number((await getAccount(connection, workerAta)).amount) };
+    }
+
+    it("Takes the fee from a contributor whose ATA the completion created", async () => {
+      const { signature, received } = await completeFor(anchor.web3.Keypair.generate().publicKey);
+      assert.equal(received, BOUNTY_AMOUNT.toNumber() - ATA_FEE);
+
+      const charged = (await fetchEvents(signature)).find((e) => e.name === "ataCreationFeeCharged");
+      assert.equal(charged.data.amount.toNumber(), ATA_FEE);
+    });
+
+    it("Pays the full amount to a contributor who already had an ATA", async () => {
+      const worker = anchor.web3.Keypair.generate().publicKey;
+      await createAssociatedTokenAccount(connection, wallet.payer, mint, worker);
+
+      const { signature, received } = await completeFor(worker);
+      assert.equal(received, BOUNTY_AMOUNT.toNumber());
+      assert.isUndefined((await fetchEvents(signature)).find((e) => e.name === "ataCreationFeeCharged"));
+    });
+  });
+
+  describe("Maintainer cancel", () => {
+    function maintainerCancel(created) {
+      return program.methods.maintainerCancel(created.bountyId).accountsPartial({
+        maintainer: maintainer.publicKey,
+        bounty: created.bounty,
+        mint,
+        escrowAuthority: created.escrowAuth,
+        rentPayer: maintainer.publicKey,
+        maintainerTokenAccount: maintainerTokenAccount,
+        escrowTokenAccount: created.escrowAta,
+        tokenProgram: TOKEN_PROGRAM_ID,
+      }).rpc();
+    }
+
+    it("Refunds and closes an unassigned bounty without the admin", async () => {
+      const created = await createBounty();
+      const before = (await getAccount(connection, maintainerTokenAccount)).amount;
+
+      const signature = await maintainerCancel(created);
+
+      const after = (await getAccount(connection, maintainerTokenAccount)).amount;
+      assert.equal((after - before).toString(), BOUNTY_AMOUNT.toString());
+      assert.isNull(await connection.getAccountInfo(created.bounty));
+      assert.isNull(await connection.getAccountInfo(created.escrowAta));
+      const cancelled = (await fetchEvents(signature)).find((e) => e.name === "bountyCancelled");
+      assert.equal(cancelled.data.reason, 0);
+    });
+
+    it("Rejects a bounty that already has a contributor", async () => {
+      const created = await createBounty();
+      await program.methods.assignContributor(false).accountsPartial({
+        maintainer: maintainer.publicKey,
+        bounty: created.bounty,
+        contributor: contributor.publicKey,
+        systemProgram: SystemProgram.programId,
+      }).rpc();
+
+      try {
+        await maintainerCancel(created);
+        assert.fail("Expected InvalidBountyStateForOperation");
+      } catch (e) {
+        expectAnchorErrorCode(e, "InvalidBountyStateForOperation");
+      }
+      const bounty = await program.account.bounty.fetch(created.bounty);
+      assert.deepEqual(bounty.state, { inProgress: {} });
+    });
+
+    it("Rejects a bounty that was already completed", async () => {
+      const created = await createBounty();
+      await adminRelease(created);
+
+      try {
+        await maintainerCancel(created);
+        assert.fail("Expected AccountNotInitialized");
+      } catch (e) {
+        expectAnchorErrorCode(e, "AccountNotInitialized");
+      }
+    });
+  });
+
+  describe("Two-step admin transfer", () => {
+    it("Only the proposed admin can accept", async () => {
+      const candidate = anchor.web3.Keypair.generate();
+      const stranger = anchor.web3.Keypair.generate();
+      await program.methods.proposeAdmin(candidate.publicKey)
+        .accounts({ admin: admin.publicKey, config: configPda })
+        .rpc();
+
+      try {
+        await program.methods.acceptAdmin()
+          .accounts({ newAdmin: stranger.publicKey, config: configPda })
+          .signers([stranger])
+          .rpc();
+        assert.fail("Expected NotPendingAdmin");
+      } catch (e) {
+        expectAnchorErrorCode(e, "NotPendingAdmin");
+      }
+
+      const signature = await program.methods.cancelAdminTransfer()
+        .accounts({ admin: admin.publicKey, config: configPda })
+        .rpc();
+      const cancelled = (await fetchEvents(signature)).find((e) => e.name === "adminTransferCancelled");
+      assert.ok(cancelled.data.pendingAdmin.equals(candidate.publicKey));
+    });
+
+    it("Cannot accept a transfer the admin cancelled", async () => {
+      const candidate = anchor.web3.Keypair.generate();
+      await program.methods.proposeAdmin(candidate.publicKey)
+        .accounts({ admin: admin.publicKey, config: configPda })
+        .rpc();
+      await program.methods.cancelAdminTransfer()
+        .accounts({ admin: admin.publicKey, config: configPda })
+        .rpc();
+
+      try {
+        await program.methods.acceptAdmin()
+          .accounts({ newAdmin: candidate.publicKey, config: configPda })
+          .signers([candidate])
+          .rpc();
+        assert.fail("Expected NotPendingAdmin");
+      } catch (e) {
+        expectAnchorErrorCode(e, "NotPendingAdmin");
+      }
+      const config = await program.account.configState.fetch(configPda);
+      assert.ok(config.admin.equals(admin.publicKey));
+      assert.isNull(config.pendingAdmin);
+    });
+
+    it("Rejects cancelling when nothing is pending", async () => {
+      try {
+        await program.methods.cancelAdminTransfer()
+          .accounts({ admin: admin.publicKey, config: configPda })
+          .rpc();
+        assert.fail("Expected NoPendingAdmin");
+      } catch (e) {
+        expectAnchorErrorCode(e, "NoPendingAdmin");
+      }
+    });
+  });
+
+  describe("Config migration", () => {
+    it("Leaves a config that already has the current layout untouched", async () => {
+      const before = await connection.getAccountInfo(configPda);
+      try {
+        await program.methods.migrateConfig(0, admin.publicKey)
+          .accountsPartial({ admin: admin.publicKey, config: configPda })
+          .rpc();
+        assert.fail("Expected ConfigAlreadyMigrated");
+      } catch (e) {
+        expectAnchorErrorCode(e, "ConfigAlreadyMigrated");
+      }
+      const after = await connection.getAccountInfo(configPda);
+      assert.ok(after.data.equals(before.data));
+    });
+  });
+
+  // Keep this block last: a renounced power can never be restored on the shared config
+  describe("Renounced admin powers", () => {
+    it("Blocks force-release for good once the power is renounced", async () => {
+      const signature = await program.methods.renouncePower(0)
+        .accountsPartial({ admin: admin.publicKey, config: configPda })
+        .rpc();
+      const renounced = (await fetchEvents(signature)).find((e) => e.name === "powerRenounced");
+      assert.ok(renounced, "PowerRenounced should be emitted");
+
+      for (let attempt = 0; attempt < 2; attempt++) {
+        const created = await createBounty();
+        try {
+          await adminRelease(created);
+          assert.fail("Expected PowerRenounced");
+        } catch (e) {
+          expectAnchorErrorCode(e, "PowerRenounced");
+        }
+      }
+
+      // Renouncing again is a no-op, there is no instruction that clears the flag
+      await program.methods.renouncePower(0)
+        .accountsPartial({ admin: admin.publicKey, config: configPda })
+        .rpc();
+      const config = await program.account.configState.fetch(configPda);
+      assert.equal(config.renouncedPowers & 1, 1);
+    });
+
+    it("Rejects an unknown power id", async () => {
+      try {
+        await program.methods.renouncePower(99)
+          .accountsPartial({ admin: admin.publicKey, config: configPda })
+          .rpc();
+        assert.fail("Expected InvalidPower");
+      } catch (e) {
+        expectAnchorErrorCode(e, "InvalidPower");
+      }
+    });
+  });
 });